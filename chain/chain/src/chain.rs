@@ -47,7 +47,7 @@ use near_primitives::types::{
     AccountId, Balance, BlockExtra, BlockHeight, BlockHeightDelta, ChunkExtra, EpochId, MerkleHash,
     NumBlocks, ShardId, ValidatorStake,
 };
-use near_primitives::unwrap_or_return;
+use near_primitives::version::CHALLENGES_PROTOCOL_VERSION;
 use near_primitives::views::{
     ExecutionOutcomeWithIdView, ExecutionStatusView, FinalExecutionOutcomeView,
     FinalExecutionStatus, LightClientBlockView,
@@ -725,8 +725,9 @@ impl Chain {
 
     /// Process challenge to invalidate chain. This is done between blocks to unroll the chain as
     /// soon as possible and allow next block producer to skip invalid blocks.
-    pub fn process_challenge(&mut self, challenge: &Challenge) {
-        let head = unwrap_or_return!(self.head());
+    /// Returns error if the challenge is invalid given the current head.
+    pub fn process_challenge(&mut self, challenge: &Challenge) -> Result<(), Error> {
+        let head = self.head()?;
         let mut chain_update = ChainUpdate::new(
             &mut self.store,
             self.runtime_adapter.clone(),
@@ -736,18 +737,13 @@ impl Chain {
             &self.block_economics_config,
             self.doomslug_threshold_mode,
         );
-        match chain_update.verify_challenges(
+        chain_update.verify_challenges(
             &vec![challenge.clone()],
             &head.epoch_id,
             &head.last_block_hash,
             None,
-        ) {
-            Ok(_) => {}
-            Err(err) => {
-                debug!(target: "chain", "Invalid challenge: {}", err);
-            }
-        }
-        unwrap_or_return!(chain_update.commit());
+        )?;
+        chain_update.commit()
     }
 
     /// Processes headers and adds them to store for syncing.
@@ -1213,7 +1209,7 @@ impl Chain {
         prev_hash: CryptoHash,
         block_accepted: F,
         block_misses_chunks: F2,
-        on_challenge: F3,
+        mut on_challenge: F3,
     ) where
         F: Copy + FnMut(AcceptedBlock) -> (),
         F2: Copy + FnMut(Vec<ShardChunkHeader>) -> (),
@@ -1236,8 +1232,11 @@ impl Chain {
                         debug!(target: "chain", "Block with missing chunks is accepted; me: {:?}", me);
                         new_blocks_accepted.push(block_hash);
                     }
-                    Err(_) => {
+                    Err(e) => {
                         debug!(target: "chain", "Block with missing chunks is declined; me: {:?}", me);
+                        if let Some(challenge_body) = e.challenge_body() {
+                            on_challenge(challenge_body);
+                        }
                     }
                 }
             }
@@ -1261,7 +1260,7 @@ impl Chain {
         prev_hash: CryptoHash,
         block_accepted: F,
         block_misses_chunks: F2,
-        on_challenge: F3,
+        mut on_challenge: F3,
     ) -> Option<Tip>
     where
         F: Copy + FnMut(AcceptedBlock) -> (),
@@ -1296,8 +1295,11 @@ impl Chain {
                             maybe_new_head = maybe_tip;
                             queue.push(block_hash);
                         }
-                        Err(_) => {
+                        Err(e) => {
                             debug!(target: "chain", "Orphan declined");
+                            if let Some(challenge_body) = e.challenge_body() {
+                                on_challenge(challenge_body);
+                            }
                         }
                     }
                 }
@@ -1930,7 +1932,10 @@ impl Chain {
         &mut self,
         hash: &CryptoHash,
     ) -> Result<ExecutionOutcomeWithIdView, Error> {
-        self.get_execution_outcome(hash).map(|r| r.clone().into())
+        let mut outcome: ExecutionOutcomeWithIdView =
+            self.get_execution_outcome(hash)?.clone().into();
+        outcome.outcome.profile = self.store.get_gas_profile(hash)?;
+        Ok(outcome)
     }
 
     fn get_recursive_transaction_results(
@@ -2580,48 +2585,66 @@ impl<'a> ChainUpdate<'a> {
         }
 
         for (shard_id, mut receipt_proofs) in receipt_proofs_by_shard_id {
-            let mut slice = [0u8; 32];
-            slice.copy_from_slice(block.hash().as_ref());
-            let mut rng: StdRng = SeedableRng::from_seed(slice);
-            receipt_proofs.shuffle(&mut rng);
+            shuffle_receipt_proofs(&mut receipt_proofs, block.hash());
             self.chain_store_update.save_incoming_receipt(&block.hash(), shard_id, receipt_proofs);
         }
 
         Ok(())
     }
 
+    /// Creates a challenge for the chunk that doesn't match the result of applying the chunk of
+    /// the same shard in the prev block. Returns None if the challenge can't be verified without
+    /// the chain: prev chunk was not included in the prev block, or the chunk before it was not
+    /// included in the block before, so prev chunk received receipts from several blocks.
     pub fn create_chunk_state_challenge(
         &mut self,
         prev_block: &Block,
         block: &Block,
         chunk_header: &ShardChunkHeader,
-    ) -> Result<ChunkState, Error> {
-        let prev_chunk_header = &prev_block.chunks()[chunk_header.inner.shard_id as usize];
-        let prev_merkle_proofs = Block::compute_chunk_headers_root(&prev_block.chunks()).1;
-        let merkle_proofs = Block::compute_chunk_headers_root(&block.chunks()).1;
+    ) -> Result<Option<ChunkState>, Error> {
+        let shard_id = chunk_header.inner.shard_id;
+        let prev_prev_block =
+            self.chain_store_update.get_block(prev_block.header().prev_hash())?.clone();
+        let prev_chunk_header = &prev_block.chunks()[shard_id as usize];
+        let prev_prev_chunk_header = &prev_prev_block.chunks()[shard_id as usize];
+        if prev_chunk_header.height_included != prev_block.header().height()
+            || prev_prev_chunk_header.height_included != prev_prev_block.header().height()
+        {
+            return Ok(None);
+        }
+
+        // Receipts sent by the chunks of the prev block, as they were received.
+        let mut incoming_receipts =
+            match self.chain_store_update.get_incoming_receipts(&prev_block.hash(), shard_id) {
+                Ok(receipt_proofs) => receipt_proofs.clone(),
+                Err(_) => return Ok(None),
+            };
+        incoming_receipts.sort_by_key(|ReceiptProof(_, shard_proof)| shard_proof.from_shard_id);
+        let expected_from_shards = prev_block
+            .chunks()
+            .iter()
+            .filter(|chunk| chunk.height_included == prev_block.header().height())
+            .map(|chunk| chunk.inner.shard_id)
+            .collect::<Vec<_>>();
+        let from_shards = incoming_receipts
+            .iter()
+            .map(|ReceiptProof(_, shard_proof)| shard_proof.from_shard_id)
+            .collect::<Vec<_>>();
+        if from_shards != expected_from_shards {
+            return Ok(None);
+        }
+        let mut receipt_proofs = incoming_receipts.clone();
+        shuffle_receipt_proofs(&mut receipt_proofs, prev_block.hash());
+        let receipts = collect_receipts(&receipt_proofs);
+
         let prev_chunk = self
             .chain_store_update
             .get_chain_store()
-            .get_chunk_clone_from_header(&prev_block.chunks()[chunk_header.inner.shard_id as usize])
-            .unwrap();
-        let receipt_proof_response: Vec<ReceiptProofResponse> =
-            self.chain_store_update.get_incoming_receipts_for_shard(
-                chunk_header.inner.shard_id,
-                *prev_block.hash(),
-                prev_chunk_header.height_included,
-            )?;
-        let receipts = collect_receipts_from_response(&receipt_proof_response);
-
-        let challenges_result = self.verify_challenges(
-            block.challenges(),
-            block.header().epoch_id(),
-            block.header().prev_hash(),
-            Some(&block.hash()),
-        )?;
+            .get_chunk_clone_from_header(prev_chunk_header)?;
         let apply_result = self
             .runtime_adapter
             .apply_transactions_with_optional_storage_proof(
-                chunk_header.inner.shard_id,
+                shard_id,
                 &prev_chunk.header.inner.prev_state_root,
                 prev_chunk.header.height_included,
                 prev_block.header().raw_timestamp(),
@@ -2630,23 +2653,33 @@ impl<'a> ChainUpdate<'a> {
                 &receipts,
                 &prev_chunk.transactions,
                 &prev_chunk.header.inner.validator_proposals,
-                prev_block.header().gas_price(),
+                prev_prev_block.header().gas_price(),
                 prev_chunk.header.inner.gas_limit,
-                &challenges_result,
-                *block.header().random_value(),
+                &prev_block.header().challenges_result(),
+                *prev_block.header().random_value(),
                 true,
             )
-            .unwrap();
+            .map_err(|e| ErrorKind::Other(e.to_string()))?;
         let partial_state = apply_result.proof.unwrap().nodes;
-        Ok(ChunkState {
+
+        let prev_prev_merkle_proofs =
+            Block::compute_chunk_headers_root(&prev_prev_block.chunks()).1;
+        let prev_merkle_proofs = Block::compute_chunk_headers_root(&prev_block.chunks()).1;
+        let merkle_proofs = Block::compute_chunk_headers_root(&block.chunks()).1;
+        Ok(Some(ChunkState {
             prev_block_header: prev_block.header().try_to_vec()?,
             block_header: block.header().try_to_vec()?,
-            prev_merkle_proof: prev_merkle_proofs[chunk_header.inner.shard_id as usize].clone(),
-            merkle_proof: merkle_proofs[chunk_header.inner.shard_id as usize].clone(),
+            prev_merkle_proof: prev_merkle_proofs[shard_id as usize].clone(),
             prev_chunk,
+            prev_prev_block_header: prev_prev_block.header().try_to_vec()?,
+            prev_prev_chunk_header: prev_prev_chunk_header.clone(),
+            prev_prev_merkle_proof: prev_prev_merkle_proofs[shard_id as usize].clone(),
+            prev_block_chunks: prev_block.chunks().clone(),
+            incoming_receipts,
+            merkle_proof: merkle_proofs[shard_id as usize].clone(),
             chunk_header: chunk_header.clone(),
             partial_state,
-        })
+        }))
     }
 
     fn apply_chunks(
@@ -2712,9 +2745,10 @@ impl<'a> ChainUpdate<'a> {
                         debug!(target: "chain", "Failed to validate chunk extra: {:?}", e);
                         byzantine_assert!(false);
                         match self.create_chunk_state_challenge(&prev_block, &block, chunk_header) {
-                            Ok(chunk_state) => {
+                            Ok(Some(chunk_state)) => {
                                 Error::from(ErrorKind::InvalidChunkState(Box::new(chunk_state)))
                             }
+                            Ok(None) => e,
                             Err(err) => err,
                         }
                     })?;
@@ -2804,6 +2838,7 @@ impl<'a> ChainUpdate<'a> {
                         apply_result.outcomes,
                         outcome_paths,
                     );
+                    self.chain_store_update.save_gas_profiles(apply_result.gas_profiles);
                     self.chain_store_update.save_transactions(chunk.transactions);
                 } else {
                     let mut new_extra = self
@@ -2949,6 +2984,26 @@ impl<'a> ChainUpdate<'a> {
             return Err(ErrorKind::InvalidGasPrice.into());
         }
 
+        if protocol_version < CHALLENGES_PROTOCOL_VERSION && !block.challenges().is_empty() {
+            byzantine_assert!(false);
+            return Err(ErrorKind::InvalidChallenge.into());
+        }
+
+        // The header carries the result of the challenges included in the previous block.
+        // Block extra is missing for a previous block that was not applied, e.g. after state sync.
+        match self.chain_store_update.get_block_extra(&prev_hash) {
+            Ok(prev_block_extra) => {
+                if &prev_block_extra.challenges_result != block.header().challenges_result() {
+                    byzantine_assert!(false);
+                    return Err(ErrorKind::InvalidChallengesResult.into());
+                }
+            }
+            Err(err) => match err.kind() {
+                ErrorKind::DBNotFoundErr(_) => {}
+                _ => return Err(err),
+            },
+        }
+
         let prev_block = self.chain_store_update.get_block(&prev_hash)?.clone();
 
         self.ping_missing_chunks(me, prev_hash, &block)?;
@@ -3465,6 +3520,7 @@ impl<'a> ChainUpdate<'a> {
             apply_result.outcomes,
             outcome_proofs,
         );
+        self.chain_store_update.save_gas_profiles(apply_result.gas_profiles);
         // Saving all incoming receipts.
         for receipt_proof_response in incoming_receipts_proofs {
             self.chain_store_update.save_incoming_receipt(
@@ -3574,3 +3630,12 @@ pub fn collect_receipts_from_response(
         receipt_proof_response.iter().flat_map(|ReceiptProofResponse(_, proofs)| proofs),
     )
 }
+
+/// Puts the receipt proofs sent to a shard by the chunks of the block, ordered by source shard,
+/// into the order in which they are applied.
+pub fn shuffle_receipt_proofs(receipt_proofs: &mut Vec<ReceiptProof>, block_hash: &CryptoHash) {
+    let mut slice = [0u8; 32];
+    slice.copy_from_slice(block_hash.as_ref());
+    let mut rng: StdRng = SeedableRng::from_seed(slice);
+    receipt_proofs.shuffle(&mut rng);
+}
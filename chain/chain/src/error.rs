@@ -6,7 +6,7 @@ use failure::{Backtrace, Context, Fail};
 use log::error;
 
 use near_primitives::block::BlockValidityError;
-use near_primitives::challenge::{ChunkProofs, ChunkState};
+use near_primitives::challenge::{ChallengeBody, ChunkProofs, ChunkState};
 use near_primitives::errors::{EpochError, StorageError};
 use near_primitives::hash::CryptoHash;
 use near_primitives::serialize::to_base;
@@ -83,6 +83,9 @@ pub enum ErrorKind {
     /// Incorrect (malicious) challenge (slash the sender).
     #[fail(display = "Malicious Challenge")]
     MaliciousChallenge,
+    /// Challenges result in the header doesn't match the challenges of the previous block.
+    #[fail(display = "Invalid Challenges Result")]
+    InvalidChallengesResult,
     /// Incorrect number of chunk headers
     #[fail(display = "Incorrect Number of Chunk Headers")]
     IncorrectNumberOfChunkHeaders,
@@ -253,6 +256,7 @@ impl Error {
             | ErrorKind::InvalidTransactions
             | ErrorKind::InvalidChallenge
             | ErrorKind::MaliciousChallenge
+            | ErrorKind::InvalidChallengesResult
             | ErrorKind::IncorrectNumberOfChunkHeaders
             | ErrorKind::InvalidEpochHash
             | ErrorKind::InvalidNextBPHash
@@ -274,6 +278,19 @@ impl Error {
         }
     }
 
+    /// Body of the challenge that proves the block invalid, if the error carries one.
+    pub fn challenge_body(&self) -> Option<ChallengeBody> {
+        match self.kind() {
+            ErrorKind::InvalidChunkProofs(chunk_proofs) => {
+                Some(ChallengeBody::ChunkProofs(*chunk_proofs))
+            }
+            ErrorKind::InvalidChunkState(chunk_state) => {
+                Some(ChallengeBody::ChunkState(*chunk_state))
+            }
+            _ => None,
+        }
+    }
+
     pub fn is_error(&self) -> bool {
         match self.kind() {
             ErrorKind::IOErr(_) | ErrorKind::Other(_) | ErrorKind::DBNotFoundErr(_) => true,
@@ -23,7 +23,7 @@ use near_primitives::syncing::{
     StateHeaderKey, StatePartKey,
 };
 use near_primitives::transaction::{
-    ExecutionOutcomeWithId, ExecutionOutcomeWithIdAndProof, SignedTransaction,
+    ExecutionOutcomeWithId, ExecutionOutcomeWithIdAndProof, GasProfile, SignedTransaction,
 };
 use near_primitives::trie_key::{trie_key_parsers, TrieKey};
 use near_primitives::types::{
@@ -36,12 +36,13 @@ use near_store::{
     read_with_cache, ColBlock, ColBlockExtra, ColBlockHeader, ColBlockHeight, ColBlockInfo,
     ColBlockMerkleTree, ColBlockMisc, ColBlockOrdinal, ColBlockPerHeight, ColBlockRefCount,
     ColBlocksToCatchup, ColChallengedBlocks, ColChunkExtra, ColChunkHashesByHeight,
-    ColChunkPerHeightShard, ColChunks, ColEpochLightClientBlocks, ColGCCount, ColIncomingReceipts,
-    ColInvalidChunks, ColLastBlockWithNewChunk, ColNextBlockHashes, ColNextBlockWithNewChunk,
-    ColOutcomesByBlockHash, ColOutgoingReceipts, ColPartialChunks, ColProcessedBlockHeights,
-    ColReceiptIdToShardId, ColState, ColStateChanges, ColStateDlInfos, ColStateHeaders,
-    ColStateParts, ColTransactionRefCount, ColTransactionResult, ColTransactions, ColTrieChanges,
-    DBCol, KeyForStateChanges, ShardTries, Store, StoreUpdate, TrieChanges, WrappedTrieChanges,
+    ColChunkPerHeightShard, ColChunks, ColEpochLightClientBlocks, ColGCCount, ColGasProfiles,
+    ColIncomingReceipts, ColInvalidChunks, ColLastBlockWithNewChunk, ColLogsIndex,
+    ColLogsIndexKeys, ColNextBlockHashes, ColNextBlockWithNewChunk, ColOutcomesByBlockHash,
+    ColOutgoingReceipts, ColPartialChunks, ColProcessedBlockHeights, ColReceiptIdToShardId,
+    ColState, ColStateChanges, ColStateDlInfos, ColStateHeaders, ColStateParts,
+    ColTransactionRefCount, ColTransactionResult, ColTransactions, ColTrieChanges, DBCol,
+    KeyForStateChanges, ShardTries, Store, StoreUpdate, TrieChanges, WrappedTrieChanges,
     CHUNK_TAIL_KEY, FORK_TAIL_KEY, HEADER_HEAD_KEY, HEAD_KEY, LARGEST_TARGET_HEIGHT_KEY,
     LATEST_KNOWN_KEY, SHOULD_COL_GC, SYNC_HEAD_KEY, TAIL_KEY,
 };
@@ -346,6 +347,63 @@ pub struct ChainStore {
     block_ordinal_to_hash: SizedCache<Vec<u8>, CryptoHash>,
     /// Processed block heights.
     processed_block_heights: SizedCache<Vec<u8>, ()>,
+    /// Whether outcomes with logs are written to the logs index.
+    save_logs_index: bool,
+}
+
+/// Number of leading log bytes used as a key of the logs index.
+pub const LOGS_INDEX_PREFIX_LEN: usize = 32;
+
+/// Key prefix of the logs index for outcomes executed on `account_id`. Account ids can't contain
+/// zero bytes, so it separates the account from the rest of the key.
+fn get_logs_index_account_prefix(account_id: &AccountId) -> Vec<u8> {
+    let mut key = Vec::with_capacity(account_id.len() + 1);
+    key.extend_from_slice(account_id.as_bytes());
+    key.push(0);
+    key
+}
+
+/// Part of a log, or of a log prefix, that is stored in keys of the logs index.
+fn get_logs_index_log_prefix(log: &str) -> &[u8] {
+    &log.as_bytes()[..std::cmp::min(log.len(), LOGS_INDEX_PREFIX_LEN)]
+}
+
+/// Keys of the logs index for every distinct log prefix of the outcome: the account prefix
+/// followed by the big-endian block height, so that the keys of an account are ordered by height,
+/// then the log prefix and the outcome id.
+fn get_logs_index_keys(
+    outcome_with_id: &ExecutionOutcomeWithId,
+    block_height: BlockHeight,
+) -> HashSet<Vec<u8>> {
+    outcome_with_id
+        .outcome
+        .logs
+        .iter()
+        .map(|log| {
+            let mut key = get_logs_index_account_prefix(&outcome_with_id.outcome.executor_id);
+            key.extend_from_slice(&block_height.to_be_bytes());
+            key.extend_from_slice(get_logs_index_log_prefix(log));
+            key.extend_from_slice(outcome_with_id.id.as_ref());
+            key
+        })
+        .collect()
+}
+
+/// Splits a key of the logs index, which starts with an account prefix of the given length, into
+/// the block height and the log prefix.
+fn parse_logs_index_key(
+    key: &[u8],
+    account_prefix_len: usize,
+) -> Result<(BlockHeight, &[u8]), Error> {
+    let log_prefix_offset = account_prefix_len + 8;
+    let log_prefix_end = key
+        .len()
+        .checked_sub(std::mem::size_of::<CryptoHash>())
+        .filter(|&end| end >= log_prefix_offset)
+        .ok_or_else(|| ErrorKind::Other("Invalid logs index key".to_string()))?;
+    let mut height_bytes = [0u8; 8];
+    height_bytes.copy_from_slice(&key[account_prefix_len..log_prefix_offset]);
+    Ok((BlockHeight::from_be_bytes(height_bytes), &key[log_prefix_offset..log_prefix_end]))
 }
 
 pub fn option_to_not_found<T>(res: io::Result<Option<T>>, field_name: &str) -> Result<T, Error> {
@@ -387,9 +445,67 @@ impl ChainStore {
             block_merkle_tree: SizedCache::with_size(CACHE_SIZE),
             block_ordinal_to_hash: SizedCache::with_size(CACHE_SIZE),
             processed_block_heights: SizedCache::with_size(CACHE_SIZE),
+            save_logs_index: false,
         }
     }
 
+    /// Enables or disables writing outcomes with logs to the logs index.
+    pub fn set_save_logs_index(&mut self, save_logs_index: bool) {
+        self.save_logs_index = save_logs_index;
+    }
+
+    /// Returns heights and ids of outcomes executed on `account_id` in blocks within the height
+    /// range, which have a log starting with `log_prefix`, ordered by height. Stops after the
+    /// block that brings the number of outcomes to at least `limit`, and then also returns the
+    /// height of the next block with such outcomes. Only the first `LOGS_INDEX_PREFIX_LEN` bytes
+    /// of the prefix are checked, so the caller has to match the logs of the returned outcomes.
+    pub fn get_outcome_ids_by_log_prefix(
+        &self,
+        account_id: &AccountId,
+        log_prefix: &str,
+        from_block_height: BlockHeight,
+        to_block_height: BlockHeight,
+        limit: usize,
+    ) -> Result<(Vec<(BlockHeight, CryptoHash)>, Option<BlockHeight>), Error> {
+        let account_prefix = get_logs_index_account_prefix(account_id);
+        let mut lower_bound = account_prefix.clone();
+        lower_bound.extend_from_slice(&from_block_height.to_be_bytes());
+        let log_prefix = get_logs_index_log_prefix(log_prefix);
+        let mut outcome_ids: Vec<(BlockHeight, CryptoHash)> = vec![];
+        let mut seen_outcome_ids = HashSet::new();
+        for (key, value) in self.store.iter_prefix_from(ColLogsIndex, &account_prefix, &lower_bound)
+        {
+            let (block_height, key_log_prefix) = parse_logs_index_key(&key, account_prefix.len())?;
+            if block_height > to_block_height {
+                break;
+            }
+            if !key_log_prefix.starts_with(log_prefix) {
+                continue;
+            }
+            let outcome_id = CryptoHash::try_from_slice(&value)?;
+            if !seen_outcome_ids.insert(outcome_id) {
+                continue;
+            }
+            // Outcomes of a single block are never split between pages.
+            match outcome_ids.last() {
+                Some((last_height, _))
+                    if outcome_ids.len() >= std::cmp::max(limit, 1)
+                        && *last_height != block_height =>
+                {
+                    return Ok((outcome_ids, Some(block_height)));
+                }
+                _ => outcome_ids.push((block_height, outcome_id)),
+            }
+        }
+        Ok((outcome_ids, None))
+    }
+
+    /// Returns the breakdown of the gas burnt by the outcome with the given id, if it was
+    /// recorded.
+    pub fn get_gas_profile(&self, outcome_id: &CryptoHash) -> Result<Option<GasProfile>, Error> {
+        self.store.get_ser(ColGasProfiles, outcome_id.as_ref()).map_err(|e| e.into())
+    }
+
     pub fn owned_store(&self) -> Arc<Store> {
         self.store.clone()
     }
@@ -1119,6 +1235,7 @@ struct ChainStoreCacheUpdate {
     outgoing_receipts: HashMap<(CryptoHash, ShardId), Vec<Receipt>>,
     incoming_receipts: HashMap<(CryptoHash, ShardId), Vec<ReceiptProof>>,
     outcomes: HashMap<CryptoHash, ExecutionOutcomeWithIdAndProof>,
+    gas_profiles: HashMap<CryptoHash, GasProfile>,
     invalid_chunks: HashMap<ChunkHash, EncodedShardChunk>,
     receipt_id_to_shard_id: HashMap<CryptoHash, ShardId>,
     next_block_with_new_chunk: HashMap<(CryptoHash, ShardId), CryptoHash>,
@@ -1853,6 +1970,10 @@ impl<'a> ChainStoreUpdate<'a> {
         }
     }
 
+    pub fn save_gas_profiles(&mut self, gas_profiles: Vec<(CryptoHash, GasProfile)>) {
+        self.chain_store_cache_update.gas_profiles.extend(gas_profiles);
+    }
+
     pub fn save_transactions(&mut self, transactions: Vec<SignedTransaction>) {
         for transaction in transactions {
             self.chain_store_cache_update.transactions.insert(transaction);
@@ -2135,8 +2256,18 @@ impl<'a> ChainStoreUpdate<'a> {
         let outcome_ids = self.get_outcomes_by_block_hash(&block_hash)?;
         for outcome_id in outcome_ids {
             self.gc_col(ColTransactionResult, &outcome_id.as_ref().into());
+            self.gc_col(ColGasProfiles, &outcome_id.as_ref().into());
         }
         self.gc_col(ColOutcomesByBlockHash, &block_hash_vec);
+        let logs_index_keys: Vec<Vec<u8>> = self
+            .chain_store
+            .store()
+            .get_ser(ColLogsIndexKeys, &block_hash_vec)?
+            .unwrap_or_default();
+        for key in logs_index_keys {
+            self.gc_col(ColLogsIndex, &key);
+        }
+        self.gc_col(ColLogsIndexKeys, &block_hash_vec);
         match gc_mode {
             GCMode::StateSync { clear_block_info: false } => {}
             _ => self.gc_col(ColBlockInfo, &block_hash_vec),
@@ -2337,6 +2468,15 @@ impl<'a> ChainStoreUpdate<'a> {
             DBCol::ColOutcomesByBlockHash => {
                 store_update.delete(col, key);
             }
+            DBCol::ColGasProfiles => {
+                store_update.delete(col, key);
+            }
+            DBCol::ColLogsIndex => {
+                store_update.delete(col, key);
+            }
+            DBCol::ColLogsIndexKeys => {
+                store_update.delete(col, key);
+            }
             DBCol::ColStateDlInfos => {
                 store_update.delete(col, key);
             }
@@ -2368,7 +2508,8 @@ impl<'a> ChainStoreUpdate<'a> {
             | DBCol::ColComponentEdges
             | DBCol::ColEpochInfo
             | DBCol::ColEpochStart
-            | DBCol::ColBlockOrdinal => {
+            | DBCol::ColBlockOrdinal
+            | DBCol::ColCachedContractCode => {
                 unreachable!();
             }
         }
@@ -2522,6 +2663,7 @@ impl<'a> ChainStoreUpdate<'a> {
             )?;
         }
         let mut block_hash_to_outcomes: HashMap<CryptoHash, HashSet<CryptoHash>> = HashMap::new();
+        let mut block_hash_to_logs_index_keys: HashMap<CryptoHash, Vec<Vec<u8>>> = HashMap::new();
         for (hash, outcome) in self.chain_store_cache_update.outcomes.iter() {
             match block_hash_to_outcomes.entry(outcome.block_hash) {
                 Entry::Occupied(mut entry) => {
@@ -2537,10 +2679,40 @@ impl<'a> ChainStoreUpdate<'a> {
                 }
             };
             store_update.set_ser(ColTransactionResult, hash.as_ref(), outcome)?;
+            if self.chain_store.save_logs_index && !outcome.outcome_with_id.outcome.logs.is_empty()
+            {
+                let block_height =
+                    match self.chain_store_cache_update.headers.get(&outcome.block_hash) {
+                        Some(header) => header.height(),
+                        None => self.chain_store.get_block_header(&outcome.block_hash)?.height(),
+                    };
+                let logs_index_keys = match block_hash_to_logs_index_keys.entry(outcome.block_hash)
+                {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(
+                        self.chain_store
+                            .store
+                            .get_ser(ColLogsIndexKeys, outcome.block_hash.as_ref())?
+                            .unwrap_or_default(),
+                    ),
+                };
+                for key in get_logs_index_keys(&outcome.outcome_with_id, block_height) {
+                    store_update.set_ser(ColLogsIndex, &key, outcome.id())?;
+                    if !logs_index_keys.contains(&key) {
+                        logs_index_keys.push(key);
+                    }
+                }
+            }
         }
         for (block_hash, hash_set) in block_hash_to_outcomes {
             store_update.set_ser(ColOutcomesByBlockHash, block_hash.as_ref(), &hash_set)?;
         }
+        for (outcome_id, gas_profile) in self.chain_store_cache_update.gas_profiles.iter() {
+            store_update.set_ser(ColGasProfiles, outcome_id.as_ref(), gas_profile)?;
+        }
+        for (block_hash, keys) in block_hash_to_logs_index_keys {
+            store_update.set_ser(ColLogsIndexKeys, block_hash.as_ref(), &keys)?;
+        }
         for (receipt_id, shard_id) in self.chain_store_cache_update.receipt_id_to_shard_id.iter() {
             store_update.set_ser(ColReceiptIdToShardId, receipt_id.as_ref(), shard_id)?;
         }
@@ -2827,6 +2999,7 @@ mod tests {
     use near_primitives::block::{Block, Tip};
     use near_primitives::errors::InvalidTxError;
     use near_primitives::hash::hash;
+    use near_primitives::transaction::{ExecutionOutcome, ExecutionOutcomeWithId};
     use near_primitives::types::{BlockHeight, EpochId, GCCount, NumBlocks};
     use near_primitives::utils::index_to_bytes;
     use near_primitives::validator_signer::InMemoryValidatorSigner;
@@ -3120,6 +3293,7 @@ mod tests {
             DBCol::ColChunkPerHeightShard,
             DBCol::ColBlockRefCount,
             DBCol::ColOutcomesByBlockHash,
+            DBCol::ColLogsIndexKeys,
         ];
         for col in DBCol::iter() {
             println!("current column is {:?}", col);
@@ -3151,6 +3325,113 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_logs_index() {
+        let mut chain = get_chain();
+        chain.mut_store().set_save_logs_index(true);
+        let genesis_hash = chain.genesis().hash().clone();
+        let outcome_id = hash(&[1]);
+        let outcome_with_id = ExecutionOutcomeWithId {
+            id: outcome_id,
+            outcome: ExecutionOutcome {
+                logs: vec!["transfer: 10".to_string(), "mint".to_string()],
+                executor_id: "test1".to_string(),
+                ..Default::default()
+            },
+        };
+        let mut store_update = chain.mut_store().store_update();
+        store_update.save_outcomes_with_proofs(&genesis_hash, vec![outcome_with_id], vec![vec![]]);
+        store_update.commit().unwrap();
+
+        let store = chain.store();
+        let test1 = "test1".to_string();
+        for prefix in &["", "transfer", "transfer: 10", "mint"] {
+            assert_eq!(
+                store.get_outcome_ids_by_log_prefix(&test1, prefix, 0, 0, 10).unwrap(),
+                (vec![(0, outcome_id)], None)
+            );
+        }
+        assert!(store
+            .get_outcome_ids_by_log_prefix(&test1, "burn", 0, 0, 10)
+            .unwrap()
+            .0
+            .is_empty());
+        assert!(store.get_outcome_ids_by_log_prefix(&test1, "", 1, 10, 10).unwrap().0.is_empty());
+        assert!(store
+            .get_outcome_ids_by_log_prefix(&"test2".to_string(), "", 0, 0, 10)
+            .unwrap()
+            .0
+            .is_empty());
+    }
+
+    /// The logs index is scanned from the start of the range in the order of heights, stops at
+    /// the limit, and is garbage collected together with the blocks.
+    #[test]
+    fn test_logs_index_range_limit_and_gc() {
+        let mut chain = get_chain_with_epoch_length(1);
+        chain.mut_store().set_save_logs_index(true);
+        let genesis = chain.get_block_by_height(0).unwrap().clone();
+        let signer =
+            Arc::new(InMemoryValidatorSigner::from_seed("test1", KeyType::ED25519, "test1"));
+        let test1 = "test1".to_string();
+        let mut prev_block = genesis.clone();
+        let mut blocks = vec![prev_block.clone()];
+        for i in 1..15 {
+            let block = Block::empty_with_height(&prev_block, i, &*signer.clone());
+            blocks.push(block.clone());
+            let mut store_update = chain.mut_store().store_update();
+            store_update.save_block(block.clone());
+            store_update.inc_block_refcount(block.header().prev_hash()).unwrap();
+            store_update.save_head(&Tip::from_header(block.header())).unwrap();
+            store_update.save_block_header(block.header().clone()).unwrap();
+            store_update
+                .chain_store_cache_update
+                .height_to_hashes
+                .insert(i, Some(*block.header().hash()));
+            store_update.save_next_block_hash(&prev_block.hash(), *block.hash());
+            let outcomes = (0..2)
+                .map(|j| ExecutionOutcomeWithId {
+                    id: hash(&[i as u8, j]),
+                    outcome: ExecutionOutcome {
+                        logs: vec![format!("event {}", i)],
+                        executor_id: test1.clone(),
+                        ..Default::default()
+                    },
+                })
+                .collect();
+            store_update.save_outcomes_with_proofs(block.hash(), outcomes, vec![vec![], vec![]]);
+            store_update.commit().unwrap();
+            prev_block = block.clone();
+        }
+
+        let (outcome_ids, next_block_height) =
+            chain.store().get_outcome_ids_by_log_prefix(&test1, "event", 3, 10, 3).unwrap();
+        let heights: Vec<_> = outcome_ids.iter().map(|(height, _)| *height).collect();
+        assert_eq!(heights, vec![3, 3, 4, 4]);
+        assert_eq!(next_block_height, Some(5));
+        let (outcome_ids, next_block_height) =
+            chain.store().get_outcome_ids_by_log_prefix(&test1, "event 1", 1, 20, 100).unwrap();
+        let heights: Vec<_> = outcome_ids.iter().map(|(height, _)| *height).collect();
+        assert_eq!(heights, vec![1, 1, 10, 10, 11, 11, 12, 12, 13, 13, 14, 14]);
+        assert_eq!(next_block_height, None);
+
+        chain.epoch_length = 1;
+        let trie = chain.runtime_adapter.get_tries();
+        assert!(chain.clear_data(trie, 100).is_ok());
+        let (outcome_ids, _) =
+            chain.store().get_outcome_ids_by_log_prefix(&test1, "", 0, 20, 100).unwrap();
+        let heights: Vec<_> = outcome_ids.iter().map(|(height, _)| *height).collect();
+        assert_eq!(heights, (8..15).flat_map(|height| vec![height, height]).collect::<Vec<_>>());
+        for block in &blocks[1..8] {
+            assert!(chain
+                .store()
+                .store
+                .get_ser::<Vec<Vec<u8>>>(DBCol::ColLogsIndexKeys, block.hash().as_ref())
+                .unwrap()
+                .is_none());
+        }
+    }
+
     #[test]
     fn test_clear_old_data_fixed_height() {
         let mut chain = get_chain();
@@ -30,7 +30,7 @@ use near_primitives::validator_signer::InMemoryValidatorSigner;
 use near_primitives::version::{ProtocolVersion, PROTOCOL_VERSION};
 use near_primitives::views::{
     AccessKeyInfoView, AccessKeyList, CallResult, EpochValidatorInfo, QueryRequest, QueryResponse,
-    QueryResponseKind, ViewStateResult,
+    QueryResponseKind, TransactionSimulationView, ViewStateResult,
 };
 use near_store::test_utils::create_test_store;
 use near_store::{
@@ -461,6 +461,7 @@ impl RuntimeAdapter for KeyValueRuntime {
         _gas_price: Balance,
         _state_update: Option<StateRoot>,
         _transaction: &SignedTransaction,
+        _current_protocol_version: ProtocolVersion,
     ) -> Result<Option<InvalidTxError>, Error> {
         Ok(None)
     }
@@ -471,6 +472,7 @@ impl RuntimeAdapter for KeyValueRuntime {
         _gas_limit: Gas,
         _shard_id: ShardId,
         _state_root: StateRoot,
+        _current_protocol_version: ProtocolVersion,
         transactions: &mut dyn PoolIterator,
         _chain_validate: &mut dyn FnMut(&SignedTransaction) -> bool,
     ) -> Result<Vec<SignedTransaction>, Error> {
@@ -660,6 +662,7 @@ impl RuntimeAdapter for KeyValueRuntime {
             total_gas_burnt: 0,
             total_balance_burnt: 0,
             proof: None,
+            gas_profiles: vec![],
         })
     }
 
@@ -877,6 +880,21 @@ impl RuntimeAdapter for KeyValueRuntime {
         Ok(PROTOCOL_VERSION)
     }
 
+    fn simulate_transaction(
+        &self,
+        _shard_id: ShardId,
+        _state_root: &StateRoot,
+        _block_height: BlockHeight,
+        _block_timestamp: u64,
+        _prev_block_hash: &CryptoHash,
+        _gas_price: Balance,
+        _random_seed: CryptoHash,
+        _transaction: &SignedTransaction,
+        _verify_signature: bool,
+    ) -> Result<Result<TransactionSimulationView, InvalidTxError>, Error> {
+        unimplemented!();
+    }
+
     fn get_validator_info(&self, _block_hash: &CryptoHash) -> Result<EpochValidatorInfo, Error> {
         Ok(EpochValidatorInfo {
             current_validators: vec![],
@@ -14,7 +14,7 @@ use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::merkle::{merklize, MerklePath};
 use near_primitives::receipt::Receipt;
 use near_primitives::sharding::ShardChunkHeader;
-use near_primitives::transaction::{ExecutionOutcomeWithId, SignedTransaction};
+use near_primitives::transaction::{ExecutionOutcomeWithId, GasProfile, SignedTransaction};
 use near_primitives::types::{
     AccountId, ApprovalStake, Balance, BlockHeight, BlockHeightDelta, EpochId, Gas, MerkleHash,
     NumBlocks, ShardId, StateRoot, StateRootNode, ValidatorStake,
@@ -23,7 +23,9 @@ use near_primitives::version::{
     ProtocolVersion, MIN_GAS_PRICE_NEP_92, MIN_GAS_PRICE_NEP_92_FIX, MIN_PROTOCOL_VERSION_NEP_92,
     MIN_PROTOCOL_VERSION_NEP_92_FIX,
 };
-use near_primitives::views::{EpochValidatorInfo, QueryRequest, QueryResponse};
+use near_primitives::views::{
+    EpochValidatorInfo, QueryRequest, QueryResponse, TransactionSimulationView,
+};
 use near_store::{PartialStorage, ShardTries, Store, Trie, WrappedTrieChanges};
 
 use crate::error::Error;
@@ -83,6 +85,9 @@ pub struct ApplyTransactionResult {
     pub total_gas_burnt: Gas,
     pub total_balance_burnt: Balance,
     pub proof: Option<PartialStorage>,
+    /// Breakdown of the burnt gas per outcome id, only recorded by nodes with gas profiles
+    /// enabled.
+    pub gas_profiles: Vec<(CryptoHash, GasProfile)>,
 }
 
 impl ApplyTransactionResult {
@@ -125,7 +130,7 @@ impl BlockHeaderInfo {
             last_finalized_height,
             last_finalized_block_hash: *header.last_final_block(),
             proposals: header.validator_proposals().to_vec(),
-            slashed_validators: vec![],
+            slashed_validators: header.challenges_result().clone(),
             chunk_mask: header.chunk_mask().to_vec(),
             total_supply: header.total_supply(),
             latest_protocol_version: header.latest_protocol_version(),
@@ -242,7 +247,7 @@ pub trait RuntimeAdapter: Send + Sync {
         vrf_proof: &near_crypto::vrf::Proof,
     ) -> Result<(), Error>;
 
-    /// Validates a given signed transaction.
+    /// Validates a given signed transaction at the given protocol version.
     /// If the state root is given, then the verification will use the account. Otherwise it will
     /// only validate the transaction math, limits and signatures.
     /// Returns an option of `InvalidTxError`, it contains `Some(InvalidTxError)` if there is
@@ -254,6 +259,7 @@ pub trait RuntimeAdapter: Send + Sync {
         gas_price: Balance,
         state_root: Option<StateRoot>,
         transaction: &SignedTransaction,
+        current_protocol_version: ProtocolVersion,
     ) -> Result<Option<InvalidTxError>, Error>;
 
     /// Returns an ordered list of valid transactions from the pool up the given limits.
@@ -269,6 +275,7 @@ pub trait RuntimeAdapter: Send + Sync {
         gas_limit: Gas,
         shard_id: ShardId,
         state_root: StateRoot,
+        current_protocol_version: ProtocolVersion,
         pool_iterator: &mut dyn PoolIterator,
         chain_validate: &mut dyn FnMut(&SignedTransaction) -> bool,
     ) -> Result<Vec<SignedTransaction>, Error>;
@@ -504,6 +511,25 @@ pub trait RuntimeAdapter: Send + Sync {
         request: &QueryRequest,
     ) -> Result<QueryResponse, Box<dyn std::error::Error>>;
 
+    /// Simulates the transaction on top of the given state of the shard of its signer, following
+    /// the receipts to accounts in the same shard, without persisting anything. The block
+    /// parameters are the ones of the next block after `prev_block_hash`.
+    /// Signature checks are skipped if `verify_signature` is false, which allows simulating
+    /// unsigned transactions.
+    /// Returns `InvalidTxError` if the transaction can't be converted into a receipt.
+    fn simulate_transaction(
+        &self,
+        shard_id: ShardId,
+        state_root: &StateRoot,
+        block_height: BlockHeight,
+        block_timestamp: u64,
+        prev_block_hash: &CryptoHash,
+        gas_price: Balance,
+        random_seed: CryptoHash,
+        transaction: &SignedTransaction,
+        verify_signature: bool,
+    ) -> Result<Result<TransactionSimulationView, InvalidTxError>, Error>;
+
     fn get_validator_info(&self, block_hash: &CryptoHash) -> Result<EpochValidatorInfo, Error>;
 
     /// Get the part of the state from given state root.
@@ -5,18 +5,18 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::PublicKey;
 use near_primitives::block::{Block, BlockHeader};
 use near_primitives::challenge::{
-    BlockDoubleSign, Challenge, ChallengeBody, ChallengesResult, ChunkProofs, ChunkState,
-    MaybeEncodedShardChunk,
+    BlockDoubleSign, Challenge, ChallengeBody, ChunkProofs, ChunkState, MaybeEncodedShardChunk,
 };
 use near_primitives::hash::{hash, CryptoHash};
-use near_primitives::merkle::merklize;
-use near_primitives::sharding::{ChunkHash, ShardChunk, ShardChunkHeader};
+use near_primitives::merkle::{merklize, verify_path};
+use near_primitives::sharding::{ChunkHash, ReceiptProof, ShardChunk, ShardChunkHeader};
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, ChunkExtra, EpochId, Nonce};
 use near_store::PartialStorage;
 
 use crate::byzantine_assert;
-use crate::types::ApplyTransactionResult;
+use crate::chain::{collect_receipts, shuffle_receipt_proofs};
+use crate::types::{ApplyTransactionResult, ReceiptList};
 use crate::{ChainStore, Error, ErrorKind, RuntimeAdapter};
 
 /// Gas limit cannot be adjusted for more than 0.1% at a time.
@@ -269,8 +269,10 @@ fn validate_chunk_state_challenge(
     runtime_adapter: &dyn RuntimeAdapter,
     chunk_state: &ChunkState,
 ) -> Result<(CryptoHash, Vec<AccountId>), Error> {
+    let prev_prev_block_header = BlockHeader::try_from_slice(&chunk_state.prev_prev_block_header)?;
     let prev_block_header = BlockHeader::try_from_slice(&chunk_state.prev_block_header)?;
     let block_header = BlockHeader::try_from_slice(&chunk_state.block_header)?;
+    let shard_id = chunk_state.chunk_header.inner.shard_id;
 
     // Validate previous chunk and block header.
     validate_header_authorship(runtime_adapter, &prev_block_header)?;
@@ -279,9 +281,60 @@ fn validate_chunk_state_challenge(
         &chunk_state.prev_chunk.header,
         &prev_block_header.chunk_headers_root(),
         &chunk_state.prev_merkle_proof,
-    ) {
+    ) || chunk_state.prev_chunk.header.inner.shard_id != shard_id
+        || chunk_state.prev_chunk.header.height_included != prev_block_header.height()
+        || !validate_chunk_proofs(&chunk_state.prev_chunk, runtime_adapter)
+    {
+        return Err(ErrorKind::MaliciousChallenge.into());
+    }
+
+    // Validate that the chunk before previous chunk was included in the block before, so that
+    // previous chunk only received receipts from the chunks of the previous block.
+    if prev_prev_block_header.hash() != prev_block_header.prev_hash()
+        || !Block::validate_chunk_header_proof(
+            &chunk_state.prev_prev_chunk_header,
+            &prev_prev_block_header.chunk_headers_root(),
+            &chunk_state.prev_prev_merkle_proof,
+        )
+        || chunk_state.prev_prev_chunk_header.inner.shard_id != shard_id
+        || chunk_state.prev_prev_chunk_header.height_included != prev_prev_block_header.height()
+    {
+        return Err(ErrorKind::MaliciousChallenge.into());
+    }
+
+    // Validate incoming receipts of previous chunk against the chunks of the previous block.
+    if &Block::compute_chunk_headers_root(&chunk_state.prev_block_chunks).0
+        != prev_block_header.chunk_headers_root()
+    {
         return Err(ErrorKind::MaliciousChallenge.into());
     }
+    let expected_from_shards = chunk_state
+        .prev_block_chunks
+        .iter()
+        .filter(|chunk| chunk.height_included == prev_block_header.height())
+        .map(|chunk| chunk.inner.shard_id)
+        .collect::<Vec<_>>();
+    if chunk_state.incoming_receipts.len() != expected_from_shards.len() {
+        return Err(ErrorKind::MaliciousChallenge.into());
+    }
+    for (ReceiptProof(receipts, shard_proof), from_shard_id) in
+        chunk_state.incoming_receipts.iter().zip(expected_from_shards)
+    {
+        let receipts_hash = hash(&ReceiptList(shard_id, receipts.clone()).try_to_vec()?);
+        if shard_proof.from_shard_id != from_shard_id
+            || shard_proof.to_shard_id != shard_id
+            || !verify_path(
+                chunk_state.prev_block_chunks[from_shard_id as usize].inner.outgoing_receipts_root,
+                &shard_proof.proof,
+                &receipts_hash,
+            )
+        {
+            return Err(ErrorKind::MaliciousChallenge.into());
+        }
+    }
+    let mut receipt_proofs = chunk_state.incoming_receipts.clone();
+    shuffle_receipt_proofs(&mut receipt_proofs, prev_block_header.hash());
+    let receipts = collect_receipts(&receipt_proofs);
 
     // Validate current chunk and block header.
     validate_header_authorship(runtime_adapter, &block_header)?;
@@ -290,28 +343,32 @@ fn validate_chunk_state_challenge(
         &chunk_state.chunk_header,
         &block_header.chunk_headers_root(),
         &chunk_state.merkle_proof,
-    ) {
+    ) || block_header.prev_hash() != prev_block_header.hash()
+        || &chunk_state.chunk_header.inner.prev_block_hash != prev_block_header.hash()
+        || chunk_state.chunk_header.height_included != block_header.height()
+    {
         return Err(ErrorKind::MaliciousChallenge.into());
     }
 
-    // Apply state transition and check that the result state and other data doesn't match.
+    // Apply previous chunk the same way it was applied in the previous block and check that the
+    // result state and other data doesn't match.
     let partial_storage = PartialStorage { nodes: chunk_state.partial_state.clone() };
     let result = runtime_adapter
         .check_state_transition(
             partial_storage,
-            chunk_state.prev_chunk.header.inner.shard_id,
+            shard_id,
             &chunk_state.prev_chunk.header.inner.prev_state_root,
-            block_header.height(),
-            block_header.raw_timestamp(),
-            &block_header.prev_hash(),
-            &block_header.hash(),
-            &chunk_state.prev_chunk.receipts,
+            prev_block_header.height(),
+            prev_block_header.raw_timestamp(),
+            &prev_block_header.prev_hash(),
+            &prev_block_header.hash(),
+            &receipts,
             &chunk_state.prev_chunk.transactions,
-            &[],
-            prev_block_header.gas_price(),
+            &chunk_state.prev_chunk.header.inner.validator_proposals,
+            prev_prev_block_header.gas_price(),
             chunk_state.prev_chunk.header.inner.gas_limit,
-            &ChallengesResult::default(),
-            *block_header.random_value(),
+            &prev_block_header.challenges_result(),
+            *prev_block_header.random_value(),
         )
         .map_err(|_| Error::from(ErrorKind::MaliciousChallenge))?;
     let outcome_root = ApplyTransactionResult::compute_outcomes_proof(&result.outcomes).0;
@@ -319,6 +376,7 @@ fn validate_chunk_state_challenge(
         || outcome_root != chunk_state.chunk_header.inner.outcome_root
         || result.validator_proposals != chunk_state.chunk_header.inner.validator_proposals
         || result.total_gas_burnt != chunk_state.chunk_header.inner.gas_used
+        || result.total_balance_burnt != chunk_state.chunk_header.inner.balance_burnt
     {
         Ok((*block_header.hash(), vec![chunk_producer]))
     } else {
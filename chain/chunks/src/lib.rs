@@ -17,6 +17,7 @@ use near_network::types::{
     NetworkAdapter, PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg,
 };
 use near_network::NetworkRequests;
+use near_pool::types::TransactionPoolConfig;
 use near_pool::{PoolIteratorWrapper, TransactionPool};
 use near_primitives::block::BlockHeader;
 use near_primitives::hash::CryptoHash;
@@ -364,6 +365,7 @@ pub struct ShardsManager {
     me: Option<AccountId>,
 
     tx_pools: HashMap<ShardId, TransactionPool>,
+    tx_pool_config: TransactionPoolConfig,
 
     runtime_adapter: Arc<dyn RuntimeAdapter>,
     network_adapter: Arc<dyn NetworkAdapter>,
@@ -380,10 +382,12 @@ impl ShardsManager {
         me: Option<AccountId>,
         runtime_adapter: Arc<dyn RuntimeAdapter>,
         network_adapter: Arc<dyn NetworkAdapter>,
+        tx_pool_config: TransactionPoolConfig,
     ) -> Self {
         Self {
             me: me.clone(),
             tx_pools: HashMap::new(),
+            tx_pool_config,
             runtime_adapter: runtime_adapter.clone(),
             network_adapter,
             encoded_chunks: EncodedChunksCache::new(),
@@ -700,9 +704,18 @@ impl ShardsManager {
         self.encoded_chunks.get_chunk_headers_for_block(&prev_block_hash)
     }
 
-    /// Returns true if transaction is not in the pool before call
+    /// Transaction pools of the shards that received transactions.
+    pub fn tx_pools(&self) -> &HashMap<ShardId, TransactionPool> {
+        &self.tx_pools
+    }
+
+    /// Returns true if transaction was added to the pool
     pub fn insert_transaction(&mut self, shard_id: ShardId, tx: SignedTransaction) -> bool {
-        self.tx_pools.entry(shard_id).or_insert_with(TransactionPool::new).insert_transaction(tx)
+        let tx_pool_config = self.tx_pool_config;
+        self.tx_pools
+            .entry(shard_id)
+            .or_insert_with(|| TransactionPool::new(tx_pool_config))
+            .insert_transaction(tx)
     }
 
     pub fn remove_transactions(
@@ -720,9 +733,10 @@ impl ShardsManager {
         shard_id: ShardId,
         transactions: &Vec<SignedTransaction>,
     ) {
+        let tx_pool_config = self.tx_pool_config;
         self.tx_pools
             .entry(shard_id)
-            .or_insert_with(TransactionPool::new)
+            .or_insert_with(|| TransactionPool::new(tx_pool_config))
             .reintroduce_transactions(transactions.clone());
     }
 
@@ -1444,6 +1458,7 @@ mod test {
     };
     use near_chain::test_utils::KeyValueRuntime;
     use near_network::test_utils::MockNetworkAdapter;
+    use near_pool::types::TransactionPoolConfig;
     use near_primitives::hash::hash;
     use near_primitives::sharding::ChunkHash;
     use near_store::test_utils::create_test_store;
@@ -1464,8 +1479,12 @@ mod test {
     fn test_request_partial_encoded_chunk_from_self() {
         let runtime_adapter = Arc::new(KeyValueRuntime::new(create_test_store()));
         let network_adapter = Arc::new(MockNetworkAdapter::default());
-        let mut shards_manager =
-            ShardsManager::new(Some("test".to_string()), runtime_adapter, network_adapter.clone());
+        let mut shards_manager = ShardsManager::new(
+            Some("test".to_string()),
+            runtime_adapter,
+            network_adapter.clone(),
+            TransactionPoolConfig::default(),
+        );
         shards_manager.requested_partial_encoded_chunks.insert(
             ChunkHash(hash(&[1])),
             ChunkRequestInfo {
@@ -1503,6 +1522,7 @@ mod test {
             Some("test".to_string()),
             runtime_adapter.clone(),
             network_adapter.clone(),
+            TransactionPoolConfig::default(),
         );
         let signer = InMemoryValidatorSigner::from_seed("test", KeyType::ED25519, "test");
         let mut rs = ReedSolomonWrapper::new(4, 10);
@@ -7,6 +7,7 @@ use near_chain::types::RuntimeAdapter;
 use near_chain::ChainStore;
 use near_crypto::KeyType;
 use near_network::test_utils::MockNetworkAdapter;
+use near_pool::types::TransactionPoolConfig;
 use near_primitives::block::BlockHeader;
 use near_primitives::hash::{self, CryptoHash};
 use near_primitives::merkle;
@@ -204,6 +205,7 @@ impl Default for ChunkForwardingTestFixture {
             Some(mock_chunk_producer.clone()),
             mock_runtime.clone(),
             mock_network.clone(),
+            TransactionPoolConfig::default(),
         );
         let receipts = Vec::new();
         let receipts_hashes = mock_runtime.build_receipts_hashes(&receipts);
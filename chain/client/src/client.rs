@@ -13,6 +13,7 @@ use log::{debug, error, info, warn};
 use near_chain::chain::TX_ROUTING_HEIGHT_HORIZON;
 use near_chain::test_utils::format_hash;
 use near_chain::types::{AcceptedBlock, LatestKnown};
+use near_chain::validate::validate_challenge;
 use near_chain::{
     BlockStatus, Chain, ChainGenesis, ChainStoreAccess, Doomslug, DoomslugThresholdMode, ErrorKind,
     Provenance, RuntimeAdapter,
@@ -21,6 +22,7 @@ use near_chain_configs::ClientConfig;
 use near_chunks::{ProcessPartialEncodedChunkResult, ShardsManager};
 use near_network::types::PartialEncodedChunkResponseMsg;
 use near_network::{FullPeerInfo, NetworkAdapter, NetworkClientResponses, NetworkRequests};
+use near_pool::types::TransactionPoolConfig;
 use near_primitives::block::{Approval, ApprovalInner, ApprovalMessage, Block, BlockHeader, Tip};
 use near_primitives::challenge::{Challenge, ChallengeBody};
 use near_primitives::hash::CryptoHash;
@@ -31,10 +33,13 @@ use near_primitives::sharding::{
 };
 use near_primitives::syncing::ReceiptResponse;
 use near_primitives::transaction::SignedTransaction;
-use near_primitives::types::{AccountId, ApprovalStake, BlockHeight, ChunkExtra, EpochId, ShardId};
+use near_primitives::types::{
+    AccountId, ApprovalStake, BlockHeight, BlockHeightDelta, ChunkExtra, EpochId, ShardId,
+};
 use near_primitives::unwrap_or_return;
 use near_primitives::utils::to_timestamp;
 use near_primitives::validator_signer::ValidatorSigner;
+use near_primitives::version::{ProtocolVersion, CHALLENGES_PROTOCOL_VERSION};
 
 use crate::metrics;
 use crate::sync::{BlockSync, HeaderSync, StateSync, StateSyncResult};
@@ -96,11 +101,17 @@ impl Client {
         } else {
             DoomslugThresholdMode::NoApprovals
         };
-        let chain = Chain::new(runtime_adapter.clone(), &chain_genesis, doomslug_threshold_mode)?;
+        let mut chain =
+            Chain::new(runtime_adapter.clone(), &chain_genesis, doomslug_threshold_mode)?;
+        chain.mut_store().set_save_logs_index(config.enable_logs_index);
         let shards_mgr = ShardsManager::new(
             validator_signer.as_ref().map(|x| x.validator_id().clone()),
             runtime_adapter.clone(),
             network_adapter.clone(),
+            TransactionPoolConfig {
+                max_transactions: config.transaction_pool_size_limit,
+                max_transactions_per_signer: config.transaction_pool_signer_limit,
+            },
         );
         let sync_status = SyncStatus::AwaitingPeers;
         let header_sync = HeaderSync::new(
@@ -408,9 +419,26 @@ impl Client {
                 None
             };
 
-        // Get all the current challenges.
-        // TODO(2445): Enable challenges when they are working correctly.
-        // let challenges = self.challenges.drain().map(|(_, challenge)| challenge).collect();
+        // Get all the current challenges that are still valid on top of the previous block.
+        // Malicious challenges are included as well to slash their senders. Challenges are
+        // removed once the block that includes them is accepted.
+        let challenges = if self.runtime_adapter.get_epoch_protocol_version(&epoch_id)?
+            >= CHALLENGES_PROTOCOL_VERSION
+        {
+            let runtime_adapter = &*self.runtime_adapter;
+            self.challenges
+                .values()
+                .filter(|challenge| {
+                    match validate_challenge(runtime_adapter, &epoch_id, &prev_hash, challenge) {
+                        Ok(_) => true,
+                        Err(err) => err.kind() == ErrorKind::MaliciousChallenge,
+                    }
+                })
+                .cloned()
+                .collect()
+        } else {
+            vec![]
+        };
         let protocol_version = self.runtime_adapter.get_epoch_protocol_version(&next_epoch_id)?;
 
         let block = Block::produce(
@@ -426,7 +454,7 @@ impl Client {
             max_gas_price,
             minted_amount,
             prev_block_extra.challenges_result,
-            vec![],
+            challenges,
             &*validator_signer,
             next_bp_hash,
             block_merkle_root,
@@ -489,7 +517,9 @@ impl Client {
             .clone();
 
         let prev_block_header = self.chain.get_block_header(&prev_block_hash)?.clone();
-        let transactions = self.prepare_transactions(shard_id, &chunk_extra, &prev_block_header);
+        let protocol_version = self.runtime_adapter.get_epoch_protocol_version(epoch_id)?;
+        let transactions =
+            self.prepare_transactions(shard_id, &chunk_extra, &prev_block_header, protocol_version);
         let num_filtered_transactions = transactions.len();
         let (tx_root, _) = merklize(&transactions);
         let ReceiptResponse(_, outgoing_receipts) = self.chain.get_outgoing_receipts_for_shard(
@@ -553,6 +583,7 @@ impl Client {
         shard_id: ShardId,
         chunk_extra: &ChunkExtra,
         prev_block_header: &BlockHeader,
+        protocol_version: ProtocolVersion,
     ) -> Vec<SignedTransaction> {
         let Self { chain, shards_mgr, runtime_adapter, .. } = self;
         let transactions = if let Some(mut iter) = shards_mgr.get_pool_iterator(shard_id) {
@@ -563,6 +594,7 @@ impl Client {
                     chunk_extra.gas_limit,
                     shard_id,
                     chunk_extra.state_root.clone(),
+                    protocol_version,
                     &mut iter,
                     &mut |tx: &SignedTransaction| -> bool {
                         chain
@@ -634,35 +666,15 @@ impl Client {
             )
         };
 
-        // Send out challenges that accumulated via on_challenge.
-        self.send_challenges(challenges);
-
-        // Send out challenge if the block was found to be invalid.
-        if let Some(validator_signer) = self.validator_signer.as_ref() {
-            match &result {
-                Err(e) => match e.kind() {
-                    near_chain::ErrorKind::InvalidChunkProofs(chunk_proofs) => {
-                        self.network_adapter.do_send(NetworkRequests::Challenge(
-                            Challenge::produce(
-                                ChallengeBody::ChunkProofs(*chunk_proofs),
-                                &**validator_signer,
-                            ),
-                        ));
-                    }
-                    near_chain::ErrorKind::InvalidChunkState(chunk_state) => {
-                        self.network_adapter.do_send(NetworkRequests::Challenge(
-                            Challenge::produce(
-                                ChallengeBody::ChunkState(*chunk_state),
-                                &**validator_signer,
-                            ),
-                        ));
-                    }
-                    _ => {}
-                },
-                _ => {}
-            }
+        // Challenge the block if it was found to be invalid.
+        if let Some(challenge_body) = result.as_ref().err().and_then(|e| e.challenge_body()) {
+            challenges.write().unwrap().push(challenge_body);
         }
 
+        // Send out challenges that accumulated via on_challenge, they are included into the next
+        // block we produce.
+        self.send_challenges(challenges);
+
         if let Ok(Some(_)) = result {
             self.last_time_head_progress_made = Instant::now();
         }
@@ -817,6 +829,7 @@ impl Client {
 
         if status.is_new_head() {
             self.shards_mgr.update_largest_seen_height(block.header().height());
+            self.prune_challenges(block.header().height());
             if !self.config.archive {
                 let timer = near_metrics::start_timer(&metrics::GC_TIME);
                 if let Err(err) = self
@@ -1220,10 +1233,13 @@ impl Client {
         }
         let gas_price = cur_block_header.gas_price();
         let epoch_id = self.runtime_adapter.get_epoch_id_from_prev_block(&head.last_block_hash)?;
+        let protocol_version = self.runtime_adapter.get_epoch_protocol_version(&epoch_id)?;
 
         // Fast transaction validation without a state root.
-        if let Some(err) =
-            self.runtime_adapter.validate_tx(gas_price, None, &tx).expect("no storage errors")
+        if let Some(err) = self
+            .runtime_adapter
+            .validate_tx(gas_price, None, &tx, protocol_version)
+            .expect("no storage errors")
         {
             debug!(target: "client", "Invalid tx during basic validation: {:?}", err);
             return Ok(NetworkClientResponses::InvalidTx(err));
@@ -1249,7 +1265,7 @@ impl Client {
             };
             if let Some(err) = self
                 .runtime_adapter
-                .validate_tx(gas_price, Some(state_root), &tx)
+                .validate_tx(gas_price, Some(state_root), &tx, protocol_version)
                 .expect("no storage errors")
             {
                 debug!(target: "client", "Invalid tx: {:?}", err);
@@ -1392,32 +1408,49 @@ impl Client {
         Ok(vec![])
     }
 
-    /// When accepting challenge, we verify that it's valid given signature with current validators.
-    pub fn process_challenge(&mut self, _challenge: Challenge) -> Result<(), Error> {
-        // TODO(2445): Enable challenges when they are working correctly.
-        //        if self.challenges.contains_key(&challenge.hash) {
-        //            return Ok(());
-        //        }
-        //        debug!(target: "client", "Received challenge: {:?}", challenge);
-        //        let head = self.chain.head()?;
-        //        if self.runtime_adapter.verify_validator_or_fisherman_signature(
-        //            &head.epoch_id,
-        //            &head.prev_block_hash,
-        //            &challenge.account_id,
-        //            challenge.hash.as_ref(),
-        //            &challenge.signature,
-        //        )? {
-        //            // If challenge is not double sign, we should process it right away to invalidate the chain.
-        //            match challenge.body {
-        //                ChallengeBody::BlockDoubleSign(_) => {}
-        //                _ => {
-        //                    self.chain.process_challenge(&challenge);
-        //                }
-        //            }
-        //            self.challenges.insert(challenge.hash, challenge);
-        //        }
+    /// When accepting challenge, we verify that it's valid given current validators and head.
+    /// Valid challenges are gossiped further and included into the next block we produce.
+    pub fn process_challenge(&mut self, challenge: Challenge) -> Result<(), Error> {
+        if self.challenges.contains_key(&challenge.hash) {
+            return Ok(());
+        }
+        let head = self.chain.head()?;
+        if self.runtime_adapter.get_epoch_protocol_version(&head.epoch_id)?
+            < CHALLENGES_PROTOCOL_VERSION
+        {
+            debug!(target: "client", "Ignoring challenge {} before challenges are enabled", challenge.hash);
+            return Ok(());
+        }
+        if is_challenge_too_old(&challenge, head.height, self.config.epoch_length) {
+            debug!(target: "client", "Ignoring challenge {} of an old block", challenge.hash);
+            return Ok(());
+        }
+        debug!(target: "client", "Received challenge: {:?}", challenge);
+        // If challenge is not double sign, this invalidates the chain right away.
+        self.chain.process_challenge(&challenge)?;
+        self.challenges.insert(challenge.hash, challenge.clone());
+        self.network_adapter.do_send(NetworkRequests::Challenge(challenge));
         Ok(())
     }
+
+    /// Drop the accumulated challenges of blocks that are too old to be included anymore.
+    fn prune_challenges(&mut self, head_height: BlockHeight) {
+        let epoch_length = self.config.epoch_length;
+        self.challenges
+            .retain(|_, challenge| !is_challenge_too_old(challenge, head_height, epoch_length));
+    }
+}
+
+/// Challenges are only accepted and kept for blocks within an epoch length from the head.
+fn is_challenge_too_old(
+    challenge: &Challenge,
+    head_height: BlockHeight,
+    epoch_length: BlockHeightDelta,
+) -> bool {
+    match challenge.body.block_height() {
+        Some(height) => height + epoch_length < head_height,
+        None => true,
+    }
 }
 
 #[cfg(test)]
@@ -37,7 +37,7 @@ use near_primitives::unwrap_or_return;
 use near_primitives::utils::from_timestamp;
 use near_primitives::validator_signer::ValidatorSigner;
 use near_primitives::version::PROTOCOL_VERSION;
-use near_primitives::views::ValidatorInfo;
+use near_primitives::views::{PendingTransactionView, ValidatorInfo};
 #[cfg(feature = "adversarial")]
 use near_store::ColBlock;
 use near_telemetry::TelemetryActor;
@@ -46,8 +46,8 @@ use crate::client::Client;
 use crate::info::InfoHelper;
 use crate::sync::{highest_height_peer, StateSync, StateSyncResult};
 use crate::types::{
-    Error, GetNetworkInfo, NetworkInfoResponse, ShardSyncDownload, ShardSyncStatus, Status,
-    StatusSyncInfo, SyncStatus,
+    Error, GetNetworkInfo, GetPendingTransactions, NetworkInfoResponse, ShardSyncDownload,
+    ShardSyncStatus, Status, StatusSyncInfo, SyncStatus,
 };
 use crate::StatusResponse;
 #[cfg(feature = "delay_detector")]
@@ -153,6 +153,7 @@ impl ClientActor {
                 received_bytes_per_sec: 0,
                 sent_bytes_per_sec: 0,
                 known_producers: vec![],
+                banned_ips: vec![],
                 #[cfg(feature = "metric_recorder")]
                 metric_recorder: MetricRecorder::default(),
             },
@@ -597,12 +598,56 @@ impl Handler<GetNetworkInfo> for ClientActor {
             sent_bytes_per_sec: self.network_info.sent_bytes_per_sec,
             received_bytes_per_sec: self.network_info.received_bytes_per_sec,
             known_producers: self.network_info.known_producers.clone(),
+            banned_ips: self.network_info.banned_ips.clone(),
             #[cfg(feature = "metric_recorder")]
             metric_recorder: self.network_info.metric_recorder.clone(),
         })
     }
 }
 
+impl Handler<GetPendingTransactions> for ClientActor {
+    type Result = Result<Vec<PendingTransactionView>, String>;
+
+    fn handle(&mut self, msg: GetPendingTransactions, ctx: &mut Context<Self>) -> Self::Result {
+        #[cfg(feature = "delay_detector")]
+        let _d = DelayDetector::new("client get pending transactions".into());
+        self.check_triggers(ctx);
+
+        // Transactions of an account always go to the pool of the shard of the account.
+        let account_shard_id = msg
+            .account_id
+            .as_ref()
+            .map(|account_id| self.client.runtime_adapter.account_id_to_shard_id(account_id));
+        let mut pending_transactions = vec![];
+        for (shard_id, pool) in self.client.shards_mgr.tx_pools() {
+            if account_shard_id.map_or(false, |account_shard_id| account_shard_id != *shard_id) {
+                continue;
+            }
+            for pending in pool.pending_transactions() {
+                let transaction = &pending.transaction.transaction;
+                if msg
+                    .account_id
+                    .as_ref()
+                    .map_or(false, |account_id| account_id != &transaction.signer_id)
+                {
+                    continue;
+                }
+                pending_transactions.push(PendingTransactionView {
+                    hash: pending.transaction.get_hash(),
+                    signer_id: transaction.signer_id.clone(),
+                    public_key: transaction.public_key.clone(),
+                    nonce: transaction.nonce,
+                    receiver_id: transaction.receiver_id.clone(),
+                    shard_id: *shard_id,
+                    pending_duration_ms: pending.pending_duration.as_millis() as u64,
+                });
+            }
+        }
+        pending_transactions.sort_by(|a, b| (&a.signer_id, a.nonce).cmp(&(&b.signer_id, b.nonce)));
+        Ok(pending_transactions)
+    }
+}
+
 impl ClientActor {
     fn sign_announce_account(&self, epoch_id: &EpochId) -> Result<Signature, ()> {
         if let Some(validator_signer) = self.client.validator_signer.as_ref() {
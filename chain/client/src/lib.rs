@@ -4,10 +4,11 @@ extern crate lazy_static;
 pub use crate::client::Client;
 pub use crate::client_actor::{start_client, ClientActor};
 pub use crate::types::{
-    Error, GetBlock, GetBlockProof, GetBlockProofResponse, GetBlockWithMerkleTree, GetChunk,
-    GetExecutionOutcome, GetExecutionOutcomeResponse, GetGasPrice, GetNetworkInfo,
-    GetNextLightClientBlock, GetStateChanges, GetStateChangesInBlock, GetValidatorInfo, Query,
-    Status, StatusResponse, SyncStatus, TxStatus, TxStatusError,
+    Error, GetAccountChangesInRange, GetBlock, GetBlockProof, GetBlockProofResponse,
+    GetBlockWithMerkleTree, GetChunk, GetExecutionOutcome, GetExecutionOutcomeResponse,
+    GetGasPrice, GetLogs, GetNetworkInfo, GetNextLightClientBlock, GetPendingTransactions,
+    GetStateChanges, GetStateChangesInBlock, GetValidatorInfo, Query, SimulateTransaction, Status,
+    StatusResponse, SyncStatus, TxStatus, TxStatusError,
 };
 pub use crate::view_client::{start_view_client, ViewClientActor};
 
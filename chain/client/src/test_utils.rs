@@ -444,6 +444,7 @@ pub fn setup_mock_all_validators(
                             sent_bytes_per_sec: 0,
                             received_bytes_per_sec: 0,
                             known_producers: vec![],
+                            banned_ips: vec![],
                             #[cfg(feature = "metric_recorder")]
                             metric_recorder: MetricRecorder::default(),
                         };
@@ -765,6 +766,9 @@ pub fn setup_mock_all_validators(
                         | NetworkRequests::PingTo(_, _)
                         | NetworkRequests::FetchPingPongInfo
                         | NetworkRequests::BanPeer { .. }
+                        | NetworkRequests::UnbanPeer { .. }
+                        | NetworkRequests::ConnectPeer(_)
+                        | NetworkRequests::FetchKnownPeers
                         | NetworkRequests::TxStatus(_, _, _)
                         | NetworkRequests::Query { .. }
                         | NetworkRequests::Challenge(_)
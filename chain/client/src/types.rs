@@ -8,12 +8,13 @@ use actix::Message;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use near_network::types::{AccountOrPeerIdOrHash, KnownProducer};
+use near_network::types::{AccountOrPeerIdOrHash, BannedIp, KnownProducer};
 use near_network::PeerInfo;
 use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{MerklePath, PartialMerkleTree};
 use near_primitives::sharding::ChunkHash;
+use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{
     AccountId, BlockHeight, BlockIdOrFinality, MaybeBlockId, ShardId, TransactionOrReceiptId,
 };
@@ -21,7 +22,8 @@ use near_primitives::utils::generate_random_string;
 use near_primitives::views::{
     BlockView, ChunkView, EpochValidatorInfo, ExecutionOutcomeWithIdView,
     FinalExecutionOutcomeView, GasPriceView, LightClientBlockLiteView, LightClientBlockView,
-    QueryRequest, QueryResponse, StateChangesKindsView, StateChangesRequestView, StateChangesView,
+    LogsView, PendingTransactionView, QueryRequest, QueryResponse, StateChangesInRangeView,
+    StateChangesKindsView, StateChangesRequestView, StateChangesView, TransactionSimulationView,
 };
 pub use near_primitives::views::{StatusResponse, StatusSyncInfo};
 
@@ -223,6 +225,15 @@ impl Message for GetNetworkInfo {
     type Result = Result<NetworkInfoResponse, String>;
 }
 
+/// Transactions in the pools of the node, optionally only the ones signed by the given account.
+pub struct GetPendingTransactions {
+    pub account_id: Option<AccountId>,
+}
+
+impl Message for GetPendingTransactions {
+    type Result = Result<Vec<PendingTransactionView>, String>;
+}
+
 pub struct GetGasPrice {
     pub block_id: MaybeBlockId,
 }
@@ -231,6 +242,18 @@ impl Message for GetGasPrice {
     type Result = Result<GasPriceView, String>;
 }
 
+/// Simulates the transaction on top of the state of the head of the chain without persisting
+/// anything. Requires tracking the shard of the signer.
+pub struct SimulateTransaction {
+    pub transaction: SignedTransaction,
+    /// Unsigned transactions are simulated without checking the signatures.
+    pub verify_signature: bool,
+}
+
+impl Message for SimulateTransaction {
+    type Result = Result<Result<TransactionSimulationView, InvalidTxError>, String>;
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct NetworkInfoResponse {
     pub active_peers: Vec<PeerInfo>,
@@ -240,6 +263,8 @@ pub struct NetworkInfoResponse {
     pub received_bytes_per_sec: u64,
     /// Accounts of known block and chunk producers from routing table.
     pub known_producers: Vec<KnownProducer>,
+    /// IP addresses banned together with misbehaving peers.
+    pub banned_ips: Vec<BannedIp>,
     #[cfg(feature = "metric_recorder")]
     pub metric_recorder: MetricRecorder,
 }
@@ -294,6 +319,34 @@ impl Message for GetStateChanges {
     type Result = Result<StateChangesView, String>;
 }
 
+/// Changes of the given accounts in the blocks of the canonical chain within the height range.
+pub struct GetAccountChangesInRange {
+    pub account_ids: Vec<AccountId>,
+    pub from_block_height: BlockHeight,
+    pub to_block_height: BlockHeight,
+    /// Stop after the block that brings the number of changes to at least `limit`.
+    pub limit: usize,
+}
+
+impl Message for GetAccountChangesInRange {
+    type Result = Result<StateChangesInRangeView, String>;
+}
+
+/// Logs of outcomes executed on the account in the blocks of the canonical chain within the height
+/// range. Requires the logs index to be enabled.
+pub struct GetLogs {
+    pub account_id: AccountId,
+    pub from_block_height: BlockHeight,
+    pub to_block_height: BlockHeight,
+    pub prefix: String,
+    /// Stop after the block that brings the number of outcomes to at least `limit`.
+    pub limit: usize,
+}
+
+impl Message for GetLogs {
+    type Result = Result<LogsView, String>;
+}
+
 pub struct GetStateChangesInBlock {
     pub block_hash: CryptoHash,
 }
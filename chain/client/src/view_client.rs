@@ -21,33 +21,38 @@ use near_network::types::{
 };
 use near_network::{NetworkAdapter, NetworkRequests};
 use near_primitives::block::{BlockHeader, GenesisId, Tip};
+use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{merklize, verify_path, PartialMerkleTree};
 use near_primitives::network::AnnounceAccount;
 use near_primitives::syncing::ShardStateSyncResponse;
+use near_primitives::transaction::{ExecutionOutcomeWithId, ExecutionOutcomeWithIdAndProof};
 use near_primitives::types::{
-    AccountId, BlockHeight, BlockId, BlockIdOrFinality, Finality, MaybeBlockId,
-    TransactionOrReceiptId,
+    AccountId, BlockHeight, BlockHeightDelta, BlockId, BlockIdOrFinality, Finality, MaybeBlockId,
+    StateChangesRequest, TransactionOrReceiptId,
 };
 use near_primitives::views::{
     BlockView, ChunkView, EpochValidatorInfo, FinalExecutionOutcomeView, FinalExecutionStatus,
-    GasPriceView, LightClientBlockView, QueryRequest, QueryResponse, StateChangesKindsView,
-    StateChangesView,
+    GasPriceView, LightClientBlockView, LogsView, OutcomeLogsView, QueryRequest, QueryResponse,
+    StateChangesInBlockView, StateChangesInRangeView, StateChangesKindsView, StateChangesView,
+    TransactionSimulationView,
 };
 
 use crate::types::{
     Error, GetBlock, GetBlockProof, GetBlockProofResponse, GetBlockWithMerkleTree,
-    GetExecutionOutcome, GetGasPrice, Query, TxStatus, TxStatusError,
+    GetExecutionOutcome, GetGasPrice, Query, SimulateTransaction, TxStatus, TxStatusError,
 };
 use crate::{
-    sync, GetChunk, GetExecutionOutcomeResponse, GetNextLightClientBlock, GetStateChanges,
-    GetStateChangesInBlock, GetValidatorInfo,
+    sync, GetAccountChangesInRange, GetChunk, GetExecutionOutcomeResponse, GetLogs,
+    GetNextLightClientBlock, GetStateChanges, GetStateChangesInBlock, GetValidatorInfo,
 };
 
 /// Max number of queries that we keep.
 const QUERY_REQUEST_LIMIT: usize = 500;
 /// Waiting time between requests, in ms
 const REQUEST_WAIT_TIME: u64 = 1000;
+/// Max number of blocks scanned by a single account changes request.
+const ACCOUNT_CHANGES_MAX_BLOCKS: BlockHeightDelta = 1000;
 
 const POISONED_LOCK_ERR: &str = "The lock was poisoned.";
 
@@ -532,6 +537,146 @@ impl Handler<GetStateChanges> for ViewClientActor {
     }
 }
 
+impl Handler<GetAccountChangesInRange> for ViewClientActor {
+    type Result = Result<StateChangesInRangeView, String>;
+
+    /// Walks the canonical chain from `from_block_height` and collects the changes of the given
+    /// accounts. At most `ACCOUNT_CHANGES_MAX_BLOCKS` heights are scanned per request, the caller
+    /// continues from `next_block_height`.
+    fn handle(&mut self, msg: GetAccountChangesInRange, _: &mut Self::Context) -> Self::Result {
+        let GetAccountChangesInRange { account_ids, from_block_height, to_block_height, limit } =
+            msg;
+        if from_block_height > to_block_height {
+            return Err(format!(
+                "Invalid block height range: {} is greater than {}",
+                from_block_height, to_block_height
+            ));
+        }
+        let tail = self.chain.store().tail().map_err(|err| err.to_string())?;
+        if from_block_height < tail {
+            return Err(format!(
+                "Block height {} is garbage collected, the earliest available height is {}",
+                from_block_height, tail
+            ));
+        }
+        let head_height = self.chain.head().map_err(|err| err.to_string())?.height;
+        let last_height = std::cmp::min(
+            std::cmp::min(to_block_height, head_height),
+            from_block_height.saturating_add(ACCOUNT_CHANGES_MAX_BLOCKS - 1),
+        );
+        if last_height < from_block_height {
+            // The range starts above the head, there is nothing to return yet.
+            return Ok(StateChangesInRangeView {
+                blocks: vec![],
+                next_block_height: Some(from_block_height),
+            });
+        }
+
+        let state_changes_request = StateChangesRequest::AccountChanges { account_ids };
+        let mut blocks = vec![];
+        let mut num_changes = 0;
+        for block_height in from_block_height..=last_height {
+            if num_changes >= limit {
+                return Ok(StateChangesInRangeView {
+                    blocks,
+                    next_block_height: Some(block_height),
+                });
+            }
+            let block_hash = match self.chain.mut_store().get_block_hash_by_height(block_height) {
+                Ok(block_hash) => block_hash,
+                Err(err) => match err.kind() {
+                    // There is no block at this height on the canonical chain.
+                    ErrorKind::DBNotFoundErr(_) => continue,
+                    _ => return Err(err.to_string()),
+                },
+            };
+            let changes = self
+                .chain
+                .store()
+                .get_state_changes(&block_hash, &state_changes_request)
+                .map_err(|err| err.to_string())?;
+            if changes.is_empty() {
+                continue;
+            }
+            num_changes += changes.len();
+            blocks.push(StateChangesInBlockView {
+                block_hash,
+                block_height,
+                changes: changes.into_iter().map(Into::into).collect(),
+            });
+        }
+        let next_block_height =
+            if last_height < to_block_height { Some(last_height + 1) } else { None };
+        Ok(StateChangesInRangeView { blocks, next_block_height })
+    }
+}
+
+impl Handler<GetLogs> for ViewClientActor {
+    type Result = Result<LogsView, String>;
+
+    /// Looks the outcomes up in the logs index and skips the ones from blocks that are not on the
+    /// canonical chain.
+    fn handle(&mut self, msg: GetLogs, _: &mut Self::Context) -> Self::Result {
+        if !self.config.enable_logs_index {
+            return Err("Logs index is disabled on this node".to_string());
+        }
+        let GetLogs { account_id, from_block_height, to_block_height, prefix, limit } = msg;
+        if from_block_height > to_block_height {
+            return Err(format!(
+                "Invalid block height range: {} is greater than {}",
+                from_block_height, to_block_height
+            ));
+        }
+        let (outcome_ids, next_block_height) = self
+            .chain
+            .store()
+            .get_outcome_ids_by_log_prefix(
+                &account_id,
+                &prefix,
+                from_block_height,
+                to_block_height,
+                limit,
+            )
+            .map_err(|err| err.to_string())?;
+
+        let mut outcomes = vec![];
+        for (block_height, outcome_id) in outcome_ids {
+            let outcome = match self.chain.get_execution_outcome(&outcome_id) {
+                Ok(outcome) => outcome.clone(),
+                Err(err) => match err.kind() {
+                    // The outcome was garbage collected together with another fork.
+                    ErrorKind::DBNotFoundErr(_) => continue,
+                    _ => return Err(err.to_string()),
+                },
+            };
+            match self.chain.mut_store().get_block_hash_by_height(block_height) {
+                Ok(block_hash) if block_hash == outcome.block_hash => {}
+                Ok(_) => continue,
+                Err(err) => match err.kind() {
+                    ErrorKind::DBNotFoundErr(_) => continue,
+                    _ => return Err(err.to_string()),
+                },
+            }
+            let ExecutionOutcomeWithIdAndProof { outcome_with_id, block_hash, .. } = outcome;
+            let ExecutionOutcomeWithId { id, outcome } = outcome_with_id;
+            let logs: Vec<_> =
+                outcome.logs.into_iter().filter(|log| log.starts_with(&prefix)).collect();
+            if logs.is_empty() {
+                continue;
+            }
+            outcomes.push(OutcomeLogsView {
+                block_hash,
+                block_height,
+                id,
+                executor_id: outcome.executor_id,
+                logs,
+            });
+        }
+        outcomes.sort_by(|a, b| (a.block_height, a.id).cmp(&(b.block_height, b.id)));
+        Ok(LogsView { outcomes, next_block_height })
+    }
+}
+
 /// Returns the next light client block, given the hash of the last block known to the light client.
 /// There are three cases:
 ///  1. The last block known to the light client is in the same epoch as the tip:
@@ -904,14 +1049,14 @@ impl Handler<NetworkViewClientMessages> for ViewClientActor {
                 let mut filtered_announce_accounts = Vec::new();
 
                 for (announce_account, last_epoch) in announce_accounts {
-                    // Keep the announcement if it is newer than the last announcement from
-                    // the same account.
+                    // Keep the announcement if it is not older than the last announcement from
+                    // the same account. Several peers can announce the same account in one epoch.
                     if let Some(last_epoch) = last_epoch {
                         match self
                             .runtime_adapter
                             .compare_epoch_id(&announce_account.epoch_id, &last_epoch)
                         {
-                            Ok(Ordering::Greater) => {}
+                            Ok(Ordering::Greater) | Ok(Ordering::Equal) => {}
                             _ => continue,
                         }
                     }
@@ -949,6 +1094,42 @@ impl Handler<GetGasPrice> for ViewClientActor {
     }
 }
 
+impl Handler<SimulateTransaction> for ViewClientActor {
+    type Result = Result<Result<TransactionSimulationView, InvalidTxError>, String>;
+
+    fn handle(&mut self, msg: SimulateTransaction, _ctx: &mut Self::Context) -> Self::Result {
+        let head = self.chain.head().map_err(|e| e.to_string())?;
+        let header =
+            self.chain.get_block_header(&head.last_block_hash).map_err(|e| e.to_string())?.clone();
+        let shard_id =
+            self.runtime_adapter.account_id_to_shard_id(&msg.transaction.transaction.signer_id);
+        let state_root = match self.chain.get_chunk_extra(header.hash(), shard_id) {
+            Ok(chunk_extra) => chunk_extra.state_root,
+            Err(e) => {
+                return match e.kind() {
+                    ErrorKind::DBNotFoundErr(_) => {
+                        Err("Node doesn't track the shard of the signer".to_string())
+                    }
+                    _ => Err(e.to_string()),
+                };
+            }
+        };
+        self.runtime_adapter
+            .simulate_transaction(
+                shard_id,
+                &state_root,
+                header.height() + 1,
+                header.raw_timestamp(),
+                header.hash(),
+                header.gas_price(),
+                *header.random_value(),
+                &msg.transaction,
+                msg.verify_signature,
+            )
+            .map_err(|e| e.to_string())
+    }
+}
+
 /// Starts the View Client in a new arbiter (thread).
 pub fn start_view_client(
     validator_account_id: Option<AccountId>,
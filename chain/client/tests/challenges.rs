@@ -19,6 +19,7 @@ use near_network::test_utils::MockNetworkAdapter;
 use near_network::NetworkRequests;
 use near_primitives::challenge::{
     BlockDoubleSign, Challenge, ChallengeBody, ChunkProofs, MaybeEncodedShardChunk,
+    SlashedValidator,
 };
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{merklize, MerklePath, PartialMerkleTree};
@@ -390,6 +391,7 @@ fn test_verify_chunk_invalid_state_challenge() {
         chain_update
             .create_chunk_state_challenge(&last_block, &block, &block.chunks()[0].clone())
             .unwrap()
+            .unwrap()
     };
     {
         let prev_merkle_proofs = Block::compute_chunk_headers_root(&last_block.chunks()).1;
@@ -442,7 +444,7 @@ fn test_verify_chunk_invalid_state_challenge() {
 }
 
 /// Receive invalid state transition in chunk as next chunk producer.
-/// TODO(2445): Enable challenges when they are working correctly.
+/// TODO(2445): Challenge chunks with invalid proofs that the shards manager refuses to store.
 #[test]
 #[ignore]
 fn test_receive_invalid_chunk_as_chunk_producer() {
@@ -518,9 +520,7 @@ fn test_receive_two_chunks_from_one_producer() {}
 fn test_receive_two_blocks_from_one_producer() {}
 
 /// Receive challenges in the blocks.
-/// TODO(2445): Enable challenges when they are working correctly.
 #[test]
-#[ignore]
 fn test_block_challenge() {
     init_test_logger();
     let mut env = TestEnv::new(ChainGenesis::test(), 1, 1);
@@ -542,11 +542,39 @@ fn test_block_challenge() {
     assert!(env.clients[0].chain.mut_store().is_block_challenged(&block.hash()).unwrap());
 }
 
+/// Challenges of blocks more than an epoch length behind the head are ignored.
+#[test]
+fn test_old_block_challenge_ignored() {
+    init_test_logger();
+    let mut env = TestEnv::new(ChainGenesis::test(), 1, 1);
+    env.produce_block(0, 1);
+    let (chunk, _merkle_paths, _receipts, block) = create_invalid_proofs_chunk(&mut env.clients[0]);
+    for i in 2..=8 {
+        env.produce_block(0, i);
+    }
+
+    let merkle_paths = Block::compute_chunk_headers_root(&block.chunks()).1;
+    let challenge = Challenge::produce(
+        ChallengeBody::ChunkProofs(ChunkProofs {
+            block_header: block.header().try_to_vec().unwrap(),
+            chunk: MaybeEncodedShardChunk::Encoded(chunk.clone()),
+            merkle_proof: merkle_paths[chunk.header.inner.shard_id as usize].clone(),
+        }),
+        &*env.clients[0].validator_signer.as_ref().unwrap().clone(),
+    );
+    env.clients[0].process_challenge(challenge).unwrap();
+    assert!(env.clients[0].challenges.is_empty());
+    assert!(!env.clients[0].chain.mut_store().is_block_challenged(&block.hash()).unwrap());
+    while let Some(request) = env.network_adapters[0].pop() {
+        if let NetworkRequests::Challenge(_) = request {
+            panic!("Unexpected challenge");
+        }
+    }
+}
+
 /// Make sure that fisherman can initiate challenges while an account that is neither a fisherman nor
 /// a validator cannot.
-// TODO(2445): Enable challenges when they are working correctly.
 #[test]
-#[ignore]
 fn test_fishermen_challenge() {
     init_test_logger();
     let mut genesis = Genesis::test(vec!["test0", "test1", "test2"], 1);
@@ -604,6 +632,253 @@ fn test_fishermen_challenge() {
     assert!(env.clients[0].chain.mut_store().is_block_challenged(&block.hash()).unwrap());
 }
 
+/// Processes the block on client `to` as if it received the block first and fetched the chunks
+/// included in it from client `from` after.
+fn process_block_with_chunks(env: &mut TestEnv, from: usize, to: usize, block: Block) {
+    let (mut accepted_blocks, result) =
+        env.clients[to].process_block(block.clone(), Provenance::NONE);
+    if let Err(e) = result {
+        match e.kind() {
+            ErrorKind::ChunksMissing(_) => {}
+            _ => panic!("unexpected error: {}", e),
+        }
+        for chunk_header in block.chunks().iter() {
+            if chunk_header.height_included != block.header().height() {
+                continue;
+            }
+            let partial_chunk = env.clients[from]
+                .chain
+                .mut_store()
+                .get_partial_chunk(&chunk_header.chunk_hash())
+                .unwrap()
+                .clone();
+            accepted_blocks
+                .extend(env.clients[to].process_partial_encoded_chunk(partial_chunk).unwrap());
+        }
+    }
+    for accepted_block in accepted_blocks {
+        env.clients[to].on_block_accepted(
+            accepted_block.hash,
+            accepted_block.status,
+            accepted_block.provenance,
+        );
+    }
+}
+
+/// Fisherman tracks the shard without a validator seat and re-executes the chunks of the blocks it
+/// receives. It challenges the chunk whose state doesn't match, once the chunk arrives after the
+/// block, and the validator accepts the challenge and includes it in its next block.
+#[test]
+fn test_fisherman_challenges_invalid_chunk_state() {
+    init_test_logger();
+    let mut genesis = Genesis::test(vec!["test0", "test1"], 1);
+    genesis.config.epoch_length = 5;
+    let genesis = Arc::new(genesis);
+    let runtimes: Vec<Arc<dyn RuntimeAdapter>> = vec![
+        Arc::new(neard::NightshadeRuntime::new(
+            Path::new("."),
+            create_test_store(),
+            Arc::clone(&genesis),
+            vec![],
+            vec![],
+        )),
+        Arc::new(neard::NightshadeRuntime::new(
+            Path::new("."),
+            create_test_store(),
+            Arc::clone(&genesis),
+            vec![],
+            vec![0],
+        )),
+    ];
+    let mut env = TestEnv::new_with_runtime(ChainGenesis::test(), 2, 1, runtimes);
+    let signer = InMemorySigner::from_seed("test1", KeyType::ED25519, "test1");
+    let genesis_hash = *env.clients[0].chain.genesis().hash();
+    let stake_transaction = SignedTransaction::stake(
+        1,
+        "test1".to_string(),
+        &signer,
+        FISHERMEN_THRESHOLD,
+        signer.public_key(),
+        genesis_hash,
+    );
+    env.clients[0].process_tx(stake_transaction, false, false);
+    for i in 1..=11 {
+        env.produce_block(0, i);
+        let block = env.clients[0].chain.get_block_by_height(i).unwrap().clone();
+        process_block_with_chunks(&mut env, 0, 1, block);
+    }
+    assert_eq!(env.clients[1].chain.head().unwrap().height, 11);
+
+    // Chunk producer includes a chunk with invalid state in its block.
+    let last_block = env.clients[0].chain.get_block_by_height(11).unwrap().clone();
+    let validator_signer = InMemoryValidatorSigner::from_seed("test0", KeyType::ED25519, "test0");
+    let total_parts = env.clients[0].runtime_adapter.num_total_parts();
+    let data_parts = env.clients[0].runtime_adapter.num_data_parts();
+    let mut rs = ReedSolomonWrapper::new(data_parts, total_parts - data_parts);
+    let receipts_hashes = env.clients[0].runtime_adapter.build_receipts_hashes(&vec![]);
+    let (receipts_root, receipts_proofs) = merklize(&receipts_hashes);
+    let (mut invalid_chunk, merkle_paths) = env.clients[0]
+        .shards_mgr
+        .create_encoded_shard_chunk(
+            *last_block.hash(),
+            StateRoot::default(),
+            CryptoHash::default(),
+            12,
+            0,
+            0,
+            1_000,
+            0,
+            vec![],
+            vec![],
+            &vec![],
+            receipts_root,
+            CryptoHash::default(),
+            &validator_signer,
+            &mut rs,
+        )
+        .unwrap();
+    invalid_chunk.header.height_included = 12;
+    let mut block_merkle_tree =
+        env.clients[0].chain.mut_store().get_block_merkle_tree(&last_block.hash()).unwrap().clone();
+    block_merkle_tree.insert(*last_block.hash());
+    let block = Block::produce(
+        PROTOCOL_VERSION,
+        &last_block.header(),
+        12,
+        vec![invalid_chunk.header.clone()],
+        last_block.header().epoch_id().clone(),
+        last_block.header().next_epoch_id().clone(),
+        vec![],
+        Rational::from_integer(0),
+        0,
+        100,
+        None,
+        vec![],
+        vec![],
+        &validator_signer,
+        *last_block.header().next_bp_hash(),
+        block_merkle_tree.root(),
+    );
+
+    // Fisherman receives the block, then the chunk, and sends out the challenge.
+    let (_, result) = env.clients[1].process_block(block.clone(), Provenance::NONE);
+    match result.unwrap_err().kind() {
+        ErrorKind::ChunksMissing(_) => {}
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+    let receipts = env.clients[1].shards_mgr.receipts_recipient_filter(
+        0,
+        &vec![0].into_iter().collect::<HashSet<_>>(),
+        &vec![],
+        &receipts_proofs,
+    );
+    let partial_chunk = invalid_chunk.create_partial_encoded_chunk(
+        (0..total_parts as u64).collect(),
+        receipts,
+        &merkle_paths,
+    );
+    assert!(env.clients[1].process_partial_encoded_chunk(partial_chunk).unwrap().is_empty());
+    assert_eq!(env.clients[1].chain.head().unwrap().height, 11);
+    let mut challenges = vec![];
+    while let Some(request) = env.network_adapters[1].pop() {
+        if let NetworkRequests::Challenge(challenge) = request {
+            challenges.push(challenge);
+        }
+    }
+    assert_eq!(challenges.len(), 1);
+    let challenge = challenges.pop().unwrap();
+    assert_eq!(challenge.account_id, "test1");
+    match &challenge.body {
+        ChallengeBody::ChunkState(chunk_state) => {
+            assert_eq!(chunk_state.chunk_header, invalid_chunk.header)
+        }
+        body => panic!("unexpected challenge {:?}", body),
+    }
+
+    // Validator accepts the challenge of the fisherman and includes it in the next block.
+    env.clients[0].process_challenge(challenge.clone()).unwrap();
+    assert!(env.clients[0].chain.mut_store().is_block_challenged(&block.hash()).unwrap());
+    env.produce_block(0, 12);
+    assert_eq!(env.clients[0].chain.get_block_by_height(12).unwrap().challenges(), &[challenge]);
+}
+
+/// Challenge included in a block slashes the producer of the challenged chunk once the next block
+/// carries the challenges result.
+#[test]
+fn test_block_challenge_slashes_chunk_producer() {
+    init_test_logger();
+    let genesis = Arc::new(Genesis::test(vec!["test0", "test1"], 1));
+    let runtimes: Vec<Arc<dyn RuntimeAdapter>> = vec![Arc::new(neard::NightshadeRuntime::new(
+        Path::new("."),
+        create_test_store(),
+        Arc::clone(&genesis),
+        vec![],
+        vec![],
+    ))];
+    let mut env = TestEnv::new_with_runtime(ChainGenesis::test(), 1, 1, runtimes);
+    env.produce_block(0, 1);
+    let (chunk, _merkle_paths, _receipts, block) = create_invalid_proofs_chunk(&mut env.clients[0]);
+
+    let merkle_paths = Block::compute_chunk_headers_root(&block.chunks()).1;
+    let challenge = Challenge::produce(
+        ChallengeBody::ChunkProofs(ChunkProofs {
+            block_header: block.header().try_to_vec().unwrap(),
+            chunk: MaybeEncodedShardChunk::Encoded(chunk.clone()),
+            merkle_proof: merkle_paths[chunk.header.inner.shard_id as usize].clone(),
+        }),
+        &*env.clients[0].validator_signer.as_ref().unwrap().clone(),
+    );
+    env.clients[0].process_challenge(challenge.clone()).unwrap();
+    env.produce_block(0, 2);
+    let block2 = env.clients[0].chain.get_block_by_height(2).unwrap().clone();
+    assert_eq!(block2.challenges(), &[challenge]);
+    assert!(block2.header().challenges_result().is_empty());
+
+    env.produce_block(0, 3);
+    let block3 = env.clients[0].chain.get_block_by_height(3).unwrap().clone();
+    assert_eq!(
+        block3.header().challenges_result(),
+        &vec![SlashedValidator::new("test0".to_string(), false)]
+    );
+    let validator_info = env.clients[0].runtime_adapter.get_validator_info(&block3.hash()).unwrap();
+    assert!(validator_info
+        .current_validators
+        .iter()
+        .any(|validator| validator.account_id == "test0" && validator.is_slashed));
+}
+
+/// Block is rejected if its challenges result doesn't match the challenges in the previous block.
+#[test]
+fn test_invalid_challenges_result() {
+    let mut env = TestEnv::new(ChainGenesis::test(), 1, 1);
+    env.produce_block(0, 1);
+    let prev_block = env.clients[0].chain.get_block_by_height(1).unwrap().clone();
+    let signer = InMemoryValidatorSigner::from_seed("test0", KeyType::ED25519, "test0");
+    let mut block_merkle_tree =
+        env.clients[0].chain.mut_store().get_block_merkle_tree(&prev_block.hash()).unwrap().clone();
+    block_merkle_tree.insert(*prev_block.hash());
+    let block = Block::produce(
+        PROTOCOL_VERSION,
+        prev_block.header(),
+        2,
+        prev_block.chunks().clone(),
+        prev_block.header().epoch_id().clone(),
+        prev_block.header().next_epoch_id().clone(),
+        vec![],
+        Rational::from_integer(0),
+        0,
+        100,
+        None,
+        vec![SlashedValidator::new("test0".to_string(), false)],
+        vec![],
+        &signer,
+        *prev_block.header().next_bp_hash(),
+        block_merkle_tree.root(),
+    );
+    let (_, result) = env.clients[0].process_block(block, Provenance::NONE);
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidChallengesResult);
+}
+
 /// If there are two blocks produced at the same height but by different block producers, no
 /// challenge should be generated
 #[test]
@@ -7,12 +7,14 @@ use serde::Serialize;
 
 use near_primitives::hash::CryptoHash;
 use near_primitives::rpc::{
-    RpcGenesisRecordsRequest, RpcQueryRequest, RpcStateChangesRequest, RpcStateChangesResponse,
+    RpcAccountChangesInRangeRequest, RpcGenesisRecordsRequest, RpcLogsRequest, RpcQueryRequest,
+    RpcSimulateTransactionRequest, RpcStateChangesRequest, RpcStateChangesResponse,
 };
 use near_primitives::types::{BlockId, BlockIdOrFinality, MaybeBlockId, ShardId};
 use near_primitives::views::{
     BlockView, ChunkView, EpochValidatorInfo, FinalExecutionOutcomeView, GasPriceView,
-    GenesisRecordsView, QueryResponse, StatusResponse,
+    GenesisRecordsView, LogsView, PendingTransactionView, QueryResponse, StateChangesInRangeView,
+    StatusResponse, TransactionSimulationView,
 };
 
 use crate::message::{from_slice, Message, RpcError};
@@ -191,6 +193,13 @@ jsonrpc_client!(pub struct JsonRpcClient {
     pub fn chunk(&self, id: ChunkId) -> RpcRequest<ChunkView>;
     pub fn validators(&self, block_id: MaybeBlockId) -> RpcRequest<EpochValidatorInfo>;
     pub fn gas_price(&self, block_id: MaybeBlockId) -> RpcRequest<GasPriceView>;
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_pending_transactions(&self) -> RpcRequest<Vec<PendingTransactionView>>;
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_pending_transactions_for_account(
+        &self,
+        account_id: String,
+    ) -> RpcRequest<Vec<PendingTransactionView>>;
 });
 
 impl JsonRpcClient {
@@ -227,6 +236,31 @@ impl JsonRpcClient {
     ) -> RpcRequest<RpcStateChangesResponse> {
         call_method(&self.client, &self.server_addr, "EXPERIMENTAL_changes", request)
     }
+
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_account_changes_in_range(
+        &self,
+        request: RpcAccountChangesInRangeRequest,
+    ) -> RpcRequest<StateChangesInRangeView> {
+        call_method(
+            &self.client,
+            &self.server_addr,
+            "EXPERIMENTAL_account_changes_in_range",
+            request,
+        )
+    }
+
+    pub fn logs(&self, request: RpcLogsRequest) -> RpcRequest<LogsView> {
+        call_method(&self.client, &self.server_addr, "logs", request)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_simulate_tx(
+        &self,
+        request: RpcSimulateTransactionRequest,
+    ) -> RpcRequest<TransactionSimulationView> {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_simulate_tx", request)
+    }
 }
 
 fn create_client() -> Client {
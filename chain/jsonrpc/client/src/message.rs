@@ -203,6 +203,18 @@ pub struct Notification {
     pub params: Option<Value>,
 }
 
+impl From<Notification> for Request {
+    /// Turns a notification into a request without an ID, to process it like any request.
+    fn from(notification: Notification) -> Self {
+        Request {
+            jsonrpc: Version,
+            method: notification.method,
+            params: notification.params,
+            id: Value::Null,
+        }
+    }
+}
+
 /// One message of the JSON RPC protocol.
 ///
 /// One message, directly mapped from the structures of the protocol. See the
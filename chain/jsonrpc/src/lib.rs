@@ -1,7 +1,9 @@
 use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::fmt::Display;
+use std::str::FromStr;
 use std::string::FromUtf8Error;
+use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -9,6 +11,7 @@ use actix::{Addr, MailboxError};
 use actix_cors::{Cors, CorsFactory};
 use actix_web::{http, middleware, web, App, Error as HttpError, HttpResponse, HttpServer};
 use borsh::BorshDeserialize;
+use futures::future::join_all;
 use futures::Future;
 use futures::{FutureExt, TryFutureExt};
 use prometheus;
@@ -20,37 +23,49 @@ use validator::Validate;
 
 use near_chain_configs::Genesis;
 use near_client::{
-    ClientActor, GetBlock, GetBlockProof, GetChunk, GetExecutionOutcome, GetGasPrice,
-    GetNetworkInfo, GetNextLightClientBlock, GetStateChanges, GetStateChangesInBlock,
-    GetValidatorInfo, Query, Status, TxStatus, TxStatusError, ViewClientActor,
+    ClientActor, GetAccountChangesInRange, GetBlock, GetBlockProof, GetChunk, GetExecutionOutcome,
+    GetGasPrice, GetLogs, GetNetworkInfo, GetNextLightClientBlock, GetPendingTransactions,
+    GetStateChanges, GetStateChangesInBlock, GetValidatorInfo, Query, SimulateTransaction, Status,
+    TxStatus, TxStatusError, ViewClientActor,
 };
-use near_crypto::PublicKey;
+use near_crypto::{KeyType, PublicKey, Signature};
 pub use near_jsonrpc_client as client;
 use near_jsonrpc_client::message::{Message, Request, RpcError};
 use near_jsonrpc_client::ChunkId;
 use near_metrics::{Encoder, TextEncoder};
+use near_network::types::ReasonForBan;
 #[cfg(feature = "adversarial")]
 use near_network::types::{NetworkAdversarialMessage, NetworkViewClientMessages};
-use near_network::{NetworkClientMessages, NetworkClientResponses};
+use near_network::{
+    NetworkAdapter, NetworkClientMessages, NetworkClientResponses, NetworkRequests,
+    NetworkResponses, PeerInfo,
+};
 use near_primitives::errors::{InvalidTxError, TxExecutionError};
 use near_primitives::hash::CryptoHash;
+use near_primitives::network::PeerId;
 use near_primitives::rpc::{
-    RpcBroadcastTxSyncResponse, RpcGenesisRecordsRequest, RpcLightClientExecutionProofRequest,
-    RpcLightClientExecutionProofResponse, RpcQueryRequest, RpcStateChangesInBlockRequest,
+    RpcAccountChangesInRangeRequest, RpcBroadcastTxSyncResponse, RpcGenesisRecordsRequest,
+    RpcLightClientExecutionProofRequest, RpcLightClientExecutionProofResponse, RpcLogsRequest,
+    RpcQueryRequest, RpcSimulateTransactionRequest, RpcStateChangesInBlockRequest,
     RpcStateChangesInBlockResponse, RpcStateChangesRequest, RpcStateChangesResponse,
     TransactionInfo,
 };
 use near_primitives::serialize::{from_base, from_base64, BaseEncode};
-use near_primitives::transaction::SignedTransaction;
+use near_primitives::transaction::{SignedTransaction, Transaction};
 use near_primitives::types::{AccountId, BlockId, BlockIdOrFinality, MaybeBlockId};
 use near_primitives::utils::is_valid_account_id;
 use near_primitives::views::{FinalExecutionOutcomeView, GenesisRecordsView, QueryRequest};
 mod metrics;
+mod subscriptions;
 
 /// Maximum byte size of the json payload.
 const JSON_PAYLOAD_MAX_SIZE: usize = 2 * 1024 * 1024;
 const QUERY_DATA_MAX_SIZE: usize = 10 * 1024;
 
+fn default_max_batch_size() -> usize {
+    100
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub struct RpcPollingConfig {
     pub polling_interval: Duration,
@@ -66,11 +81,64 @@ impl Default for RpcPollingConfig {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct RpcSubscriptionsConfig {
+    /// Maximum number of subscriptions a single WebSocket connection may hold.
+    #[serde(default = "default_max_subscriptions_per_connection")]
+    pub max_subscriptions_per_connection: usize,
+    /// Maximum number of blocks a subscription is notified about in one polling round.
+    #[serde(default = "default_max_blocks_per_poll")]
+    pub max_blocks_per_poll: u64,
+    /// Maximum number of subscriptions held by all WebSocket connections together.
+    #[serde(default = "default_max_subscriptions")]
+    pub max_subscriptions: usize,
+    /// Maximum number of bytes queued for a connection and not yet taken by the client. The
+    /// connection is closed when a slow client lets it grow beyond this limit.
+    #[serde(default = "default_max_buffered_bytes_per_connection")]
+    pub max_buffered_bytes_per_connection: usize,
+}
+
+fn default_max_subscriptions_per_connection() -> usize {
+    100
+}
+
+fn default_max_blocks_per_poll() -> u64 {
+    10
+}
+
+fn default_max_subscriptions() -> usize {
+    10_000
+}
+
+fn default_max_buffered_bytes_per_connection() -> usize {
+    8 * 1024 * 1024
+}
+
+impl Default for RpcSubscriptionsConfig {
+    fn default() -> Self {
+        Self {
+            max_subscriptions_per_connection: default_max_subscriptions_per_connection(),
+            max_blocks_per_poll: default_max_blocks_per_poll(),
+            max_subscriptions: default_max_subscriptions(),
+            max_buffered_bytes_per_connection: default_max_buffered_bytes_per_connection(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct RpcConfig {
     pub addr: String,
     pub cors_allowed_origins: Vec<String>,
     pub polling_config: RpcPollingConfig,
+    #[serde(default)]
+    pub subscriptions_config: RpcSubscriptionsConfig,
+    /// Maximum number of requests in a single JSON-RPC batch.
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+    /// Address to serve the admin RPC methods on. They are disabled if not set.
+    /// It should never be reachable from outside of the node's host.
+    #[serde(default)]
+    pub admin_addr: Option<String>,
 }
 
 impl Default for RpcConfig {
@@ -79,6 +147,9 @@ impl Default for RpcConfig {
             addr: "0.0.0.0:3030".to_owned(),
             cors_allowed_origins: vec!["*".to_owned()],
             polling_config: Default::default(),
+            subscriptions_config: Default::default(),
+            max_batch_size: default_max_batch_size(),
+            admin_addr: None,
         }
     }
 }
@@ -117,13 +188,17 @@ fn jsonify<T: serde::Serialize>(
         .map_err(|err| RpcError::server_error(Some(err)))
 }
 
-fn parse_tx(params: Option<Value>) -> Result<SignedTransaction, RpcError> {
-    let (encoded,) = parse_params::<(String,)>(params)?;
+fn decode_tx<T: BorshDeserialize>(encoded: String) -> Result<T, RpcError> {
     let bytes = from_base64_or_parse_err(encoded)?;
-    SignedTransaction::try_from_slice(&bytes)
+    T::try_from_slice(&bytes)
         .map_err(|e| RpcError::invalid_params(format!("Failed to decode transaction: {}", e)))
 }
 
+fn parse_tx(params: Option<Value>) -> Result<SignedTransaction, RpcError> {
+    let (encoded,) = parse_params::<(String,)>(params)?;
+    decode_tx(encoded)
+}
+
 /// A general Server Error
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, near_rpc_error_macro::RpcError)]
 pub enum ServerError {
@@ -173,17 +248,60 @@ struct JsonRpcHandler {
     client_addr: Addr<ClientActor>,
     view_client_addr: Addr<ViewClientActor>,
     polling_config: RpcPollingConfig,
+    subscriptions_config: RpcSubscriptionsConfig,
+    /// Number of subscriptions held by all WebSocket connections, shared by the workers.
+    active_subscriptions: Arc<AtomicUsize>,
+    max_batch_size: usize,
     genesis: Arc<Genesis>,
 }
 
 impl JsonRpcHandler {
-    pub async fn process(&self, message: Message) -> Result<Message, HttpError> {
+    /// Returns the response to the message, or `None` if it must not be answered.
+    pub async fn process(&self, message: Message) -> Result<Option<Message>, HttpError> {
         let id = message.id();
         match message {
             Message::Request(request) => {
-                Ok(Message::response(id, self.process_request(request).await))
+                Ok(Some(Message::response(id, self.process_request(request).await)))
             }
-            _ => Ok(Message::error(RpcError::invalid_request())),
+            Message::Batch(messages) => Ok(self.process_batch(messages).await),
+            _ => Ok(Some(Message::error(RpcError::invalid_request()))),
+        }
+    }
+
+    /// Processes all requests of a batch concurrently. Responses are returned in the order of
+    /// the requests, every entry that is not a valid request gets its own error response.
+    /// Notifications are processed too, but are not answered, so a batch of only notifications
+    /// gets no response at all.
+    async fn process_batch(&self, messages: Vec<Message>) -> Option<Message> {
+        if messages.is_empty() {
+            return Some(Message::error(RpcError::invalid_request()));
+        }
+        if messages.len() > self.max_batch_size {
+            return Some(Message::error(RpcError::invalid_params(format!(
+                "Batch size {} exceeds the limit of {}",
+                messages.len(),
+                self.max_batch_size
+            ))));
+        }
+        near_metrics::inc_counter_by(&metrics::RPC_BATCH_REQUEST_COUNT, messages.len() as i64);
+        let responses = messages.into_iter().map(|message| async move {
+            match message {
+                Message::Request(request) => {
+                    let id = request.id.clone();
+                    Some(Message::response(id, self.process_request(request).await))
+                }
+                Message::Notification(notification) => {
+                    let _ = self.process_request(notification.into()).await;
+                    None
+                }
+                _ => Some(Message::error(RpcError::invalid_request())),
+            }
+        });
+        let responses: Vec<_> = join_all(responses).await.into_iter().flatten().collect();
+        if responses.is_empty() {
+            None
+        } else {
+            Some(Message::Batch(responses))
         }
     }
 
@@ -216,6 +334,7 @@ impl JsonRpcHandler {
             "EXPERIMENTAL_broadcast_tx_sync" => self.send_tx_sync(request.params).await,
             "broadcast_tx_commit" => self.send_tx_commit(request.params).await,
             "EXPERIMENTAL_check_tx" => self.check_tx(request.params).await,
+            "EXPERIMENTAL_simulate_tx" => self.simulate_tx(request.params).await,
             "validators" => self.validators(request.params).await,
             "query" => self.query(request.params).await,
             "health" => self.health().await,
@@ -227,12 +346,20 @@ impl JsonRpcHandler {
             "chunk" => self.chunk(request.params).await,
             "EXPERIMENTAL_changes" => self.changes_in_block_by_type(request.params).await,
             "EXPERIMENTAL_changes_in_block" => self.changes_in_block(request.params).await,
+            "EXPERIMENTAL_account_changes_in_range" => {
+                self.account_changes_in_range(request.params).await
+            }
+            "logs" => self.logs(request.params).await,
             "next_light_client_block" => self.next_light_client_block(request.params).await,
             "EXPERIMENTAL_light_client_proof" => {
                 self.light_client_execution_outcome_proof(request.params).await
             }
             "light_client_proof" => self.light_client_execution_outcome_proof(request.params).await,
             "network_info" => self.network_info().await,
+            "EXPERIMENTAL_pending_transactions" => self.pending_transactions().await,
+            "EXPERIMENTAL_pending_transactions_for_account" => {
+                self.pending_transactions_for_account(request.params).await
+            }
             "gas_price" => self.gas_price(request.params).await,
             _ => Err(RpcError::method_not_found(request.method)),
         }
@@ -430,6 +557,30 @@ impl JsonRpcHandler {
         }
     }
 
+    async fn simulate_tx(&self, params: Option<Value>) -> Result<Value, RpcError> {
+        let (transaction, verify_signature) = match parse_params(params)? {
+            RpcSimulateTransactionRequest::SignedTransaction(encoded) => {
+                (decode_tx(encoded)?, true)
+            }
+            RpcSimulateTransactionRequest::Transaction(encoded) => {
+                let transaction: Transaction = decode_tx(encoded)?;
+                (SignedTransaction::new(Signature::empty(KeyType::ED25519), transaction), false)
+            }
+        };
+        match self
+            .view_client_addr
+            .send(SimulateTransaction { transaction, verify_signature })
+            .await
+            .map_err(|err| RpcError::server_error(Some(err.to_string())))?
+        {
+            Ok(Ok(simulation)) => jsonify(Ok(Ok(simulation))),
+            Ok(Err(err)) => {
+                Err(RpcError::server_error(Some(ServerError::TxExecutionError(err.into()))))
+            }
+            Err(err) => Err(RpcError::server_error(Some(err))),
+        }
+    }
+
     async fn send_tx_commit(&self, params: Option<Value>) -> Result<Value, RpcError> {
         let tx = parse_tx(params)?;
         match self.tx_status_fetch(TransactionInfo::Transaction(tx.clone())).await {
@@ -663,6 +814,39 @@ impl JsonRpcHandler {
         )
     }
 
+    async fn account_changes_in_range(&self, params: Option<Value>) -> Result<Value, RpcError> {
+        let request: RpcAccountChangesInRangeRequest = parse_params(params)?;
+        request.validate().map_err(RpcError::invalid_params)?;
+        let RpcAccountChangesInRangeRequest {
+            account_ids,
+            from_block_height,
+            to_block_height,
+            limit,
+        } = request;
+        jsonify(
+            self.view_client_addr
+                .send(GetAccountChangesInRange {
+                    account_ids,
+                    from_block_height,
+                    to_block_height,
+                    limit,
+                })
+                .await,
+        )
+    }
+
+    async fn logs(&self, params: Option<Value>) -> Result<Value, RpcError> {
+        let request: RpcLogsRequest = parse_params(params)?;
+        request.validate().map_err(RpcError::invalid_params)?;
+        let RpcLogsRequest { account_id, from_block_height, to_block_height, prefix, limit } =
+            request;
+        jsonify(
+            self.view_client_addr
+                .send(GetLogs { account_id, from_block_height, to_block_height, prefix, limit })
+                .await,
+        )
+    }
+
     async fn next_light_client_block(&self, params: Option<Value>) -> Result<Value, RpcError> {
         let (last_block_hash,) = parse_params::<(CryptoHash,)>(params)?;
         jsonify(self.view_client_addr.send(GetNextLightClientBlock { last_block_hash }).await)
@@ -700,6 +884,20 @@ impl JsonRpcHandler {
         jsonify(self.client_addr.send(GetNetworkInfo {}).await)
     }
 
+    async fn pending_transactions(&self) -> Result<Value, RpcError> {
+        jsonify(self.client_addr.send(GetPendingTransactions { account_id: None }).await)
+    }
+
+    async fn pending_transactions_for_account(
+        &self,
+        params: Option<Value>,
+    ) -> Result<Value, RpcError> {
+        let (account_id,) = parse_params::<(AccountId,)>(params)?;
+        jsonify(
+            self.client_addr.send(GetPendingTransactions { account_id: Some(account_id) }).await,
+        )
+    }
+
     async fn gas_price(&self, params: Option<Value>) -> Result<Value, RpcError> {
         let (block_id,) = parse_params::<(MaybeBlockId,)>(params)?;
         jsonify(self.view_client_addr.send(GetGasPrice { block_id }).await)
@@ -832,12 +1030,91 @@ impl JsonRpcHandler {
     }
 }
 
+/// Handler of the node administration methods, served separately from the public RPC.
+struct AdminRpcHandler {
+    network_adapter: Arc<dyn NetworkAdapter>,
+}
+
+impl AdminRpcHandler {
+    pub async fn process(&self, message: Message) -> Result<Message, HttpError> {
+        let id = message.id();
+        match message {
+            Message::Request(request) => {
+                Ok(Message::response(id, self.process_request(request).await))
+            }
+            _ => Ok(Message::error(RpcError::invalid_request())),
+        }
+    }
+
+    async fn process_request(&self, request: Request) -> Result<Value, RpcError> {
+        let _rpc_processing_time = near_metrics::start_timer(&metrics::RPC_PROCESSING_TIME);
+
+        match request.method.as_ref() {
+            "admin_ban_peer" => self.ban_peer(request.params).await,
+            "admin_unban_peer" => self.unban_peer(request.params).await,
+            "admin_connect_peer" => self.connect_peer(request.params).await,
+            "admin_known_peers" => self.known_peers().await,
+            _ => Err(RpcError::method_not_found(request.method)),
+        }
+    }
+
+    async fn send(&self, request: NetworkRequests) -> Result<NetworkResponses, RpcError> {
+        self.network_adapter.send(request).await.map_err(|err| ServerError::from(err).into())
+    }
+
+    async fn ban_peer(&self, params: Option<Value>) -> Result<Value, RpcError> {
+        let (peer_id,) = parse_params::<(PeerId,)>(params)?;
+        self.send(NetworkRequests::BanPeer { peer_id, ban_reason: ReasonForBan::None }).await?;
+        Ok(Value::Null)
+    }
+
+    async fn unban_peer(&self, params: Option<Value>) -> Result<Value, RpcError> {
+        let (peer_id,) = parse_params::<(PeerId,)>(params)?;
+        self.send(NetworkRequests::UnbanPeer { peer_id }).await?;
+        Ok(Value::Null)
+    }
+
+    /// Connect to the peer given in the same format as boot nodes: `peer_id@ip:port`.
+    async fn connect_peer(&self, params: Option<Value>) -> Result<Value, RpcError> {
+        let (peer_info,) = parse_params::<(String,)>(params)?;
+        let peer_info = PeerInfo::from_str(&peer_info)
+            .map_err(|err| RpcError::invalid_params(format!("Invalid peer info: {}", err)))?;
+        if peer_info.addr.is_none() {
+            return Err(RpcError::invalid_params("Peer address is required".to_owned()));
+        }
+        self.send(NetworkRequests::ConnectPeer(peer_info)).await?;
+        Ok(Value::Null)
+    }
+
+    async fn known_peers(&self) -> Result<Value, RpcError> {
+        match self.send(NetworkRequests::FetchKnownPeers).await? {
+            NetworkResponses::KnownPeers(known_peers) => jsonify(Ok(Ok(known_peers))),
+            _ => Err(RpcError::server_error(Some(ServerError::InternalError))),
+        }
+    }
+}
+
 fn rpc_handler(
     message: web::Json<Message>,
     handler: web::Data<JsonRpcHandler>,
 ) -> impl Future<Output = Result<HttpResponse, HttpError>> {
     near_metrics::inc_counter(&metrics::HTTP_RPC_REQUEST_COUNT);
 
+    let response = async move {
+        match handler.process(message.0).await? {
+            Some(message) => Ok(HttpResponse::Ok().json(message)),
+            None => Ok(HttpResponse::NoContent().finish()),
+        }
+    };
+    response.boxed()
+}
+
+fn admin_rpc_handler(
+    message: web::Json<Message>,
+    handler: web::Data<AdminRpcHandler>,
+) -> impl Future<Output = Result<HttpResponse, HttpError>> {
+    near_metrics::inc_counter(&metrics::HTTP_ADMIN_RPC_REQUEST_COUNT);
+
     let response = async move {
         let message = handler.process(message.0).await?;
         Ok(HttpResponse::Ok().json(message))
@@ -917,7 +1194,15 @@ pub fn start_http(
     client_addr: Addr<ClientActor>,
     view_client_addr: Addr<ViewClientActor>,
 ) {
-    let RpcConfig { addr, polling_config, cors_allowed_origins } = config;
+    let RpcConfig {
+        addr,
+        polling_config,
+        cors_allowed_origins,
+        subscriptions_config,
+        max_batch_size,
+        admin_addr: _,
+    } = config;
+    let active_subscriptions = Arc::new(AtomicUsize::new(0));
     HttpServer::new(move || {
         App::new()
             .wrap(get_cors(&cors_allowed_origins))
@@ -925,6 +1210,9 @@ pub fn start_http(
                 client_addr: client_addr.clone(),
                 view_client_addr: view_client_addr.clone(),
                 polling_config,
+                subscriptions_config,
+                active_subscriptions: Arc::clone(&active_subscriptions),
+                max_batch_size,
                 genesis: Arc::clone(&genesis),
             })
             .app_data(web::JsonConfig::default().limit(JSON_PAYLOAD_MAX_SIZE))
@@ -942,6 +1230,7 @@ pub fn start_http(
             )
             .service(web::resource("/network_info").route(web::get().to(network_info_handler)))
             .service(web::resource("/metrics").route(web::get().to(prometheus_handler)))
+            .service(web::resource("/ws").route(web::get().to(subscriptions::ws_handler)))
     })
     .bind(addr)
     .unwrap()
@@ -949,3 +1238,19 @@ pub fn start_http(
     .shutdown_timeout(5)
     .run();
 }
+
+/// Start the admin RPC server. It must listen on a different address than the public RPC.
+pub fn start_admin_http(addr: String, network_adapter: Arc<dyn NetworkAdapter>) {
+    HttpServer::new(move || {
+        App::new()
+            .data(AdminRpcHandler { network_adapter: network_adapter.clone() })
+            .app_data(web::JsonConfig::default().limit(JSON_PAYLOAD_MAX_SIZE))
+            .wrap(middleware::Logger::default())
+            .service(web::resource("/").route(web::post().to(admin_rpc_handler)))
+    })
+    .bind(addr)
+    .unwrap()
+    .workers(1)
+    .shutdown_timeout(5)
+    .run();
+}
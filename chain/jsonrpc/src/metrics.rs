@@ -1,5 +1,5 @@
 use lazy_static::lazy_static;
-use near_metrics::{Histogram, IntCounter};
+use near_metrics::{Histogram, IntCounter, IntGauge};
 
 lazy_static! {
     pub static ref RPC_PROCESSING_TIME: near_metrics::Result<Histogram> =
@@ -22,9 +22,34 @@ lazy_static! {
             "http_rpc_requests_total",
             "Total count of HTTP RPC requests received"
         );
+    pub static ref HTTP_ADMIN_RPC_REQUEST_COUNT: near_metrics::Result<IntCounter> =
+        near_metrics::try_create_int_counter(
+            "http_admin_rpc_requests_total",
+            "Total count of HTTP admin RPC requests received"
+        );
+    pub static ref RPC_BATCH_REQUEST_COUNT: near_metrics::Result<IntCounter> =
+        near_metrics::try_create_int_counter(
+            "near_rpc_batch_requests_total",
+            "Total count of requests received as part of JSON-RPC batches"
+        );
     pub static ref HTTP_STATUS_REQUEST_COUNT: near_metrics::Result<IntCounter> =
         near_metrics::try_create_int_counter(
             "http_status_requests_total",
             "Total count of HTTP Status requests received"
         );
+    pub static ref WS_CONNECTION_COUNT: near_metrics::Result<IntCounter> =
+        near_metrics::try_create_int_counter(
+            "near_rpc_ws_connections_total",
+            "Total count of WebSocket connections opened"
+        );
+    pub static ref RPC_ACTIVE_SUBSCRIPTIONS: near_metrics::Result<IntGauge> =
+        near_metrics::try_create_int_gauge(
+            "near_rpc_active_subscriptions",
+            "Number of active WebSocket subscriptions"
+        );
+    pub static ref RPC_SUBSCRIPTION_NOTIFICATIONS: near_metrics::Result<IntCounter> =
+        near_metrics::try_create_int_counter(
+            "near_rpc_subscription_notifications_total",
+            "Total count of notifications sent to WebSocket subscribers"
+        );
 }
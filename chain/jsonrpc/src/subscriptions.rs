@@ -0,0 +1,459 @@
+//! WebSocket endpoint that lets clients subscribe to chain events instead of polling.
+//!
+//! Every connection is served by its own `SubscriptionSession` actor, which polls the
+//! `ViewClientActor` on behalf of all subscriptions of the connection. The view client is
+//! never blocked by a subscriber: each session has at most one poll in flight, and catches up
+//! on at most `max_blocks_per_poll` blocks per subscription and round.
+//!
+//! The number of subscriptions of all connections is capped, and so is the number of bytes
+//! queued for a connection that the client has not taken yet. A connection that exceeds either
+//! limit is closed.
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::{Actor, ActorContext, ActorFuture, Addr, AsyncContext, StreamHandler, WrapFuture};
+use actix_web::{web, Error as HttpError, HttpRequest, HttpResponse};
+use actix_web_actors::ws;
+use futures::future::join_all;
+use futures::StreamExt;
+use serde::Serialize;
+use serde_json::Value;
+
+use near_client::{GetBlock, GetStateChanges, TxStatus, TxStatusError, ViewClientActor};
+use near_jsonrpc_client::message::{from_str, Message, Request, RpcError};
+use near_primitives::rpc::{
+    RpcStateChangesResponse, RpcSubscriptionNotification, RpcSubscriptionRequest, SubscriptionId,
+};
+use near_primitives::types::{BlockHeight, BlockId, BlockIdOrFinality, Finality};
+use near_primitives::views::{BlockView, StateChangesRequestView};
+
+use crate::{metrics, parse_params, JsonRpcHandler, RpcSubscriptionsConfig};
+
+/// Method name of the notifications sent to subscribers.
+const SUBSCRIPTION_NOTIFICATION: &str = "subscription";
+
+struct Subscription {
+    request: RpcSubscriptionRequest,
+    /// Height of the last block this subscription was notified about.
+    last_height: Option<BlockHeight>,
+}
+
+/// Result of polling a single subscription.
+struct PollResult {
+    id: SubscriptionId,
+    last_height: Option<BlockHeight>,
+    notifications: Vec<Value>,
+    /// Whether the subscription is fulfilled and must be removed.
+    finished: bool,
+}
+
+impl PollResult {
+    fn new(id: SubscriptionId, last_height: Option<BlockHeight>) -> Self {
+        Self { id, last_height, notifications: vec![], finished: false }
+    }
+}
+
+pub(crate) struct SubscriptionSession {
+    view_client_addr: Addr<ViewClientActor>,
+    polling_interval: Duration,
+    config: RpcSubscriptionsConfig,
+    next_subscription_id: SubscriptionId,
+    subscriptions: HashMap<SubscriptionId, Subscription>,
+    /// Number of subscriptions of all connections.
+    active_subscriptions: Arc<AtomicUsize>,
+    /// Number of bytes written to the connection and not yet taken by the HTTP server. It is
+    /// released by the response stream, see `ws_handler`.
+    buffered_bytes: Rc<Cell<usize>>,
+    /// Whether a poll of the view client is in flight.
+    polling: bool,
+    /// Reason to close the connection with once the current message is answered.
+    close_reason: Option<ws::CloseReason>,
+}
+
+impl SubscriptionSession {
+    fn new(
+        view_client_addr: Addr<ViewClientActor>,
+        polling_interval: Duration,
+        config: RpcSubscriptionsConfig,
+        active_subscriptions: Arc<AtomicUsize>,
+        buffered_bytes: Rc<Cell<usize>>,
+    ) -> Self {
+        Self {
+            view_client_addr,
+            polling_interval,
+            config,
+            next_subscription_id: 0,
+            subscriptions: HashMap::new(),
+            active_subscriptions,
+            buffered_bytes,
+            polling: false,
+            close_reason: None,
+        }
+    }
+
+    fn process_message(&mut self, text: &str) -> Message {
+        match from_str(text) {
+            Ok(Message::Request(request)) => {
+                let id = request.id.clone();
+                Message::response(id, self.process_request(request))
+            }
+            Ok(_) => Message::error(RpcError::invalid_request()),
+            Err(broken) => broken.reply(),
+        }
+    }
+
+    fn process_request(&mut self, request: Request) -> Result<Value, RpcError> {
+        match request.method.as_ref() {
+            "subscribe" => self.subscribe(request.params),
+            "unsubscribe" => self.unsubscribe(request.params),
+            _ => Err(RpcError::method_not_found(request.method)),
+        }
+    }
+
+    fn subscribe(&mut self, params: Option<Value>) -> Result<Value, RpcError> {
+        let request = parse_params::<RpcSubscriptionRequest>(params)?;
+        if self.subscriptions.len() >= self.config.max_subscriptions_per_connection {
+            return Err(RpcError::server_error(Some(format!(
+                "Exceeded the limit of {} subscriptions per connection",
+                self.config.max_subscriptions_per_connection
+            ))));
+        }
+        if self.active_subscriptions.fetch_add(1, Ordering::SeqCst) >= self.config.max_subscriptions
+        {
+            self.active_subscriptions.fetch_sub(1, Ordering::SeqCst);
+            let message = format!(
+                "Exceeded the limit of {} subscriptions of all connections",
+                self.config.max_subscriptions
+            );
+            self.close_reason = Some(ws::CloseReason {
+                code: ws::CloseCode::Again,
+                description: Some(message.clone()),
+            });
+            return Err(RpcError::server_error(Some(message)));
+        }
+        let id = self.next_subscription_id;
+        self.next_subscription_id += 1;
+        self.subscriptions.insert(id, Subscription { request, last_height: None });
+        near_metrics::inc_gauge(&metrics::RPC_ACTIVE_SUBSCRIPTIONS);
+        Ok(Value::from(id))
+    }
+
+    fn unsubscribe(&mut self, params: Option<Value>) -> Result<Value, RpcError> {
+        let (id,) = parse_params::<(SubscriptionId,)>(params)?;
+        self.remove_subscription(id);
+        Ok(Value::Null)
+    }
+
+    fn remove_subscription(&mut self, id: SubscriptionId) {
+        if self.subscriptions.remove(&id).is_some() {
+            self.active_subscriptions.fetch_sub(1, Ordering::SeqCst);
+            near_metrics::dec_gauge(&metrics::RPC_ACTIVE_SUBSCRIPTIONS);
+        }
+    }
+
+    /// Writes a message to the connection, and closes it if the client doesn't keep up.
+    fn send<T: Serialize>(&mut self, ctx: &mut ws::WebsocketContext<Self>, message: &T) {
+        if self.close_reason.is_some() {
+            return;
+        }
+        let text = serde_json::to_string(message).unwrap_or_else(|_| {
+            serde_json::to_string(&Message::error(RpcError::server_error::<()>(None)))
+                .unwrap_or_default()
+        });
+        let buffered_bytes = self.buffered_bytes.get().saturating_add(text.len());
+        if buffered_bytes > self.config.max_buffered_bytes_per_connection {
+            self.close(
+                ctx,
+                ws::CloseReason {
+                    code: ws::CloseCode::Policy,
+                    description: Some(format!(
+                        "Exceeded the limit of {} buffered bytes per connection",
+                        self.config.max_buffered_bytes_per_connection
+                    )),
+                },
+            );
+            return;
+        }
+        self.buffered_bytes.set(buffered_bytes);
+        ctx.text(text);
+    }
+
+    fn close(&mut self, ctx: &mut ws::WebsocketContext<Self>, reason: ws::CloseReason) {
+        self.close_reason = Some(reason.clone());
+        ctx.close(Some(reason));
+        ctx.stop();
+    }
+
+    /// Answers a request, and then closes the connection if the request exceeded a limit.
+    fn respond(&mut self, ctx: &mut ws::WebsocketContext<Self>, response: &Message) {
+        match self.close_reason.take() {
+            Some(reason) => {
+                self.send(ctx, response);
+                self.close(ctx, reason);
+            }
+            None => self.send(ctx, response),
+        }
+    }
+
+    /// Polls the view client for all subscriptions of this connection. Skips the round if the
+    /// previous one is still in flight.
+    fn poll(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        if self.polling || self.subscriptions.is_empty() {
+            return;
+        }
+        self.polling = true;
+        let view_client_addr = self.view_client_addr.clone();
+        let max_blocks_per_poll = self.config.max_blocks_per_poll;
+        let subscriptions: Vec<_> = self
+            .subscriptions
+            .iter()
+            .map(|(id, subscription)| (*id, subscription.request.clone(), subscription.last_height))
+            .collect();
+        ctx.spawn(
+            poll_subscriptions(view_client_addr, subscriptions, max_blocks_per_poll)
+                .into_actor(self)
+                .map(|results, act, ctx| {
+                    act.polling = false;
+                    for result in results {
+                        // The subscription may have been cancelled while the poll was in flight.
+                        let subscription = match act.subscriptions.get_mut(&result.id) {
+                            Some(subscription) => subscription,
+                            None => continue,
+                        };
+                        subscription.last_height = result.last_height;
+                        for notification in result.notifications {
+                            near_metrics::inc_counter(&metrics::RPC_SUBSCRIPTION_NOTIFICATIONS);
+                            act.send(
+                                ctx,
+                                &Message::notification(
+                                    SUBSCRIPTION_NOTIFICATION.to_string(),
+                                    Some(notification),
+                                ),
+                            );
+                        }
+                        if result.finished {
+                            act.remove_subscription(result.id);
+                        }
+                    }
+                }),
+        );
+    }
+}
+
+impl Actor for SubscriptionSession {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(self.polling_interval, |act, ctx| act.poll(ctx));
+    }
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        for _ in self.subscriptions.drain() {
+            self.active_subscriptions.fetch_sub(1, Ordering::SeqCst);
+            near_metrics::dec_gauge(&metrics::RPC_ACTIVE_SUBSCRIPTIONS);
+        }
+    }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for SubscriptionSession {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        match msg {
+            Ok(ws::Message::Text(text)) => {
+                let response = self.process_message(&text);
+                self.respond(ctx, &response);
+            }
+            Ok(ws::Message::Binary(bytes)) => {
+                let response = match std::str::from_utf8(&bytes) {
+                    Ok(text) => self.process_message(text),
+                    Err(err) => Message::error(RpcError::parse_error(err.to_string())),
+                };
+                self.respond(ctx, &response);
+            }
+            Ok(ws::Message::Ping(msg)) => ctx.pong(&msg),
+            Ok(ws::Message::Close(reason)) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            Ok(ws::Message::Pong(_)) | Ok(ws::Message::Continuation(_)) | Ok(ws::Message::Nop) => {}
+            Err(_) => ctx.stop(),
+        }
+    }
+}
+
+fn notification<T: Serialize>(id: SubscriptionId, result: T) -> Option<Value> {
+    serde_json::to_value(RpcSubscriptionNotification { subscription: id, result }).ok()
+}
+
+async fn get_block(
+    view_client_addr: &Addr<ViewClientActor>,
+    block_id_or_finality: BlockIdOrFinality,
+) -> Option<BlockView> {
+    view_client_addr.send(GetBlock(block_id_or_finality)).await.ok()?.ok()
+}
+
+async fn poll_subscriptions(
+    view_client_addr: Addr<ViewClientActor>,
+    subscriptions: Vec<(SubscriptionId, RpcSubscriptionRequest, Option<BlockHeight>)>,
+    max_blocks_per_poll: u64,
+) -> Vec<PollResult> {
+    let head = get_block(&view_client_addr, BlockIdOrFinality::Finality(Finality::None)).await;
+    let final_head = if subscriptions
+        .iter()
+        .any(|(_, request, _)| request == &RpcSubscriptionRequest::FinalBlocks)
+    {
+        get_block(&view_client_addr, BlockIdOrFinality::Finality(Finality::Final)).await
+    } else {
+        None
+    };
+    join_all(subscriptions.into_iter().map(|(id, request, last_height)| {
+        poll_subscription(
+            &view_client_addr,
+            id,
+            request,
+            last_height,
+            head.as_ref(),
+            final_head.as_ref(),
+            max_blocks_per_poll,
+        )
+    }))
+    .await
+}
+
+/// Heights a subscription is notified about in a polling round. The first round starts from the
+/// current head, and every later one walks the blocks produced since the previous round, so that
+/// none is skipped when the head moves by more than one block in between.
+fn heights_to_poll(
+    last_height: Option<BlockHeight>,
+    head_height: BlockHeight,
+    max_blocks_per_poll: u64,
+) -> RangeInclusive<BlockHeight> {
+    let from_height = last_height.map_or(head_height, |height| height + 1);
+    let to_height = std::cmp::min(
+        head_height,
+        from_height.saturating_add(max_blocks_per_poll).saturating_sub(1),
+    );
+    from_height..=to_height
+}
+
+async fn poll_subscription(
+    view_client_addr: &Addr<ViewClientActor>,
+    id: SubscriptionId,
+    request: RpcSubscriptionRequest,
+    last_height: Option<BlockHeight>,
+    head: Option<&BlockView>,
+    final_head: Option<&BlockView>,
+    max_blocks_per_poll: u64,
+) -> PollResult {
+    let mut result = PollResult::new(id, last_height);
+    match request {
+        RpcSubscriptionRequest::NewBlocks | RpcSubscriptionRequest::FinalBlocks => {
+            let head = match (&request, head, final_head) {
+                (RpcSubscriptionRequest::NewBlocks, Some(head), _) => head,
+                (RpcSubscriptionRequest::FinalBlocks, _, Some(final_head)) => final_head,
+                _ => return result,
+            };
+            for height in heights_to_poll(last_height, head.header.height, max_blocks_per_poll) {
+                result.last_height = Some(height);
+                if height == head.header.height {
+                    result.notifications.extend(notification(id, head));
+                } else if let Some(block) =
+                    get_block(view_client_addr, BlockIdOrFinality::BlockId(BlockId::Height(height)))
+                        .await
+                {
+                    result.notifications.extend(notification(id, block));
+                }
+            }
+        }
+        RpcSubscriptionRequest::AccountChanges { account_ids } => {
+            let head_height = match head {
+                Some(head) => head.header.height,
+                None => return result,
+            };
+            for height in heights_to_poll(last_height, head_height, max_blocks_per_poll) {
+                result.last_height = Some(height);
+                let block = match get_block(
+                    view_client_addr,
+                    BlockIdOrFinality::BlockId(BlockId::Height(height)),
+                )
+                .await
+                {
+                    Some(block) => block,
+                    // There is no block at this height.
+                    None => continue,
+                };
+                let changes = view_client_addr
+                    .send(GetStateChanges {
+                        block_hash: block.header.hash,
+                        state_changes_request: StateChangesRequestView::AccountChanges {
+                            account_ids: account_ids.clone(),
+                        },
+                    })
+                    .await;
+                match changes {
+                    Ok(Ok(changes)) if changes.is_empty() => {}
+                    Ok(Ok(changes)) => result.notifications.extend(notification(
+                        id,
+                        RpcStateChangesResponse { block_hash: block.header.hash, changes },
+                    )),
+                    // Retry this height on the next poll.
+                    _ => {
+                        result.last_height = height.checked_sub(1);
+                        break;
+                    }
+                }
+            }
+        }
+        RpcSubscriptionRequest::TransactionOutcome { transaction_hash, sender_id } => {
+            match view_client_addr
+                .send(TxStatus { tx_hash: transaction_hash, signer_account_id: sender_id })
+                .await
+            {
+                Ok(Ok(Some(outcome))) => {
+                    result.notifications.extend(notification(id, outcome));
+                    result.finished = true;
+                }
+                // The transaction is not executed yet.
+                Ok(Ok(None)) | Ok(Err(TxStatusError::MissingTransaction(_))) => {}
+                Ok(Err(err)) => {
+                    let err: String = err.into();
+                    result
+                        .notifications
+                        .extend(notification(id, RpcError::server_error(Some(err))));
+                    result.finished = true;
+                }
+                Err(_) => {}
+            }
+        }
+    }
+    result
+}
+
+pub(crate) async fn ws_handler(
+    req: HttpRequest,
+    stream: web::Payload,
+    handler: web::Data<JsonRpcHandler>,
+) -> Result<HttpResponse, HttpError> {
+    near_metrics::inc_counter(&metrics::WS_CONNECTION_COUNT);
+    let mut response = ws::handshake(&req)?;
+    let buffered_bytes = Rc::new(Cell::new(0));
+    let session = SubscriptionSession::new(
+        handler.view_client_addr.clone(),
+        handler.polling_config.polling_interval,
+        handler.subscriptions_config,
+        Arc::clone(&handler.active_subscriptions),
+        Rc::clone(&buffered_bytes),
+    );
+    // The HTTP server only takes the next chunk once it has written out the previous ones, so
+    // whatever it has not taken yet is buffered for a slow client.
+    Ok(response.streaming(ws::WebsocketContext::create(session, stream).map(move |chunk| {
+        if let Ok(bytes) = &chunk {
+            buffered_bytes.set(buffered_bytes.get().saturating_sub(bytes.len()));
+        }
+        chunk
+    })))
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use actix::System;
+use serde_json::{json, Value};
+
+use near_crypto::{KeyType, SecretKey};
+use near_jsonrpc::start_admin_http;
+use near_jsonrpc_client::message::{from_slice, Message, Response};
+use near_logger_utils::init_test_logger;
+use near_network::test_utils::{open_port, MockNetworkAdapter};
+use near_network::types::ReasonForBan;
+use near_network::NetworkRequests;
+use near_primitives::network::PeerId;
+
+pub mod test_utils;
+
+async fn send_request(addr: &str, method: &str, params: Value) -> Response {
+    let request = json!({"jsonrpc": "2.0", "id": "dontcare", "method": method, "params": params});
+    let mut response = awc::Client::new()
+        .post(format!("http://{}", addr))
+        .header("Content-Type", "application/json")
+        .send_json(&request)
+        .await
+        .unwrap();
+    let body = response.body().await.unwrap();
+    match from_slice(&body).unwrap() {
+        Message::Response(response) => response,
+        message => panic!("Unexpected message {:?}", message),
+    }
+}
+
+fn start_admin() -> (Arc<MockNetworkAdapter>, String) {
+    let network_adapter = Arc::new(MockNetworkAdapter::default());
+    let addr = format!("127.0.0.1:{}", open_port());
+    start_admin_http(addr.clone(), network_adapter.clone());
+    (network_adapter, addr)
+}
+
+/// Admin methods are forwarded to the network.
+#[test]
+fn test_admin_peer_requests() {
+    init_test_logger();
+
+    System::run(|| {
+        let (network_adapter, addr) = start_admin();
+        let peer_id: PeerId = SecretKey::from_seed(KeyType::ED25519, "peer").public_key().into();
+
+        actix::spawn(async move {
+            let response = send_request(&addr, "admin_ban_peer", json!([peer_id])).await;
+            assert_eq!(response.result.unwrap(), Value::Null);
+            assert_eq!(
+                network_adapter.pop(),
+                Some(NetworkRequests::BanPeer {
+                    peer_id: peer_id.clone(),
+                    ban_reason: ReasonForBan::None
+                })
+            );
+
+            let response = send_request(&addr, "admin_unban_peer", json!([peer_id])).await;
+            assert_eq!(response.result.unwrap(), Value::Null);
+            assert_eq!(
+                network_adapter.pop(),
+                Some(NetworkRequests::UnbanPeer { peer_id: peer_id.clone() })
+            );
+
+            let peer_info = format!("{}@127.0.0.1:24567", peer_id);
+            let response = send_request(&addr, "admin_connect_peer", json!([peer_info])).await;
+            assert_eq!(response.result.unwrap(), Value::Null);
+            match network_adapter.pop() {
+                Some(NetworkRequests::ConnectPeer(peer_info)) => {
+                    assert_eq!(peer_info.id, peer_id);
+                    assert_eq!(peer_info.addr, Some("127.0.0.1:24567".parse().unwrap()));
+                }
+                request => panic!("Unexpected request {:?}", request),
+            }
+
+            // Peers without an address can't be connected to.
+            let response =
+                send_request(&addr, "admin_connect_peer", json!([peer_id.to_string()])).await;
+            assert_eq!(response.result.unwrap_err().code, -32_602);
+            assert_eq!(network_adapter.pop(), None);
+            System::current().stop();
+        });
+    })
+    .unwrap();
+}
+
+/// Admin methods are not served by the public RPC.
+#[test]
+fn test_admin_methods_not_public() {
+    init_test_logger();
+
+    System::run(|| {
+        let (_view_client_addr, addr) = test_utils::start_all(test_utils::NodeType::NonValidator);
+
+        actix::spawn(async move {
+            let response = send_request(&addr, "admin_known_peers", json!([])).await;
+            assert_eq!(response.result.unwrap_err().code, -32_601);
+            System::current().stop();
+        });
+    })
+    .unwrap();
+}
@@ -0,0 +1,125 @@
+use actix::System;
+use actix_web::http::StatusCode;
+use serde_json::{json, Value};
+
+use near_jsonrpc_client::message::{from_slice, Message};
+use near_logger_utils::init_test_logger;
+
+pub mod test_utils;
+
+async fn send_batch_raw(addr: &str, batch: Value) -> (StatusCode, Vec<u8>) {
+    let mut response = awc::Client::new()
+        .post(format!("http://{}", addr))
+        .header("Content-Type", "application/json")
+        .send_json(&batch)
+        .await
+        .unwrap();
+    (response.status(), response.body().await.unwrap().to_vec())
+}
+
+async fn send_batch(addr: &str, batch: Value) -> Message {
+    let (_status, body) = send_batch_raw(addr, batch).await;
+    from_slice(&body).unwrap()
+}
+
+/// Batched requests are answered in order, with an error for every invalid entry.
+#[test]
+fn test_batch_request() {
+    init_test_logger();
+
+    System::run(|| {
+        let (_view_client_addr, addr) = test_utils::start_all(test_utils::NodeType::NonValidator);
+
+        actix::spawn(async move {
+            let batch = json!([
+                {"jsonrpc": "2.0", "id": "first", "method": "block", "params": [0]},
+                {"jsonrpc": "2.0", "id": "second", "method": "no_such_method", "params": []},
+                {"jsonrpc": "2.0", "id": "third", "method": "status", "params": []},
+                {"not": "a request"},
+            ]);
+            let responses = match send_batch(&addr, batch).await {
+                Message::Batch(responses) => responses,
+                message => panic!("Unexpected message {:?}", message),
+            };
+            assert_eq!(responses.len(), 4);
+            let responses: Vec<_> = responses
+                .into_iter()
+                .map(|message| match message {
+                    Message::Response(response) => response,
+                    message => panic!("Unexpected message {:?}", message),
+                })
+                .collect();
+            assert_eq!(responses[0].id, json!("first"));
+            assert_eq!(responses[0].result.as_ref().unwrap()["header"]["height"], json!(0));
+            assert_eq!(responses[1].id, json!("second"));
+            assert_eq!(responses[1].result.as_ref().unwrap_err().code, -32_601);
+            assert_eq!(responses[2].id, json!("third"));
+            assert_eq!(responses[2].result.as_ref().unwrap()["chain_id"], json!("unittest"));
+            assert_eq!(responses[3].id, Value::Null);
+            assert_eq!(responses[3].result.as_ref().unwrap_err().code, -32_600);
+            System::current().stop();
+        });
+    })
+    .unwrap();
+}
+
+/// Batches above the configured size are rejected as a whole.
+#[test]
+fn test_batch_request_too_large() {
+    init_test_logger();
+
+    System::run(|| {
+        let (_view_client_addr, addr) = test_utils::start_all(test_utils::NodeType::NonValidator);
+
+        actix::spawn(async move {
+            let batch: Vec<_> = (0..101)
+                .map(|id| json!({"jsonrpc": "2.0", "id": id, "method": "status", "params": []}))
+                .collect();
+            match send_batch(&addr, Value::Array(batch)).await {
+                Message::Response(response) => {
+                    assert_eq!(response.result.unwrap_err().code, -32_602)
+                }
+                message => panic!("Unexpected message {:?}", message),
+            }
+            System::current().stop();
+        });
+    })
+    .unwrap();
+}
+
+/// Notifications in a batch are not answered, and a batch of only notifications gets no response.
+#[test]
+fn test_batch_request_with_notifications() {
+    init_test_logger();
+
+    System::run(|| {
+        let (_view_client_addr, addr) = test_utils::start_all(test_utils::NodeType::NonValidator);
+
+        actix::spawn(async move {
+            let batch = json!([
+                {"jsonrpc": "2.0", "method": "status", "params": []},
+                {"jsonrpc": "2.0", "id": "first", "method": "status", "params": []},
+                {"jsonrpc": "2.0", "method": "no_such_method", "params": []},
+            ]);
+            let responses = match send_batch(&addr, batch).await {
+                Message::Batch(responses) => responses,
+                message => panic!("Unexpected message {:?}", message),
+            };
+            assert_eq!(responses.len(), 1);
+            match &responses[0] {
+                Message::Response(response) => assert_eq!(response.id, json!("first")),
+                message => panic!("Unexpected message {:?}", message),
+            }
+
+            let batch = json!([
+                {"jsonrpc": "2.0", "method": "status", "params": []},
+                {"jsonrpc": "2.0", "method": "health", "params": []},
+            ]);
+            let (status, body) = send_batch_raw(&addr, batch).await;
+            assert_eq!(status, StatusCode::NO_CONTENT);
+            assert!(body.is_empty());
+            System::current().stop();
+        });
+    })
+    .unwrap();
+}
@@ -10,7 +10,9 @@ use near_logger_utils::init_test_logger;
 use near_network::test_utils::WaitOrTimeout;
 use near_primitives::account::{AccessKey, AccessKeyPermission};
 use near_primitives::hash::CryptoHash;
-use near_primitives::rpc::{RpcGenesisRecordsRequest, RpcPagination, RpcQueryRequest};
+use near_primitives::rpc::{
+    RpcAccountChangesInRangeRequest, RpcGenesisRecordsRequest, RpcPagination, RpcQueryRequest,
+};
 use near_primitives::types::{BlockId, BlockIdOrFinality, Finality, ShardId};
 use near_primitives::version::PROTOCOL_VERSION;
 use near_primitives::views::{QueryRequest, QueryResponseKind};
@@ -524,6 +526,40 @@ fn test_invalid_genesis_records_arguments() {
     });
 }
 
+/// Retrieve account changes over a range of blocks via JSON RPC.
+#[test]
+fn test_account_changes_in_range() {
+    test_with_client!(test_utils::NodeType::NonValidator, client, async move {
+        let changes = client
+            .EXPERIMENTAL_account_changes_in_range(RpcAccountChangesInRangeRequest {
+                account_ids: vec!["test1".to_string()],
+                from_block_height: 0,
+                to_block_height: 0,
+                limit: 100,
+            })
+            .await
+            .unwrap();
+        assert!(changes.blocks.iter().all(|block| block.block_height == 0));
+        assert_eq!(changes.next_block_height, None);
+    });
+}
+
+/// Check invalid block ranges of account changes via JSON RPC.
+#[test]
+fn test_account_changes_in_range_invalid_range() {
+    test_with_client!(test_utils::NodeType::NonValidator, client, async move {
+        let response = client
+            .EXPERIMENTAL_account_changes_in_range(RpcAccountChangesInRangeRequest {
+                account_ids: vec!["test1".to_string()],
+                from_block_height: 1,
+                to_block_height: 0,
+                limit: 100,
+            })
+            .await;
+        assert!(response.is_err());
+    });
+}
+
 /// Retrieve gas price
 #[test]
 fn test_gas_price_by_height() {
@@ -552,6 +588,20 @@ fn test_gas_price() {
     });
 }
 
+/// Retrieve pending transactions of a node without any transactions
+#[test]
+fn test_pending_transactions_empty() {
+    test_with_client!(test_utils::NodeType::NonValidator, client, async move {
+        let pending = client.EXPERIMENTAL_pending_transactions().await.unwrap();
+        assert!(pending.is_empty());
+        let pending = client
+            .EXPERIMENTAL_pending_transactions_for_account("test1".to_string())
+            .await
+            .unwrap();
+        assert!(pending.is_empty());
+    });
+}
+
 #[test]
 fn test_invalid_methods() {
     test_with_client!(test_utils::NodeType::NonValidator, client, async move {
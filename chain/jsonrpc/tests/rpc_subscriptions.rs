@@ -0,0 +1,142 @@
+use actix::System;
+use awc::ws::{Frame, Message as WsMessage};
+use futures::{SinkExt, StreamExt};
+use serde_json::json;
+
+use near_jsonrpc::RpcSubscriptionsConfig;
+use near_jsonrpc_client::message::{from_slice, Message};
+use near_logger_utils::init_test_logger;
+use near_primitives::rpc::RpcSubscriptionRequest;
+
+#[macro_use]
+pub mod test_utils;
+
+/// Subscribe to new blocks via WebSocket and receive a notification.
+#[test]
+fn test_subscribe_new_blocks() {
+    init_test_logger();
+
+    System::run(|| {
+        let (_view_client_addr, addr) = test_utils::start_all(test_utils::NodeType::Validator);
+
+        actix::spawn(async move {
+            let (_response, mut framed) =
+                awc::Client::new().ws(format!("ws://{}/ws", addr)).connect().await.unwrap();
+            let request = Message::request(
+                "subscribe".to_string(),
+                Some(serde_json::to_value(RpcSubscriptionRequest::NewBlocks).unwrap()),
+            );
+            framed.send(WsMessage::Text(serde_json::to_string(&request).unwrap())).await.unwrap();
+
+            let mut subscribed = false;
+            while let Some(Ok(frame)) = framed.next().await {
+                let bytes = match frame {
+                    Frame::Text(bytes) => bytes,
+                    _ => continue,
+                };
+                match from_slice(&bytes).unwrap() {
+                    Message::Response(response) => {
+                        assert_eq!(response.result.unwrap(), json!(0));
+                        subscribed = true;
+                    }
+                    Message::Notification(notification) => {
+                        assert!(subscribed);
+                        assert_eq!(notification.method, "subscription");
+                        let params = notification.params.unwrap();
+                        assert_eq!(params["subscription"], json!(0));
+                        assert!(params["result"]["header"]["height"].is_u64());
+                        break;
+                    }
+                    message => panic!("Unexpected message {:?}", message),
+                }
+            }
+            assert!(subscribed);
+            System::current().stop();
+        });
+    })
+    .unwrap();
+}
+
+/// Unknown methods and malformed subscriptions are rejected.
+#[test]
+fn test_subscribe_invalid_request() {
+    init_test_logger();
+
+    System::run(|| {
+        let (_view_client_addr, addr) = test_utils::start_all(test_utils::NodeType::NonValidator);
+
+        actix::spawn(async move {
+            let (_response, mut framed) =
+                awc::Client::new().ws(format!("ws://{}/ws", addr)).connect().await.unwrap();
+            for (method, params) in
+                vec![("block", json!([0])), ("subscribe", json!({"subscription": "unknown"}))]
+            {
+                let request = Message::request(method.to_string(), Some(params));
+                framed
+                    .send(WsMessage::Text(serde_json::to_string(&request).unwrap()))
+                    .await
+                    .unwrap();
+                match framed.next().await {
+                    Some(Ok(Frame::Text(bytes))) => match from_slice(&bytes).unwrap() {
+                        Message::Response(response) => assert!(response.result.is_err()),
+                        message => panic!("Unexpected message {:?}", message),
+                    },
+                    frame => panic!("Unexpected frame {:?}", frame),
+                }
+            }
+            System::current().stop();
+        });
+    })
+    .unwrap();
+}
+
+/// A connection that exceeds the limit of subscriptions of all connections is told so and closed.
+#[test]
+fn test_subscribe_over_node_limit_closes_connection() {
+    init_test_logger();
+
+    System::run(|| {
+        let (_view_client_addr, addr) = test_utils::start_all_with_subscriptions_config(
+            test_utils::NodeType::NonValidator,
+            RpcSubscriptionsConfig { max_subscriptions: 1, ..Default::default() },
+        );
+
+        actix::spawn(async move {
+            let (_response, mut framed) =
+                awc::Client::new().ws(format!("ws://{}/ws", addr)).connect().await.unwrap();
+            let request = Message::request(
+                "subscribe".to_string(),
+                Some(serde_json::to_value(RpcSubscriptionRequest::FinalBlocks).unwrap()),
+            );
+            for _ in 0..2 {
+                framed
+                    .send(WsMessage::Text(serde_json::to_string(&request).unwrap()))
+                    .await
+                    .unwrap();
+            }
+
+            let mut responses = vec![];
+            let mut closed = false;
+            while let Some(Ok(frame)) = framed.next().await {
+                match frame {
+                    Frame::Text(bytes) => match from_slice(&bytes).unwrap() {
+                        Message::Response(response) => responses.push(response.result),
+                        Message::Notification(_) => {}
+                        message => panic!("Unexpected message {:?}", message),
+                    },
+                    Frame::Close(_) => {
+                        closed = true;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            assert_eq!(responses.len(), 2);
+            assert_eq!(responses[0].as_ref().unwrap(), &json!(0));
+            assert!(responses[1].is_err());
+            assert!(closed);
+            System::current().stop();
+        });
+    })
+    .unwrap();
+}
@@ -5,7 +5,7 @@ use actix::Addr;
 use near_chain_configs::{Genesis, GenesisConfig};
 use near_client::test_utils::setup_no_network_with_validity_period;
 use near_client::ViewClientActor;
-use near_jsonrpc::{start_http, RpcConfig};
+use near_jsonrpc::{start_http, RpcConfig, RpcSubscriptionsConfig};
 use near_network::test_utils::open_port;
 use near_primitives::account::Account;
 use near_primitives::state_record::StateRecord;
@@ -29,6 +29,27 @@ pub fn start_all_with_validity_period(
     node_type: NodeType,
     transaction_validity_period: NumBlocks,
     enable_doomslug: bool,
+) -> (Addr<ViewClientActor>, String) {
+    start_all_with_config(
+        node_type,
+        transaction_validity_period,
+        enable_doomslug,
+        Default::default(),
+    )
+}
+
+pub fn start_all_with_subscriptions_config(
+    node_type: NodeType,
+    subscriptions_config: RpcSubscriptionsConfig,
+) -> (Addr<ViewClientActor>, String) {
+    start_all_with_config(node_type, 100, false, subscriptions_config)
+}
+
+fn start_all_with_config(
+    node_type: NodeType,
+    transaction_validity_period: NumBlocks,
+    enable_doomslug: bool,
+    subscriptions_config: RpcSubscriptionsConfig,
 ) -> (Addr<ViewClientActor>, String) {
     let records = (0_u128..200)
         .map(|x| StateRecord::Account {
@@ -53,7 +74,7 @@ pub fn start_all_with_validity_period(
     let addr = format!("127.0.0.1:{}", open_port());
 
     start_http(
-        RpcConfig::new(&addr),
+        RpcConfig { subscriptions_config, ..RpcConfig::new(&addr) },
         Arc::new(genesis),
         client_addr.clone(),
         view_client_addr.clone(),
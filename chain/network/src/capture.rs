@@ -0,0 +1,232 @@
+//! Capture of all messages exchanged with peers, to inspect or replay network incidents.
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+use actix::{Actor, ActorContext, Context, Handler, Message};
+use borsh::{BorshDeserialize, BorshSerialize};
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use near_primitives::network::PeerId;
+
+use crate::types::PeerMessage;
+
+const CAPTURE_FILE_PREFIX: &str = "capture-";
+const CAPTURE_FILE_EXTENSION: &str = ".borsh";
+
+fn default_max_file_size() -> u64 {
+    100 << 20
+}
+
+fn default_max_files() -> usize {
+    10
+}
+
+/// Configuration of the message capture.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CaptureConfig {
+    /// Directory to write the capture files to.
+    pub dir: PathBuf,
+    /// A new file is started when the current one reaches this size in bytes.
+    #[serde(default = "default_max_file_size")]
+    pub max_file_size: u64,
+    /// Number of files to keep. The oldest file is removed when a new one is started.
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+/// Message sent to or received from a peer.
+#[derive(BorshSerialize, BorshDeserialize, Message, Clone, Debug, PartialEq)]
+#[rtype(result = "()")]
+pub struct CapturedMessage {
+    /// Time in nanoseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub direction: Direction,
+    /// Other end of the connection. Unknown for messages received before the handshake.
+    pub peer_id: Option<PeerId>,
+    pub message: PeerMessage,
+}
+
+/// Writes captured messages into a rotating set of files. Every message is stored as its length
+/// as little endian `u32` followed by the Borsh serialized `CapturedMessage`.
+/// Runs as an actor on its own arbiter, so that peers don't wait on the disk.
+pub struct MessageCapture {
+    config: CaptureConfig,
+    file: Option<File>,
+    file_size: u64,
+}
+
+impl MessageCapture {
+    pub fn new(config: CaptureConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.dir)?;
+        Ok(Self { config, file: None, file_size: 0 })
+    }
+
+    pub fn write(&mut self, message: &CapturedMessage) -> io::Result<()> {
+        let bytes = message.try_to_vec()?;
+        let mut record = Vec::with_capacity(bytes.len() + 4);
+        record.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        record.extend(bytes);
+
+        if self.file.is_none() {
+            self.open(message.timestamp)?;
+        }
+        self.file.as_mut().unwrap().write_all(&record)?;
+        self.file_size += record.len() as u64;
+        if self.file_size >= self.config.max_file_size {
+            self.file = None;
+        }
+        Ok(())
+    }
+
+    /// Start a new file named after the first message in it and remove the oldest files.
+    fn open(&mut self, timestamp: u64) -> io::Result<()> {
+        let name = format!("{}{:020}{}", CAPTURE_FILE_PREFIX, timestamp, CAPTURE_FILE_EXTENSION);
+        self.file =
+            Some(OpenOptions::new().create(true).append(true).open(self.config.dir.join(name))?);
+        self.file_size = 0;
+
+        let files = capture_files(&self.config.dir)?;
+        if files.len() > self.config.max_files {
+            for path in files[..files.len() - self.config.max_files].iter() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Actor for MessageCapture {
+    type Context = Context<Self>;
+}
+
+impl Handler<CapturedMessage> for MessageCapture {
+    type Result = ();
+
+    fn handle(&mut self, msg: CapturedMessage, ctx: &mut Self::Context) {
+        if let Err(err) = self.write(&msg) {
+            error!(target: "network", "Failed to capture message, stopping capture: {}", err);
+            ctx.stop();
+        }
+    }
+}
+
+/// Capture files in the directory, from the oldest to the newest.
+pub fn capture_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_capture = path.file_name().and_then(|name| name.to_str()).map_or(false, |name| {
+            name.starts_with(CAPTURE_FILE_PREFIX) && name.ends_with(CAPTURE_FILE_EXTENSION)
+        });
+        if is_capture {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Read the messages of a single capture file. A message cut short at the end of the file, e.g.
+/// because the node was killed while writing it, is ignored.
+pub fn read_capture_file(path: &Path) -> io::Result<Vec<CapturedMessage>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut messages = vec![];
+    loop {
+        let mut len_bytes = [0u8; 4];
+        let mut bytes = vec![];
+        match reader.read_exact(&mut len_bytes) {
+            Ok(()) => {
+                bytes.resize(u32::from_le_bytes(len_bytes) as usize, 0);
+                match reader.read_exact(&mut bytes) {
+                    Ok(()) => messages.push(CapturedMessage::try_from_slice(&bytes)?),
+                    Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+                    Err(err) => return Err(err),
+                }
+            }
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(messages)
+}
+
+/// Read all messages captured in the directory, in the order they were captured.
+pub fn read_capture(dir: &Path) -> io::Result<Vec<CapturedMessage>> {
+    let mut messages = vec![];
+    for path in capture_files(dir)? {
+        messages.extend(read_capture_file(&path)?);
+    }
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use crate::test_utils::peer_id_from_seed;
+
+    use super::*;
+
+    fn captured_message(timestamp: u64) -> CapturedMessage {
+        CapturedMessage {
+            timestamp,
+            direction: if timestamp % 2 == 0 { Direction::Sent } else { Direction::Received },
+            peer_id: Some(peer_id_from_seed("test")),
+            message: PeerMessage::PeersRequest,
+        }
+    }
+
+    #[test]
+    fn test_capture_roundtrip() {
+        let dir = tempdir().unwrap();
+        let config =
+            CaptureConfig { dir: dir.path().to_path_buf(), max_file_size: 1 << 20, max_files: 10 };
+        let mut capture = MessageCapture::new(config).unwrap();
+        let messages: Vec<_> = (0..10).map(captured_message).collect();
+        for message in messages.iter() {
+            capture.write(message).unwrap();
+        }
+        assert_eq!(capture_files(dir.path()).unwrap().len(), 1);
+        assert_eq!(read_capture(dir.path()).unwrap(), messages);
+    }
+
+    #[test]
+    fn test_capture_rotation() {
+        let dir = tempdir().unwrap();
+        let record_size = captured_message(0).try_to_vec().unwrap().len() as u64 + 4;
+        let config = CaptureConfig {
+            dir: dir.path().to_path_buf(),
+            max_file_size: 2 * record_size,
+            max_files: 3,
+        };
+        let mut capture = MessageCapture::new(config).unwrap();
+        let messages: Vec<_> = (0..10).map(captured_message).collect();
+        for message in messages.iter() {
+            capture.write(message).unwrap();
+        }
+        // Two messages per file, only the last three files are kept.
+        assert_eq!(capture_files(dir.path()).unwrap().len(), 3);
+        assert_eq!(read_capture(dir.path()).unwrap(), messages[4..].to_vec());
+    }
+
+    #[test]
+    fn test_capture_truncated_file() {
+        let dir = tempdir().unwrap();
+        let config =
+            CaptureConfig { dir: dir.path().to_path_buf(), max_file_size: 1 << 20, max_files: 10 };
+        let mut capture = MessageCapture::new(config).unwrap();
+        capture.write(&captured_message(0)).unwrap();
+        let path = capture_files(dir.path()).unwrap().pop().unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[100, 0, 0, 0, 1, 2]).unwrap();
+        assert_eq!(read_capture_file(&path).unwrap(), vec![captured_message(0)]);
+    }
+}
@@ -1,12 +1,19 @@
-use std::io::{Error, ErrorKind};
+use std::io::{Error, ErrorKind, Read};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use bytes::{Buf, BufMut, BytesMut};
 use tokio_util::codec::{Decoder, Encoder};
+use tracing::warn;
 
 use crate::types::{PeerMessage, ReasonForBan};
 
 const NETWORK_MESSAGE_MAX_SIZE: u32 = 512 << 20; // 512MB
+/// Compression level used for messages. Favours speed over ratio.
+const COMPRESSION_LEVEL: i32 = 3;
+/// First byte of the message, when compression is negotiated, for messages sent as is.
+const UNCOMPRESSED_MESSAGE: u8 = 0;
+/// First byte of the message, when compression is negotiated, for zstd compressed messages.
+const ZSTD_COMPRESSED_MESSAGE: u8 = 1;
 
 pub struct Codec {
     max_length: u32,
@@ -74,6 +81,46 @@ pub fn bytes_to_peer_message(bytes: &[u8]) -> Result<PeerMessage, std::io::Error
     PeerMessage::try_from_slice(bytes)
 }
 
+/// Prefix the serialized message with its compression. Messages larger than `threshold` are
+/// compressed with zstd if that makes them smaller; without a threshold nothing is compressed.
+pub fn compress_message(bytes: Vec<u8>, threshold: Option<usize>) -> Vec<u8> {
+    if threshold.map_or(false, |threshold| bytes.len() > threshold) {
+        match zstd::stream::encode_all(&bytes[..], COMPRESSION_LEVEL) {
+            Ok(compressed) if compressed.len() < bytes.len() => {
+                let mut result = Vec::with_capacity(compressed.len() + 1);
+                result.push(ZSTD_COMPRESSED_MESSAGE);
+                result.extend(compressed);
+                return result;
+            }
+            Ok(_) => {}
+            Err(err) => warn!(target: "network", "Failed to compress message: {}", err),
+        }
+    }
+    let mut result = Vec::with_capacity(bytes.len() + 1);
+    result.push(UNCOMPRESSED_MESSAGE);
+    result.extend(bytes);
+    result
+}
+
+/// Reverse of `compress_message`. Fails on unknown compression or if the decompressed message is
+/// larger than the maximum message size.
+pub fn decompress_message(bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+    match bytes.split_first() {
+        Some((&UNCOMPRESSED_MESSAGE, message)) => Ok(message.to_vec()),
+        Some((&ZSTD_COMPRESSED_MESSAGE, compressed)) => {
+            let mut result = vec![];
+            zstd::stream::read::Decoder::new(compressed)?
+                .take(NETWORK_MESSAGE_MAX_SIZE as u64 + 1)
+                .read_to_end(&mut result)?;
+            if result.len() > NETWORK_MESSAGE_MAX_SIZE as usize {
+                return Err(Error::new(ErrorKind::InvalidData, "Decompressed message is too long"));
+            }
+            Ok(result)
+        }
+        _ => Err(Error::new(ErrorKind::InvalidData, "Unknown message compression")),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use near_crypto::{KeyType, SecretKey};
@@ -163,6 +210,27 @@ mod test {
         test_codec(msg);
     }
 
+    #[test]
+    fn test_compress_message() {
+        let bytes = peer_message_to_bytes(PeerMessage::PeersResponse(vec![PeerInfo::random(); 50]))
+            .unwrap();
+
+        let uncompressed = compress_message(bytes.clone(), None);
+        assert_eq!(uncompressed.len(), bytes.len() + 1);
+        assert_eq!(decompress_message(&uncompressed).unwrap(), bytes);
+
+        let uncompressed = compress_message(bytes.clone(), Some(bytes.len()));
+        assert_eq!(uncompressed.len(), bytes.len() + 1);
+
+        let compressed = compress_message(bytes.clone(), Some(100));
+        assert!(compressed.len() < bytes.len());
+        assert_eq!(decompress_message(&compressed).unwrap(), bytes);
+
+        assert!(decompress_message(&[]).is_err());
+        assert!(decompress_message(&[ZSTD_COMPRESSED_MESSAGE, 1, 2, 3]).is_err());
+        assert!(decompress_message(&[2, 1, 2, 3]).is_err());
+    }
+
     #[test]
     fn test_account_id_bytes() {
         let account_id = "near0".to_string();
@@ -0,0 +1,224 @@
+use borsh::BorshSerialize;
+use chacha20poly1305::aead::generic_array::GenericArray;
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::ChaCha20Poly1305;
+use rand::rngs::OsRng;
+use x25519_dalek::{EphemeralSecret, PublicKey as X25519PublicKey};
+
+use near_crypto::SecretKey;
+use near_primitives::hash::{hash, CryptoHash};
+use near_primitives::network::PeerId;
+use near_primitives::version::ProtocolVersion;
+
+use crate::types::{KeyExchange, PeerType};
+
+/// Domain separator of the signed ephemeral key.
+const KEY_EXCHANGE_DOMAIN: &[u8] = b"near-peer-key-exchange";
+/// Domain separator of the derived session keys.
+const SESSION_KEY_DOMAIN: &[u8] = b"near-peer-session-key";
+
+fn peer_id_bytes(peer_id: &PeerId) -> Vec<u8> {
+    peer_id.try_to_vec().expect("Failed to serialize")
+}
+
+/// Hash of the ephemeral key signed by the sender. It covers the protocol versions both ends
+/// advertised in their handshakes, so that a handshake modified in transit is detected.
+fn key_exchange_hash(
+    ephemeral_key: &[u8; 32],
+    sender: &PeerId,
+    receiver: &PeerId,
+    sender_version: ProtocolVersion,
+    receiver_version: ProtocolVersion,
+) -> CryptoHash {
+    let mut data = KEY_EXCHANGE_DOMAIN.to_vec();
+    data.extend_from_slice(ephemeral_key);
+    data.extend(peer_id_bytes(sender));
+    data.extend(peer_id_bytes(receiver));
+    data.extend_from_slice(&sender_version.to_le_bytes());
+    data.extend_from_slice(&receiver_version.to_le_bytes());
+    hash(&data)
+}
+
+/// Generate an ephemeral key for the connection with `peer_id` and sign it with the node key.
+/// `node_version` and `peer_version` are the protocol versions of the handshakes of both ends.
+pub fn new_key_exchange(
+    secret_key: &SecretKey,
+    node_id: &PeerId,
+    peer_id: &PeerId,
+    node_version: ProtocolVersion,
+    peer_version: ProtocolVersion,
+) -> (EphemeralSecret, KeyExchange) {
+    let ephemeral_secret = EphemeralSecret::new(&mut OsRng);
+    let ephemeral_key = *X25519PublicKey::from(&ephemeral_secret).as_bytes();
+    let data = key_exchange_hash(&ephemeral_key, node_id, peer_id, node_version, peer_version);
+    let signature = secret_key.sign(data.as_ref());
+    (ephemeral_secret, KeyExchange { ephemeral_key, signature })
+}
+
+/// Check that the ephemeral key was signed by `peer_id` for the connection with this node, and
+/// that both ends saw the same protocol versions in the handshakes.
+pub fn verify_key_exchange(
+    key_exchange: &KeyExchange,
+    peer_id: &PeerId,
+    node_id: &PeerId,
+    peer_version: ProtocolVersion,
+    node_version: ProtocolVersion,
+) -> bool {
+    let data = key_exchange_hash(
+        &key_exchange.ephemeral_key,
+        peer_id,
+        node_id,
+        peer_version,
+        node_version,
+    );
+    key_exchange.signature.verify(data.as_ref(), &peer_id.public_key())
+}
+
+/// Cipher for a single direction of the session. Every frame uses the next nonce.
+struct Cipher {
+    cipher: ChaCha20Poly1305,
+    nonce: u64,
+}
+
+impl Cipher {
+    fn new(key: CryptoHash) -> Self {
+        Self {
+            cipher: ChaCha20Poly1305::new(GenericArray::clone_from_slice(key.as_ref())),
+            nonce: 0,
+        }
+    }
+
+    fn next_nonce(&mut self) -> [u8; 12] {
+        let mut nonce = [0; 12];
+        nonce[4..].copy_from_slice(&self.nonce.to_le_bytes());
+        self.nonce += 1;
+        nonce
+    }
+}
+
+/// Encrypted and authenticated session with a peer.
+pub struct Session {
+    send: Cipher,
+    receive: Cipher,
+}
+
+impl Session {
+    /// Derive the session keys from our ephemeral secret and the key exchange of the peer.
+    /// Both ends derive the same keys, with send and receive directions swapped.
+    pub fn new(
+        ephemeral_secret: EphemeralSecret,
+        key_exchange: &KeyExchange,
+        node_id: &PeerId,
+        peer_id: &PeerId,
+        peer_type: PeerType,
+    ) -> Self {
+        let our_key = *X25519PublicKey::from(&ephemeral_secret).as_bytes();
+        let shared_secret =
+            ephemeral_secret.diffie_hellman(&X25519PublicKey::from(key_exchange.ephemeral_key));
+        // The node that started the connection is the initiator.
+        let (initiator, responder, initiator_key, responder_key) = match peer_type {
+            PeerType::Outbound => (node_id, peer_id, our_key, key_exchange.ephemeral_key),
+            PeerType::Inbound => (peer_id, node_id, key_exchange.ephemeral_key, our_key),
+        };
+        let session_key = |direction: u8| {
+            let mut data = SESSION_KEY_DOMAIN.to_vec();
+            data.extend_from_slice(shared_secret.as_bytes());
+            data.extend(peer_id_bytes(initiator));
+            data.extend(peer_id_bytes(responder));
+            data.extend_from_slice(&initiator_key);
+            data.extend_from_slice(&responder_key);
+            data.push(direction);
+            hash(&data)
+        };
+        let initiator_cipher = Cipher::new(session_key(0));
+        let responder_cipher = Cipher::new(session_key(1));
+        match peer_type {
+            PeerType::Outbound => Self { send: initiator_cipher, receive: responder_cipher },
+            PeerType::Inbound => Self { send: responder_cipher, receive: initiator_cipher },
+        }
+    }
+
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> Vec<u8> {
+        let nonce = self.send.next_nonce();
+        self.send
+            .cipher
+            .encrypt(GenericArray::from_slice(&nonce), plaintext)
+            .expect("Encryption never fails")
+    }
+
+    /// Decrypt the next frame. Returns `None` if it was not encrypted by the peer for this
+    /// session, or was modified or reordered.
+    pub fn decrypt(&mut self, ciphertext: &[u8]) -> Option<Vec<u8>> {
+        let nonce = self.receive.next_nonce();
+        self.receive.cipher.decrypt(GenericArray::from_slice(&nonce), ciphertext).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_crypto::KeyType;
+    use near_primitives::version::PROTOCOL_VERSION;
+
+    use super::*;
+
+    fn key_and_id(seed: &str) -> (SecretKey, PeerId) {
+        let secret_key = SecretKey::from_seed(KeyType::ED25519, seed);
+        let peer_id = secret_key.public_key().into();
+        (secret_key, peer_id)
+    }
+
+    fn sessions() -> (Session, Session) {
+        let (key_a, id_a) = key_and_id("a");
+        let (key_b, id_b) = key_and_id("b");
+        let v = PROTOCOL_VERSION;
+        let (secret_a, exchange_a) = new_key_exchange(&key_a, &id_a, &id_b, v, v);
+        let (secret_b, exchange_b) = new_key_exchange(&key_b, &id_b, &id_a, v, v);
+        assert!(verify_key_exchange(&exchange_a, &id_a, &id_b, v, v));
+        assert!(verify_key_exchange(&exchange_b, &id_b, &id_a, v, v));
+        (
+            Session::new(secret_a, &exchange_b, &id_a, &id_b, PeerType::Outbound),
+            Session::new(secret_b, &exchange_a, &id_b, &id_a, PeerType::Inbound),
+        )
+    }
+
+    #[test]
+    fn test_key_exchange_signature() {
+        let (key_a, id_a) = key_and_id("a");
+        let (_, id_b) = key_and_id("b");
+        let (_, id_c) = key_and_id("c");
+        let v = PROTOCOL_VERSION;
+        let (_, exchange) = new_key_exchange(&key_a, &id_a, &id_b, v, v - 1);
+        assert!(verify_key_exchange(&exchange, &id_a, &id_b, v, v - 1));
+        // The key exchange is only valid for the connection it was created for.
+        assert!(!verify_key_exchange(&exchange, &id_a, &id_c, v, v - 1));
+        assert!(!verify_key_exchange(&exchange, &id_c, &id_b, v, v - 1));
+        // And only if both ends saw the same protocol versions.
+        assert!(!verify_key_exchange(&exchange, &id_a, &id_b, v, v));
+        assert!(!verify_key_exchange(&exchange, &id_a, &id_b, v - 1, v - 1));
+    }
+
+    #[test]
+    fn test_session_roundtrip() {
+        let (mut session_a, mut session_b) = sessions();
+        for i in 0..3u8 {
+            let message = vec![i; 100];
+            let ciphertext = session_a.encrypt(&message);
+            assert_ne!(ciphertext[..message.len()], message[..]);
+            assert_eq!(session_b.decrypt(&ciphertext), Some(message.clone()));
+            assert_eq!(session_a.decrypt(&session_b.encrypt(&message)), Some(message));
+        }
+    }
+
+    #[test]
+    fn test_session_rejects_tampered_and_replayed() {
+        let (mut session_a, mut session_b) = sessions();
+        let mut ciphertext = session_a.encrypt(b"block");
+        ciphertext[0] ^= 1;
+        assert_eq!(session_b.decrypt(&ciphertext), None);
+
+        let (mut session_a, mut session_b) = sessions();
+        let ciphertext = session_a.encrypt(b"block");
+        assert!(session_b.decrypt(&ciphertext).is_some());
+        assert_eq!(session_b.decrypt(&ciphertext), None);
+    }
+}
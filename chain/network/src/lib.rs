@@ -8,11 +8,14 @@ pub use types::{
 };
 
 mod cache;
+pub mod capture;
 mod codec;
+mod encryption;
 pub mod metrics;
 mod peer;
 mod peer_manager;
 pub mod peer_store;
+mod pending;
 mod rate_counter;
 #[cfg(feature = "metric_recorder")]
 pub mod recorder;
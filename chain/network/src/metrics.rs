@@ -66,6 +66,56 @@ lazy_static! {
             "near_drop_message_unknown_account",
             "Total messages dropped because target account is not known"
         );
+    pub static ref BROADCAST_MESSAGES_SKIPPED: near_metrics::Result<IntCounter> =
+        try_create_int_counter(
+            "near_broadcast_messages_skipped",
+            "Total broadcast messages not sent to an active peer because of smart broadcast"
+        );
+    pub static ref BROADCAST_BYTES_SAVED: near_metrics::Result<IntCounter> =
+        try_create_int_counter(
+            "near_broadcast_bytes_saved",
+            "Total bytes not sent to active peers because of smart broadcast"
+        );
+    pub static ref ROUTED_MESSAGES_PENDING: near_metrics::Result<IntGauge> =
+        try_create_int_gauge(
+            "near_routed_messages_pending",
+            "Routed messages waiting for a route to their target"
+        );
+    pub static ref ROUTED_MESSAGES_RETRIED: near_metrics::Result<IntCounter> =
+        try_create_int_counter(
+            "near_routed_messages_retried",
+            "Total routed messages sent after waiting for a route to their target"
+        );
+    pub static ref ROUTED_MESSAGES_DROPPED_QUEUE_FULL: near_metrics::Result<IntCounter> =
+        try_create_int_counter(
+            "near_routed_messages_dropped_queue_full",
+            "Total routed messages dropped because too many messages were waiting for a route"
+        );
+    pub static ref ROUTED_MESSAGES_DROPPED_TTL: near_metrics::Result<IntCounter> =
+        try_create_int_counter(
+            "near_routed_messages_dropped_ttl",
+            "Total routed messages dropped because no route was found in time"
+        );
+    pub static ref PEER_DATA_SENT_UNCOMPRESSED_BYTES: near_metrics::Result<IntCounter> =
+        try_create_int_counter(
+            "near_peer_data_sent_uncompressed_bytes",
+            "Total size of messages sent to peers supporting compression, before compression"
+        );
+    pub static ref PEER_DATA_SENT_COMPRESSED_BYTES: near_metrics::Result<IntCounter> =
+        try_create_int_counter(
+            "near_peer_data_sent_compressed_bytes",
+            "Total size of messages sent to peers supporting compression, after compression"
+        );
+    pub static ref PEER_DATA_RECEIVED_UNCOMPRESSED_BYTES: near_metrics::Result<IntCounter> =
+        try_create_int_counter(
+            "near_peer_data_received_uncompressed_bytes",
+            "Total size of messages received from peers supporting compression, after decompression"
+        );
+    pub static ref PEER_DATA_RECEIVED_COMPRESSED_BYTES: near_metrics::Result<IntCounter> =
+        try_create_int_counter(
+            "near_peer_data_received_compressed_bytes",
+            "Total size of messages received from peers supporting compression, before decompression"
+        );
     pub static ref RECEIVED_INFO_ABOUT_ITSELF: near_metrics::Result<IntCounter> = try_create_int_counter("received_info_about_itself", "Number of times a peer tried to connect to itself");
 }
 
@@ -8,23 +8,35 @@ use actix::{
     Actor, ActorContext, ActorFuture, Addr, Arbiter, AsyncContext, Context, ContextFutureSpawner,
     Handler, Recipient, Running, StreamHandler, WrapFuture,
 };
+use chrono::Utc;
 use tracing::{debug, error, info, trace, warn};
+use x25519_dalek::EphemeralSecret;
 
+use near_crypto::SecretKey;
 use near_metrics;
 use near_primitives::block::GenesisId;
+use near_primitives::challenge::ChallengeBody;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::PeerId;
 use near_primitives::unwrap_option_or_return;
-use near_primitives::utils::DisplayOption;
-use near_primitives::version::{FIRST_BACKWARD_COMPATIBLE_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use near_primitives::utils::{to_timestamp, DisplayOption};
+use near_primitives::version::{
+    ProtocolVersion, CHALLENGES_PROTOCOL_VERSION, ENCRYPTED_TRANSPORT_PROTOCOL_VERSION,
+    FIRST_BACKWARD_COMPATIBLE_PROTOCOL_VERSION, MESSAGE_COMPRESSION_PROTOCOL_VERSION,
+    PROTOCOL_VERSION,
+};
 
-use crate::codec::{bytes_to_peer_message, peer_message_to_bytes, Codec};
+use crate::capture::{CapturedMessage, Direction, MessageCapture};
+use crate::codec::{
+    bytes_to_peer_message, compress_message, decompress_message, peer_message_to_bytes, Codec,
+};
+use crate::encryption::{new_key_exchange, verify_key_exchange, Session};
 use crate::rate_counter::RateCounter;
 #[cfg(feature = "metric_recorder")]
 use crate::recorder::{PeerMessageMetadata, Status};
 use crate::routing::{Edge, EdgeInfo};
 use crate::types::{
-    Ban, Consolidate, ConsolidateResponse, Handshake, HandshakeFailureReason,
+    Ban, Consolidate, ConsolidateResponse, Handshake, HandshakeFailureReason, KeyExchange,
     NetworkClientMessages, NetworkClientResponses, NetworkRequests, NetworkViewClientMessages,
     NetworkViewClientResponses, PeerChainInfo, PeerInfo, PeerManagerRequest, PeerMessage,
     PeerRequest, PeerResponse, PeerStatsResult, PeerStatus, PeerType, PeersRequest, PeersResponse,
@@ -44,6 +56,21 @@ const MAX_TRACK_SIZE: usize = 30;
 /// Maximum number of messages per minute from single peer.
 // TODO: current limit is way to high due to us sending lots of messages during sync.
 const MAX_PEER_MSG_PER_MIN: u64 = std::u64::MAX;
+/// Maximum number of messages queued while the encrypted session is being established.
+const MAX_ENCRYPTION_QUEUE_SIZE: usize = 1000;
+
+/// Whether the message has the same layout for a peer with the given protocol version.
+/// Chunk state challenges carry the chunks and receipts of the block before the previous one
+/// since `CHALLENGES_PROTOCOL_VERSION`, older peers can't decode them.
+fn is_supported_by_peer(msg: &PeerMessage, peer_version: ProtocolVersion) -> bool {
+    match msg {
+        PeerMessage::Challenge(challenge) => match challenge.body {
+            ChallengeBody::ChunkState(_) => peer_version >= CHALLENGES_PROTOCOL_VERSION,
+            _ => true,
+        },
+        _ => true,
+    }
+}
 
 /// Internal structure to keep a circular queue within a tracker with unique hashes.
 struct CircularUniqueQueue {
@@ -128,6 +155,25 @@ impl Tracker {
     }
 }
 
+/// Encryption of the connection with the peer.
+enum Encryption {
+    /// Messages are sent in plain text, either before the handshake or to peers that don't
+    /// support encrypted transport.
+    Disabled,
+    /// Waiting for the key exchange of the peer. Messages other than the handshake and the key
+    /// exchange are queued until the session is established.
+    Pending {
+        ephemeral_secret: EphemeralSecret,
+        /// Our key exchange, if it was not sent yet.
+        key_exchange: Option<KeyExchange>,
+        /// Protocol version from the handshake of the peer.
+        peer_version: ProtocolVersion,
+        queue: Vec<PeerMessage>,
+    },
+    /// All messages are encrypted and authenticated with the session keys.
+    Enabled(Session),
+}
+
 pub struct Peer {
     /// This node's id and address (either listening or socket address).
     pub node_info: PeerInfo,
@@ -139,6 +185,18 @@ pub struct Peer {
     pub peer_type: PeerType,
     /// Peer status.
     pub peer_status: PeerStatus,
+    /// Protocol version of the peer from its handshake.
+    protocol_version: ProtocolVersion,
+    /// This node's secret key, used to sign the key exchange.
+    secret_key: SecretKey,
+    /// Encryption of the connection.
+    encryption: Encryption,
+    /// Whether messages in the encrypted session are prefixed with their compression.
+    compression: bool,
+    /// Messages larger than this are compressed, if compression is used with the peer.
+    compression_threshold: Option<usize>,
+    /// Writer of all messages exchanged with the peer, if capture is enabled.
+    capture: Option<Addr<MessageCapture>>,
     /// Framed wrapper to send messages through the TCP connection.
     framed: FramedWrite<WriteHalf, Codec>,
     /// Handshake timeout.
@@ -169,6 +227,9 @@ impl Peer {
         peer_addr: SocketAddr,
         peer_info: Option<PeerInfo>,
         peer_type: PeerType,
+        secret_key: SecretKey,
+        compression_threshold: Option<usize>,
+        capture: Option<Addr<MessageCapture>>,
         framed: FramedWrite<WriteHalf, Codec>,
         handshake_timeout: Duration,
         peer_manager_addr: Addr<PeerManagerActor>,
@@ -183,6 +244,12 @@ impl Peer {
             peer_info: peer_info.into(),
             peer_type,
             peer_status: PeerStatus::Connecting,
+            protocol_version: FIRST_BACKWARD_COMPATIBLE_PROTOCOL_VERSION,
+            secret_key,
+            encryption: Encryption::Disabled,
+            compression: false,
+            compression_threshold,
+            capture,
             framed,
             handshake_timeout,
             peer_manager_addr,
@@ -206,7 +273,28 @@ impl Peer {
             || self.tracker.sent_bytes.count_per_min() > MAX_PEER_MSG_PER_MIN
     }
 
-    fn send_message(&mut self, msg: PeerMessage) {
+    fn send_message(&mut self, ctx: &mut Context<Peer>, msg: PeerMessage) {
+        // Wait for the encrypted session before sending anything besides the handshake.
+        if let Encryption::Pending { queue, .. } = &mut self.encryption {
+            match msg {
+                PeerMessage::Handshake(_)
+                | PeerMessage::HandshakeFailure(_, _)
+                | PeerMessage::KeyExchange(_) => {}
+                msg => {
+                    if queue.len() >= MAX_ENCRYPTION_QUEUE_SIZE {
+                        info!(target: "network", "Too many messages queued for {} before the encrypted session. Disconnect.", self.peer_info);
+                        ctx.stop();
+                        return;
+                    }
+                    queue.push(msg);
+                    return;
+                }
+            }
+        }
+        if !is_supported_by_peer(&msg, self.protocol_version) {
+            debug!(target: "network", "Skip sending {} to {} with protocol version {}", msg.msg_variant(), self.peer_info, self.protocol_version);
+            return;
+        }
         // Skip sending block and headers if we received it or header from this peer.
         // Record block requests in tracker.
         match &msg {
@@ -214,6 +302,7 @@ impl Peer {
             PeerMessage::BlockRequest(h) => self.tracker.push_request(*h),
             _ => (),
         };
+        self.capture_message(Direction::Sent, &msg);
         #[cfg(feature = "metric_recorder")]
         let metadata = {
             let mut metadata: PeerMessageMetadata = (&msg).into();
@@ -227,7 +316,33 @@ impl Peer {
         match peer_message_to_bytes(msg) {
             Ok(bytes) => {
                 #[cfg(feature = "metric_recorder")]
-                self.peer_manager_addr.do_send(metadata.set_size(bytes.len()));
+                let mut metadata = metadata.set_size(bytes.len());
+                let bytes = match &mut self.encryption {
+                    Encryption::Enabled(session) => {
+                        let bytes = if self.compression {
+                            near_metrics::inc_counter_by(
+                                &metrics::PEER_DATA_SENT_UNCOMPRESSED_BYTES,
+                                bytes.len() as i64,
+                            );
+                            let compressed = compress_message(bytes, self.compression_threshold);
+                            near_metrics::inc_counter_by(
+                                &metrics::PEER_DATA_SENT_COMPRESSED_BYTES,
+                                compressed.len() as i64,
+                            );
+                            #[cfg(feature = "metric_recorder")]
+                            {
+                                metadata = metadata.set_compressed_size(compressed.len());
+                            }
+                            compressed
+                        } else {
+                            bytes
+                        };
+                        session.encrypt(&bytes)
+                    }
+                    _ => bytes,
+                };
+                #[cfg(feature = "metric_recorder")]
+                self.peer_manager_addr.do_send(metadata);
                 self.tracker.increment_sent(bytes.len() as u64);
                 self.framed.write(bytes);
             }
@@ -235,6 +350,17 @@ impl Peer {
         };
     }
 
+    fn capture_message(&self, direction: Direction, msg: &PeerMessage) {
+        if let Some(capture) = &self.capture {
+            capture.do_send(CapturedMessage {
+                timestamp: to_timestamp(Utc::now()),
+                direction,
+                peer_id: self.peer_id(),
+                message: msg.clone(),
+            });
+        }
+    }
+
     fn fetch_client_chain_info(&mut self, ctx: &mut Context<Peer>) {
         ctx.wait(
             self.view_client_addr
@@ -263,7 +389,7 @@ impl Peer {
         self.view_client_addr
             .send(NetworkViewClientMessages::GetChainInfo)
             .into_actor(self)
-            .then(move |res, act, _ctx| match res {
+            .then(move |res, act, ctx| match res {
                 Ok(NetworkViewClientResponses::ChainInfo {
                     genesis_id,
                     height,
@@ -276,7 +402,8 @@ impl Peer {
                         PeerChainInfo { genesis_id, height, tracked_shards },
                         act.edge_info.as_ref().unwrap().clone(),
                     );
-                    act.send_message(PeerMessage::Handshake(handshake));
+                    act.send_message(ctx, PeerMessage::Handshake(handshake));
+                    act.send_key_exchange(ctx);
                     actix::fut::ready(())
                 }
                 Err(err) => {
@@ -288,6 +415,71 @@ impl Peer {
             .spawn(ctx);
     }
 
+    /// Start establishing the encrypted session with the peer.
+    fn start_encryption(&mut self, peer_version: ProtocolVersion) {
+        let (ephemeral_secret, key_exchange) = new_key_exchange(
+            &self.secret_key,
+            &self.node_id(),
+            &self.peer_id().unwrap(),
+            PROTOCOL_VERSION,
+            peer_version,
+        );
+        self.encryption = Encryption::Pending {
+            ephemeral_secret,
+            key_exchange: Some(key_exchange),
+            peer_version,
+            queue: vec![],
+        };
+    }
+
+    /// Send our key exchange if the session is pending and it was not sent yet.
+    fn send_key_exchange(&mut self, ctx: &mut Context<Peer>) {
+        if let Encryption::Pending { key_exchange, .. } = &mut self.encryption {
+            if let Some(key_exchange) = key_exchange.take() {
+                self.send_message(ctx, PeerMessage::KeyExchange(key_exchange));
+            }
+        }
+    }
+
+    /// Establish the encrypted session from the key exchange of the peer and send queued messages.
+    fn receive_key_exchange(&mut self, ctx: &mut Context<Peer>, key_exchange: KeyExchange) {
+        let peer_id = self.peer_id().unwrap();
+        let peer_version = match &self.encryption {
+            Encryption::Pending { key_exchange: None, peer_version, .. } => *peer_version,
+            _ => {
+                info!(target: "network", "{:?}: Unexpected key exchange from {}. Disconnect.", self.node_id(), peer_id);
+                ctx.stop();
+                return;
+            }
+        };
+        if !verify_key_exchange(
+            &key_exchange,
+            &peer_id,
+            &self.node_id(),
+            peer_version,
+            PROTOCOL_VERSION,
+        ) {
+            warn!(target: "network", "Received invalid signature on key exchange. Disconnecting peer {}", peer_id);
+            self.ban_peer(ctx, ReasonForBan::InvalidSignature);
+            return;
+        }
+        if let Encryption::Pending { ephemeral_secret, queue, .. } =
+            std::mem::replace(&mut self.encryption, Encryption::Disabled)
+        {
+            self.encryption = Encryption::Enabled(Session::new(
+                ephemeral_secret,
+                &key_exchange,
+                &self.node_id(),
+                &peer_id,
+                self.peer_type,
+            ));
+            debug!(target: "network", "{:?}: Encrypted session established with {}", self.node_id(), peer_id);
+            for msg in queue {
+                self.send_message(ctx, msg);
+            }
+        }
+    }
+
     fn ban_peer(&mut self, ctx: &mut Context<Peer>, ban_reason: ReasonForBan) {
         warn!(target: "network", "Banning peer {} for {:?}", self.peer_info, ban_reason);
         self.peer_status = PeerStatus::Banned(ban_reason);
@@ -365,7 +557,7 @@ impl Peer {
         self.view_client_addr
             .send(view_client_message)
             .into_actor(self)
-            .then(move |res, act, _ctx| {
+            .then(move |res, act, ctx| {
                 // Ban peer if client thinks received data is bad.
                 match res {
                     Ok(NetworkViewClientResponses::TxStatus(tx_result)) => {
@@ -391,10 +583,10 @@ impl Peer {
                     }
                     Ok(NetworkViewClientResponses::Block(block)) => {
                         // MOO need protocol version
-                        act.send_message(PeerMessage::Block(*block))
+                        act.send_message(ctx, PeerMessage::Block(*block))
                     }
                     Ok(NetworkViewClientResponses::BlockHeaders(headers)) => {
-                        act.send_message(PeerMessage::BlockHeaders(headers))
+                        act.send_message(ctx, PeerMessage::BlockHeaders(headers))
                     }
                     Err(err) => {
                         error!(
@@ -417,81 +609,24 @@ impl Peer {
         let peer_id = unwrap_option_or_return!(self.peer_id());
 
         // Wrap peer message into what client expects.
-        let network_client_msg = match msg {
+        let was_requested = match &msg {
             PeerMessage::Block(block) => {
                 near_metrics::inc_counter(&metrics::PEER_BLOCK_RECEIVED_TOTAL);
                 let block_hash = *block.hash();
                 self.tracker.push_received(block_hash);
                 self.chain_info.height = max(self.chain_info.height, block.header().height());
-                NetworkClientMessages::Block(block, peer_id, self.tracker.has_request(&block_hash))
+                self.tracker.has_request(&block_hash)
             }
-            PeerMessage::Transaction(transaction) => {
+            PeerMessage::Transaction(_) => {
                 near_metrics::inc_counter(&metrics::PEER_TRANSACTION_RECEIVED_TOTAL);
-                NetworkClientMessages::Transaction {
-                    transaction,
-                    is_forwarded: false,
-                    check_only: false,
-                }
+                false
             }
-            PeerMessage::BlockHeaders(headers) => {
-                NetworkClientMessages::BlockHeaders(headers, peer_id)
-            }
-            // All Routed messages received at this point are for us.
-            PeerMessage::Routed(routed_message) => {
-                let msg_hash = routed_message.hash();
-
-                match routed_message.body {
-                    RoutedMessageBody::BlockApproval(approval) => {
-                        NetworkClientMessages::BlockApproval(approval, peer_id)
-                    }
-                    RoutedMessageBody::ForwardTx(transaction) => {
-                        NetworkClientMessages::Transaction {
-                            transaction,
-                            is_forwarded: true,
-                            check_only: false,
-                        }
-                    }
-
-                    RoutedMessageBody::StateResponse(info) => {
-                        NetworkClientMessages::StateResponse(info)
-                    }
-                    RoutedMessageBody::PartialEncodedChunkRequest(request) => {
-                        NetworkClientMessages::PartialEncodedChunkRequest(request, msg_hash)
-                    }
-                    RoutedMessageBody::PartialEncodedChunkResponse(response) => {
-                        NetworkClientMessages::PartialEncodedChunkResponse(response)
-                    }
-                    RoutedMessageBody::PartialEncodedChunk(partial_encoded_chunk) => {
-                        NetworkClientMessages::PartialEncodedChunk(partial_encoded_chunk)
-                    }
-                    RoutedMessageBody::Ping(_)
-                    | RoutedMessageBody::Pong(_)
-                    | RoutedMessageBody::TxStatusRequest(_, _)
-                    | RoutedMessageBody::TxStatusResponse(_)
-                    | RoutedMessageBody::QueryRequest { .. }
-                    | RoutedMessageBody::QueryResponse { .. }
-                    | RoutedMessageBody::ReceiptOutcomeRequest(_)
-                    | RoutedMessageBody::ReceiptOutComeResponse(_)
-                    | RoutedMessageBody::StateRequestHeader(_, _)
-                    | RoutedMessageBody::StateRequestPart(_, _, _) => {
-                        error!(target: "network", "Peer receive_client_message received unexpected type: {:?}", routed_message);
-                        return;
-                    }
-                }
-            }
-            PeerMessage::Challenge(challenge) => NetworkClientMessages::Challenge(challenge),
-            PeerMessage::Handshake(_)
-            | PeerMessage::HandshakeFailure(_, _)
-            | PeerMessage::PeersRequest
-            | PeerMessage::PeersResponse(_)
-            | PeerMessage::RoutingTableSync(_)
-            | PeerMessage::LastEdge(_)
-            | PeerMessage::Disconnect
-            | PeerMessage::RequestUpdateNonce(_)
-            | PeerMessage::ResponseUpdateNonce(_)
-            | PeerMessage::BlockRequest(_)
-            | PeerMessage::BlockHeadersRequest(_) => {
-                error!(target: "network", "Peer receive_client_message received unexpected type: {:?}", msg);
+            _ => false,
+        };
+        let network_client_msg = match msg.into_client_message(peer_id, was_requested) {
+            Some(network_client_msg) => network_client_msg,
+            None => {
+                error!(target: "network", "Peer receive_client_message received unexpected type from {}", self.peer_info);
                 return;
             }
         };
@@ -599,10 +734,39 @@ impl StreamHandler<Result<Vec<u8>, ReasonForBan>> for Peer {
         near_metrics::inc_counter_by(&metrics::PEER_DATA_RECEIVED_BYTES, msg.len() as i64);
         near_metrics::inc_counter(&metrics::PEER_MESSAGE_RECEIVED_TOTAL);
 
-        #[cfg(feature = "metric_recorder")]
-        let msg_size = msg.len();
-
         self.tracker.increment_received(msg.len() as u64);
+        let msg = match &mut self.encryption {
+            Encryption::Enabled(session) => match session.decrypt(&msg) {
+                Some(msg) => msg,
+                None => {
+                    info!(target: "network", "Received frame that failed decryption from {}. Disconnect.", self.peer_info);
+                    ctx.stop();
+                    return;
+                }
+            },
+            _ => msg,
+        };
+        let (msg, compressed_size) = match self.encryption {
+            Encryption::Enabled(_) if self.compression => match decompress_message(&msg) {
+                Ok(decompressed) => (decompressed, Some(msg.len())),
+                Err(err) => {
+                    info!(target: "network", "Received invalid compressed data from {}: {}. Disconnect.", self.peer_info, err);
+                    ctx.stop();
+                    return;
+                }
+            },
+            _ => (msg, None),
+        };
+        if let Some(compressed_size) = compressed_size {
+            near_metrics::inc_counter_by(
+                &metrics::PEER_DATA_RECEIVED_COMPRESSED_BYTES,
+                compressed_size as i64,
+            );
+            near_metrics::inc_counter_by(
+                &metrics::PEER_DATA_RECEIVED_UNCOMPRESSED_BYTES,
+                msg.len() as i64,
+            );
+        }
         let peer_msg = match bytes_to_peer_message(&msg) {
             Ok(peer_msg) => peer_msg,
             Err(err) => {
@@ -613,13 +777,40 @@ impl StreamHandler<Result<Vec<u8>, ReasonForBan>> for Peer {
 
         trace!(target: "network", "Received message: {}", peer_msg);
 
+        // Once both ends support encryption, only the handshake and the key exchange are accepted
+        // in plain text.
+        if let Encryption::Pending { .. } = self.encryption {
+            match peer_msg {
+                PeerMessage::Handshake(_)
+                | PeerMessage::HandshakeFailure(_, _)
+                | PeerMessage::KeyExchange(_) => {}
+                _ => {
+                    info!(target: "network", "Received plain text message from {} before the encrypted session. Disconnect.", self.peer_info);
+                    ctx.stop();
+                    return;
+                }
+            }
+        }
+
+        if !is_supported_by_peer(&peer_msg, self.protocol_version) {
+            debug!(target: "network", "Dropping {} from {} with protocol version {}", peer_msg.msg_variant(), self.peer_info, self.protocol_version);
+            return;
+        }
+
+        self.capture_message(Direction::Received, &peer_msg);
+
         self.on_receive_message();
 
         #[cfg(feature = "metric_recorder")]
         {
             let mut metadata: PeerMessageMetadata = (&peer_msg).into();
-            metadata =
-                metadata.set_size(msg_size).set_target(self.node_id()).set_status(Status::Received);
+            metadata = metadata
+                .set_size(msg.len())
+                .set_target(self.node_id())
+                .set_status(Status::Received);
+            if let Some(compressed_size) = compressed_size {
+                metadata = metadata.set_compressed_size(compressed_size);
+            }
 
             if let Some(peer_id) = self.peer_id() {
                 metadata = metadata.set_source(peer_id);
@@ -669,10 +860,13 @@ impl StreamHandler<Result<Vec<u8>, ReasonForBan>> for Peer {
 
                 if handshake.version < FIRST_BACKWARD_COMPATIBLE_PROTOCOL_VERSION {
                     debug!(target: "network", "Received connection from node with different network protocol version.");
-                    self.send_message(PeerMessage::HandshakeFailure(
-                        self.node_info.clone(),
-                        HandshakeFailureReason::ProtocolVersionMismatch(PROTOCOL_VERSION),
-                    ));
+                    self.send_message(
+                        ctx,
+                        PeerMessage::HandshakeFailure(
+                            self.node_info.clone(),
+                            HandshakeFailureReason::ProtocolVersionMismatch(PROTOCOL_VERSION),
+                        ),
+                    );
                     return;
                     // Connection will be closed by a handshake timeout
                 }
@@ -686,10 +880,13 @@ impl StreamHandler<Result<Vec<u8>, ReasonForBan>> for Peer {
 
                 if handshake.target_peer_id != self.node_info.id {
                     debug!(target: "network", "Received handshake from {:?} to {:?} but I am {:?}", handshake.peer_id, handshake.target_peer_id, self.node_info.id);
-                    self.send_message(PeerMessage::HandshakeFailure(
-                        self.node_info.clone(),
-                        HandshakeFailureReason::InvalidTarget,
-                    ));
+                    self.send_message(
+                        ctx,
+                        PeerMessage::HandshakeFailure(
+                            self.node_info.clone(),
+                            HandshakeFailureReason::InvalidTarget,
+                        ),
+                    );
                     return;
                     // Connection will be closed by a handshake timeout
                 }
@@ -729,6 +926,7 @@ impl StreamHandler<Result<Vec<u8>, ReasonForBan>> for Peer {
                         actor: ctx.address(),
                         peer_info: peer_info.clone(),
                         peer_type: self.peer_type,
+                        peer_addr: self.peer_addr,
                         chain_info: handshake.chain_info.clone(),
                         this_edge_info: self.edge_info.clone(),
                         other_edge_info: handshake.edge_info.clone(),
@@ -739,16 +937,26 @@ impl StreamHandler<Result<Vec<u8>, ReasonForBan>> for Peer {
                             Ok(ConsolidateResponse::Accept(edge_info)) => {
                                 act.peer_info = Some(peer_info).into();
                                 act.peer_status = PeerStatus::Ready;
+                                act.protocol_version = handshake.version;
+                                if handshake.version >= ENCRYPTED_TRANSPORT_PROTOCOL_VERSION {
+                                    act.start_encryption(handshake.version);
+                                }
+                                // Compression starts together with the encrypted session.
+                                act.compression =
+                                    handshake.version >= MESSAGE_COMPRESSION_PROTOCOL_VERSION;
                                 // Respond to handshake if it's inbound and connection was consolidated.
+                                // Inbound peer sends its key exchange right after the handshake.
                                 if act.peer_type == PeerType::Inbound {
                                     act.edge_info = edge_info;
                                     act.send_handshake(ctx);
+                                } else {
+                                    act.send_key_exchange(ctx);
                                 }
                                 actix::fut::ready(())
                             },
                             Ok(ConsolidateResponse::InvalidNonce(edge)) => {
                                 debug!(target: "network", "{:?}: Received invalid nonce from peer {:?} sending evidence.", act.node_id(), act.peer_addr);
-                                act.send_message(PeerMessage::LastEdge(*edge));
+                                act.send_message(ctx, PeerMessage::LastEdge(*edge));
                                 actix::fut::ready(())
                             }
                             _ => {
@@ -798,12 +1006,15 @@ impl StreamHandler<Result<Vec<u8>, ReasonForBan>> for Peer {
                 // Received handshake after already have seen handshake from this peer.
                 debug!(target: "network", "Duplicate handshake from {}", self.peer_info);
             }
+            (_, PeerStatus::Ready, PeerMessage::KeyExchange(key_exchange)) => {
+                self.receive_key_exchange(ctx, key_exchange);
+            }
             (_, PeerStatus::Ready, PeerMessage::PeersRequest) => {
-                self.peer_manager_addr.send(PeersRequest {}).into_actor(self).then(|res, act, _ctx| {
+                self.peer_manager_addr.send(PeersRequest {}).into_actor(self).then(|res, act, ctx| {
                     if let Ok(peers) = res {
                         if !peers.peers.is_empty() {
                             debug!(target: "network", "Peers request from {}: sending {} peers.", act.peer_info, peers.peers.len());
-                            act.send_message(PeerMessage::PeersResponse(peers.peers));
+                            act.send_message(ctx, PeerMessage::PeersResponse(peers.peers));
                         }
                     }
                     actix::fut::ready(())
@@ -820,7 +1031,7 @@ impl StreamHandler<Result<Vec<u8>, ReasonForBan>> for Peer {
                 .then(|res, act, ctx| {
                     match res {
                         Ok(NetworkResponses::EdgeUpdate(edge)) => {
-                            act.send_message(PeerMessage::ResponseUpdateNonce(*edge));
+                            act.send_message(ctx, PeerMessage::ResponseUpdateNonce(*edge));
                         }
                         Ok(NetworkResponses::BanPeer(reason_for_ban)) => {
                             act.ban_peer(ctx, reason_for_ban);
@@ -883,10 +1094,10 @@ impl StreamHandler<Result<Vec<u8>, ReasonForBan>> for Peer {
 impl Handler<SendMessage> for Peer {
     type Result = ();
 
-    fn handle(&mut self, msg: SendMessage, _: &mut Self::Context) {
+    fn handle(&mut self, msg: SendMessage, ctx: &mut Self::Context) {
         #[cfg(feature = "delay_detector")]
         let _d = DelayDetector::new("send message".into());
-        self.send_message(msg.message);
+        self.send_message(ctx, msg.message);
     }
 }
 
@@ -1,7 +1,7 @@
 use rand::seq::SliceRandom;
 use std::cmp;
 use std::collections::{HashMap, HashSet};
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -12,6 +12,8 @@ use actix::{
     Actor, ActorFuture, Addr, Arbiter, AsyncContext, Context, ContextFutureSpawner, Handler,
     Recipient, Running, StreamHandler, SyncArbiter, SyncContext, SystemService, WrapFuture,
 };
+use borsh::BorshSerialize;
+use cached::{Cached, SizedCache};
 use chrono::Utc;
 use futures::task::Poll;
 use futures::{future, Stream, StreamExt};
@@ -25,10 +27,12 @@ use near_primitives::types::AccountId;
 use near_primitives::utils::from_timestamp;
 use near_store::Store;
 
+use crate::capture::MessageCapture;
 use crate::codec::Codec;
 use crate::metrics;
 use crate::peer::Peer;
 use crate::peer_store::{PeerStore, TrustLevel};
+use crate::pending::PendingMessages;
 #[cfg(feature = "metric_recorder")]
 use crate::recorder::{MetricRecorder, PeerMessageMetadata};
 use crate::routing::{Edge, EdgeInfo, EdgeType, ProcessEdgeResult, RoutingTable};
@@ -44,6 +48,7 @@ use crate::types::{
     EdgeList, KnownPeerState, NetworkClientMessages, NetworkConfig, NetworkRequests,
     NetworkResponses, PeerInfo,
 };
+use crate::utils::ip_subnet;
 #[cfg(feature = "delay_detector")]
 use delay_detector::DelayDetector;
 use metrics::NetworkMetrics;
@@ -58,6 +63,14 @@ const WAIT_ON_TRY_UPDATE_NONCE: u64 = 6_000;
 const WAIT_PEER_BEFORE_REMOVE: u64 = 6_000;
 /// Maximum number an edge can increase between oldest known edge and new proposed edge.
 const EDGE_NONCE_BUMP_ALLOWED: u64 = 1_000;
+/// Number of recently broadcast blocks and challenges to remember the receiving peers for.
+const BROADCAST_CACHE_SIZE: usize = 100;
+/// Maximum number of routed messages waiting for a route, for accounts and peers respectively.
+const MAX_PENDING_MESSAGES: usize = 10_000;
+/// Maximum number of routed messages waiting for a route to a single target.
+const MAX_PENDING_MESSAGES_PER_TARGET: usize = 100;
+/// How much time (in milliseconds) a routed message waits for a route before being dropped.
+const PENDING_MESSAGE_TTL: u64 = 10_000;
 /// Time to wait before sending ping to all reachable peers.
 #[cfg(feature = "metric_recorder")]
 const WAIT_BEFORE_PING: u64 = 20_000;
@@ -86,6 +99,8 @@ struct ActivePeer {
     connection_established_time: Instant,
     /// Who started connection. Inbound (other) or Outbound (us).
     peer_type: PeerType,
+    /// Address of the other end of the connection.
+    peer_addr: SocketAddr,
 }
 
 struct EdgeVerifier {}
@@ -124,12 +139,20 @@ pub struct PeerManagerActor {
     monitor_peers_attempts: u64,
     /// Active peers we have sent new edge update, but we haven't received response so far.
     pending_update_nonce_request: HashMap<PeerId, u64>,
+    /// Peers that recently broadcast blocks and challenges were sent to, used by smart broadcast.
+    broadcast_cache: SizedCache<CryptoHash, HashSet<PeerId>>,
+    /// Messages to accounts without a known owner, waiting for the account to be announced.
+    pending_account_messages: PendingMessages<AccountId, RoutedMessageBody>,
+    /// Messages to peers without a route, waiting for the routing table to find one.
+    pending_peer_messages: PendingMessages<PeerId, RoutedMessage>,
     /// Dynamic Prometheus metrics
     network_metrics: NetworkMetrics,
     /// Store all collected metrics from a node.
     #[cfg(feature = "metric_recorder")]
     metric_recorder: MetricRecorder,
     edge_verifier_pool: Addr<EdgeVerifier>,
+    /// Writes the messages exchanged with peers, if capture is enabled.
+    capture: Option<Addr<MessageCapture>>,
 }
 
 impl PeerManagerActor {
@@ -147,6 +170,13 @@ impl PeerManagerActor {
 
         let me: PeerId = config.public_key.clone().into();
         let routing_table = RoutingTable::new(me.clone(), store);
+        let capture = match config.capture.clone() {
+            Some(capture_config) => {
+                let capture = MessageCapture::new(capture_config)?;
+                Some(MessageCapture::start_in_arbiter(&Arbiter::new(), move |_ctx| capture))
+            }
+            None => None,
+        };
 
         #[cfg(feature = "metric_recorder")]
         let metric_recorder = MetricRecorder::default().set_me(me.clone());
@@ -162,10 +192,22 @@ impl PeerManagerActor {
             routing_table,
             monitor_peers_attempts: 0,
             pending_update_nonce_request: HashMap::new(),
+            broadcast_cache: SizedCache::with_size(BROADCAST_CACHE_SIZE),
+            pending_account_messages: PendingMessages::new(
+                MAX_PENDING_MESSAGES,
+                MAX_PENDING_MESSAGES_PER_TARGET,
+                Duration::from_millis(PENDING_MESSAGE_TTL),
+            ),
+            pending_peer_messages: PendingMessages::new(
+                MAX_PENDING_MESSAGES,
+                MAX_PENDING_MESSAGES_PER_TARGET,
+                Duration::from_millis(PENDING_MESSAGE_TTL),
+            ),
             network_metrics: NetworkMetrics::new(),
             edge_verifier_pool,
             #[cfg(feature = "metric_recorder")]
             metric_recorder,
+            capture,
         })
     }
 
@@ -184,6 +226,38 @@ impl PeerManagerActor {
         }
     }
 
+    /// Loopback addresses are allowed implicitly, since local networks run all nodes on one host.
+    fn is_ip_allowed(&self, ip: &IpAddr) -> bool {
+        ip.is_loopback() || self.config.ip_allowlist.contains(ip)
+    }
+
+    fn is_ip_banned(&self, ip: &IpAddr) -> bool {
+        !self.is_ip_allowed(ip) && self.peer_store.is_ip_banned(ip)
+    }
+
+    /// Check that there are not too many inbound connections from the same IP address or subnet.
+    fn is_inbound_ip_allowed(&self, ip: &IpAddr) -> bool {
+        if self.is_ip_allowed(ip) {
+            return true;
+        }
+        let subnet = ip_subnet(ip);
+        let mut same_ip = 0;
+        let mut same_subnet = 0;
+        for active_peer in self.active_peers.values() {
+            if active_peer.peer_type != PeerType::Inbound {
+                continue;
+            }
+            if active_peer.peer_addr.ip() == *ip {
+                same_ip += 1;
+            }
+            if ip_subnet(&active_peer.peer_addr.ip()) == subnet {
+                same_subnet += 1;
+            }
+        }
+        same_ip < self.config.max_inbound_connections_per_ip
+            && same_subnet < self.config.max_inbound_connections_per_subnet
+    }
+
     /// Register a direct connection to a new peer. This will be called after successfully
     /// establishing a connection with another peer. It become part of the active peers.
     ///
@@ -195,6 +269,7 @@ impl PeerManagerActor {
         full_peer_info: FullPeerInfo,
         edge_info: EdgeInfo,
         peer_type: PeerType,
+        peer_addr: SocketAddr,
         addr: Addr<Peer>,
         ctx: &mut Context<Self>,
     ) {
@@ -229,6 +304,7 @@ impl PeerManagerActor {
                 last_time_received_message: Instant::now(),
                 connection_established_time: Instant::now(),
                 peer_type,
+                peer_addr,
             },
         );
 
@@ -322,8 +398,20 @@ impl PeerManagerActor {
     /// Note: Use `try_ban_peer` if there might be a Peer instance still active.
     fn ban_peer(&mut self, ctx: &mut Context<Self>, peer_id: &PeerId, ban_reason: ReasonForBan) {
         warn!(target: "network", "Banning peer {:?} for {:?}", peer_id, ban_reason);
+        let ip = self.active_peers.get(peer_id).map(|active_peer| active_peer.peer_addr.ip());
         self.remove_active_peer(ctx, peer_id, None);
         unwrap_or_error!(self.peer_store.peer_ban(peer_id, ban_reason), "Failed to save peer data");
+        // Ban the address of abusive peers as well, so they can't come back with a new peer id.
+        // Other reasons may be honest mistakes, and the address may be shared with other nodes.
+        if let Some(ip) =
+            ip.filter(|ip| ban_reason == ReasonForBan::Abusive && !self.is_ip_allowed(ip))
+        {
+            warn!(target: "network", "Banning IP address {} for {:?}", ip, ban_reason);
+            unwrap_or_error!(
+                self.peer_store.ip_ban(ip, peer_id, ban_reason),
+                "Failed to save peer data"
+            );
+        }
     }
 
     /// Ban peer. Stop peer instance if it is still active,
@@ -358,6 +446,9 @@ impl PeerManagerActor {
         let account_id = self.config.account_id.clone();
         let server_addr = self.config.addr;
         let handshake_timeout = self.config.handshake_timeout;
+        let secret_key = self.config.secret_key.clone();
+        let compression_threshold = self.config.compression_threshold;
+        let capture = self.capture.clone();
         let client_addr = self.client_addr.clone();
         let view_client_addr = self.view_client_addr.clone();
 
@@ -380,6 +471,11 @@ impl PeerManagerActor {
             }
         };
 
+        if peer_type == PeerType::Inbound && self.is_ip_banned(&remote_addr.ip()) {
+            debug!(target: "network", "Dropping connection from banned address: {:?}", remote_addr);
+            return;
+        }
+
         let network_metrics = self.network_metrics.clone();
 
         // Start every peer actor on separate thread.
@@ -387,7 +483,6 @@ impl PeerManagerActor {
         Peer::start_in_arbiter(&arbiter, move |ctx| {
             let (read, write) = tokio::io::split(stream);
 
-            // TODO: check if peer is banned or known based on IP address and port.
             Peer::add_stream(
                 FramedRead::new(read, Codec::new())
                     .take_while(|x| match x {
@@ -406,6 +501,9 @@ impl PeerManagerActor {
                 remote_addr,
                 peer_info,
                 peer_type,
+                secret_key,
+                compression_threshold,
+                capture,
                 FramedWrite::new(write, Codec::new(), ctx),
                 handshake_timeout,
                 recipient,
@@ -508,10 +606,11 @@ impl PeerManagerActor {
             self.routing_table.process_edge(edge);
 
         if let Some(duration) = schedule_computation {
-            ctx.run_later(duration, |act, _ctx| {
+            ctx.run_later(duration, |act, ctx| {
                 act.routing_table.update();
                 #[cfg(feature = "metric_recorder")]
-                act.metric_recorder.set_graph(act.routing_table.get_raw_graph())
+                act.metric_recorder.set_graph(act.routing_table.get_raw_graph());
+                act.retry_pending_messages(ctx);
             });
         }
 
@@ -716,6 +815,20 @@ impl PeerManagerActor {
             unwrap_or_error!(self.peer_store.peer_unban(&peer_id), "Failed to unban a peer");
         }
 
+        for banned_ip in self.peer_store.banned_ips() {
+            let interval = unwrap_or_error!(
+                (Utc::now() - banned_ip.banned_at()).to_std(),
+                "Failed to convert time"
+            );
+            if interval > self.config.ban_window {
+                info!(target: "network", "Monitor peers: unbanned IP address {} after {:?}.", banned_ip.ip, interval);
+                unwrap_or_error!(
+                    self.peer_store.ip_unban(&banned_ip.ip),
+                    "Failed to unban an IP address"
+                );
+            }
+        }
+
         if self.is_outbound_bootstrap_needed() {
             if let Some(peer_info) = self.sample_random_peer(|peer_state| {
                 // Ignore connecting to ourself
@@ -753,11 +866,24 @@ impl PeerManagerActor {
     }
 
     /// Broadcast message to all active peers.
-    fn broadcast_message(&self, ctx: &mut Context<Self>, msg: SendMessage) {
-        // TODO(MarX, #1363): Implement smart broadcasting. (MST)
+    /// With smart broadcast enabled, blocks and challenges are only sent to a subset of active
+    /// peers, since every peer relays the ones it accepts to its own peers.
+    fn broadcast_message(&mut self, ctx: &mut Context<Self>, msg: SendMessage) {
+        let peer_ids: Vec<PeerId> = match &msg.message {
+            PeerMessage::Block(block) if self.config.smart_broadcast => {
+                self.smart_broadcast_targets(*block.hash(), &msg.message)
+            }
+            PeerMessage::Challenge(challenge) if self.config.smart_broadcast => {
+                self.smart_broadcast_targets(challenge.hash, &msg.message)
+            }
+            _ => self.active_peers.keys().cloned().collect(),
+        };
 
-        let mut requests: futures::stream::FuturesUnordered<_> =
-            self.active_peers.values().map(|peer| peer.addr.send(msg.clone())).collect();
+        let mut requests: futures::stream::FuturesUnordered<_> = peer_ids
+            .iter()
+            .filter_map(|peer_id| self.active_peers.get(peer_id))
+            .map(|peer| peer.addr.send(msg.clone()))
+            .collect();
 
         ctx.spawn(async move {
             while let Some(response) = requests.next().await {
@@ -768,10 +894,40 @@ impl PeerManagerActor {
         }.into_actor(self));
     }
 
+    /// Select active peers to send the broadcast message with given hash to, skipping the peers it
+    /// was already sent to. Records how many bytes are saved compared to sending it to every peer.
+    fn smart_broadcast_targets(&mut self, hash: CryptoHash, message: &PeerMessage) -> Vec<PeerId> {
+        let mut sent_to = self.broadcast_cache.cache_remove(&hash).unwrap_or_default();
+        let candidates: Vec<PeerId> = self
+            .active_peers
+            .keys()
+            .filter(|peer_id| !sent_to.contains(peer_id))
+            .cloned()
+            .collect();
+        let targets = self
+            .routing_table
+            .broadcast_targets(&candidates, self.config.broadcast_min_fanout as usize);
+
+        let skipped = self.active_peers.len() - targets.len();
+        if skipped > 0 {
+            let num_bytes = message.try_to_vec().map(|bytes| bytes.len()).unwrap_or_default();
+            near_metrics::inc_counter_by(&metrics::BROADCAST_MESSAGES_SKIPPED, skipped as i64);
+            near_metrics::inc_counter_by(
+                &metrics::BROADCAST_BYTES_SAVED,
+                (skipped * num_bytes) as i64,
+            );
+        }
+
+        sent_to.extend(targets.iter().cloned());
+        self.broadcast_cache.cache_set(hash, sent_to);
+        targets
+    }
+
     fn announce_account(&mut self, ctx: &mut Context<Self>, announce_account: AnnounceAccount) {
         debug!(target: "network", "{:?} Account announce: {:?}", self.config.account_id, announce_account);
         if !self.routing_table.contains_account(&announce_account) {
             self.routing_table.add_account(announce_account.clone());
+            self.retry_pending_messages(ctx);
             self.broadcast_message(
                 ctx,
                 SendMessage {
@@ -859,7 +1015,21 @@ impl PeerManagerActor {
                 self.send_message(ctx, peer_id, PeerMessage::Routed(msg))
             }
             Err(find_route_error) => {
-                // TODO(MarX, #1369): Message is dropped here. Define policy for this case.
+                if let PeerIdOrHash::PeerId(target) = &msg.target {
+                    if msg.body.retry_without_route() {
+                        debug!(target: "network", "{:?} No route to {:?}, message will be retried. Reason {:?}. Message {:?}",
+                              self.config.account_id,
+                              target,
+                              find_route_error,
+                              msg.body,
+                        );
+                        let queued =
+                            self.pending_peer_messages.push(target.clone(), msg, Instant::now());
+                        self.record_pending_message(queued);
+                        return false;
+                    }
+                }
+
                 self.network_metrics.inc(
                     NetworkMetrics::peer_message_dropped(strum::AsStaticRef::as_static(&msg.body))
                         .as_str(),
@@ -895,7 +1065,17 @@ impl PeerManagerActor {
         let target = match self.routing_table.account_owner(&account_id) {
             Ok(peer_id) => peer_id,
             Err(find_route_error) => {
-                // TODO(MarX, #1369): Message is dropped here. Define policy for this case.
+                if msg.retry_without_route() {
+                    debug!(target: "network", "{:?} Unknown account {}, message will be retried. Message {:?}",
+                           self.config.account_id,
+                           account_id,
+                           msg,
+                    );
+                    let queued =
+                        self.pending_account_messages.push(account_id.clone(), msg, Instant::now());
+                    self.record_pending_message(queued);
+                    return false;
+                }
                 near_metrics::inc_counter(&metrics::DROP_MESSAGE_UNKNOWN_ACCOUNT);
                 debug!(target: "network", "{:?} Drop message to {} Reason {:?}. Message {:?}",
                        self.config.account_id,
@@ -912,6 +1092,70 @@ impl PeerManagerActor {
         self.send_message_to_peer(ctx, msg)
     }
 
+    /// Update metrics after queueing a routed message that can't be sent yet.
+    /// `queued` is false if a pending message was dropped because the queue is full.
+    fn record_pending_message(&self, queued: bool) {
+        if !queued {
+            near_metrics::inc_counter(&metrics::ROUTED_MESSAGES_DROPPED_QUEUE_FULL);
+        }
+        self.update_pending_messages_gauge();
+    }
+
+    fn update_pending_messages_gauge(&self) {
+        near_metrics::set_gauge(
+            &metrics::ROUTED_MESSAGES_PENDING,
+            (self.pending_account_messages.len() + self.pending_peer_messages.len()) as i64,
+        );
+    }
+
+    /// Drop pending routed messages that have been waiting for a route for too long.
+    fn remove_expired_pending_messages(&mut self) {
+        let now = Instant::now();
+        let expired = self.pending_account_messages.remove_expired(now)
+            + self.pending_peer_messages.remove_expired(now);
+        near_metrics::inc_counter_by(&metrics::ROUTED_MESSAGES_DROPPED_TTL, expired as i64);
+    }
+
+    /// Periodically drop expired pending routed messages, so messages for targets that stay
+    /// unreachable don't wait for the next routing table update.
+    fn sweep_pending_messages(&mut self, ctx: &mut Context<Self>) {
+        self.remove_expired_pending_messages();
+        self.update_pending_messages_gauge();
+
+        ctx.run_later(Duration::from_millis(PENDING_MESSAGE_TTL), move |act, ctx| {
+            act.sweep_pending_messages(ctx);
+        });
+    }
+
+    /// Send pending routed messages whose target is reachable now, and drop expired ones.
+    fn retry_pending_messages(&mut self, ctx: &mut Context<Self>) {
+        self.remove_expired_pending_messages();
+
+        for account_id in self.pending_account_messages.targets() {
+            let reachable = self
+                .routing_table
+                .account_owner(&account_id)
+                .map_or(false, |peer_id| self.routing_table.peer_forwarding.contains_key(&peer_id));
+            if reachable {
+                for msg in self.pending_account_messages.take(&account_id) {
+                    near_metrics::inc_counter(&metrics::ROUTED_MESSAGES_RETRIED);
+                    self.send_message_to_account(ctx, &account_id, msg);
+                }
+            }
+        }
+
+        for peer_id in self.pending_peer_messages.targets() {
+            if self.routing_table.peer_forwarding.contains_key(&peer_id) {
+                for msg in self.pending_peer_messages.take(&peer_id) {
+                    near_metrics::inc_counter(&metrics::ROUTED_MESSAGES_RETRIED);
+                    self.send_signed_message_to_peer(ctx, msg);
+                }
+            }
+        }
+
+        self.update_pending_messages_gauge();
+    }
+
     fn sign_routed_message(&self, msg: RawRoutedMessage) -> RoutedMessage {
         msg.sign(self.peer_id.clone(), &self.config.secret_key, self.config.routed_message_ttl)
     }
@@ -998,6 +1242,7 @@ impl PeerManagerActor {
                     addr: None,
                 })
                 .collect(),
+            banned_ips: self.peer_store.banned_ips(),
             #[cfg(feature = "metric_recorder")]
             metric_recorder: self.metric_recorder.clone(),
         }
@@ -1058,6 +1303,9 @@ impl Actor for PeerManagerActor {
         // Start active peer stats querying.
         self.monitor_peer_stats(ctx);
 
+        // Periodically drop expired pending routed messages.
+        self.sweep_pending_messages(ctx);
+
         // Periodically ping all peers to determine latencies between pair of peers.
         #[cfg(feature = "metric_recorder")]
         self.ping_all_peers(ctx);
@@ -1244,16 +1492,14 @@ impl Handler<NetworkRequests> for PeerManagerActor {
                             let new_accounts = accounts
                                 .into_iter()
                                 .filter_map(|announce_account| {
-                                    if let Some(current_announce_account) =
-                                        act.routing_table.get_announce(&announce_account.account_id)
-                                    {
-                                        if announce_account.epoch_id == current_announce_account.epoch_id {
-                                            None
-                                        } else {
-                                            Some((announce_account, Some(current_announce_account.epoch_id)))
-                                        }
+                                    if act.routing_table.contains_account(&announce_account) {
+                                        None
                                     } else {
-                                        Some((announce_account, None))
+                                        let last_epoch = act.routing_table
+                                            .get_announces(&announce_account.account_id)
+                                            .first()
+                                            .map(|current_announce_account| current_announce_account.epoch_id.clone());
+                                        Some((announce_account, last_epoch))
                                     }
                                 })
                                 .collect();
@@ -1317,6 +1563,9 @@ impl Handler<NetworkRequests> for PeerManagerActor {
                                         for account in accounts.iter() {
                                             act.routing_table.add_account(account.clone());
                                         }
+                                        if !accounts.is_empty() {
+                                            act.retry_pending_messages(ctx);
+                                        }
 
                                         let new_data = SyncData { edges: new_edges, accounts };
 
@@ -1344,7 +1593,6 @@ impl Handler<NetworkRequests> for PeerManagerActor {
                 NetworkResponses::NoResponse
             }
             NetworkRequests::Challenge(challenge) => {
-                // TODO(illia): smarter routing?
                 self.broadcast_message(
                     ctx,
                     SendMessage { message: PeerMessage::Challenge(challenge) },
@@ -1400,6 +1648,37 @@ impl Handler<NetworkRequests> for PeerManagerActor {
                 let (pings, pongs) = self.routing_table.fetch_ping_pong();
                 NetworkResponses::PingPongInfo { pings, pongs }
             }
+            NetworkRequests::UnbanPeer { peer_id } => {
+                if self.peer_store.is_banned(&peer_id) {
+                    info!(target: "network", "Unbanning peer {:?}", peer_id);
+                    if let Err(err) = self.peer_store.peer_unban(&peer_id) {
+                        error!(target: "network", "Failed to unban a peer: {}", err);
+                    }
+                }
+                // The address the peer was connected from could be banned with it.
+                if let Err(err) = self.peer_store.ip_unban_peer(&peer_id) {
+                    error!(target: "network", "Failed to unban the address of a peer: {}", err);
+                }
+                NetworkResponses::NoResponse
+            }
+            NetworkRequests::ConnectPeer(peer_info) => {
+                if let Err(err) =
+                    self.peer_store.add_trusted_peer(peer_info.clone(), TrustLevel::Direct)
+                {
+                    error!(target: "network", "Failed to save peer data: {}", err);
+                }
+                if peer_info.id != self.peer_id
+                    && !self.active_peers.contains_key(&peer_info.id)
+                    && !self.outgoing_peers.contains(&peer_info.id)
+                {
+                    self.outgoing_peers.insert(peer_info.id.clone());
+                    ctx.notify(OutboundTcpConnect { peer_info });
+                }
+                NetworkResponses::NoResponse
+            }
+            NetworkRequests::FetchKnownPeers => NetworkResponses::KnownPeers(
+                self.peer_store.iter().map(|(_, peer_state)| peer_state.clone()).collect(),
+            ),
         }
     }
 }
@@ -1470,8 +1749,10 @@ impl Handler<Consolidate> for PeerManagerActor {
         #[cfg(feature = "delay_detector")]
         let _d = DelayDetector::new("consolidate".into());
         // Check if this is a blacklisted peer.
-        if msg.peer_info.addr.as_ref().map_or(true, |addr| self.is_blacklisted(addr)) {
-            debug!(target: "network", "Dropping connection from blacklisted peer or unknown address: {:?}", msg.peer_info);
+        if self.is_blacklisted(&msg.peer_addr)
+            || msg.peer_info.addr.as_ref().map_or(true, |addr| self.is_blacklisted(addr))
+        {
+            debug!(target: "network", "Dropping connection from blacklisted peer: {:?} {:?}", msg.peer_info, msg.peer_addr);
             return ConsolidateResponse::Reject;
         }
 
@@ -1480,6 +1761,11 @@ impl Handler<Consolidate> for PeerManagerActor {
             return ConsolidateResponse::Reject;
         }
 
+        if self.is_ip_banned(&msg.peer_addr.ip()) {
+            debug!(target: "network", "Dropping connection from banned address: {:?} {:?}", msg.peer_info, msg.peer_addr);
+            return ConsolidateResponse::Reject;
+        }
+
         // We already connected to this peer.
         if self.active_peers.contains_key(&msg.peer_info.id) {
             debug!(target: "network", "Dropping handshake (Active Peer). {:?} {:?}", self.peer_id, msg.peer_info.id);
@@ -1502,6 +1788,11 @@ impl Handler<Consolidate> for PeerManagerActor {
             return ConsolidateResponse::Reject;
         }
 
+        if msg.peer_type == PeerType::Inbound && !self.is_inbound_ip_allowed(&msg.peer_addr.ip()) {
+            debug!(target: "network", "Inbound connection dropped (too many connections from address). {:?} {:?}", msg.peer_info, msg.peer_addr);
+            return ConsolidateResponse::Reject;
+        }
+
         if msg.other_edge_info.nonce == 0 {
             debug!(target: "network", "Invalid nonce. It must be greater than 0. nonce={}", msg.other_edge_info.nonce);
             return ConsolidateResponse::Reject;
@@ -1539,6 +1830,7 @@ impl Handler<Consolidate> for PeerManagerActor {
             },
             edge_info,
             msg.peer_type,
+            msg.peer_addr,
             msg.actor,
             ctx,
         );
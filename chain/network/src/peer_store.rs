@@ -3,10 +3,10 @@ use std::collections::{
     HashMap,
 };
 use std::convert::TryInto;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 
-use borsh::BorshSerialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use chrono::Utc;
 use log::{debug, error};
 use rand::seq::SliceRandom;
@@ -16,7 +16,17 @@ use near_primitives::network::PeerId;
 use near_primitives::utils::to_timestamp;
 use near_store::{ColPeers, Store};
 
-use crate::types::{KnownPeerState, KnownPeerStatus, NetworkConfig, PeerInfo, ReasonForBan};
+use crate::types::{
+    BannedIp, KnownPeerState, KnownPeerStatus, NetworkConfig, PeerInfo, ReasonForBan,
+};
+
+/// Prefix of the keys of banned IP addresses in `ColPeers`.
+/// It doesn't collide with serialized peer ids, which start with the key type.
+const IP_BAN_KEY_PREFIX: &[u8] = b"ip_ban:";
+
+fn ip_ban_key(ip: &IpAddr) -> Vec<u8> {
+    [IP_BAN_KEY_PREFIX, ip.to_string().as_bytes()].concat()
+}
 
 /// Level of trust we have about a new (PeerId, Addr) pair.
 #[derive(Eq, PartialEq, Debug, Clone)]
@@ -52,6 +62,7 @@ pub struct PeerStore {
     // It can happens that some peers don't have known address, so
     // they will not be present in this list, otherwise they will be present.
     addr_peers: HashMap<SocketAddr, VerifiedPeer>,
+    banned_ips: HashMap<IpAddr, BannedIp>,
 }
 
 impl PeerStore {
@@ -61,6 +72,7 @@ impl PeerStore {
     ) -> Result<Self, Box<dyn std::error::Error>> {
         let mut peer_states = HashMap::default();
         let mut addr_peers = HashMap::default();
+        let mut banned_ips = HashMap::default();
 
         for peer_info in boot_nodes.iter() {
             if !peer_states.contains_key(&peer_info.id) {
@@ -85,6 +97,13 @@ impl PeerStore {
         for (key, value) in store.iter(ColPeers) {
             let key: Vec<u8> = key.into();
             let value: Vec<u8> = value.into();
+            if key.starts_with(IP_BAN_KEY_PREFIX) {
+                let ip: IpAddr = std::str::from_utf8(&key[IP_BAN_KEY_PREFIX.len()..])?.parse()?;
+                let (peer_id, reason, banned_at) =
+                    <(PeerId, ReasonForBan, u64)>::try_from_slice(&value)?;
+                banned_ips.insert(ip, BannedIp { ip, peer_id, reason, banned_at });
+                continue;
+            }
             let peer_id: PeerId = key.try_into()?;
             let mut peer_state: KnownPeerState = value.try_into()?;
             // Mark loaded node last seen to now, to avoid deleting them as soon as they are loaded.
@@ -107,9 +126,12 @@ impl PeerStore {
                     entry.insert(VerifiedPeer::new(peer_state.peer_info.id.clone()));
                     peer_states.insert(peer_id, peer_state);
                 }
+            } else if peer_state.status.is_banned() {
+                // Keep bans of peers with unknown address.
+                peer_states.insert(peer_id, peer_state);
             }
         }
-        Ok(PeerStore { store, peer_states, addr_peers })
+        Ok(PeerStore { store, peer_states, addr_peers, banned_ips })
     }
 
     pub fn len(&self) -> usize {
@@ -154,14 +176,25 @@ impl PeerStore {
         }
     }
 
+    /// Ban peer. Unknown peers are added to the store, so they are banned as soon as they show up.
     pub fn peer_ban(
         &mut self,
         peer_id: &PeerId,
         ban_reason: ReasonForBan,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        let peer_state = self.peer_states.entry(peer_id.clone()).or_insert_with(|| {
+            KnownPeerState::new(PeerInfo { id: peer_id.clone(), addr: None, account_id: None })
+        });
+        peer_state.last_seen = to_timestamp(Utc::now());
+        peer_state.status = KnownPeerStatus::Banned(ban_reason, to_timestamp(Utc::now()));
+        let mut store_update = self.store.store_update();
+        store_update.set_ser(ColPeers, &peer_id.try_to_vec()?, peer_state)?;
+        store_update.commit().map_err(|err| err.into())
+    }
+
+    pub fn peer_unban(&mut self, peer_id: &PeerId) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(peer_state) = self.peer_states.get_mut(peer_id) {
-            peer_state.last_seen = to_timestamp(Utc::now());
-            peer_state.status = KnownPeerStatus::Banned(ban_reason, to_timestamp(Utc::now()));
+            peer_state.status = KnownPeerStatus::NotConnected;
             let mut store_update = self.store.store_update();
             store_update.set_ser(ColPeers, &peer_id.try_to_vec()?, peer_state)?;
             store_update.commit().map_err(|err| err.into())
@@ -170,17 +203,60 @@ impl PeerStore {
         }
     }
 
-    pub fn peer_unban(&mut self, peer_id: &PeerId) -> Result<(), Box<dyn std::error::Error>> {
-        if let Some(peer_state) = self.peer_states.get_mut(peer_id) {
-            peer_state.status = KnownPeerStatus::NotConnected;
+    pub fn is_ip_banned(&self, ip: &IpAddr) -> bool {
+        self.banned_ips.contains_key(ip)
+    }
+
+    /// Ban IP address of the banned peer, so no peer is accepted from it regardless of its
+    /// peer id.
+    pub fn ip_ban(
+        &mut self,
+        ip: IpAddr,
+        peer_id: &PeerId,
+        ban_reason: ReasonForBan,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let banned_at = to_timestamp(Utc::now());
+        let mut store_update = self.store.store_update();
+        store_update.set_ser(
+            ColPeers,
+            &ip_ban_key(&ip),
+            &(peer_id.clone(), ban_reason, banned_at),
+        )?;
+        store_update.commit()?;
+        self.banned_ips
+            .insert(ip, BannedIp { ip, peer_id: peer_id.clone(), reason: ban_reason, banned_at });
+        Ok(())
+    }
+
+    pub fn ip_unban(&mut self, ip: &IpAddr) -> Result<(), Box<dyn std::error::Error>> {
+        if self.banned_ips.remove(ip).is_some() {
             let mut store_update = self.store.store_update();
-            store_update.set_ser(ColPeers, &peer_id.try_to_vec()?, peer_state)?;
+            store_update.delete(ColPeers, &ip_ban_key(ip));
             store_update.commit().map_err(|err| err.into())
         } else {
-            Err(format!("Peer {} is missing in the peer store", peer_id).into())
+            Err(format!("IP address {} is not banned", ip).into())
         }
     }
 
+    /// Unban all IP addresses banned together with the peer.
+    pub fn ip_unban_peer(&mut self, peer_id: &PeerId) -> Result<(), Box<dyn std::error::Error>> {
+        let ips: Vec<IpAddr> = self
+            .banned_ips
+            .values()
+            .filter(|banned_ip| &banned_ip.peer_id == peer_id)
+            .map(|banned_ip| banned_ip.ip)
+            .collect();
+        for ip in ips {
+            self.ip_unban(&ip)?;
+        }
+        Ok(())
+    }
+
+    /// Return all banned IP addresses.
+    pub fn banned_ips(&self) -> Vec<BannedIp> {
+        self.banned_ips.values().cloned().collect()
+    }
+
     fn find_peers<F>(&self, mut filter: F, count: u32) -> Vec<PeerInfo>
     where
         F: FnMut(&KnownPeerState) -> bool,
@@ -419,6 +495,70 @@ mod test {
         }
     }
 
+    #[test]
+    fn ban_unknown_peer() {
+        let store = create_test_store();
+        let mut peer_store = PeerStore::new(store.clone(), &[]).unwrap();
+        let peer_id = get_peer_id("node".to_string());
+        peer_store.peer_ban(&peer_id, ReasonForBan::None).unwrap();
+        assert!(peer_store.is_banned(&peer_id));
+
+        let mut peer_store = PeerStore::new(store, &[]).unwrap();
+        assert!(peer_store.is_banned(&peer_id));
+        peer_store.peer_unban(&peer_id).unwrap();
+        assert!(!peer_store.is_banned(&peer_id));
+    }
+
+    #[test]
+    fn ban_ip_store() {
+        let tmp_dir = tempfile::Builder::new().prefix("_test_store_ban_ip").tempdir().unwrap();
+        let peer_info = gen_peer_info(0);
+        let ip = peer_info.addr.unwrap().ip();
+        {
+            let store = create_store(tmp_dir.path().to_str().unwrap());
+            let mut peer_store = PeerStore::new(store, &[]).unwrap();
+            peer_store.peer_connected(&peer_info).unwrap();
+            peer_store.ip_ban(ip, &peer_info.id, ReasonForBan::Abusive).unwrap();
+            assert!(peer_store.is_ip_banned(&ip));
+        }
+        {
+            let store = create_store(tmp_dir.path().to_str().unwrap());
+            let mut peer_store = PeerStore::new(store, &[]).unwrap();
+            // Banned addresses are not loaded as peers.
+            assert_eq!(peer_store.len(), 1);
+            assert!(peer_store.is_ip_banned(&ip));
+            assert_eq!(peer_store.banned_ips()[0].peer_id, peer_info.id);
+            assert_eq!(peer_store.banned_ips()[0].reason, ReasonForBan::Abusive);
+            peer_store.ip_unban(&ip).unwrap();
+            assert!(!peer_store.is_ip_banned(&ip));
+        }
+        {
+            let store = create_store(tmp_dir.path().to_str().unwrap());
+            let peer_store = PeerStore::new(store, &[]).unwrap();
+            assert!(!peer_store.is_ip_banned(&ip));
+        }
+    }
+
+    #[test]
+    fn unban_ip_of_peer() {
+        let store = create_test_store();
+        let mut peer_store = PeerStore::new(store.clone(), &[]).unwrap();
+        let peer_info = gen_peer_info(0);
+        let other_ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let ip = peer_info.addr.unwrap().ip();
+        peer_store.peer_ban(&peer_info.id, ReasonForBan::Abusive).unwrap();
+        peer_store.ip_ban(ip, &peer_info.id, ReasonForBan::Abusive).unwrap();
+        peer_store
+            .ip_ban(other_ip, &get_peer_id("other".to_string()), ReasonForBan::Abusive)
+            .unwrap();
+
+        let mut peer_store = PeerStore::new(store, &[]).unwrap();
+        peer_store.ip_unban_peer(&peer_info.id).unwrap();
+        assert!(!peer_store.is_ip_banned(&ip));
+        // Addresses banned with other peers stay banned.
+        assert!(peer_store.is_ip_banned(&other_ip));
+    }
+
     fn check_exist(
         peer_store: &PeerStore,
         peer_id: &PeerId,
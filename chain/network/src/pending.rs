@@ -0,0 +1,144 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Bounded queue of messages waiting for a route to their target.
+///
+/// Messages are kept per target in arrival order. If a target has too many pending messages
+/// the oldest one is dropped, and new messages are rejected once the whole queue is full.
+/// Messages older than `ttl` are dropped when expired messages are removed.
+pub struct PendingMessages<K, V> {
+    messages: HashMap<K, VecDeque<(Instant, V)>>,
+    /// Total number of pending messages.
+    len: usize,
+    max_len: usize,
+    max_len_per_target: usize,
+    ttl: Duration,
+}
+
+impl<K, V> PendingMessages<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(max_len: usize, max_len_per_target: usize, ttl: Duration) -> Self {
+        Self { messages: HashMap::new(), len: 0, max_len, max_len_per_target, ttl }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Add message for the target. Return whether the message was queued without dropping any
+    /// pending message.
+    pub fn push(&mut self, target: K, message: V, now: Instant) -> bool {
+        let max_len_per_target = self.max_len_per_target;
+        if self.len >= self.max_len && !self.messages.contains_key(&target) {
+            return false;
+        }
+        let queue = self.messages.entry(target).or_insert_with(VecDeque::new);
+        if queue.len() >= max_len_per_target || self.len >= self.max_len {
+            queue.pop_front();
+            queue.push_back((now, message));
+            false
+        } else {
+            queue.push_back((now, message));
+            self.len += 1;
+            true
+        }
+    }
+
+    /// Targets with pending messages.
+    pub fn targets(&self) -> Vec<K> {
+        self.messages.keys().cloned().collect()
+    }
+
+    /// Remove all pending messages for the target.
+    pub fn take(&mut self, target: &K) -> Vec<V> {
+        let messages: Vec<_> = self
+            .messages
+            .remove(target)
+            .map_or_else(Vec::new, |queue| queue.into_iter().map(|(_, message)| message).collect());
+        self.len -= messages.len();
+        messages
+    }
+
+    /// Remove messages that have been pending for longer than `ttl`.
+    /// Return the number of removed messages.
+    pub fn remove_expired(&mut self, now: Instant) -> usize {
+        let ttl = self.ttl;
+        let mut removed = 0;
+        self.messages.retain(|_, queue| {
+            while queue
+                .front()
+                .map_or(false, |(time, _)| now.saturating_duration_since(*time) > ttl)
+            {
+                queue.pop_front();
+                removed += 1;
+            }
+            !queue.is_empty()
+        });
+        self.len -= removed;
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_and_take() {
+        let now = Instant::now();
+        let mut pending = PendingMessages::new(10, 10, Duration::from_secs(1));
+        assert!(pending.push(0, "a", now));
+        assert!(pending.push(0, "b", now));
+        assert!(pending.push(1, "c", now));
+        assert_eq!(pending.len(), 3);
+
+        assert_eq!(pending.take(&0), vec!["a", "b"]);
+        assert_eq!(pending.take(&0), Vec::<&str>::new());
+        assert_eq!(pending.targets(), vec![1]);
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn bounded_per_target() {
+        let now = Instant::now();
+        let mut pending = PendingMessages::new(10, 2, Duration::from_secs(1));
+        assert!(pending.push(0, "a", now));
+        assert!(pending.push(0, "b", now));
+        // The oldest message of the target is dropped.
+        assert!(!pending.push(0, "c", now));
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending.take(&0), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn bounded_total() {
+        let now = Instant::now();
+        let mut pending = PendingMessages::new(2, 2, Duration::from_secs(1));
+        assert!(pending.push(0, "a", now));
+        assert!(pending.push(1, "b", now));
+        // New targets are rejected when the queue is full.
+        assert!(!pending.push(2, "c", now));
+        // Known targets replace their oldest message.
+        assert!(!pending.push(1, "d", now));
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending.take(&1), vec!["d"]);
+        assert_eq!(pending.take(&2), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn remove_expired() {
+        let now = Instant::now();
+        let mut pending = PendingMessages::new(10, 10, Duration::from_secs(1));
+        pending.push(0, "a", now);
+        pending.push(0, "b", now + Duration::from_secs(2));
+        pending.push(1, "c", now);
+
+        assert_eq!(pending.remove_expired(now + Duration::from_millis(1500)), 2);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending.targets(), vec![0]);
+        assert_eq!(pending.take(&0), vec!["b"]);
+    }
+}
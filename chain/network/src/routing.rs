@@ -10,6 +10,8 @@ use byteorder::{LittleEndian, WriteBytesExt};
 use cached::{Cached, SizedCache};
 use chrono;
 use log::{debug, trace, warn};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
 
 use near_crypto::{SecretKey, Signature};
 use near_metrics;
@@ -32,6 +34,8 @@ use crate::{
 use delay_detector::DelayDetector;
 
 const ANNOUNCE_ACCOUNT_CACHE_SIZE: usize = 10_000;
+/// Maximum number of peers an account id can be routed to.
+const MAX_ANNOUNCEMENTS_PER_ACCOUNT: usize = 8;
 const ROUTE_BACK_CACHE_SIZE: u64 = 1_000_000;
 const ROUTE_BACK_CACHE_EVICT_TIMEOUT: u64 = 120_000; // 120 seconds
 const ROUTE_BACK_CACHE_REMOVE_BATCH: u64 = 100;
@@ -254,8 +258,9 @@ impl Edge {
 }
 
 pub struct RoutingTable {
-    /// PeerId associated for every known account id.
-    account_peers: SizedCache<AccountId, AnnounceAccount>,
+    /// Announcements of every known account id. All announcements of an account id are from
+    /// the same epoch and the first one is preferred while its peer is reachable.
+    account_peers: SizedCache<AccountId, Vec<AnnounceAccount>>,
     /// Active PeerId that are part of the shortest path to each PeerId.
     pub peer_forwarding: HashMap<PeerId, HashSet<PeerId>>,
     /// Store last update for known edges.
@@ -379,32 +384,61 @@ impl RoutingTable {
     }
 
     /// Find peer that owns this AccountId.
+    /// If the account id was announced by several peers, the first announced peer that is
+    /// reachable is used, so messages fail over to the next peer when a route disappears.
     pub fn account_owner(&mut self, account_id: &AccountId) -> Result<PeerId, FindRouteError> {
-        self.get_announce(account_id)
-            .map(|announce_account| announce_account.peer_id)
-            .ok_or_else(|| FindRouteError::AccountNotFound)
+        let announcements = self.get_announces(account_id);
+        let owner = announcements
+            .iter()
+            .find(|announce_account| {
+                &announce_account.peer_id == self.peer_id()
+                    || self.peer_forwarding.contains_key(&announce_account.peer_id)
+            })
+            .or_else(|| announcements.first())
+            .map(|announce_account| announce_account.peer_id.clone());
+        owner.ok_or_else(|| FindRouteError::AccountNotFound)
     }
 
     /// Add (account id, peer id) to routing table.
-    /// Note: There is at most on peer id per account id.
+    /// An announcement from a newer epoch replaces all known announcements of the account id,
+    /// while an announcement from the same epoch is added as one more peer for the account id.
     pub fn add_account(&mut self, announce_account: AnnounceAccount) {
         let account_id = announce_account.account_id.clone();
-        self.account_peers.cache_set(account_id.clone(), announce_account.clone());
+        let mut announcements = self.get_announces(&account_id);
+        if announcements.iter().any(|current| current.epoch_id != announce_account.epoch_id) {
+            announcements.clear();
+        }
+        if let Some(current) =
+            announcements.iter_mut().find(|current| current.peer_id == announce_account.peer_id)
+        {
+            *current = announce_account;
+        } else if announcements.len() < MAX_ANNOUNCEMENTS_PER_ACCOUNT {
+            announcements.push(announce_account);
+        } else {
+            debug!(target: "network", "Too many peers for account {}, ignoring announcement from {}", account_id, announce_account.peer_id);
+            return;
+        }
+        self.set_announces(account_id, announcements);
+    }
 
+    fn set_announces(&mut self, account_id: AccountId, announcements: Vec<AnnounceAccount>) {
         // Add account to store
         let mut update = self.store.store_update();
         if let Err(e) = update
-            .set_ser(ColAccountAnnouncements, account_id.as_bytes(), &announce_account)
+            .set_ser(ColAccountAnnouncements, account_id.as_bytes(), &announcements)
             .and_then(|_| update.commit())
         {
             warn!(target: "network", "Error saving announce account to store: {:?}", e);
         }
+
+        self.account_peers.cache_set(account_id, announcements);
     }
 
-    // TODO(MarX, #1694): Allow one account id to be routed to several peer id.
+    /// Whether this announcement (same account id, peer id and epoch) is already known.
     pub fn contains_account(&mut self, announce_account: &AnnounceAccount) -> bool {
-        self.get_announce(&announce_account.account_id).map_or(false, |current_announce_account| {
-            current_announce_account.epoch_id == announce_account.epoch_id
+        self.get_announces(&announce_account.account_id).iter().any(|current_announce_account| {
+            current_announce_account.peer_id == announce_account.peer_id
+                && current_announce_account.epoch_id == announce_account.epoch_id
         })
     }
 
@@ -610,9 +644,11 @@ impl RoutingTable {
 
     pub fn info(&mut self) -> RoutingTableInfo {
         let account_peers = self
-            .get_announce_accounts()
+            .get_accounts_keys()
             .into_iter()
-            .map(|announce_account| (announce_account.account_id, announce_account.peer_id))
+            .filter_map(|account_id| {
+                self.account_owner(&account_id).ok().map(|peer_id| (account_id, peer_id))
+            })
             .collect();
         RoutingTableInfo { account_peers, peer_forwarding: self.peer_forwarding.clone() }
     }
@@ -708,27 +744,27 @@ impl RoutingTable {
 
     /// Get announce accounts on cache.
     pub fn get_announce_accounts(&mut self) -> Vec<AnnounceAccount> {
-        self.account_peers.value_order().cloned().collect()
+        self.account_peers.value_order().flatten().cloned().collect()
     }
 
-    /// Get account announce from
-    pub fn get_announce(&mut self, account_id: &AccountId) -> Option<AnnounceAccount> {
-        if let Some(announce_account) = self.account_peers.cache_get(&account_id) {
-            Some(announce_account.clone())
+    /// Get all announcements of the account id, loading them from disk if they are not cached.
+    pub fn get_announces(&mut self, account_id: &AccountId) -> Vec<AnnounceAccount> {
+        if let Some(announcements) = self.account_peers.cache_get(&account_id) {
+            announcements.clone()
         } else {
             self.store
                 .get_ser(ColAccountAnnouncements, account_id.as_bytes())
-                .and_then(|res: Option<AnnounceAccount>| {
-                    if let Some(announce_account) = res {
-                        self.add_account(announce_account.clone());
-                        Ok(Some(announce_account))
+                .map(|res: Option<Vec<AnnounceAccount>>| {
+                    if let Some(announcements) = res {
+                        self.account_peers.cache_set(account_id.clone(), announcements.clone());
+                        announcements
                     } else {
-                        Ok(None)
+                        vec![]
                     }
                 })
                 .unwrap_or_else(|e| {
                     warn!(target: "network", "Error loading announce account from store: {:?}", e);
-                    None
+                    vec![]
                 })
         }
     }
@@ -736,6 +772,11 @@ impl RoutingTable {
     pub fn get_raw_graph(&self) -> &HashMap<PeerId, HashSet<PeerId>> {
         &self.raw_graph.adjacency
     }
+
+    /// Select peers among `candidates` to broadcast a message to. See `Graph::broadcast_targets`.
+    pub fn broadcast_targets(&self, candidates: &[PeerId], min_fanout: usize) -> Vec<PeerId> {
+        self.raw_graph.broadcast_targets(candidates, min_fanout)
+    }
 }
 
 pub struct ProcessEdgeResult {
@@ -795,6 +836,34 @@ impl Graph {
         }
     }
 
+    /// Select which of the `candidates` should receive a broadcast message, assuming that every
+    /// peer relays it to its own peers. Candidates are visited in random order and picked unless
+    /// they are adjacent to an already picked peer, so every candidate is either picked or one hop
+    /// away from a picked one. Random candidates are added until at least `min_fanout` are picked.
+    pub fn broadcast_targets(&self, candidates: &[PeerId], min_fanout: usize) -> Vec<PeerId> {
+        let mut rng = thread_rng();
+        let mut shuffled = candidates.to_vec();
+        shuffled.shuffle(&mut rng);
+
+        let mut covered = HashSet::new();
+        let mut targets = vec![];
+        let mut skipped = vec![];
+        for peer_id in shuffled {
+            if covered.contains(&peer_id) {
+                skipped.push(peer_id);
+                continue;
+            }
+            if let Some(neighbors) = self.adjacency.get(&peer_id) {
+                covered.extend(neighbors.iter().cloned());
+            }
+            covered.insert(peer_id.clone());
+            targets.push(peer_id);
+        }
+        let missing = min_fanout.saturating_sub(targets.len());
+        targets.extend(skipped.into_iter().take(missing));
+        targets
+    }
+
     // TODO(MarX, #1363): This is too slow right now. (See benchmarks)
     /// Compute for every node `u` on the graph (other than `source`) which are the neighbors of
     /// `sources` which belong to the shortest path from `source` to `u`. Nodes that are
@@ -851,6 +920,8 @@ impl Graph {
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashSet;
+
     use crate::routing::Graph;
     use crate::test_utils::{expected_routing_tables, random_peer_id};
 
@@ -996,4 +1067,36 @@ mod test {
 
         assert!(expected_routing_tables(graph.calculate_distance(), next_hops));
     }
+
+    #[test]
+    fn graph_broadcast_targets() {
+        let source = random_peer_id();
+        let nodes: Vec<_> = (0..6).map(|_| random_peer_id()).collect();
+
+        let mut graph = Graph::new(source.clone());
+        for node in nodes.iter() {
+            graph.add_edge(source.clone(), node.clone());
+        }
+        // Nodes 0, 1 and 2 are fully connected, node 3 is connected to node 4 and 5 is isolated.
+        graph.add_edge(nodes[0].clone(), nodes[1].clone());
+        graph.add_edge(nodes[0].clone(), nodes[2].clone());
+        graph.add_edge(nodes[1].clone(), nodes[2].clone());
+        graph.add_edge(nodes[3].clone(), nodes[4].clone());
+
+        for _ in 0..10 {
+            let targets = graph.broadcast_targets(&nodes, 0);
+            assert_eq!(targets.len(), 3);
+            assert!(targets.contains(&nodes[5]));
+            let covered: HashSet<_> = targets
+                .iter()
+                .flat_map(|target| {
+                    graph.adjacency.get(target).unwrap().iter().chain(Some(target)).cloned()
+                })
+                .collect();
+            assert!(nodes.iter().all(|node| covered.contains(node)));
+        }
+
+        assert_eq!(graph.broadcast_targets(&nodes, 4).len(), 4);
+        assert_eq!(graph.broadcast_targets(&nodes, 10).len(), nodes.len());
+    }
 }
@@ -78,6 +78,14 @@ impl NetworkConfig {
             push_info_period: Duration::from_millis(100),
             blacklist: HashMap::new(),
             outbound_disabled: false,
+            smart_broadcast: false,
+            broadcast_min_fanout: 4,
+            ip_allowlist: HashSet::new(),
+            // All test nodes run on the same host.
+            max_inbound_connections_per_ip: u32::MAX,
+            max_inbound_connections_per_subnet: u32::MAX,
+            compression_threshold: Some(1024),
+            capture: None,
         }
     }
 }
@@ -29,9 +29,10 @@ use near_primitives::syncing::ShardStateSyncResponse;
 use near_primitives::transaction::{ExecutionOutcomeWithIdAndProof, SignedTransaction};
 use near_primitives::types::{AccountId, BlockHeight, BlockIdOrFinality, EpochId, ShardId};
 use near_primitives::utils::{from_timestamp, to_timestamp};
-use near_primitives::version::FIRST_BACKWARD_COMPATIBLE_PROTOCOL_VERSION;
+use near_primitives::version::PROTOCOL_VERSION;
 use near_primitives::views::{FinalExecutionOutcomeView, QueryRequest, QueryResponse};
 
+use crate::capture::CaptureConfig;
 use crate::peer::Peer;
 #[cfg(feature = "metric_recorder")]
 use crate::recorder::MetricRecorder;
@@ -178,8 +179,7 @@ impl Handshake {
         edge_info: EdgeInfo,
     ) -> Self {
         Handshake {
-            // TODO: figure out how we are going to indicate backward compatible versions of protocol.
-            version: FIRST_BACKWARD_COMPATIBLE_PROTOCOL_VERSION,
+            version: PROTOCOL_VERSION,
             peer_id,
             target_peer_id,
             listen_port,
@@ -189,6 +189,15 @@ impl Handshake {
     }
 }
 
+/// Ephemeral key of the sender for the encrypted session, signed with its node key.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, PartialEq, Eq, Clone, Debug)]
+pub struct KeyExchange {
+    /// X25519 public key generated for this connection.
+    pub ephemeral_key: [u8; 32],
+    /// Signature of the ephemeral key and the peer ids of both ends of the connection.
+    pub signature: Signature,
+}
+
 /// Account route description
 #[derive(BorshSerialize, BorshDeserialize, Serialize, PartialEq, Eq, Clone, Debug)]
 pub struct AnnounceAccountRoute {
@@ -256,6 +265,21 @@ pub enum RoutedMessageBody {
     Pong(Pong),
 }
 
+impl RoutedMessageBody {
+    /// Whether the message is still useful if it is delivered once a route to its target is
+    /// known, so it should be kept instead of dropped while there is no route.
+    pub fn retry_without_route(&self) -> bool {
+        match self {
+            RoutedMessageBody::BlockApproval(_)
+            | RoutedMessageBody::ForwardTx(_)
+            | RoutedMessageBody::PartialEncodedChunk(_)
+            | RoutedMessageBody::PartialEncodedChunkRequest(_)
+            | RoutedMessageBody::PartialEncodedChunkResponse(_) => true,
+            _ => false,
+        }
+    }
+}
+
 impl Debug for RoutedMessageBody {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
         match self {
@@ -492,6 +516,9 @@ pub enum PeerMessage {
     Disconnect,
 
     Challenge(Challenge),
+
+    /// Key exchange sent after the handshake by peers supporting encrypted transport.
+    KeyExchange(KeyExchange),
 }
 
 impl fmt::Display for PeerMessage {
@@ -529,6 +556,78 @@ impl PeerMessage {
         }
     }
 
+    /// Message for the client from a message received from `peer_id`, if it is a client message.
+    /// `was_requested` tells whether a received block was requested from this peer.
+    pub fn into_client_message(
+        self,
+        peer_id: PeerId,
+        was_requested: bool,
+    ) -> Option<NetworkClientMessages> {
+        Some(match self {
+            PeerMessage::Block(block) => {
+                NetworkClientMessages::Block(block, peer_id, was_requested)
+            }
+            PeerMessage::Transaction(transaction) => NetworkClientMessages::Transaction {
+                transaction,
+                is_forwarded: false,
+                check_only: false,
+            },
+            PeerMessage::BlockHeaders(headers) => {
+                NetworkClientMessages::BlockHeaders(headers, peer_id)
+            }
+            PeerMessage::Challenge(challenge) => NetworkClientMessages::Challenge(challenge),
+            PeerMessage::Routed(routed_message) => {
+                let msg_hash = routed_message.hash();
+                match routed_message.body {
+                    RoutedMessageBody::BlockApproval(approval) => {
+                        NetworkClientMessages::BlockApproval(approval, peer_id)
+                    }
+                    RoutedMessageBody::ForwardTx(transaction) => {
+                        NetworkClientMessages::Transaction {
+                            transaction,
+                            is_forwarded: true,
+                            check_only: false,
+                        }
+                    }
+                    RoutedMessageBody::StateResponse(info) => {
+                        NetworkClientMessages::StateResponse(info)
+                    }
+                    RoutedMessageBody::PartialEncodedChunkRequest(request) => {
+                        NetworkClientMessages::PartialEncodedChunkRequest(request, msg_hash)
+                    }
+                    RoutedMessageBody::PartialEncodedChunkResponse(response) => {
+                        NetworkClientMessages::PartialEncodedChunkResponse(response)
+                    }
+                    RoutedMessageBody::PartialEncodedChunk(partial_encoded_chunk) => {
+                        NetworkClientMessages::PartialEncodedChunk(partial_encoded_chunk)
+                    }
+                    RoutedMessageBody::Ping(_)
+                    | RoutedMessageBody::Pong(_)
+                    | RoutedMessageBody::TxStatusRequest(_, _)
+                    | RoutedMessageBody::TxStatusResponse(_)
+                    | RoutedMessageBody::QueryRequest { .. }
+                    | RoutedMessageBody::QueryResponse { .. }
+                    | RoutedMessageBody::ReceiptOutcomeRequest(_)
+                    | RoutedMessageBody::ReceiptOutComeResponse(_)
+                    | RoutedMessageBody::StateRequestHeader(_, _)
+                    | RoutedMessageBody::StateRequestPart(_, _, _) => return None,
+                }
+            }
+            PeerMessage::Handshake(_)
+            | PeerMessage::HandshakeFailure(_, _)
+            | PeerMessage::PeersRequest
+            | PeerMessage::PeersResponse(_)
+            | PeerMessage::RoutingTableSync(_)
+            | PeerMessage::LastEdge(_)
+            | PeerMessage::Disconnect
+            | PeerMessage::RequestUpdateNonce(_)
+            | PeerMessage::ResponseUpdateNonce(_)
+            | PeerMessage::BlockRequest(_)
+            | PeerMessage::BlockHeadersRequest(_)
+            | PeerMessage::KeyExchange(_) => return None,
+        })
+    }
+
     pub fn is_view_client_message(&self) -> bool {
         match self {
             PeerMessage::Routed(r) => match r.body {
@@ -609,6 +708,23 @@ pub struct NetworkConfig {
     /// are satisfied.
     /// This flag should be ALWAYS FALSE. Only set to true for testing purposes.
     pub outbound_disabled: bool,
+    /// Send blocks and challenges only to a subset of active peers that covers the rest of them in the routing
+    /// table graph, instead of sending them to every active peer.
+    pub smart_broadcast: bool,
+    /// Minimum number of active peers a block or challenge is sent to when smart broadcast is enabled.
+    pub broadcast_min_fanout: u32,
+    /// IP addresses that are never banned and not subject to the inbound connection limits.
+    /// Loopback addresses are always allowed.
+    pub ip_allowlist: HashSet<IpAddr>,
+    /// Maximum number of inbound connections from the same IP address.
+    pub max_inbound_connections_per_ip: u32,
+    /// Maximum number of inbound connections from the same subnet (/24 for IPv4 and /64 for IPv6).
+    pub max_inbound_connections_per_subnet: u32,
+    /// Messages larger than this many bytes are compressed when sent to peers that support
+    /// compression. Messages are never compressed if not set.
+    pub compression_threshold: Option<usize>,
+    /// Write all messages exchanged with peers to files, if set.
+    pub capture: Option<CaptureConfig>,
 }
 
 impl NetworkConfig {
@@ -703,6 +819,22 @@ pub struct KnownPeerState {
     pub last_seen: u64,
 }
 
+/// IP address banned together with a misbehaving peer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BannedIp {
+    pub ip: IpAddr,
+    /// The peer that was banned together with the address.
+    pub peer_id: PeerId,
+    pub reason: ReasonForBan,
+    pub banned_at: u64,
+}
+
+impl BannedIp {
+    pub fn banned_at(&self) -> DateTime<Utc> {
+        from_timestamp(self.banned_at)
+    }
+}
+
 impl KnownPeerState {
     pub fn new(peer_info: PeerInfo) -> Self {
         KnownPeerState {
@@ -765,6 +897,8 @@ pub struct Consolidate {
     pub actor: Addr<Peer>,
     pub peer_info: PeerInfo,
     pub peer_type: PeerType,
+    // Address of the other end of the connection.
+    pub peer_addr: SocketAddr,
     pub chain_info: PeerChainInfo,
     // Edge information from this node.
     // If this is None it implies we are outbound connection, so we need to create our
@@ -843,7 +977,9 @@ where
 }
 
 /// Ban reason.
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Copy,
+)]
 pub enum ReasonForBan {
     None = 0,
     BadBlock = 1,
@@ -912,6 +1048,14 @@ pub enum NetworkRequests {
         peer_id: PeerId,
         ban_reason: ReasonForBan,
     },
+    /// Remove the ban of given peer.
+    UnbanPeer {
+        peer_id: PeerId,
+    },
+    /// Connect to given peer.
+    ConnectPeer(PeerInfo),
+    /// Fetch all known peers with their state.
+    FetchKnownPeers,
     /// Announce account
     AnnounceAccount(AnnounceAccount),
 
@@ -1005,6 +1149,8 @@ pub struct NetworkInfo {
     pub received_bytes_per_sec: u64,
     /// Accounts of known block and chunk producers from routing table.
     pub known_producers: Vec<KnownProducer>,
+    /// IP addresses banned together with misbehaving peers.
+    pub banned_ips: Vec<BannedIp>,
     #[cfg(feature = "metric_recorder")]
     pub metric_recorder: MetricRecorder,
 }
@@ -1029,6 +1175,7 @@ pub enum NetworkResponses {
     BanPeer(ReasonForBan),
     EdgeUpdate(Box<Edge>),
     RouteNotFound,
+    KnownPeers(Vec<KnownPeerState>),
 }
 
 impl<A, M> MessageResponse<A, M> for NetworkResponses
@@ -1170,7 +1317,7 @@ pub enum NetworkViewClientMessages {
     GetChainInfo,
     /// Account announcements that needs to be validated before being processed.
     /// They are paired with last epoch id known to this announcement, in order to accept only
-    /// newer announcements.
+    /// announcements that are not older.
     AnnounceAccount(Vec<(AnnounceAccount, Option<EpochId>)>),
 }
 
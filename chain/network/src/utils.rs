@@ -1,7 +1,8 @@
 use cached::SizedCache;
 use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 use std::iter::FromIterator;
-use std::{hash::Hash, net::IpAddr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use crate::types::{BlockedPorts, PatternAddr};
 
@@ -38,6 +39,21 @@ where
     blacklist_map
 }
 
+/// Subnet of the IP address used to limit connections from the same network:
+/// /24 for IPv4 and /64 for IPv6 addresses.
+pub fn ip_subnet(ip: &IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            IpAddr::V4(Ipv4Addr::new(a, b, c, 0))
+        }
+        IpAddr::V6(ip) => {
+            let s = ip.segments();
+            IpAddr::V6(Ipv6Addr::new(s[0], s[1], s[2], s[3], 0, 0, 0, 0))
+        }
+    }
+}
+
 pub fn cache_to_hashmap<K: Hash + Eq + Clone, V: Clone>(cache: &SizedCache<K, V>) -> HashMap<K, V> {
     let keys: Vec<_> = cache.key_order().cloned().collect();
     keys.into_iter().zip(cache.value_order().cloned()).collect()
@@ -51,3 +51,29 @@ fn connect_to_unbanned_peer() {
 
     start_test(runner);
 }
+
+/// Check a banned peer is able to connect again once it is unbanned before the ban expires.
+#[test]
+fn connect_to_manually_unbanned_peer() {
+    let mut runner = Runner::new(2, 2)
+        .enable_outbound()
+        .use_boot_nodes(vec![0, 1])
+        .ban_window(Duration::from_secs(60));
+
+    runner.push(Action::CheckRoutingTable(0, vec![(1, vec![1])]));
+    runner.push(Action::CheckRoutingTable(1, vec![(0, vec![0])]));
+
+    runner.push_action(ban_peer(0, 1));
+    runner.push(Action::Wait(1000));
+
+    runner.push(Action::CheckRoutingTable(0, vec![]));
+    runner.push(Action::CheckRoutingTable(1, vec![]));
+
+    // Lift the ban long before the ban window is over.
+    runner.push_action(unban_peer(0, 1));
+
+    runner.push(Action::CheckRoutingTable(0, vec![(1, vec![1])]));
+    runner.push(Action::CheckRoutingTable(1, vec![(0, vec![0])]));
+
+    start_test(runner);
+}
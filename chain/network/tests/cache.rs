@@ -1,5 +1,5 @@
 use near_crypto::Signature;
-use near_network::routing::RoutingTable;
+use near_network::routing::{Edge, RoutingTable};
 use near_network::test_utils::{random_epoch_id, random_peer_id};
 use near_primitives::network::AnnounceAccount;
 use near_store::test_utils::create_test_store;
@@ -31,14 +31,82 @@ fn announcement_same_epoch() {
 
     routing_table.add_account(announce0.clone());
     assert!(routing_table.contains_account(&announce0));
-    assert!(routing_table.contains_account(&announce1));
+    assert!(!routing_table.contains_account(&announce1));
     assert_eq!(routing_table.get_announce_accounts().len(), 1);
     assert_eq!(routing_table.account_owner(&announce0.account_id).unwrap(), peer_id0);
     routing_table.add_account(announce1.clone());
-    assert_eq!(routing_table.get_announce_accounts().len(), 1);
+    assert!(routing_table.contains_account(&announce1));
+    assert_eq!(routing_table.get_announce_accounts().len(), 2);
+    // First announcement is preferred while it is reachable.
+    assert_eq!(routing_table.account_owner(&announce1.account_id).unwrap(), peer_id0);
+}
+
+#[test]
+fn announcement_new_epoch() {
+    let store = create_test_store();
+
+    let peer_id0 = random_peer_id();
+    let peer_id1 = random_peer_id();
+
+    let mut routing_table = RoutingTable::new(peer_id0.clone(), store);
+
+    let announce0 = AnnounceAccount {
+        account_id: "near0".to_string(),
+        peer_id: peer_id0,
+        epoch_id: random_epoch_id(),
+        signature: Signature::default(),
+    };
+
+    // Same account id from a different peer in a newer epoch
+    let announce1 = AnnounceAccount {
+        account_id: "near0".to_string(),
+        peer_id: peer_id1.clone(),
+        epoch_id: random_epoch_id(),
+        signature: Signature::default(),
+    };
+
+    routing_table.add_account(announce0.clone());
+    routing_table.add_account(announce1.clone());
+    assert!(!routing_table.contains_account(&announce0));
+    assert_eq!(routing_table.get_announce_accounts(), vec![announce1.clone()]);
     assert_eq!(routing_table.account_owner(&announce1.account_id).unwrap(), peer_id1);
 }
 
+#[test]
+fn announcement_failover() {
+    let store = create_test_store();
+
+    let source = random_peer_id();
+    let peer_id1 = random_peer_id();
+    let peer_id2 = random_peer_id();
+    let epoch_id = random_epoch_id();
+
+    let mut routing_table = RoutingTable::new(source.clone(), store);
+
+    for peer_id in vec![peer_id1.clone(), peer_id2.clone()] {
+        routing_table.add_account(AnnounceAccount {
+            account_id: "near0".to_string(),
+            peer_id,
+            epoch_id: epoch_id.clone(),
+            signature: Signature::default(),
+        });
+    }
+
+    // No peer is reachable, so the first announcement is used.
+    assert_eq!(routing_table.account_owner(&"near0".to_string()).unwrap(), peer_id1);
+
+    // Only the second peer is reachable.
+    routing_table.process_edge(Edge::new(
+        source,
+        peer_id2.clone(),
+        1,
+        Signature::default(),
+        Signature::default(),
+    ));
+    routing_table.update();
+    assert_eq!(routing_table.account_owner(&"near0".to_string()).unwrap(), peer_id2);
+}
+
 #[test]
 fn dont_load_on_build() {
     let store = create_test_store();
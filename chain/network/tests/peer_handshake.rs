@@ -1,17 +1,27 @@
 pub use runner::*;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 mod runner;
 use actix::actors::mocker::Mocker;
 use actix::Actor;
 use actix::System;
+use borsh::{BorshDeserialize, BorshSerialize};
 use futures::{future, FutureExt};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
 
 use near_client::{ClientActor, ViewClientActor};
+use near_crypto::{KeyType, SecretKey};
 use near_logger_utils::init_test_logger;
-use near_network::test_utils::{convert_boot_nodes, open_port, GetInfo, StopSignal, WaitOrTimeout};
-use near_network::types::{NetworkViewClientMessages, NetworkViewClientResponses};
+use near_network::routing::EdgeInfo;
+use near_network::test_utils::{
+    convert_boot_nodes, open_port, peer_id_from_seed, GetInfo, StopSignal, WaitOrTimeout,
+};
+use near_network::types::{
+    Handshake, NetworkViewClientMessages, NetworkViewClientResponses, PeerChainInfo, PeerMessage,
+};
 use near_network::{NetworkClientResponses, NetworkConfig, PeerManagerActor};
 use near_store::test_utils::create_test_store;
 
@@ -119,6 +129,52 @@ fn peers_connect_all() {
     .unwrap()
 }
 
+/// Inbound peer that doesn't listen for connections, and so sends no listen port in the handshake,
+/// is accepted.
+#[test]
+fn inbound_peer_without_listen_port() {
+    init_test_logger();
+
+    System::run(|| {
+        let port = open_port();
+        let _pm = make_peer_manager("test0", port, vec![], 10).start();
+
+        actix::spawn(async move {
+            // Give the peer manager time to start listening.
+            tokio::time::delay_for(Duration::from_millis(100)).await;
+            let mut stream = TcpStream::connect(format!("127.0.0.1:{}", port)).await.unwrap();
+
+            let secret_key = SecretKey::from_seed(KeyType::ED25519, "test1");
+            let peer_id = peer_id_from_seed("test1");
+            let target_peer_id = peer_id_from_seed("test0");
+            let edge_info = EdgeInfo::new(peer_id.clone(), target_peer_id.clone(), 1, &secret_key);
+            let handshake = Handshake::new(
+                peer_id,
+                target_peer_id,
+                None,
+                PeerChainInfo { genesis_id: Default::default(), height: 1, tracked_shards: vec![] },
+                edge_info,
+            );
+            let bytes = PeerMessage::Handshake(handshake).try_to_vec().unwrap();
+            stream.write_all(&(bytes.len() as u32).to_le_bytes()).await.unwrap();
+            stream.write_all(&bytes).await.unwrap();
+
+            // The handshake is answered only if the connection is consolidated.
+            let mut len = [0u8; 4];
+            stream.read_exact(&mut len).await.unwrap();
+            let mut bytes = vec![0u8; u32::from_le_bytes(len) as usize];
+            stream.read_exact(&mut bytes).await.unwrap();
+            match PeerMessage::try_from_slice(&bytes).unwrap() {
+                PeerMessage::Handshake(handshake) => assert_eq!(handshake.listen_port, Some(port)),
+                message => panic!("Unexpected message {:?}", message),
+            }
+            System::current().stop();
+        });
+        WaitOrTimeout::new(Box::new(|_| {}), 100, 5000).start();
+    })
+    .unwrap();
+}
+
 /// Check network is able to recover after node restart.
 #[test]
 fn peer_recover() {
@@ -744,6 +744,31 @@ pub fn ban_peer(target_peer: usize, banned_peer: usize) -> ActionFn {
     )
 }
 
+/// Unban peer `unbanned_peer` from perspective of `target_peer`.
+pub fn unban_peer(target_peer: usize, unbanned_peer: usize) -> ActionFn {
+    Box::new(
+        move |info: SharedRunningInfo,
+              flag: Arc<AtomicBool>,
+              _ctx: &mut Context<WaitOrTimeout>,
+              _runner| {
+            let info = info.read().unwrap();
+            let peer_id = info.peers_info[unbanned_peer].id.clone();
+            actix::spawn(
+                info.pm_addr
+                    .get(target_peer)
+                    .unwrap()
+                    .send(NetworkRequests::UnbanPeer { peer_id })
+                    .map_err(|_| ())
+                    .and_then(move |_| {
+                        flag.store(true, Ordering::Relaxed);
+                        future::ok(())
+                    })
+                    .map(drop),
+            );
+        },
+    )
+}
+
 /// Change account id from a stopped peer. Notice this will also change its peer id, since
 /// peer_id is derived from account id with NetworkConfig::from_seed
 pub fn change_account_id(node_id: usize, account_id: String) -> ActionFn {
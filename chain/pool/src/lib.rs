@@ -1,13 +1,18 @@
-use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 
-use crate::types::{PoolIterator, PoolKey, TransactionGroup};
+use crate::types::{
+    PendingTransaction, PoolEntry, PoolIterator, PoolKey, TransactionGroup, TransactionPoolConfig,
+    TransactionPriority,
+};
 use borsh::BorshSerialize;
 use near_crypto::PublicKey;
 use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::transaction::SignedTransaction;
-use near_primitives::types::AccountId;
+use near_primitives::types::{AccountId, Nonce};
 use rand::RngCore;
 use std::ops::Bound;
+use std::time::Instant;
 
 pub mod types;
 
@@ -17,8 +22,14 @@ pub struct TransactionPool {
     /// NOTE: It's more efficient on average to keep transactions unsorted and with potentially
     /// conflicting nonce than to create a BTreeMap for every transaction.
     pub transactions: BTreeMap<PoolKey, Vec<SignedTransaction>>,
-    /// Set of all hashes to quickly check if the given transaction is in the pool.
-    pub unique_transactions: HashSet<CryptoHash>,
+    /// All hashes to quickly check if the given transaction is in the pool and to find its group.
+    unique_transactions: HashMap<CryptoHash, PoolEntry>,
+    /// Transactions ordered by priority, the first one is evicted when the pool is full.
+    transactions_by_priority: BTreeSet<(TransactionPriority, CryptoHash)>,
+    /// Number of transactions in the pool per signer account.
+    num_transactions_per_signer: HashMap<AccountId, usize>,
+    /// Limits of the pool.
+    config: TransactionPoolConfig,
     /// A uniquely generated key seed to randomize PoolKey order.
     key_seed: Vec<u8>,
     /// The key after which the pool iterator starts. Doesn't have to be present in the pool.
@@ -26,11 +37,14 @@ pub struct TransactionPool {
 }
 
 impl TransactionPool {
-    pub fn new() -> Self {
+    pub fn new(config: TransactionPoolConfig) -> Self {
         Self {
             key_seed: rand::thread_rng().next_u64().to_le_bytes().to_vec(),
             transactions: BTreeMap::new(),
-            unique_transactions: HashSet::new(),
+            unique_transactions: HashMap::new(),
+            transactions_by_priority: BTreeSet::new(),
+            num_transactions_per_signer: HashMap::new(),
+            config,
             last_used_key: CryptoHash::default(),
         }
     }
@@ -43,19 +57,102 @@ impl TransactionPool {
     }
 
     /// Insert a signed transaction into the pool that passed validation.
+    /// A pending transaction with the same signer, public key and nonce is replaced if the new one
+    /// has a higher priority. A transaction of a signer that reached its limit is rejected. When
+    /// the pool is full, the transaction with the lowest priority is evicted to make room for a
+    /// transaction with a higher priority.
+    /// Returns whether the transaction was added to the pool.
     pub fn insert_transaction(&mut self, signed_transaction: SignedTransaction) -> bool {
-        if !self.unique_transactions.insert(signed_transaction.get_hash()) {
+        let tx_hash = signed_transaction.get_hash();
+        if self.unique_transactions.contains_key(&tx_hash) {
             return false;
         }
         let signer_id = &signed_transaction.transaction.signer_id;
         let signer_public_key = &signed_transaction.transaction.public_key;
-        self.transactions
-            .entry(self.key(signer_id, signer_public_key))
-            .or_insert_with(Vec::new)
-            .push(signed_transaction);
+        let key = self.key(signer_id, signer_public_key);
+        let priority = TransactionPriority::new(&signed_transaction);
+
+        if let Some(same_nonce_hash) =
+            self.find_transaction_with_nonce(&key, signed_transaction.transaction.nonce)
+        {
+            if self.unique_transactions[&same_nonce_hash].priority >= priority {
+                return false;
+            }
+            self.remove_transaction(&same_nonce_hash);
+        } else if self.num_transactions_per_signer.get(signer_id).cloned().unwrap_or_default()
+            >= self.config.max_transactions_per_signer
+        {
+            return false;
+        } else if self.len() >= self.config.max_transactions {
+            match self.transactions_by_priority.iter().next() {
+                Some(&(lowest_priority, lowest_hash)) if lowest_priority < priority => {
+                    self.remove_transaction(&lowest_hash);
+                }
+                _ => return false,
+            }
+        }
+
+        let signer_id = signer_id.clone();
+        *self.num_transactions_per_signer.entry(signer_id.clone()).or_insert(0) += 1;
+        self.transactions_by_priority.insert((priority, tx_hash));
+        self.unique_transactions
+            .insert(tx_hash, PoolEntry { key, signer_id, priority, inserted: Instant::now() });
+        self.transactions.entry(key).or_insert_with(Vec::new).push(signed_transaction);
         true
     }
 
+    /// Returns all transactions in the pool together with the time they have been waiting.
+    /// Transactions pulled by an existing pool iterator are not included.
+    pub fn pending_transactions(&self) -> Vec<PendingTransaction<'_>> {
+        self.transactions
+            .values()
+            .flatten()
+            .filter_map(|transaction| {
+                self.unique_transactions.get(&transaction.get_hash()).map(|entry| {
+                    PendingTransaction { transaction, pending_duration: entry.inserted.elapsed() }
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the hash of a pending transaction of the group with the given nonce.
+    fn find_transaction_with_nonce(&self, key: &PoolKey, nonce: Nonce) -> Option<CryptoHash> {
+        self.transactions
+            .get(key)?
+            .iter()
+            .find(|tx| tx.transaction.nonce == nonce)
+            .map(|tx| tx.get_hash())
+    }
+
+    /// Removes the transaction from its group and forgets about it.
+    fn remove_transaction(&mut self, tx_hash: &CryptoHash) {
+        if let Some(entry) = self.forget_transaction(tx_hash) {
+            let mut remove_entry = false;
+            if let Some(v) = self.transactions.get_mut(&entry.key) {
+                v.retain(|tx| tx.get_hash() != *tx_hash);
+                remove_entry = v.is_empty();
+            }
+            if remove_entry {
+                self.transactions.remove(&entry.key);
+            }
+        }
+    }
+
+    /// Removes the transaction from the bookkeeping, but not from its group.
+    fn forget_transaction(&mut self, tx_hash: &CryptoHash) -> Option<PoolEntry> {
+        let entry = self.unique_transactions.remove(tx_hash)?;
+        self.transactions_by_priority.remove(&(entry.priority, *tx_hash));
+        if let Entry::Occupied(mut count) =
+            self.num_transactions_per_signer.entry(entry.signer_id.clone())
+        {
+            *count.get_mut() -= 1;
+            if *count.get() == 0 {
+                count.remove();
+            }
+        }
+        Some(entry)
+    }
+
     /// Returns a pool iterator wrapper that implements an iterator like trait to iterate over
     /// transaction groups in the proper order defined by the protocol.
     /// When the iterator is dropped, all remaining groups are inserted back into the pool.
@@ -68,7 +165,7 @@ impl TransactionPool {
     pub fn remove_transactions(&mut self, transactions: &[SignedTransaction]) {
         let mut grouped_transactions = HashMap::new();
         for tx in transactions {
-            if self.unique_transactions.contains(&tx.get_hash()) {
+            if self.unique_transactions.contains_key(&tx.get_hash()) {
                 let signer_id = &tx.transaction.signer_id;
                 let signer_public_key = &tx.transaction.public_key;
                 grouped_transactions
@@ -87,7 +184,7 @@ impl TransactionPool {
                 self.transactions.remove(&key);
             }
             for hash in hashes {
-                self.unique_transactions.remove(&hash);
+                self.forget_transaction(&hash);
             }
         }
     }
@@ -133,14 +230,14 @@ impl<'a> PoolIteratorWrapper<'a> {
 /// If the pool is empty, the iterator gets the group from the front of the sorted groups queue.
 ///
 /// If this group is empty (no transactions left inside), then the iterator discards it and
-/// updates `unique_transactions` in the pool. Then gets the next one.
+/// forgets its pulled transactions in the pool. Then gets the next one.
 ///
 /// Once a non-empty group is found, this group is pushed to the back of the sorted groups queue
 /// and the iterator returns a mutable reference to this group.
 ///
 /// If the sorted groups queue is empty, the iterator returns None.
 ///
-/// When the iterator is dropped, `unique_transactions` in the pool is updated for every group.
+/// When the iterator is dropped, pulled transactions of every group are forgotten by the pool.
 /// And all non-empty group from the sorted groups queue are inserted back into the pool.
 impl<'a> PoolIterator for PoolIteratorWrapper<'a> {
     fn next(&mut self) -> Option<&mut TransactionGroup> {
@@ -172,7 +269,7 @@ impl<'a> PoolIterator for PoolIteratorWrapper<'a> {
             while let Some(sorted_group) = self.sorted_groups.pop_front() {
                 if sorted_group.transactions.is_empty() {
                     for hash in sorted_group.removed_transaction_hashes {
-                        self.pool.unique_transactions.remove(&hash);
+                        self.pool.forget_transaction(&hash);
                     }
                 } else {
                     self.sorted_groups.push_back(sorted_group);
@@ -186,12 +283,12 @@ impl<'a> PoolIterator for PoolIteratorWrapper<'a> {
 
 /// When a pool iterator is dropped, all remaining non empty transaction groups from the sorted
 /// groups queue are inserted back into the pool. And removed transactions hashes from groups are
-/// removed from the pool's unique_transactions.
+/// forgotten by the pool.
 impl<'a> Drop for PoolIteratorWrapper<'a> {
     fn drop(&mut self) {
         for group in self.sorted_groups.drain(..) {
             for hash in group.removed_transaction_hashes {
-                self.pool.unique_transactions.remove(&hash);
+                self.pool.forget_transaction(&hash);
             }
             if !group.transactions.is_empty() {
                 self.pool.transactions.insert(group.key, group.transactions);
@@ -239,7 +336,7 @@ mod tests {
         mut transactions: Vec<SignedTransaction>,
         expected_weight: u32,
     ) -> (Vec<u64>, TransactionPool) {
-        let mut pool = TransactionPool::new();
+        let mut pool = TransactionPool::new(TransactionPoolConfig::default());
         let mut rng = thread_rng();
         transactions.shuffle(&mut rng);
         for tx in transactions {
@@ -350,7 +447,7 @@ mod tests {
             })
             .collect::<Vec<_>>();
 
-        let mut pool = TransactionPool::new();
+        let mut pool = TransactionPool::new(TransactionPoolConfig::default());
         let mut rng = thread_rng();
         transactions.shuffle(&mut rng);
         for tx in transactions.clone() {
@@ -450,4 +547,92 @@ mod tests {
         new_nonces.sort();
         assert_ne!(nonces, new_nonces);
     }
+
+    fn transfer(
+        signer_id: &str,
+        signer_seed: &str,
+        nonce: u64,
+        deposit: Balance,
+    ) -> SignedTransaction {
+        let signer = InMemorySigner::from_seed(signer_seed, KeyType::ED25519, signer_seed);
+        SignedTransaction::send_money(
+            nonce,
+            signer_id.to_string(),
+            "bob.near".to_string(),
+            &signer,
+            deposit,
+            CryptoHash::default(),
+        )
+    }
+
+    /// A transaction with the same nonce replaces the pending one only with a higher priority.
+    #[test]
+    fn test_replace_same_nonce() {
+        let mut pool = TransactionPool::new(TransactionPoolConfig::default());
+        assert!(pool.insert_transaction(transfer("alice.near", "alice.near", 1, 10)));
+        assert!(!pool.insert_transaction(transfer("alice.near", "alice.near", 1, 5)));
+        assert!(pool.insert_transaction(transfer("alice.near", "alice.near", 1, 20)));
+        assert_eq!(pool.len(), 1);
+        let txs = prepare_transactions(&mut pool, 10);
+        assert_eq!(txs, vec![transfer("alice.near", "alice.near", 1, 20)]);
+        assert!(pool.is_empty());
+    }
+
+    /// Transactions of a signer above the limit are rejected.
+    #[test]
+    fn test_per_signer_limit() {
+        let config =
+            TransactionPoolConfig { max_transactions: 100, max_transactions_per_signer: 3 };
+        let mut pool = TransactionPool::new(config);
+        for tx in generate_transactions("alice.near", "alice.near", 1, 5) {
+            pool.insert_transaction(tx);
+        }
+        for tx in generate_transactions("alice.near", "bob.near", 1, 5) {
+            pool.insert_transaction(tx);
+        }
+        for tx in generate_transactions("bob.near", "bob.near", 1, 2) {
+            pool.insert_transaction(tx);
+        }
+        assert_eq!(pool.len(), 5);
+
+        // Pulling transactions out of the pool frees up the limit.
+        assert_eq!(prepare_transactions(&mut pool, 5).len(), 5);
+        assert!(pool.insert_transaction(transfer("alice.near", "alice.near", 10, 1)));
+    }
+
+    /// Transactions with the lowest priority are evicted when the pool is full.
+    #[test]
+    fn test_evict_lowest_priority() {
+        let config = TransactionPoolConfig { max_transactions: 3, max_transactions_per_signer: 3 };
+        let mut pool = TransactionPool::new(config);
+        assert!(pool.insert_transaction(transfer("alice.near", "alice.near", 1, 10)));
+        assert!(pool.insert_transaction(transfer("bob.near", "bob.near", 1, 5)));
+        assert!(pool.insert_transaction(transfer("carol.near", "carol.near", 1, 20)));
+        assert!(!pool.insert_transaction(transfer("dave.near", "dave.near", 1, 5)));
+        assert!(pool.insert_transaction(transfer("dave.near", "dave.near", 1, 15)));
+        assert_eq!(pool.len(), 3);
+
+        let mut deposits: Vec<_> = prepare_transactions(&mut pool, 10)
+            .iter()
+            .map(|tx| TransactionPriority::new(tx).attached_deposit)
+            .collect();
+        deposits.sort();
+        assert_eq!(deposits, vec![10, 15, 20]);
+        assert!(pool.is_empty());
+    }
+
+    /// Pending transactions are reported until they are pulled from the pool.
+    #[test]
+    fn test_pending_transactions() {
+        let transactions = generate_transactions("alice.near", "alice.near", 1, 3);
+        let (nonces, pool) = process_txs_to_nonces(transactions, 1);
+        assert_eq!(nonces, vec![1]);
+        let mut pending_nonces: Vec<_> = pool
+            .pending_transactions()
+            .iter()
+            .map(|pending| pending.transaction.transaction.nonce)
+            .collect();
+        pending_nonces.sort();
+        assert_eq!(pending_nonces, vec![2, 3]);
+    }
 }
@@ -1,5 +1,8 @@
+use std::time::{Duration, Instant};
+
 use near_primitives::hash::CryptoHash;
-use near_primitives::transaction::SignedTransaction;
+use near_primitives::transaction::{Action, SignedTransaction};
+use near_primitives::types::{AccountId, Balance, Gas};
 
 /// Trait acts like an iterator. It iterates over transactions groups by returning mutable
 /// references to them. Each transaction group implements a draining iterator to pull transactions.
@@ -13,6 +16,61 @@ pub trait PoolIterator {
 /// Used to randomize the order of the keys.
 pub(crate) type PoolKey = CryptoHash;
 
+/// Limits of the transaction pool.
+#[derive(Clone, Copy, Debug)]
+pub struct TransactionPoolConfig {
+    /// Maximum total number of transactions in the pool.
+    pub max_transactions: usize,
+    /// Maximum number of transactions in the pool signed by a single account.
+    pub max_transactions_per_signer: usize,
+}
+
+impl Default for TransactionPoolConfig {
+    fn default() -> Self {
+        Self { max_transactions: 100_000, max_transactions_per_signer: 1_000 }
+    }
+}
+
+/// Priority of a transaction: the total gas and then the total deposit attached to its actions.
+/// When the pool is full, transactions with the lowest priority are evicted first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TransactionPriority {
+    pub attached_gas: Gas,
+    pub attached_deposit: Balance,
+}
+
+impl TransactionPriority {
+    pub fn new(signed_transaction: &SignedTransaction) -> Self {
+        let actions = &signed_transaction.transaction.actions;
+        Self {
+            attached_gas: actions
+                .iter()
+                .map(Action::get_prepaid_gas)
+                .fold(0, |total, gas| total.saturating_add(gas)),
+            attached_deposit: actions
+                .iter()
+                .map(Action::get_deposit_balance)
+                .fold(0, |total, deposit| total.saturating_add(deposit)),
+        }
+    }
+}
+
+/// Bookkeeping of a single transaction in the pool.
+pub(crate) struct PoolEntry {
+    /// The key of the group that holds the transaction.
+    pub(crate) key: PoolKey,
+    pub(crate) signer_id: AccountId,
+    pub(crate) priority: TransactionPriority,
+    /// When the transaction was added to the pool.
+    pub(crate) inserted: Instant,
+}
+
+/// A transaction in the pool and the time it has been waiting there.
+pub struct PendingTransaction<'a> {
+    pub transaction: &'a SignedTransaction,
+    pub pending_duration: Duration,
+}
+
 /// Represents a group of transactions with the same key.
 pub struct TransactionGroup {
     /// The key of the group.
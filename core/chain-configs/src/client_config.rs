@@ -75,8 +75,19 @@ pub struct ClientConfig {
     pub tracked_shards: Vec<ShardId>,
     /// Not clear old data, set `true` for archive nodes.
     pub archive: bool,
+    /// Track all shards and challenge invalid chunks, without a validator seat. The validator key
+    /// is used to sign challenges, so its account must be staked as a fisherman.
+    pub fisherman: bool,
     /// Number of threads for ViewClientActor pool.
     pub view_client_threads: usize,
+    /// Index outcomes by executor account and log prefix, required for the `logs` RPC.
+    pub enable_logs_index: bool,
+    /// Record the breakdown of the burnt gas in the outcomes returned by the `tx` RPC.
+    pub enable_gas_profiles: bool,
+    /// Maximum number of transactions in the transaction pool of each shard.
+    pub transaction_pool_size_limit: usize,
+    /// Maximum number of transactions of a single signer in the transaction pool of each shard.
+    pub transaction_pool_signer_limit: usize,
 }
 
 impl ClientConfig {
@@ -127,7 +138,12 @@ impl ClientConfig {
             tracked_accounts: vec![],
             tracked_shards: vec![],
             archive,
+            fisherman: false,
             view_client_threads: 1,
+            enable_logs_index: false,
+            enable_gas_profiles: false,
+            transaction_pool_size_limit: 100_000,
+            transaction_pool_signer_limit: 1_000,
         }
     }
 }
@@ -1,5 +1,7 @@
 pub use key_file::KeyFile;
-pub use signature::{KeyType, PublicKey, SecretKey, Signature};
+pub use signature::{
+    KeyType, PublicKey, Secp256K1PublicKey, Secp256K1Signature, SecretKey, Signature,
+};
 pub use signer::{EmptySigner, InMemorySigner, Signer};
 
 #[macro_use]
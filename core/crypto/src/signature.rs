@@ -95,6 +95,18 @@ impl Ord for Secp256K1PublicKey {
     }
 }
 
+impl From<[u8; 64]> for Secp256K1PublicKey {
+    fn from(data: [u8; 64]) -> Self {
+        Self(data)
+    }
+}
+
+impl AsRef<[u8]> for Secp256K1PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct ED25519PublicKey(pub [u8; ed25519_dalek::PUBLIC_KEY_LENGTH]);
 
@@ -161,6 +173,28 @@ impl PublicKey {
             PublicKey::SECP256K1(_) => panic!(),
         }
     }
+
+    /// Constructs a public key of the given type from its raw bytes, without the key type prefix.
+    pub fn from_parts(key_type: KeyType, data: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        match key_type {
+            KeyType::ED25519 => {
+                let array: [u8; ed25519_dalek::PUBLIC_KEY_LENGTH] = data
+                    .try_into()
+                    .map_err(|_| format!("Invalid length {} of ED25519 public key", data.len()))?;
+                Ok(PublicKey::ED25519(ED25519PublicKey(array)))
+            }
+            KeyType::SECP256K1 => {
+                if data.len() != 64 {
+                    return Err(
+                        format!("Invalid length {} of SECP256K1 public key", data.len()).into()
+                    );
+                }
+                let mut array = [0; 64];
+                array.copy_from_slice(data);
+                Ok(PublicKey::SECP256K1(Secp256K1PublicKey(array)))
+            }
+        }
+    }
 }
 
 // This `Hash` implementation is safe since it retains the property
@@ -479,6 +513,30 @@ impl Debug for Secp256K1Signature {
     }
 }
 
+impl From<[u8; 65]> for Secp256K1Signature {
+    fn from(data: [u8; 65]) -> Self {
+        Self(data)
+    }
+}
+
+impl Secp256K1Signature {
+    fn to_recoverable(&self) -> Option<secp256k1::RecoverableSignature> {
+        let recovery_id = secp256k1::RecoveryId::from_i32(i32::from(self.0[64])).ok()?;
+        secp256k1::RecoverableSignature::from_compact(&SECP256K1, &self.0[0..64], recovery_id).ok()
+    }
+
+    /// Recovers the public key that signed the given 32 byte message hash.
+    /// Returns `None` if the signature is malformed or no key can be recovered from it.
+    pub fn recover(&self, msg: [u8; 32]) -> Option<Secp256K1PublicKey> {
+        let signature = self.to_recoverable()?;
+        let msg = secp256k1::Message::from_slice(&msg).ok()?;
+        let serialized = SECP256K1.recover(&msg, &signature).ok()?.serialize_vec(&SECP256K1, false);
+        let mut public_key = Secp256K1PublicKey([0; 64]);
+        public_key.0.copy_from_slice(&serialized[1..65]);
+        Some(public_key)
+    }
+}
+
 /// Signature container supporting different curves.
 #[derive(Clone, PartialEq, Eq)]
 pub enum Signature {
@@ -498,26 +556,23 @@ impl Signature {
                 }
             }
             (Signature::SECP256K1(signature), PublicKey::SECP256K1(public_key)) => {
-                let rsig = secp256k1::RecoverableSignature::from_compact(
-                    &SECP256K1,
-                    &signature.0[0..64],
-                    secp256k1::RecoveryId::from_i32(i32::from(signature.0[64])).unwrap(),
-                )
-                .unwrap();
-                let sig = rsig.to_standard(&SECP256K1);
+                let sig = match signature.to_recoverable() {
+                    Some(rsig) => rsig.to_standard(&SECP256K1),
+                    None => return false,
+                };
                 let pdata: [u8; 65] = {
                     // code borrowed from https://github.com/paritytech/parity-ethereum/blob/98b7c07171cd320f32877dfa5aa528f585dc9a72/ethkey/src/signature.rs#L210
                     let mut temp = [4u8; 65];
                     temp[1..65].copy_from_slice(&public_key.0);
                     temp
                 };
-                SECP256K1
-                    .verify(
-                        &secp256k1::Message::from_slice(data).expect("32 bytes"),
-                        &sig,
-                        &secp256k1::key::PublicKey::from_slice(&SECP256K1, &pdata).unwrap(),
-                    )
-                    .is_ok()
+                match (
+                    secp256k1::Message::from_slice(data),
+                    secp256k1::key::PublicKey::from_slice(&SECP256K1, &pdata),
+                ) {
+                    (Ok(msg), Ok(public_key)) => SECP256K1.verify(&msg, &sig, &public_key).is_ok(),
+                    _ => false,
+                }
             }
             _ => false,
         }
@@ -529,6 +584,26 @@ impl Signature {
             Signature::SECP256K1(_) => KeyType::SECP256K1,
         }
     }
+
+    /// Constructs a signature of the given type from its raw bytes, without the key type prefix.
+    pub fn from_parts(key_type: KeyType, data: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        match key_type {
+            KeyType::ED25519 => Ok(Signature::ED25519(
+                ed25519_dalek::Signature::from_bytes(data)
+                    .map_err(|e| format!("Invalid ED25519 signature: {}", e.to_string()))?,
+            )),
+            KeyType::SECP256K1 => {
+                if data.len() != 65 {
+                    return Err(
+                        format!("Invalid length {} of SECP256K1 signature", data.len()).into()
+                    );
+                }
+                let mut array = [0; 65];
+                array.copy_from_slice(data);
+                Ok(Signature::SECP256K1(Secp256K1Signature(array)))
+            }
+        }
+    }
 }
 
 impl Default for Signature {
@@ -696,6 +771,46 @@ mod tests {
         assert_eq!(signature, signature2);
     }
 
+    #[test]
+    fn test_secp256k1_recover() {
+        use sha2::Digest;
+        let data = sha2::Sha256::digest(b"123");
+        let mut msg = [0; 32];
+        msg.copy_from_slice(&data);
+
+        let sk = SecretKey::from_seed(KeyType::SECP256K1, "test");
+        let signature = match sk.sign(&msg) {
+            Signature::SECP256K1(signature) => signature,
+            _ => unreachable!(),
+        };
+        assert_eq!(PublicKey::SECP256K1(signature.recover(msg).unwrap()), sk.public_key());
+
+        let mut malformed = signature.0;
+        malformed[64] = 4;
+        let malformed = Secp256K1Signature::from(malformed);
+        assert!(malformed.recover(msg).is_none());
+        assert!(!Signature::SECP256K1(malformed).verify(&msg, &sk.public_key()));
+        assert!(!Signature::SECP256K1(signature).verify(b"123", &sk.public_key()));
+    }
+
+    #[test]
+    fn test_from_parts() {
+        for key_type in vec![KeyType::ED25519, KeyType::SECP256K1] {
+            let sk = SecretKey::from_seed(key_type, "test");
+            let pk = sk.public_key();
+            let signature = sk.sign(&[7; 32]);
+            let pk_bytes = pk.try_to_vec().unwrap();
+            let signature_bytes = signature.try_to_vec().unwrap();
+            assert_eq!(PublicKey::from_parts(pk.key_type(), &pk_bytes[1..]).unwrap(), pk);
+            assert_eq!(
+                Signature::from_parts(pk.key_type(), &signature_bytes[1..]).unwrap(),
+                signature
+            );
+            assert!(PublicKey::from_parts(pk.key_type(), &pk_bytes[2..]).is_err());
+            assert!(Signature::from_parts(pk.key_type(), &signature_bytes[2..]).is_err());
+        }
+    }
+
     #[test]
     fn test_json_serialize_secp256k1() {
         use sha2::Digest;
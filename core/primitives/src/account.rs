@@ -1,6 +1,8 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
+use near_crypto::PublicKey;
+
 use crate::hash::CryptoHash;
 use crate::serialize::{option_u128_dec_format, u128_dec_format_compatible};
 use crate::types::{AccountId, Balance, Nonce, StorageUsage};
@@ -55,6 +57,9 @@ pub enum AccessKeyPermission {
     /// Grants full access to the account.
     /// NOTE: It's used to replace account-level public keys.
     FullAccess,
+
+    /// Grants full access to the account to transactions signed by enough member keys.
+    MultiSig(MultiSigPermission),
 }
 
 /// Grants limited permission to make transactions with FunctionCallActions
@@ -83,6 +88,22 @@ pub struct FunctionCallPermission {
     pub method_names: Vec<String>,
 }
 
+/// Requires a transaction to be signed by at least `threshold` distinct keys out of the
+/// `public_keys`. The public key of the access key itself only identifies the access key and
+/// can't sign transactions on its own.
+/// NOTE: The member keys can't be changed. To change them or the threshold, the access key needs to
+/// be deleted and a new access key should be created.
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug,
+)]
+pub struct MultiSigPermission {
+    /// The minimum number of valid signatures by distinct member keys.
+    pub threshold: u32,
+
+    /// Member keys that can sign transactions for this access key.
+    pub public_keys: Vec<PublicKey>,
+}
+
 #[cfg(test)]
 mod tests {
     use borsh::BorshSerialize;
@@ -3,10 +3,11 @@ use serde::{Deserialize, Serialize};
 
 use near_crypto::Signature;
 
+use crate::block_header::BlockHeader;
 use crate::hash::{hash, CryptoHash};
 use crate::merkle::MerklePath;
-use crate::sharding::{EncodedShardChunk, ShardChunk, ShardChunkHeader};
-use crate::types::AccountId;
+use crate::sharding::{EncodedShardChunk, ReceiptProof, ShardChunk, ShardChunkHeader};
+use crate::types::{AccountId, BlockHeight};
 use crate::validator_signer::ValidatorSigner;
 
 /// Serialized TrieNodeWithSize
@@ -60,6 +61,17 @@ pub struct ChunkState {
     pub prev_merkle_proof: MerklePath,
     /// Previous chunk that contains transactions.
     pub prev_chunk: ShardChunk,
+    /// Encoded header of the block before the prev block, which sets the gas price of prev chunk.
+    pub prev_prev_block_header: Vec<u8>,
+    /// Chunk of the same shard in the block before the prev block. It must be included in that
+    /// block, so that prev chunk only receives receipts from the chunks of the prev block.
+    pub prev_prev_chunk_header: ShardChunkHeader,
+    /// Merkle proof of inclusion of the chunk before prev chunk.
+    pub prev_prev_merkle_proof: MerklePath,
+    /// All chunk headers of the prev block.
+    pub prev_block_chunks: Vec<ShardChunkHeader>,
+    /// Receipts sent to this shard by the chunks included in the prev block, ordered by source shard.
+    pub incoming_receipts: Vec<ReceiptProof>,
     /// Merkle proof of inclusion of this chunk.
     pub merkle_proof: MerklePath,
     /// Invalid chunk header.
@@ -77,6 +89,20 @@ pub enum ChallengeBody {
     ChunkState(ChunkState),
 }
 
+impl ChallengeBody {
+    /// Height of the challenged block, `None` if its header can't be decoded.
+    pub fn block_height(&self) -> Option<BlockHeight> {
+        let block_header = match self {
+            ChallengeBody::BlockDoubleSign(block_double_sign) => {
+                &block_double_sign.left_block_header
+            }
+            ChallengeBody::ChunkProofs(chunk_proofs) => &chunk_proofs.block_header,
+            ChallengeBody::ChunkState(chunk_state) => &chunk_state.block_header,
+        };
+        BlockHeader::try_from_slice(block_header).ok().map(|header| header.height())
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Serialize, PartialEq, Eq, Clone, Debug)]
 #[borsh_init(init)]
 pub struct Challenge {
@@ -1,5 +1,6 @@
 use crate::serialize::u128_dec_format;
-use crate::types::{AccountId, Balance, EpochId, Gas, Nonce};
+use crate::types::{AccountId, Balance, BlockHeight, EpochId, Gas, Nonce};
+use crate::version::ProtocolVersion;
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::PublicKey;
 use serde::{Deserialize, Serialize};
@@ -58,6 +59,9 @@ pub enum RuntimeError {
     ReceiptValidationError(ReceiptValidationError),
     /// Error when accessing validator information. Happens inside epoch manager.
     ValidatorError(EpochError),
+    /// The simulated transaction and its receipts burnt more gas than a simulation is allowed to.
+    /// Only returned by `Runtime::simulate_transaction`.
+    SimulationGasLimitExceeded,
 }
 
 /// Error used by `RuntimeExt`. This error has to be serializable, because it's transferred through
@@ -141,6 +145,8 @@ pub enum InvalidTxError {
     Expired,
     /// An error occurred while validating actions of a Transaction.
     ActionsValidation(ActionsValidationError),
+    /// The transaction uses a feature that is not enabled at the current protocol version.
+    UnsupportedProtocolFeature { protocol_version: ProtocolVersion },
 }
 
 #[derive(
@@ -166,6 +172,13 @@ pub enum InvalidAccessKeyError {
     },
     /// Having a deposit with a function call action is not allowed with a function call access key.
     DepositWithFunctionCall,
+    /// Transaction is not signed by enough member keys of the multi-signature access key.
+    NotEnoughSignatures {
+        account_id: AccountId,
+        public_key: PublicKey,
+        num_signatures: u64,
+        threshold: u64,
+    },
 }
 
 /// Describes the error for validating a list of actions.
@@ -197,6 +210,16 @@ pub enum ActionsValidationError {
     UnsuitableStakingKey { public_key: PublicKey },
     /// The attached amount of gas in a FunctionCall action has to be a positive number.
     FunctionCallZeroAttachedGas,
+    /// The threshold of a multi-signature access key in a Add Key action has to be a positive
+    /// number not greater than the number of member keys.
+    AddKeyMultiSigInvalidThreshold { threshold: u64, number_of_keys: u64 },
+    /// The member keys of a multi-signature access key in a Add Key action contain a duplicate.
+    AddKeyMultiSigDuplicateKey { public_key: PublicKey },
+    /// A Delegate action contains another Delegate action.
+    NestedDelegateAction,
+    /// The number of member keys of a multi-signature access key in a Add Key action exceeded
+    /// the limit.
+    AddKeyMultiSigNumberOfKeysExceeded { number_of_keys: u64, limit: u64 },
 }
 
 /// Describes the error for validating a receipt.
@@ -311,6 +334,25 @@ impl Display for ActionsValidationError {
                 f,
                 "The attached amount of gas in a FunctionCall action has to be a positive number",
             ),
+            ActionsValidationError::AddKeyMultiSigInvalidThreshold { threshold, number_of_keys } => write!(
+                f,
+                "The threshold {} of a multi-signature access key has to be between 1 and the number of member keys {} in a AddKey action",
+                threshold, number_of_keys
+            ),
+            ActionsValidationError::AddKeyMultiSigDuplicateKey { public_key } => write!(
+                f,
+                "The member key {} of a multi-signature access key is duplicated in a AddKey action",
+                public_key
+            ),
+            ActionsValidationError::NestedDelegateAction => write!(
+                f,
+                "A Delegate action can't contain another Delegate action",
+            ),
+            ActionsValidationError::AddKeyMultiSigNumberOfKeysExceeded { number_of_keys, limit } => write!(
+                f,
+                "The number of member keys {} of a multi-signature access key exceeds the limit {} in a AddKey action",
+                number_of_keys, limit
+            ),
         }
     }
 }
@@ -384,6 +426,16 @@ pub enum ActionErrorKind {
     /// Error occurs when a new `ActionReceipt` created by the `FunctionCall` action fails
     /// receipt validation.
     NewReceiptValidationError(ReceiptValidationError),
+    /// The signature of a `Delegate` action is not valid.
+    DelegateActionInvalidSignature,
+    /// The `sender_id` of a `Delegate` action is not the receiver of the action receipt.
+    DelegateActionSenderDoesNotMatchReceiver { sender_id: AccountId, receiver_id: AccountId },
+    /// A `Delegate` action is executed after its `max_block_height`.
+    DelegateActionExpired { max_block_height: BlockHeight, block_height: BlockHeight },
+    /// The access key of a `Delegate` action doesn't exist or doesn't allow the delegated actions.
+    DelegateActionAccessKeyError(InvalidAccessKeyError),
+    /// The nonce of a `Delegate` action must be larger than the nonce of the access key.
+    DelegateActionInvalidNonce { delegate_nonce: Nonce, ak_nonce: Nonce },
 }
 
 impl From<ActionErrorKind> for ActionError {
@@ -433,6 +485,11 @@ impl Display for InvalidTxError {
             InvalidTxError::ActionsValidation(error) => {
                 write!(f, "Transaction actions validation error: {}", error)
             }
+            InvalidTxError::UnsupportedProtocolFeature { protocol_version } => write!(
+                f,
+                "Transaction uses a feature that is not supported at protocol version {}",
+                protocol_version
+            ),
         }
     }
 }
@@ -480,6 +537,16 @@ impl Display for InvalidAccessKeyError {
             InvalidAccessKeyError::DepositWithFunctionCall => {
                 write!(f, "Having a deposit with a function call action is not allowed with a function call access key.")
             }
+            InvalidAccessKeyError::NotEnoughSignatures {
+                account_id,
+                public_key,
+                num_signatures,
+                threshold,
+            } => write!(
+                f,
+                "Multi-signature access key {:?}:{} requires {} signatures of member keys, but the transaction has {}",
+                account_id, public_key, threshold, num_signatures
+            ),
         }
     }
 }
@@ -670,7 +737,28 @@ impl Display for ActionErrorKind {
             ActionErrorKind::NewReceiptValidationError(e) => {
                 write!(f, "An new action receipt created during a FunctionCall is not valid: {}", e)
             }
-            ActionErrorKind::InsufficientStake { account_id, stake, minimum_stake } => write!(f, "Account {} tries to stake {} but minimum required stake is {}", account_id, stake, minimum_stake)
+            ActionErrorKind::InsufficientStake { account_id, stake, minimum_stake } => write!(f, "Account {} tries to stake {} but minimum required stake is {}", account_id, stake, minimum_stake),
+            ActionErrorKind::DelegateActionInvalidSignature => {
+                write!(f, "The signature of the Delegate action is not valid")
+            }
+            ActionErrorKind::DelegateActionSenderDoesNotMatchReceiver { sender_id, receiver_id } => write!(
+                f,
+                "The sender {:?} of the Delegate action doesn't match the receiver {:?} of the receipt",
+                sender_id, receiver_id
+            ),
+            ActionErrorKind::DelegateActionExpired { max_block_height, block_height } => write!(
+                f,
+                "The Delegate action expired at block height {}, current block height is {}",
+                max_block_height, block_height
+            ),
+            ActionErrorKind::DelegateActionAccessKeyError(access_key_error) => {
+                Display::fmt(&access_key_error, f)
+            }
+            ActionErrorKind::DelegateActionInvalidNonce { delegate_nonce, ak_nonce } => write!(
+                f,
+                "The Delegate action nonce {} must be larger than nonce of the used access key {}",
+                delegate_nonce, ak_nonce
+            ),
         }
     }
 }
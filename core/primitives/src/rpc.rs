@@ -10,7 +10,7 @@ use validator_derive::Validate;
 use crate::hash::CryptoHash;
 use crate::merkle::MerklePath;
 use crate::transaction::SignedTransaction;
-use crate::types::{AccountId, BlockIdOrFinality, TransactionOrReceiptId};
+use crate::types::{AccountId, BlockHeight, BlockIdOrFinality, TransactionOrReceiptId};
 use crate::views::{
     ExecutionOutcomeWithIdView, LightClientBlockLiteView, QueryRequest, StateChangeWithCauseView,
     StateChangesKindsView, StateChangesRequestView,
@@ -66,6 +66,52 @@ pub struct RpcStateChangesInBlockResponse {
     pub changes: StateChangesKindsView,
 }
 
+fn default_account_changes_limit() -> usize {
+    100
+}
+
+#[derive(Serialize, Deserialize, Validate)]
+pub struct RpcAccountChangesInRangeRequest {
+    pub account_ids: Vec<AccountId>,
+    pub from_block_height: BlockHeight,
+    pub to_block_height: BlockHeight,
+    /// Maximum number of changes to return. Changes of a single block are never split between
+    /// pages, so a page may hold more changes than this.
+    #[serde(default = "default_account_changes_limit")]
+    #[validate(range(min = 1, max = 1000))]
+    pub limit: usize,
+}
+
+fn default_logs_limit() -> usize {
+    100
+}
+
+#[derive(Serialize, Deserialize, Validate)]
+pub struct RpcLogsRequest {
+    /// Account on which the transactions or receipts were executed.
+    pub account_id: AccountId,
+    pub from_block_height: BlockHeight,
+    pub to_block_height: BlockHeight,
+    /// Only logs starting with this prefix are returned.
+    #[serde(default)]
+    pub prefix: String,
+    /// Maximum number of outcomes to return. Outcomes of a single block are never split between
+    /// pages, so a page may hold more outcomes than this.
+    #[serde(default = "default_logs_limit")]
+    #[validate(range(min = 1, max = 1000))]
+    pub limit: usize,
+}
+
+/// Transaction to simulate, borsh-serialized and encoded in base64.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcSimulateTransactionRequest {
+    /// A `SignedTransaction`, the signature of which is checked.
+    SignedTransaction(String),
+    /// An unsigned `Transaction`, which is simulated without checking the signatures.
+    Transaction(String),
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct RpcBroadcastTxSyncResponse {
     pub transaction_hash: String,
@@ -92,3 +138,25 @@ pub enum TransactionInfo {
     Transaction(SignedTransaction),
     TransactionId { hash: CryptoHash, account_id: AccountId },
 }
+
+/// Identifier of a subscription within a single WebSocket connection.
+pub type SubscriptionId = u64;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "subscription", rename_all = "snake_case")]
+pub enum RpcSubscriptionRequest {
+    /// New heads of the chain.
+    NewBlocks,
+    /// Heads advanced by finality.
+    FinalBlocks,
+    /// Changes of the given accounts in every new block.
+    AccountChanges { account_ids: Vec<AccountId> },
+    /// Final outcome of the given transaction. The subscription is closed once it is delivered.
+    TransactionOutcome { transaction_hash: CryptoHash, sender_id: AccountId },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcSubscriptionNotification<T> {
+    pub subscription: SubscriptionId,
+    pub result: T,
+}
@@ -7,9 +7,9 @@ use crate::errors::EpochError;
 use crate::hash::CryptoHash;
 use crate::merkle::PartialMerkleTree;
 use crate::transaction::{
-    Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
-    DeployContractAction, FunctionCallAction, SignedTransaction, StakeAction, Transaction,
-    TransferAction,
+    Action, AddKeyAction, CreateAccountAction, DelegateAction, DeleteAccountAction,
+    DeleteKeyAction, DeployContractAction, FunctionCallAction, MultiSignature,
+    SignedDelegateAction, SignedTransaction, StakeAction, Transaction, TransferAction,
 };
 use crate::types::{AccountId, Balance, BlockHeight, EpochId, EpochInfoProvider, Gas, Nonce};
 use crate::validator_signer::ValidatorSigner;
@@ -21,6 +21,13 @@ pub fn account_new(amount: Balance, code_hash: CryptoHash) -> Account {
     Account { amount, locked: 0, code_hash, storage_usage: std::mem::size_of::<Account>() as u64 }
 }
 
+impl DelegateAction {
+    pub fn sign(self, signer: &dyn Signer) -> SignedDelegateAction {
+        let signature = signer.sign(self.get_hash().as_ref());
+        SignedDelegateAction::new(signature, self)
+    }
+}
+
 impl Transaction {
     pub fn new(
         signer_id: AccountId,
@@ -37,6 +44,16 @@ impl Transaction {
         SignedTransaction::new(signature, self)
     }
 
+    /// Signs the transaction with the member keys of a multi-signature access key.
+    pub fn multi_sign(self, signers: &[&dyn Signer]) -> SignedTransaction {
+        let hash = self.get_hash();
+        let signatures = signers
+            .iter()
+            .map(|signer| (signer.public_key(), signer.sign(hash.as_ref())))
+            .collect();
+        SignedTransaction::new_multi_signed(MultiSignature { signatures }, self)
+    }
+
     pub fn create_account(mut self) -> Self {
         self.actions.push(Action::CreateAccount(CreateAccountAction {}));
         self
@@ -1,6 +1,8 @@
 use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
@@ -13,7 +15,7 @@ use crate::hash::{hash, CryptoHash};
 use crate::logging;
 use crate::merkle::MerklePath;
 use crate::serialize::{base64_format, u128_dec_format, u128_dec_format_compatible};
-use crate::types::{AccountId, Balance, Gas, Nonce};
+use crate::types::{AccountId, Balance, BlockHeight, Gas, Nonce};
 
 pub type LogEntry = String;
 
@@ -56,19 +58,35 @@ pub enum Action {
     AddKey(AddKeyAction),
     DeleteKey(DeleteKeyAction),
     DeleteAccount(DeleteAccountAction),
+    /// Executes actions signed by another account, paid for by the signer of the transaction.
+    Delegate(SignedDelegateAction),
 }
 
 impl Action {
+    /// Returns the gas attached to function calls. The gas of the delegated actions is prepaid by
+    /// the delegate action.
     pub fn get_prepaid_gas(&self) -> Gas {
         match self {
             Action::FunctionCall(a) => a.gas,
+            Action::Delegate(a) => a
+                .delegate_action
+                .actions
+                .iter()
+                .fold(0, |acc, action| acc.saturating_add(action.get_prepaid_gas())),
             _ => 0,
         }
     }
+    /// Returns the attached deposit. The deposits of the delegated actions are paid by the
+    /// delegate action.
     pub fn get_deposit_balance(&self) -> Balance {
         match self {
             Action::FunctionCall(a) => a.deposit,
             Action::Transfer(a) => a.deposit,
+            Action::Delegate(a) => a
+                .delegate_action
+                .actions
+                .iter()
+                .fold(0, |acc, action| acc.saturating_add(action.get_deposit_balance())),
             _ => 0,
         }
     }
@@ -150,17 +168,145 @@ pub struct DeleteAccountAction {
     pub beneficiary_id: AccountId,
 }
 
+/// Prefix of the hash preimage of a delegate action. A valid transaction can't start with these
+/// bytes, so a signature of a delegate action can never be used as a signature of a transaction.
+const DELEGATE_ACTION_HASH_PREFIX: &[u8] = b"NEAR delegate action";
+
+/// Actions that `sender_id` signs off-chain and a relayer submits within a transaction sent to
+/// `sender_id`. The relayer pays for the gas and the deposits of the delegated actions.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct DelegateAction {
+    /// Account on whose behalf the actions are executed.
+    pub sender_id: AccountId,
+    /// Receiver of the delegated actions.
+    pub receiver_id: AccountId,
+    /// Delegated actions. They can't contain another delegate action.
+    pub actions: Vec<Action>,
+    /// Nonce of the access key, must be greater than the nonce of the access key of `public_key`.
+    pub nonce: Nonce,
+    /// The delegate action expires after this block height.
+    pub max_block_height: BlockHeight,
+    /// Access key of `sender_id` that signed the delegate action.
+    pub public_key: PublicKey,
+}
+
+impl DelegateAction {
+    /// Computes a hash of the delegate action for signing.
+    pub fn get_hash(&self) -> CryptoHash {
+        let mut bytes = DELEGATE_ACTION_HASH_PREFIX.to_vec();
+        bytes.extend(self.try_to_vec().expect("Failed to serialize"));
+        hash(&bytes)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct SignedDelegateAction {
+    pub delegate_action: DelegateAction,
+    pub signature: Signature,
+}
+
+impl SignedDelegateAction {
+    pub fn new(signature: Signature, delegate_action: DelegateAction) -> Self {
+        Self { delegate_action, signature }
+    }
+
+    /// Checks that the delegate action is signed by its access key.
+    pub fn verify(&self) -> bool {
+        self.signature
+            .verify(self.delegate_action.get_hash().as_ref(), &self.delegate_action.public_key)
+    }
+}
+
+/// Signatures of a transaction signed with a multi-signature access key.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct MultiSignature {
+    /// Member public keys of the access key and their signatures of the transaction hash.
+    pub signatures: Vec<(PublicKey, Signature)>,
+}
+
+impl MultiSignature {
+    /// Verifies that every signature is valid for its public key and that no public key signed
+    /// twice.
+    pub fn verify(&self, data: &[u8]) -> bool {
+        let mut public_keys = HashSet::new();
+        self.signatures.iter().all(|(public_key, signature)| {
+            public_keys.insert(public_key) && signature.verify(data, public_key)
+        })
+    }
+}
+
+/// Borsh prefix of `TransactionSignature::Multi`. It can't be a valid key type, so a single
+/// signature is serialized exactly as a plain `Signature` and transactions keep their format.
+const MULTI_SIGNATURE_PREFIX: u8 = u8::max_value();
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(untagged)]
+pub enum TransactionSignature {
+    /// Signature by the access key of the transaction.
+    Single(Signature),
+    /// Signatures by the member keys of the multi-signature access key of the transaction.
+    Multi(MultiSignature),
+}
+
+impl BorshSerialize for TransactionSignature {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            TransactionSignature::Single(signature) => signature.serialize(writer),
+            TransactionSignature::Multi(multi_signature) => {
+                MULTI_SIGNATURE_PREFIX.serialize(writer)?;
+                multi_signature.serialize(writer)
+            }
+        }
+    }
+}
+
+impl BorshDeserialize for TransactionSignature {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        if buf.first() == Some(&MULTI_SIGNATURE_PREFIX) {
+            *buf = &buf[1..];
+            Ok(TransactionSignature::Multi(MultiSignature::deserialize(buf)?))
+        } else {
+            Ok(TransactionSignature::Single(Signature::deserialize(buf)?))
+        }
+    }
+}
+
+impl TransactionSignature {
+    /// Number of signatures that have to be verified.
+    pub fn num_signatures(&self) -> usize {
+        match self {
+            TransactionSignature::Single(_) => 1,
+            TransactionSignature::Multi(multi_signature) => multi_signature.signatures.len(),
+        }
+    }
+}
+
+impl From<Signature> for TransactionSignature {
+    fn from(signature: Signature) -> Self {
+        TransactionSignature::Single(signature)
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Eq, Debug, Clone)]
 #[borsh_init(init)]
 pub struct SignedTransaction {
     pub transaction: Transaction,
-    pub signature: Signature,
+    pub signature: TransactionSignature,
     #[borsh_skip]
     hash: CryptoHash,
 }
 
 impl SignedTransaction {
     pub fn new(signature: Signature, transaction: Transaction) -> Self {
+        Self::new_with_signature(signature.into(), transaction)
+    }
+
+    /// Creates a transaction signed with a multi-signature access key.
+    pub fn new_multi_signed(multi_signature: MultiSignature, transaction: Transaction) -> Self {
+        Self::new_with_signature(TransactionSignature::Multi(multi_signature), transaction)
+    }
+
+    fn new_with_signature(signature: TransactionSignature, transaction: Transaction) -> Self {
         let mut signed_tx = Self { signature, transaction, hash: CryptoHash::default() };
         signed_tx.init();
         signed_tx
@@ -271,6 +417,20 @@ impl From<ExecutionStatus> for PartialExecutionStatus {
     }
 }
 
+/// Breakdown of the gas burnt by one signed transaction or one receipt.
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, PartialEq, Clone, Default, Eq,
+)]
+pub struct GasProfile {
+    /// Gas burnt by the fees of the actions and the receipts, including the ones created by
+    /// contract calls.
+    pub action_gas: Gas,
+    /// Gas burnt by executing Wasm instructions of contract calls.
+    pub wasm_gas: Gas,
+    /// Gas burnt by the host functions of contract calls per cost category.
+    pub ext_costs: BTreeMap<String, Gas>,
+}
+
 /// Execution outcome for one signed transaction or one receipt.
 #[derive(BorshSerialize, BorshDeserialize, Serialize, PartialEq, Clone, Default, Eq)]
 pub struct ExecutionOutcome {
@@ -357,7 +517,18 @@ pub fn verify_transaction_signature(
 ) -> bool {
     let hash = transaction.get_hash();
     let hash = hash.as_ref();
-    public_keys.iter().any(|key| transaction.signature.verify(&hash, &key))
+    match &transaction.signature {
+        TransactionSignature::Single(signature) => {
+            public_keys.iter().any(|key| signature.verify(&hash, &key))
+        }
+        TransactionSignature::Multi(multi_signature) => {
+            multi_signature.verify(&hash)
+                && multi_signature
+                    .signatures
+                    .iter()
+                    .all(|(public_key, _)| public_keys.contains(public_key))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -397,6 +568,42 @@ mod tests {
         assert!(verify_transaction_signature(&decoded_tx, &valid_keys));
     }
 
+    #[test]
+    fn test_multi_signed_transaction() {
+        let signers: Vec<_> = (0..3)
+            .map(|i| InMemorySigner::from_seed("test", KeyType::ED25519, &format!("test{}", i)))
+            .collect();
+        let transaction = Transaction {
+            signer_id: "test".to_string(),
+            public_key: PublicKey::from_seed(KeyType::ED25519, "multisig"),
+            nonce: 0,
+            receiver_id: "test".to_string(),
+            block_hash: Default::default(),
+            actions: vec![],
+        };
+        let single_signed = transaction.clone().sign(&signers[0]);
+        let mut expected_bytes = transaction.try_to_vec().unwrap();
+        expected_bytes
+            .extend(signers[0].sign(transaction.get_hash().as_ref()).try_to_vec().unwrap());
+        assert_eq!(single_signed.try_to_vec().unwrap(), expected_bytes);
+
+        let signed_tx = transaction.multi_sign(&[&signers[0], &signers[2]]);
+        let bytes = signed_tx.try_to_vec().unwrap();
+        let decoded_tx = SignedTransaction::try_from_slice(&bytes).unwrap();
+        assert_eq!(decoded_tx, signed_tx);
+        assert_eq!(decoded_tx.signature.num_signatures(), 2);
+        let public_keys: Vec<_> = signers.iter().map(|signer| signer.public_key()).collect();
+        assert!(verify_transaction_signature(&decoded_tx, &public_keys));
+        assert!(!verify_transaction_signature(&decoded_tx, &public_keys[..2]));
+
+        let json = serde_json::to_string(&signed_tx).unwrap();
+        let decoded_signature: TransactionSignature = serde_json::from_value(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()["signature"].clone(),
+        )
+        .unwrap();
+        assert_eq!(decoded_signature, signed_tx.signature);
+    }
+
     /// This test is change checker for a reason - we don't expect transaction format to change.
     /// If it does - you MUST update all of the dependencies: like nearlib and other clients.
     #[test]
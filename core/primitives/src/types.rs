@@ -357,6 +357,36 @@ impl StateChanges {
 
         Ok(state_changes)
     }
+
+    /// Converts the changes of accounts, access keys, contract code and contract data, skipping
+    /// the changes of other kinds such as postponed receipts.
+    pub fn from_changes(
+        raw_changes: impl Iterator<Item = Result<RawStateChangesWithTrieKey, std::io::Error>>,
+    ) -> Result<StateChanges, std::io::Error> {
+        let mut state_changes = Self::new();
+
+        for raw_change in raw_changes {
+            let raw_change = raw_change?;
+            let changes = match raw_change.trie_key {
+                TrieKey::Account { .. } => {
+                    Self::from_account_changes(std::iter::once(Ok(raw_change)))?
+                }
+                TrieKey::AccessKey { .. } => {
+                    Self::from_access_key_changes(std::iter::once(Ok(raw_change)))?
+                }
+                TrieKey::ContractCode { .. } => {
+                    Self::from_contract_code_changes(std::iter::once(Ok(raw_change)))?
+                }
+                TrieKey::ContractData { .. } => {
+                    Self::from_data_changes(std::iter::once(Ok(raw_change)))?
+                }
+                _ => continue,
+            };
+            state_changes.extend(changes);
+        }
+
+        Ok(state_changes)
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, BorshSerialize, BorshDeserialize, Serialize)]
@@ -559,3 +589,19 @@ pub trait EpochInfoProvider {
 
     fn minimum_stake(&self, prev_block_hash: &CryptoHash) -> Result<Balance, EpochError>;
 }
+
+/// Storage for compiled contract artifacts.
+/// Used to break dependency between the VM runner and the store.
+pub trait CompiledContractCache: Send + Sync {
+    /// Stores the serialized artifact under the given key.
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<(), std::io::Error>;
+
+    /// Returns the serialized artifact stored under the given key, if any.
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, std::io::Error>;
+}
+
+impl std::fmt::Debug for dyn CompiledContractCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CompiledContractCache")
+    }
+}
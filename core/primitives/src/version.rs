@@ -12,13 +12,13 @@ pub struct Version {
 pub type DbVersion = u32;
 
 /// Current version of the database.
-pub const DB_VERSION: DbVersion = 6;
+pub const DB_VERSION: DbVersion = 10;
 
 /// Protocol version type.
 pub type ProtocolVersion = u32;
 
 /// Current latest version of the protocol.
-pub const PROTOCOL_VERSION: ProtocolVersion = 33;
+pub const PROTOCOL_VERSION: ProtocolVersion = 40;
 
 pub const FIRST_BACKWARD_COMPATIBLE_PROTOCOL_VERSION: ProtocolVersion = 29;
 
@@ -31,3 +31,26 @@ pub const MIN_GAS_PRICE_NEP_92_FIX: Balance = 100_000_000;
 pub const MIN_PROTOCOL_VERSION_NEP_92_FIX: ProtocolVersion = 32;
 
 pub const CORRECT_RANDOM_VALUE_PROTOCOL_VERSION: ProtocolVersion = 33;
+
+/// Protocol version that enables multi-signature access keys and multi-signed transactions.
+pub const MULTISIG_ACCESS_KEY_PROTOCOL_VERSION: ProtocolVersion = 34;
+
+/// Protocol version that enables delegate actions, i.e. actions submitted and paid for by a relayer.
+pub const DELEGATE_ACTION_PROTOCOL_VERSION: ProtocolVersion = 35;
+
+/// Protocol version from which peers encrypt the session after the handshake.
+pub const ENCRYPTED_TRANSPORT_PROTOCOL_VERSION: ProtocolVersion = 36;
+
+/// Protocol version from which peers may compress messages in the encrypted session.
+pub const MESSAGE_COMPRESSION_PROTOCOL_VERSION: ProtocolVersion = 37;
+
+/// Protocol version from which blocks include challenges and slash the validators they prove wrong.
+pub const CHALLENGES_PROTOCOL_VERSION: ProtocolVersion = 38;
+
+/// Protocol version from which contracts can import the ed25519 and secp256k1 signature
+/// verification, ecrecover, ripemd160 and blake2b host functions.
+pub const CRYPTO_HOST_FUNCTIONS_PROTOCOL_VERSION: ProtocolVersion = 39;
+
+/// Protocol version from which contracts can import the alt_bn128 G1 multiexp, G1 sum and
+/// pairing check host functions.
+pub const ALT_BN128_PROTOCOL_VERSION: ProtocolVersion = 40;
@@ -13,7 +13,9 @@ use serde::{Deserialize, Serialize};
 
 use near_crypto::{PublicKey, Signature};
 
-use crate::account::{AccessKey, AccessKeyPermission, Account, FunctionCallPermission};
+use crate::account::{
+    AccessKey, AccessKeyPermission, Account, FunctionCallPermission, MultiSigPermission,
+};
 use crate::block::{Block, BlockHeader};
 use crate::block_header::{
     BlockHeaderInnerLite, BlockHeaderInnerRest, BlockHeaderInnerRestV2, BlockHeaderV1,
@@ -33,9 +35,10 @@ use crate::serialize::{
 use crate::sharding::{ChunkHash, ShardChunk, ShardChunkHeader, ShardChunkHeaderInner};
 use crate::state_record::StateRecord;
 use crate::transaction::{
-    Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
-    DeployContractAction, ExecutionOutcome, ExecutionOutcomeWithIdAndProof, ExecutionStatus,
-    FunctionCallAction, SignedTransaction, StakeAction, TransferAction,
+    Action, AddKeyAction, CreateAccountAction, DelegateAction, DeleteAccountAction,
+    DeleteKeyAction, DeployContractAction, ExecutionOutcome, ExecutionOutcomeWithId,
+    ExecutionOutcomeWithIdAndProof, ExecutionStatus, FunctionCallAction, GasProfile,
+    SignedDelegateAction, SignedTransaction, StakeAction, TransactionSignature, TransferAction,
 };
 use crate::types::{
     AccountId, AccountWithPublicKey, Balance, BlockHeight, EpochId, FunctionArgs, Gas, Nonce,
@@ -91,6 +94,10 @@ pub enum AccessKeyPermissionView {
         method_names: Vec<String>,
     },
     FullAccess,
+    MultiSig {
+        threshold: u32,
+        public_keys: Vec<PublicKey>,
+    },
 }
 
 impl From<AccessKeyPermission> for AccessKeyPermissionView {
@@ -102,6 +109,10 @@ impl From<AccessKeyPermission> for AccessKeyPermissionView {
                 method_names: func_call.method_names,
             },
             AccessKeyPermission::FullAccess => AccessKeyPermissionView::FullAccess,
+            AccessKeyPermission::MultiSig(multi_sig) => AccessKeyPermissionView::MultiSig {
+                threshold: multi_sig.threshold,
+                public_keys: multi_sig.public_keys,
+            },
         }
     }
 }
@@ -117,6 +128,9 @@ impl From<AccessKeyPermissionView> for AccessKeyPermission {
                 })
             }
             AccessKeyPermissionView::FullAccess => AccessKeyPermission::FullAccess,
+            AccessKeyPermissionView::MultiSig { threshold, public_keys } => {
+                AccessKeyPermission::MultiSig(MultiSigPermission { threshold, public_keys })
+            }
         }
     }
 }
@@ -669,6 +683,20 @@ pub enum ActionView {
     DeleteAccount {
         beneficiary_id: AccountId,
     },
+    Delegate {
+        delegate_action: DelegateActionView,
+        signature: Signature,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
+pub struct DelegateActionView {
+    pub sender_id: AccountId,
+    pub receiver_id: AccountId,
+    pub actions: Vec<ActionView>,
+    pub nonce: Nonce,
+    pub max_block_height: BlockHeight,
+    pub public_key: PublicKey,
 }
 
 impl From<Action> for ActionView {
@@ -696,6 +724,20 @@ impl From<Action> for ActionView {
             Action::DeleteAccount(action) => {
                 ActionView::DeleteAccount { beneficiary_id: action.beneficiary_id }
             }
+            Action::Delegate(action) => {
+                let delegate_action = action.delegate_action;
+                ActionView::Delegate {
+                    delegate_action: DelegateActionView {
+                        sender_id: delegate_action.sender_id,
+                        receiver_id: delegate_action.receiver_id,
+                        actions: delegate_action.actions.into_iter().map(Into::into).collect(),
+                        nonce: delegate_action.nonce,
+                        max_block_height: delegate_action.max_block_height,
+                        public_key: delegate_action.public_key,
+                    },
+                    signature: action.signature,
+                }
+            }
         }
     }
 }
@@ -730,6 +772,23 @@ impl TryFrom<ActionView> for Action {
             ActionView::DeleteAccount { beneficiary_id } => {
                 Action::DeleteAccount(DeleteAccountAction { beneficiary_id })
             }
+            ActionView::Delegate { delegate_action, signature } => {
+                Action::Delegate(SignedDelegateAction {
+                    delegate_action: DelegateAction {
+                        sender_id: delegate_action.sender_id,
+                        receiver_id: delegate_action.receiver_id,
+                        actions: delegate_action
+                            .actions
+                            .into_iter()
+                            .map(Action::try_from)
+                            .collect::<Result<_, _>>()?,
+                        nonce: delegate_action.nonce,
+                        max_block_height: delegate_action.max_block_height,
+                        public_key: delegate_action.public_key,
+                    },
+                    signature,
+                })
+            }
         })
     }
 }
@@ -741,7 +800,7 @@ pub struct SignedTransactionView {
     pub nonce: Nonce,
     pub receiver_id: AccountId,
     pub actions: Vec<ActionView>,
-    pub signature: Signature,
+    pub signature: TransactionSignature,
     pub hash: CryptoHash,
 }
 
@@ -862,6 +921,11 @@ pub struct ExecutionOutcomeView {
     /// The id of the account on which the execution happens. For transaction this is signer_id,
     /// for receipt this is receiver_id.
     pub executor_id: AccountId,
+    /// Breakdown of the burnt gas. Only returned by the RPC of nodes with gas profiles enabled,
+    /// it is not part of the outcomes exchanged between nodes.
+    #[borsh_skip]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<GasProfile>,
     /// Execution status. Contains the result in case of successful execution.
     pub status: ExecutionStatusView,
 }
@@ -874,6 +938,7 @@ impl From<ExecutionOutcome> for ExecutionOutcomeView {
             gas_burnt: outcome.gas_burnt,
             tokens_burnt: outcome.tokens_burnt,
             executor_id: outcome.executor_id,
+            profile: None,
             status: outcome.status.into(),
         }
     }
@@ -925,6 +990,39 @@ impl fmt::Debug for FinalExecutionOutcomeView {
     }
 }
 
+/// Execution outcome of a simulated transaction or receipt. Unlike `ExecutionOutcomeWithIdView`,
+/// it has no proof since it is not included in any block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedExecutionOutcomeView {
+    /// The transaction hash or the receipt ID.
+    pub id: CryptoHash,
+    pub outcome: ExecutionOutcomeView,
+}
+
+impl From<ExecutionOutcomeWithId> for SimulatedExecutionOutcomeView {
+    fn from(outcome_with_id: ExecutionOutcomeWithId) -> Self {
+        Self { id: outcome_with_id.id, outcome: outcome_with_id.outcome.into() }
+    }
+}
+
+/// Result of simulating a transaction on top of the state of a block without persisting anything.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionSimulationView {
+    /// The block on top of which the transaction was simulated.
+    pub block_hash: CryptoHash,
+    /// Total gas burnt by the transaction and the simulated receipts.
+    pub gas_burnt: Gas,
+    /// The execution outcome of the transaction.
+    pub transaction_outcome: SimulatedExecutionOutcomeView,
+    /// The execution outcomes of the receipts in the order of execution. Only receipts to accounts
+    /// in the shard of the signer are executed.
+    pub receipts_outcome: Vec<SimulatedExecutionOutcomeView>,
+    /// Receipts to accounts in other shards, which were not executed.
+    pub outgoing_receipts: Vec<ReceiptView>,
+    /// Changes of the state of the signer's shard.
+    pub state_changes: StateChangesView,
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 pub struct ValidatorStakeView {
     pub account_id: AccountId,
@@ -1339,3 +1437,53 @@ impl From<StateChangeWithCause> for StateChangeWithCauseView {
 }
 
 pub type StateChangesView = Vec<StateChangeWithCauseView>;
+
+/// State changes that happened in a single block.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateChangesInBlockView {
+    pub block_hash: CryptoHash,
+    pub block_height: BlockHeight,
+    pub changes: StateChangesView,
+}
+
+/// A page of state changes over a range of block heights.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateChangesInRangeView {
+    /// Blocks with at least one change, ordered by height.
+    pub blocks: Vec<StateChangesInBlockView>,
+    /// Height to continue from, if the range was not exhausted.
+    pub next_block_height: Option<BlockHeight>,
+}
+
+/// Logs of a single execution outcome that match the requested prefix.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutcomeLogsView {
+    pub block_hash: CryptoHash,
+    pub block_height: BlockHeight,
+    /// Id of the transaction or receipt.
+    pub id: CryptoHash,
+    pub executor_id: AccountId,
+    pub logs: Vec<String>,
+}
+
+/// A page of logs over a range of block heights.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogsView {
+    /// Outcomes with at least one matching log, ordered by block height.
+    pub outcomes: Vec<OutcomeLogsView>,
+    /// Height to continue from, if the range was not exhausted.
+    pub next_block_height: Option<BlockHeight>,
+}
+
+/// A transaction waiting in the transaction pool of the node.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingTransactionView {
+    pub hash: CryptoHash,
+    pub signer_id: AccountId,
+    pub public_key: PublicKey,
+    pub nonce: Nonce,
+    pub receiver_id: AccountId,
+    pub shard_id: ShardId,
+    /// Time since the transaction was added to the pool, in milliseconds.
+    pub pending_duration_ms: u64,
+}
@@ -107,10 +107,18 @@ pub enum DBCol {
     ColTransactionRefCount = 43,
     /// Heights of blocks that have been processed
     ColProcessedBlockHeights = 44,
+    /// Serialized compiled contract artifacts, keyed by contract code hash and VM configuration
+    ColCachedContractCode = 45,
+    /// Optional index of execution outcomes by executor account, block height and log prefix
+    ColLogsIndex = 46,
+    /// Keys of `ColLogsIndex` written for a block, to garbage collect them with the block
+    ColLogsIndexKeys = 47,
+    /// Optional breakdown of the burnt gas of execution outcomes, keyed by outcome id
+    ColGasProfiles = 48,
 }
 
 // Do not move this line from enum DBCol
-pub const NUM_COLS: usize = 45;
+pub const NUM_COLS: usize = 49;
 
 impl std::fmt::Display for DBCol {
     fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
@@ -160,6 +168,10 @@ impl std::fmt::Display for DBCol {
             Self::ColOutcomesByBlockHash => "outcomes by block hash",
             Self::ColTransactionRefCount => "refcount per transaction",
             Self::ColProcessedBlockHeights => "processed block heights",
+            Self::ColCachedContractCode => "cached compiled contracts",
+            Self::ColLogsIndex => "outcome ids by account, block height and log prefix",
+            Self::ColLogsIndexKeys => "logs index keys by block hash",
+            Self::ColGasProfiles => "gas profiles of outcomes",
         };
         write!(formatter, "{}", desc)
     }
@@ -184,6 +196,7 @@ lazy_static! {
         col_gc[DBCol::ColBlockOrdinal as usize] = false;
         col_gc[DBCol::ColEpochInfo as usize] = false; // https://github.com/nearprotocol/nearcore/pull/2952
         col_gc[DBCol::ColEpochStart as usize] = false; // https://github.com/nearprotocol/nearcore/pull/2952
+        col_gc[DBCol::ColCachedContractCode as usize] = false; // compiled contracts are not tied to blocks
         col_gc
     };
 }
@@ -274,6 +287,14 @@ pub trait Database: Sync + Send {
         col: DBCol,
         key_prefix: &'a [u8],
     ) -> Box<dyn Iterator<Item = (Box<[u8]>, Box<[u8]>)> + 'a>;
+    /// Iterates over the keys starting with `key_prefix` that are not less than `lower_bound`,
+    /// in the ascending order of keys.
+    fn iter_prefix_from<'a>(
+        &'a self,
+        col: DBCol,
+        key_prefix: &'a [u8],
+        lower_bound: &'a [u8],
+    ) -> Box<dyn Iterator<Item = (Box<[u8]>, Box<[u8]>)> + 'a>;
     fn write(&self, batch: DBTransaction) -> Result<(), DBError>;
 }
 
@@ -318,6 +339,27 @@ impl Database for RocksDB {
         }
     }
 
+    fn iter_prefix_from<'a>(
+        &'a self,
+        col: DBCol,
+        key_prefix: &'a [u8],
+        lower_bound: &'a [u8],
+    ) -> Box<dyn Iterator<Item = (Box<[u8]>, Box<[u8]>)> + 'a> {
+        let read_options = rocksdb_read_options();
+        unsafe {
+            let cf_handle = &*self.cfs[col as usize];
+            let iterator = self
+                .db
+                .iterator_cf_opt(
+                    cf_handle,
+                    read_options,
+                    IteratorMode::From(std::cmp::max(key_prefix, lower_bound), Direction::Forward),
+                )
+                .take_while(move |(key, _value)| key.starts_with(key_prefix));
+            RocksDB::empty_value_filtering_iter(col, iterator)
+        }
+    }
+
     fn write(&self, transaction: DBTransaction) -> Result<(), DBError> {
         let mut batch = WriteBatch::default();
         for op in transaction.ops {
@@ -358,6 +400,20 @@ impl Database for TestDB {
         Box::new(self.iter(col).filter(move |(key, _value)| key.starts_with(key_prefix)))
     }
 
+    fn iter_prefix_from<'a>(
+        &'a self,
+        col: DBCol,
+        key_prefix: &'a [u8],
+        lower_bound: &'a [u8],
+    ) -> Box<dyn Iterator<Item = (Box<[u8]>, Box<[u8]>)> + 'a> {
+        let mut items: Vec<_> = self
+            .iter_prefix(col, key_prefix)
+            .filter(|(key, _value)| key.as_ref() >= lower_bound)
+            .collect();
+        items.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Box::new(items.into_iter())
+    }
+
     fn write(&self, transaction: DBTransaction) -> Result<(), DBError> {
         let mut db = self.db.write().unwrap();
         for op in transaction.ops {
@@ -24,7 +24,7 @@ use near_primitives::hash::CryptoHash;
 use near_primitives::receipt::{Receipt, ReceivedData};
 use near_primitives::serialize::to_base;
 use near_primitives::trie_key::{trie_key_parsers, TrieKey};
-use near_primitives::types::AccountId;
+use near_primitives::types::{AccountId, CompiledContractCache};
 
 use crate::db::{DBOp, DBTransaction, Database, RocksDB};
 pub use crate::trie::{
@@ -91,6 +91,15 @@ impl Store {
         self.storage.iter_prefix(column, key_prefix)
     }
 
+    pub fn iter_prefix_from<'a>(
+        &'a self,
+        column: DBCol,
+        key_prefix: &'a [u8],
+        lower_bound: &'a [u8],
+    ) -> Box<dyn Iterator<Item = (Box<[u8]>, Box<[u8]>)> + 'a> {
+        self.storage.iter_prefix_from(column, key_prefix, lower_bound)
+    }
+
     pub fn iter_prefix_ser<'a, T: BorshDeserialize>(
         &'a self,
         column: DBCol,
@@ -267,6 +276,23 @@ pub fn read_with_cache<'a, T: BorshDeserialize + 'a>(
     Ok(None)
 }
 
+/// Persistent cache of compiled contracts backed by `ColCachedContractCode`.
+pub struct StoreCompiledContractCache {
+    pub store: Arc<Store>,
+}
+
+impl CompiledContractCache for StoreCompiledContractCache {
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<(), io::Error> {
+        let mut store_update = self.store.store_update();
+        store_update.set(DBCol::ColCachedContractCode, key, value);
+        store_update.commit()
+    }
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, io::Error> {
+        self.store.get(DBCol::ColCachedContractCode, key)
+    }
+}
+
 pub fn create_store(path: &str) -> Arc<Store> {
     let db = Arc::pin(RocksDB::new(path).expect("Failed to open the database"));
     Arc::new(Store::new(db))
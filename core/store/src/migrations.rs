@@ -4,6 +4,7 @@ use std::collections::{HashMap, HashSet};
 use borsh::BorshDeserialize;
 
 use near_primitives::hash::CryptoHash;
+use near_primitives::network::AnnounceAccount;
 use near_primitives::transaction::ExecutionOutcomeWithIdAndProof;
 use near_primitives::version::DbVersion;
 
@@ -81,3 +82,15 @@ pub fn fill_col_transaction_refcount(store: &Store) {
             .expect("BorshSerialize should not fail");
     }
 }
+
+pub fn migrate_col_account_announcements(store: &Store) {
+    let mut store_update = store.store_update();
+    for (key, value) in store.iter(DBCol::ColAccountAnnouncements) {
+        let announce_account =
+            AnnounceAccount::try_from_slice(&value).expect("BorshDeserialize should not fail");
+        store_update
+            .set_ser(DBCol::ColAccountAnnouncements, &key, &vec![announce_account])
+            .expect("BorshSerialize should not fail");
+    }
+    store_update.commit().expect("Failed to migrate account announcements");
+}
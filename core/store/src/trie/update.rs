@@ -134,6 +134,13 @@ impl TrieUpdate {
         Ok((trie_changes, state_changes))
     }
 
+    /// Returns the committed changes without computing the new state root. Used for updates that
+    /// are thrown away instead of being applied to the trie.
+    pub fn into_state_changes(self) -> Vec<RawStateChangesWithTrieKey> {
+        assert!(self.prospective.is_empty(), "Finalize cannot be called with uncommitted changes.");
+        self.committed.into_iter().map(|(_, changes)| changes).collect()
+    }
+
     /// Returns Error if the underlying storage fails
     pub fn iter(&self, key_prefix: &[u8]) -> Result<TrieUpdateIterator<'_>, StorageError> {
         TrieUpdateIterator::new(self, key_prefix, b"", None)
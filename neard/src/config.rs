@@ -2,6 +2,7 @@ use std::convert::TryInto;
 use std::fs;
 use std::fs::File;
 use std::io::{Read, Write};
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
@@ -17,6 +18,7 @@ use lazy_static::lazy_static;
 use near_chain_configs::{ClientConfig, Genesis, GenesisConfig};
 use near_crypto::{InMemorySigner, KeyFile, KeyType, PublicKey, Signer};
 use near_jsonrpc::RpcConfig;
+use near_network::capture::CaptureConfig;
 use near_network::test_utils::open_port;
 use near_network::types::ROUTED_MESSAGE_TTL;
 use near_network::utils::blacklist_from_iter;
@@ -185,6 +187,24 @@ fn default_peer_stats_period() -> Duration {
     Duration::from_secs(5)
 }
 
+fn default_broadcast_min_fanout() -> u32 {
+    4
+}
+
+/// Maximum number of inbound connections from the same IP address.
+fn default_max_inbound_connections_per_ip() -> u32 {
+    8
+}
+/// Maximum number of inbound connections from the same subnet.
+fn default_max_inbound_connections_per_subnet() -> u32 {
+    16
+}
+
+/// Messages larger than this many bytes are compressed.
+fn default_compression_threshold() -> Option<usize> {
+    Some(1024)
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Network {
     /// Address to listen for incoming connections.
@@ -231,6 +251,29 @@ pub struct Network {
     /// Period to check on peer status
     #[serde(default = "default_peer_stats_period")]
     pub peer_stats_period: Duration,
+    /// Send blocks and challenges only to a subset of peers that covers the rest of the known network graph.
+    #[serde(default)]
+    pub smart_broadcast: bool,
+    /// Minimum number of peers a block or challenge is sent to when smart broadcast is enabled.
+    #[serde(default = "default_broadcast_min_fanout")]
+    pub broadcast_min_fanout: u32,
+    /// IP addresses that are never banned and not subject to the inbound connection limits.
+    /// Loopback addresses are always allowed.
+    #[serde(default)]
+    pub ip_allowlist: Vec<IpAddr>,
+    /// Maximum number of inbound connections from the same IP address.
+    #[serde(default = "default_max_inbound_connections_per_ip")]
+    pub max_inbound_connections_per_ip: u32,
+    /// Maximum number of inbound connections from the same subnet (/24 for IPv4 and /64 for IPv6).
+    #[serde(default = "default_max_inbound_connections_per_subnet")]
+    pub max_inbound_connections_per_subnet: u32,
+    /// Messages larger than this many bytes are compressed when sent to peers that support it.
+    /// Set to null to disable compression.
+    #[serde(default = "default_compression_threshold")]
+    pub compression_threshold: Option<usize>,
+    /// Write all messages exchanged with peers into files in the given directory.
+    #[serde(default)]
+    pub capture: Option<CaptureConfig>,
 }
 
 impl Default for Network {
@@ -252,6 +295,13 @@ impl Default for Network {
             blacklist: vec![],
             ttl_account_id_router: default_ttl_account_id_router(),
             peer_stats_period: default_peer_stats_period(),
+            smart_broadcast: false,
+            broadcast_min_fanout: default_broadcast_min_fanout(),
+            ip_allowlist: vec![],
+            max_inbound_connections_per_ip: default_max_inbound_connections_per_ip(),
+            max_inbound_connections_per_subnet: default_max_inbound_connections_per_subnet(),
+            compression_threshold: default_compression_threshold(),
+            capture: None,
         }
     }
 }
@@ -293,6 +343,14 @@ fn default_view_client_threads() -> usize {
     4
 }
 
+fn default_transaction_pool_size_limit() -> usize {
+    100_000
+}
+
+fn default_transaction_pool_signer_limit() -> usize {
+    1_000
+}
+
 fn default_doomslug_step_period() -> Duration {
     Duration::from_millis(100)
 }
@@ -388,10 +446,17 @@ pub struct Config {
     pub tracked_accounts: Vec<AccountId>,
     pub tracked_shards: Vec<ShardId>,
     pub archive: bool,
+    pub fisherman: bool,
     #[serde(default = "default_gc_blocks_limit")]
     pub gc_blocks_limit: NumBlocks,
     #[serde(default = "default_view_client_threads")]
     pub view_client_threads: usize,
+    pub enable_logs_index: bool,
+    pub enable_gas_profiles: bool,
+    #[serde(default = "default_transaction_pool_size_limit")]
+    pub transaction_pool_size_limit: usize,
+    #[serde(default = "default_transaction_pool_signer_limit")]
+    pub transaction_pool_signer_limit: usize,
 }
 
 impl Default for Config {
@@ -408,8 +473,13 @@ impl Default for Config {
             tracked_accounts: vec![],
             tracked_shards: vec![],
             archive: false,
+            fisherman: false,
             gc_blocks_limit: default_gc_blocks_limit(),
             view_client_threads: 4,
+            enable_logs_index: false,
+            enable_gas_profiles: false,
+            transaction_pool_size_limit: default_transaction_pool_size_limit(),
+            transaction_pool_signer_limit: default_transaction_pool_signer_limit(),
         }
     }
 }
@@ -573,8 +643,13 @@ impl NearConfig {
                 tracked_accounts: config.tracked_accounts,
                 tracked_shards: config.tracked_shards,
                 archive: config.archive,
+                fisherman: config.fisherman,
                 gc_blocks_limit: config.gc_blocks_limit,
                 view_client_threads: config.view_client_threads,
+                enable_logs_index: config.enable_logs_index,
+                enable_gas_profiles: config.enable_gas_profiles,
+                transaction_pool_size_limit: config.transaction_pool_size_limit,
+                transaction_pool_signer_limit: config.transaction_pool_signer_limit,
             },
             network_config: NetworkConfig {
                 public_key: network_key_pair.public_key,
@@ -615,6 +690,15 @@ impl NearConfig {
                 push_info_period: Duration::from_millis(100),
                 blacklist: blacklist_from_iter(config.network.blacklist),
                 outbound_disabled: false,
+                smart_broadcast: config.network.smart_broadcast,
+                broadcast_min_fanout: config.network.broadcast_min_fanout,
+                ip_allowlist: config.network.ip_allowlist.into_iter().collect(),
+                max_inbound_connections_per_ip: config.network.max_inbound_connections_per_ip,
+                max_inbound_connections_per_subnet: config
+                    .network
+                    .max_inbound_connections_per_subnet,
+                compression_threshold: config.network.compression_threshold,
+                capture: config.network.capture,
             },
             telemetry_config: config.telemetry,
             rpc_config: config.rpc,
@@ -1,17 +1,20 @@
 use std::fs;
+use std::net::ToSocketAddrs;
 use std::path::Path;
 use std::sync::Arc;
 
 use actix::{Actor, Addr, Arbiter};
-use log::{error, info};
+use log::{error, info, warn};
 use tracing::trace;
 
 use near_chain::ChainGenesis;
 use near_client::{start_client, start_view_client, ClientActor, ViewClientActor};
-use near_jsonrpc::start_http;
+use near_jsonrpc::{start_admin_http, start_http};
 use near_network::{NetworkRecipient, PeerManagerActor};
+use near_primitives::types::ShardId;
 use near_store::migrations::{
-    fill_col_outcomes_by_hash, fill_col_transaction_refcount, get_store_version, set_store_version,
+    fill_col_outcomes_by_hash, fill_col_transaction_refcount, get_store_version,
+    migrate_col_account_announcements, set_store_version,
 };
 use near_store::{create_store, Store};
 use near_telemetry::TelemetryActor;
@@ -102,6 +105,31 @@ pub fn apply_store_migrations(path: &String) {
         let store = create_store(&path);
         set_store_version(&store, 6);
     }
+    if db_version <= 6 {
+        // version 6 => 7: add ColCachedContractCode
+        // the cache is filled lazily, so there is nothing to backfill
+        let store = create_store(&path);
+        set_store_version(&store, 7);
+    }
+    if db_version <= 7 {
+        // version 7 => 8: add ColLogsIndex, keyed by account and block height, and ColLogsIndexKeys
+        // the index only covers blocks processed after it is enabled, so there is nothing to backfill
+        let store = create_store(&path);
+        set_store_version(&store, 8);
+    }
+    if db_version <= 8 {
+        // version 8 => 9: ColAccountAnnouncements stores several announcements per account
+        info!(target: "near", "Migrate DB from version 8 to 9");
+        let store = create_store(&path);
+        migrate_col_account_announcements(&store);
+        set_store_version(&store, 9);
+    }
+    if db_version <= 9 {
+        // version 9 => 10: add ColGasProfiles
+        // profiles are only recorded for outcomes of blocks processed after they are enabled
+        let store = create_store(&path);
+        set_store_version(&store, 10);
+    }
 
     let db_version = get_store_version(path);
     debug_assert_eq!(db_version, near_primitives::version::DB_VERSION);
@@ -120,20 +148,56 @@ pub fn init_and_migrate_store(home_dir: &Path) -> Arc<Store> {
     store
 }
 
+/// Refuse to serve the admin RPC on the public RPC address, and warn if it can be reached
+/// from outside of the host.
+fn verify_admin_addr(admin_addr: &str, rpc_addr: &str) {
+    let admin_addrs: Vec<_> = match admin_addr.to_socket_addrs() {
+        Ok(addrs) => addrs.collect(),
+        Err(err) => panic!("Failed to resolve admin_addr {}: {}", admin_addr, err),
+    };
+    let rpc_addrs: Vec<_> = rpc_addr.to_socket_addrs().map(Iterator::collect).unwrap_or_default();
+    // An unspecified address listens on every interface, so it overlaps any address with the same port.
+    let overlaps = admin_addrs.iter().any(|admin| {
+        rpc_addrs.iter().any(|rpc| {
+            admin.port() == rpc.port()
+                && (admin.ip() == rpc.ip()
+                    || admin.ip().is_unspecified()
+                    || rpc.ip().is_unspecified())
+        })
+    });
+    if admin_addr == rpc_addr || overlaps {
+        panic!("admin_addr {} must be different from the RPC address {}", admin_addr, rpc_addr);
+    }
+    if !admin_addrs.iter().all(|addr| addr.ip().is_loopback()) {
+        warn!(target: "near", "admin_addr {} is not a loopback address, admin RPC methods may be reachable from outside of this host", admin_addr);
+    }
+}
+
 pub fn start_with_config(
     home_dir: &Path,
-    config: NearConfig,
+    mut config: NearConfig,
 ) -> (Addr<ClientActor>, Addr<ViewClientActor>, Vec<Arbiter>) {
     let store = init_and_migrate_store(home_dir);
     near_actix_utils::init_stop_on_panic();
 
-    let runtime = Arc::new(NightshadeRuntime::new(
+    // Fisherman re-executes the chunks of all shards to challenge the invalid ones.
+    if config.client_config.fisherman {
+        let num_shards = config.genesis.config.num_block_producer_seats_per_shard.len();
+        config.client_config.tracked_shards = (0..num_shards as ShardId).collect();
+        if config.validator_signer.is_none() {
+            warn!(target: "near", "Running as fisherman without a validator key, challenges can't be signed");
+        }
+    }
+
+    let mut runtime = NightshadeRuntime::new(
         home_dir,
         Arc::clone(&store),
         Arc::clone(&config.genesis),
         config.client_config.tracked_accounts.clone(),
         config.client_config.tracked_shards.clone(),
-    ));
+    );
+    runtime.set_record_gas_profiles(config.client_config.enable_gas_profiles);
+    let runtime = Arc::new(runtime);
 
     let telemetry = TelemetryActor::new(config.telemetry_config.clone()).start();
     let chain_genesis = ChainGenesis::from(&config.genesis);
@@ -156,6 +220,10 @@ pub fn start_with_config(
         config.validator_signer,
         telemetry,
     );
+    if let Some(admin_addr) = config.rpc_config.admin_addr.clone() {
+        verify_admin_addr(&admin_addr, &config.rpc_config.addr);
+        start_admin_http(admin_addr, network_adapter.clone());
+    }
     start_http(
         config.rpc_config,
         Arc::clone(&config.genesis),
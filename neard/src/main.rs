@@ -102,6 +102,7 @@ fn main() {
             .arg(Arg::with_name("rpc-addr").long("rpc-addr").help("Customize RPC listening address (useful for running multiple nodes on the same machine)").takes_value(true))
             .arg(Arg::with_name("telemetry-url").long("telemetry-url").help("Customize telemetry url").takes_value(true))
             .arg(Arg::with_name("archive").long("archive").help("Keep old blocks in the storage (default false)").takes_value(false))
+            .arg(Arg::with_name("fisherman").long("fisherman").help("Track all shards and challenge invalid chunks, signing with the validator key (default false)").takes_value(false))
         )
         .subcommand(SubCommand::with_name("unsafe_reset_data").about("(unsafe) Remove all the data, effectively resetting node to genesis state (keeps genesis and config)"))
         .subcommand(SubCommand::with_name("unsafe_reset_all").about("(unsafe) Remove all the config, keys, data and effectively removing all information about the network"))
@@ -226,6 +227,9 @@ fn main() {
             if args.is_present("archive") {
                 near_config.client_config.archive = true;
             }
+            if args.is_present("fisherman") {
+                near_config.client_config.fisherman = true;
+            }
 
             let system = System::new("NEAR");
             let (_, _, arbiters) = start_with_config(home_dir, near_config);
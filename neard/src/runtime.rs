@@ -26,19 +26,21 @@ use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::receipt::Receipt;
 use near_primitives::sharding::ShardChunkHeader;
 use near_primitives::state_record::StateRecord;
-use near_primitives::transaction::SignedTransaction;
+use near_primitives::transaction::{GasProfile, SignedTransaction};
 use near_primitives::trie_key::trie_key_parsers;
 use near_primitives::types::{
     AccountId, ApprovalStake, Balance, BlockHeight, EpochHeight, EpochId, EpochInfoProvider, Gas,
-    MerkleHash, NumShards, ShardId, StateChangeCause, StateRoot, StateRootNode, ValidatorStake,
+    MerkleHash, NumShards, ShardId, StateChangeCause, StateChanges, StateChangesExt, StateRoot,
+    StateRootNode, ValidatorStake,
 };
 use near_primitives::version::ProtocolVersion;
 use near_primitives::views::{
     AccessKeyInfoView, CallResult, EpochValidatorInfo, QueryError, QueryRequest, QueryResponse,
-    QueryResponseKind, ViewStateResult,
+    QueryResponseKind, SimulatedExecutionOutcomeView, TransactionSimulationView, ViewStateResult,
 };
 use near_store::{
-    get_access_key_raw, ColState, PartialStorage, ShardTries, Store, Trie, WrappedTrieChanges,
+    get_access_key_raw, ColState, PartialStorage, ShardTries, Store, StoreCompiledContractCache,
+    Trie, WrappedTrieChanges,
 };
 use node_runtime::adapter::ViewRuntimeAdapter;
 use node_runtime::state_viewer::TrieViewer;
@@ -121,6 +123,7 @@ pub struct NightshadeRuntime {
     epoch_manager: SafeEpochManager,
     shard_tracker: ShardTracker,
     genesis_state_roots: Vec<StateRoot>,
+    record_gas_profiles: bool,
 }
 
 impl NightshadeRuntime {
@@ -204,9 +207,14 @@ impl NightshadeRuntime {
             epoch_manager: SafeEpochManager(epoch_manager),
             shard_tracker,
             genesis_state_roots: state_roots,
+            record_gas_profiles: false,
         }
     }
 
+    pub fn set_record_gas_profiles(&mut self, record_gas_profiles: bool) {
+        self.record_gas_profiles = record_gas_profiles;
+    }
+
     fn get_epoch_height_from_prev_block(
         &self,
         prev_block_hash: &CryptoHash,
@@ -407,6 +415,8 @@ impl NightshadeRuntime {
             gas_limit: Some(gas_limit),
             random_seed,
             current_protocol_version,
+            cache: Some(Arc::new(StoreCompiledContractCache { store: self.store.clone() })),
+            record_gas_profiles: self.record_gas_profiles,
         };
 
         let apply_result = self
@@ -432,6 +442,9 @@ impl NightshadeRuntime {
                 // TODO(#2152): process gracefully
                 RuntimeError::ReceiptValidationError(e) => panic!("{}", e),
                 RuntimeError::ValidatorError(e) => e.into(),
+                RuntimeError::SimulationGasLimitExceeded => {
+                    unreachable!("Only returned by simulate_transaction")
+                }
             })?;
 
         let total_gas_burnt =
@@ -469,6 +482,7 @@ impl NightshadeRuntime {
             total_gas_burnt,
             total_balance_burnt,
             proof: apply_result.proof,
+            gas_profiles: apply_result.stats.gas_profiles,
         };
 
         Ok(result)
@@ -527,6 +541,7 @@ impl RuntimeAdapter for NightshadeRuntime {
         gas_price: Balance,
         state_root: Option<StateRoot>,
         transaction: &SignedTransaction,
+        current_protocol_version: ProtocolVersion,
     ) -> Result<Option<InvalidTxError>, Error> {
         if let Some(state_root) = state_root {
             let shard_id = self.account_id_to_shard_id(&transaction.transaction.signer_id);
@@ -537,6 +552,7 @@ impl RuntimeAdapter for NightshadeRuntime {
                 &mut state_update,
                 gas_price,
                 &transaction,
+                current_protocol_version,
             ) {
                 Ok(_) => Ok(None),
                 Err(RuntimeError::InvalidTxError(err)) => {
@@ -550,7 +566,12 @@ impl RuntimeAdapter for NightshadeRuntime {
             }
         } else {
             // Doing basic validation without a state root
-            match validate_transaction(&self.runtime.config, gas_price, &transaction) {
+            match validate_transaction(
+                &self.runtime.config,
+                gas_price,
+                &transaction,
+                current_protocol_version,
+            ) {
                 Ok(_) => Ok(None),
                 Err(RuntimeError::InvalidTxError(err)) => {
                     debug!(target: "runtime", "Tx {:?} validation failed: {:?}", transaction, err);
@@ -570,6 +591,7 @@ impl RuntimeAdapter for NightshadeRuntime {
         gas_limit: Gas,
         shard_id: ShardId,
         state_root: StateRoot,
+        current_protocol_version: ProtocolVersion,
         pool_iterator: &mut dyn PoolIterator,
         chain_validate: &mut dyn FnMut(&SignedTransaction) -> bool,
     ) -> Result<Vec<SignedTransaction>, Error> {
@@ -594,6 +616,7 @@ impl RuntimeAdapter for NightshadeRuntime {
                             &mut state_update,
                             gas_price,
                             &tx,
+                            current_protocol_version,
                         ) {
                             Ok(verification_result) => {
                                 state_update.commit(StateChangeCause::NotWritableToDisk);
@@ -1150,6 +1173,80 @@ impl RuntimeAdapter for NightshadeRuntime {
         }
     }
 
+    fn simulate_transaction(
+        &self,
+        shard_id: ShardId,
+        state_root: &StateRoot,
+        block_height: BlockHeight,
+        block_timestamp: u64,
+        prev_block_hash: &CryptoHash,
+        gas_price: Balance,
+        random_seed: CryptoHash,
+        transaction: &SignedTransaction,
+        verify_signature: bool,
+    ) -> Result<Result<TransactionSimulationView, InvalidTxError>, Error> {
+        let epoch_height = self.get_epoch_height_from_prev_block(prev_block_hash)?;
+        let epoch_id = self.get_epoch_id_from_prev_block(prev_block_hash)?;
+        let current_protocol_version = self.get_epoch_protocol_version(&epoch_id)?;
+
+        let apply_state = ApplyState {
+            block_index: block_height,
+            last_block_hash: *prev_block_hash,
+            epoch_id,
+            epoch_height,
+            gas_price,
+            block_timestamp,
+            gas_limit: None,
+            random_seed,
+            current_protocol_version,
+            cache: Some(Arc::new(StoreCompiledContractCache { store: self.store.clone() })),
+            record_gas_profiles: self.record_gas_profiles,
+        };
+
+        let result = match self.runtime.simulate_transaction(
+            self.get_trie_for_shard(shard_id),
+            *state_root,
+            &apply_state,
+            transaction,
+            verify_signature,
+            &|account_id| self.account_id_to_shard_id(account_id) == shard_id,
+            &self.epoch_manager,
+        ) {
+            Ok(result) => result,
+            Err(RuntimeError::InvalidTxError(err)) => return Ok(Err(err)),
+            Err(RuntimeError::StorageError(err)) => return Err(ErrorKind::StorageError(err).into()),
+            Err(RuntimeError::ValidatorError(err)) => return Err(err.into()),
+            Err(RuntimeError::SimulationGasLimitExceeded) => {
+                return Err(ErrorKind::Other(format!(
+                    "Simulation exceeded the limit of {} burnt gas",
+                    self.runtime.config.wasm_config.limit_config.max_gas_burnt_view
+                ))
+                .into())
+            }
+            Err(err) => return Err(ErrorKind::Other(format!("{:?}", err)).into()),
+        };
+
+        let gas_burnt = result.outcomes.iter().map(|outcome| outcome.outcome.gas_burnt).sum();
+        let mut gas_profiles: HashMap<CryptoHash, GasProfile> =
+            result.gas_profiles.into_iter().collect();
+        let mut outcomes = result.outcomes.into_iter().map(|outcome| {
+            let mut outcome = SimulatedExecutionOutcomeView::from(outcome);
+            outcome.outcome.profile = gas_profiles.remove(&outcome.id);
+            outcome
+        });
+        let transaction_outcome =
+            outcomes.next().expect("The transaction outcome is always recorded");
+        let state_changes = StateChanges::from_changes(result.state_changes.into_iter().map(Ok))?;
+        Ok(Ok(TransactionSimulationView {
+            block_hash: *prev_block_hash,
+            gas_burnt,
+            transaction_outcome,
+            receipts_outcome: outcomes.collect(),
+            outgoing_receipts: result.outgoing_receipts.into_iter().map(Into::into).collect(),
+            state_changes: state_changes.into_iter().map(Into::into).collect(),
+        }))
+    }
+
     fn get_validator_info(&self, block_hash: &CryptoHash) -> Result<EpochValidatorInfo, Error> {
         let mut epoch_manager = self.epoch_manager.as_ref().write().expect(POISONED_LOCK_ERR);
         epoch_manager.get_validator_info(block_hash).map_err(|e| e.into())
@@ -1297,6 +1394,10 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         logs: &mut Vec<String>,
         epoch_info_provider: &dyn EpochInfoProvider,
     ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let current_protocol_version = {
+            let mut epoch_manager = self.epoch_manager.as_ref().write().expect(POISONED_LOCK_ERR);
+            epoch_manager.get_epoch_info(epoch_id)?.protocol_version
+        };
         let state_update = self.get_tries().new_trie_update(shard_id, state_root);
         self.trie_viewer.call_function(
             state_update,
@@ -1305,6 +1406,7 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
             last_block_hash,
             epoch_height,
             epoch_id,
+            current_protocol_version,
             contract_id,
             method_name,
             args,
@@ -6,20 +6,25 @@ use borsh::BorshSerialize;
 use futures::future::join_all;
 use futures::{future, FutureExt, TryFutureExt};
 
+use near_chain_configs::Genesis;
 use near_client::{GetBlock, GetExecutionOutcome, TxStatus};
 use near_crypto::{InMemorySigner, KeyType};
 use near_jsonrpc::client::new_client;
 use near_logger_utils::init_integration_logger;
-use near_network::test_utils::WaitOrTimeout;
+use near_network::test_utils::{open_port, WaitOrTimeout};
 use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::merkle::{compute_root_from_path_and_item, verify_path};
+use near_primitives::rpc::{RpcLogsRequest, RpcSimulateTransactionRequest};
 use near_primitives::serialize::{from_base64, to_base64};
-use near_primitives::transaction::{PartialExecutionStatus, SignedTransaction};
+use near_primitives::transaction::{
+    Action, DeployContractAction, FunctionCallAction, PartialExecutionStatus, SignedTransaction,
+};
 use near_primitives::types::{BlockId, BlockIdOrFinality, TransactionOrReceiptId};
 use near_primitives::views::{
     ExecutionOutcomeView, ExecutionStatusView, FinalExecutionStatus, QueryResponseKind,
 };
-use neard::config::TESTING_INIT_BALANCE;
+use neard::config::{GenesisExt, TESTING_INIT_BALANCE};
+use neard::{load_test_config, start_with_config};
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::SeqCst;
 use std::sync::Arc;
@@ -468,6 +473,156 @@ fn test_get_validator_info_rpc() {
     });
 }
 
+/// Simulates an unsigned transaction and checks that the receipt within the shard is executed.
+#[test]
+fn test_simulate_unsigned_tx_rpc() {
+    init_integration_logger();
+    heavy_test(|| {
+        let system = System::new("NEAR");
+        let num_nodes = 1;
+        let dirs = (0..num_nodes)
+            .map(|i| {
+                tempfile::Builder::new().prefix(&format!("simulate_tx{}", i)).tempdir().unwrap()
+            })
+            .collect::<Vec<_>>();
+        let (_, rpc_addrs, clients) = start_nodes(1, &dirs, 1, 0, 10, 0);
+        let view_client = clients[0].1.clone();
+
+        let signer = InMemorySigner::from_seed("near.0", KeyType::ED25519, "near.0");
+        let transaction = SignedTransaction::send_money(
+            1,
+            "near.0".to_string(),
+            "near.0".to_string(),
+            &signer,
+            10000,
+            CryptoHash::default(),
+        )
+        .transaction;
+        let tx_hash = transaction.get_hash();
+
+        WaitOrTimeout::new(
+            Box::new(move |_ctx| {
+                let rpc_addrs_copy = rpc_addrs.clone();
+                let bytes = transaction.try_to_vec().unwrap();
+                actix::spawn(view_client.send(GetBlock::latest()).then(move |res| {
+                    if res.unwrap().unwrap().header.height > 1 {
+                        let client = new_client(&format!("http://{}", rpc_addrs_copy[0]));
+                        actix::spawn(
+                            client
+                                .EXPERIMENTAL_simulate_tx(
+                                    RpcSimulateTransactionRequest::Transaction(to_base64(&bytes)),
+                                )
+                                .map_err(|err| panic_on_rpc_error!(err))
+                                .map_ok(move |result| {
+                                    assert_eq!(result.transaction_outcome.id, tx_hash);
+                                    assert_eq!(result.receipts_outcome.len(), 1);
+                                    assert_eq!(
+                                        result.receipts_outcome[0].outcome.status,
+                                        ExecutionStatusView::SuccessValue("".to_string())
+                                    );
+                                    assert!(result.outgoing_receipts.is_empty());
+                                    assert!(!result.state_changes.is_empty());
+                                    System::current().stop();
+                                })
+                                .map(drop),
+                        );
+                    }
+                    future::ready(())
+                }));
+            }),
+            100,
+            20000,
+        )
+        .start();
+
+        system.run().unwrap();
+    });
+}
+
+/// Calls a contract that logs, and looks the log up with the `logs` method.
+#[test]
+fn test_logs_rpc() {
+    init_integration_logger();
+    heavy_test(|| {
+        let system = System::new("NEAR");
+        let dir = tempfile::Builder::new().prefix("logs_rpc").tempdir().unwrap();
+        let genesis = Arc::new(Genesis::test(vec!["test1"], 1));
+        let mut near_config = load_test_config("test1", open_port(), genesis);
+        near_config.client_config.enable_logs_index = true;
+        let rpc_addr = near_config.rpc_config.addr.clone();
+        let (_, view_client, _) = start_with_config(dir.path(), near_config);
+
+        let signer = InMemorySigner::from_seed("test1", KeyType::ED25519, "test1");
+        let sent = Arc::new(AtomicBool::new(false));
+        WaitOrTimeout::new(
+            Box::new(move |_ctx| {
+                let rpc_addr = rpc_addr.clone();
+                let signer = signer.clone();
+                let sent = sent.clone();
+                actix::spawn(view_client.send(GetBlock::latest()).then(move |res| {
+                    let block = res.unwrap().unwrap();
+                    if block.header.height > 1 && !sent.swap(true, SeqCst) {
+                        let transaction = SignedTransaction::from_actions(
+                            1,
+                            "test1".to_string(),
+                            "test1".to_string(),
+                            &signer,
+                            vec![
+                                Action::DeployContract(DeployContractAction {
+                                    code: include_bytes!(
+                                        "../../runtime/near-vm-runner/tests/res/test_contract_rs.wasm"
+                                    )
+                                    .to_vec(),
+                                }),
+                                Action::FunctionCall(FunctionCallAction {
+                                    method_name: "log_something".to_string(),
+                                    args: vec![],
+                                    gas: 10u64.pow(14),
+                                    deposit: 0,
+                                }),
+                            ],
+                            block.header.hash,
+                        );
+                        let client = new_client(&format!("http://{}", rpc_addr));
+                        actix::spawn(
+                            client
+                                .broadcast_tx_commit(to_base64(&transaction.try_to_vec().unwrap()))
+                                .and_then(move |outcome| {
+                                    assert_eq!(
+                                        outcome.status,
+                                        FinalExecutionStatus::SuccessValue("".to_string())
+                                    );
+                                    client.logs(RpcLogsRequest {
+                                        account_id: "test1".to_string(),
+                                        from_block_height: 0,
+                                        to_block_height: 1000,
+                                        prefix: "hel".to_string(),
+                                        limit: 10,
+                                    })
+                                })
+                                .map_err(|err| panic!("{:?}", err))
+                                .map_ok(|logs| {
+                                    assert_eq!(logs.outcomes.len(), 1);
+                                    assert_eq!(logs.outcomes[0].executor_id, "test1");
+                                    assert_eq!(logs.outcomes[0].logs, vec!["hello".to_string()]);
+                                    assert_eq!(logs.next_block_height, None);
+                                    System::current().stop();
+                                })
+                                .map(drop),
+                        );
+                    }
+                    future::ready(())
+                }));
+            }),
+            100,
+            30000,
+        )
+        .start();
+
+        system.run().unwrap();
+    });
+}
+
 fn outcome_view_to_hashes(outcome: &ExecutionOutcomeView) -> Vec<CryptoHash> {
     let status = match &outcome.status {
         ExecutionStatusView::Unknown => PartialExecutionStatus::Unknown,
@@ -52,6 +52,10 @@ pub struct RuntimeFeesConfig {
     pub action_creation_config: ActionCreationConfig,
     /// Describes fees for storage.
     pub storage_usage_config: StorageUsageConfig,
+    /// Describes the cost of verifying a signature of a transaction signed with a multi-signature
+    /// access key. Only the send fee is charged, when the transaction is converted into a receipt.
+    #[serde(default = "default_multisig_signature_cost")]
+    pub multisig_signature_cost: Fee,
 
     /// Fraction of the burnt gas to reward to the contract account for execution.
     pub burnt_gas_reward: Rational,
@@ -99,6 +103,10 @@ pub struct ActionCreationConfig {
 
     /// Base cost of deleting an account.
     pub delete_account_cost: Fee,
+
+    /// Base cost of a delegate action, excluding the cost of the delegated actions.
+    #[serde(default = "default_delegate_cost")]
+    pub delegate_cost: Fee,
 }
 
 /// Describes the cost of creating an access key.
@@ -110,6 +118,12 @@ pub struct AccessKeyCreationConfig {
     pub function_call_cost: Fee,
     /// Cost per byte of method_names of creating a restricted access-key.
     pub function_call_cost_per_byte: Fee,
+    /// Base cost of creating a multi-signature access-key.
+    #[serde(default = "default_multisig_cost")]
+    pub multisig_cost: Fee,
+    /// Cost per member key of creating a multi-signature access-key.
+    #[serde(default = "default_multisig_cost_per_key")]
+    pub multisig_cost_per_key: Fee,
 }
 
 /// Describes cost of storage per block
@@ -121,6 +135,22 @@ pub struct StorageUsageConfig {
     pub num_extra_bytes_record: u64,
 }
 
+fn default_multisig_signature_cost() -> Fee {
+    Fee { send_sir: 90_000_000_000, send_not_sir: 90_000_000_000, execution: 0 }
+}
+
+fn default_multisig_cost() -> Fee {
+    Fee { send_sir: 101_765_125_000, send_not_sir: 101_765_125_000, execution: 101_765_125_000 }
+}
+
+fn default_multisig_cost_per_key() -> Fee {
+    Fee { send_sir: 63_535_923, send_not_sir: 63_535_923, execution: 63_535_923 }
+}
+
+fn default_delegate_cost() -> Fee {
+    Fee { send_sir: 200_000_000_000, send_not_sir: 200_000_000_000, execution: 200_000_000_000 }
+}
+
 impl Default for RuntimeFeesConfig {
     fn default() -> Self {
         #[allow(clippy::unreadable_literal)]
@@ -194,6 +224,8 @@ impl Default for RuntimeFeesConfig {
                         send_not_sir: 1925331,
                         execution: 1925331,
                     },
+                    multisig_cost: default_multisig_cost(),
+                    multisig_cost_per_key: default_multisig_cost_per_key(),
                 },
                 delete_key_cost: Fee {
                     send_sir: 94946625000,
@@ -205,6 +237,7 @@ impl Default for RuntimeFeesConfig {
                     send_not_sir: 147489000000,
                     execution: 147489000000,
                 },
+                delegate_cost: default_delegate_cost(),
             },
             storage_usage_config: StorageUsageConfig {
                 // See Account in core/primitives/src/account.rs for the data structure.
@@ -212,6 +245,7 @@ impl Default for RuntimeFeesConfig {
                 num_bytes_account: 100,
                 num_extra_bytes_record: 40,
             },
+            multisig_signature_cost: default_multisig_signature_cost(),
             burnt_gas_reward: Rational::new(3, 10),
             pessimistic_gas_price_inflation_ratio: Rational::new(103, 100),
         }
@@ -239,14 +273,18 @@ impl RuntimeFeesConfig {
                     full_access_cost: free.clone(),
                     function_call_cost: free.clone(),
                     function_call_cost_per_byte: free.clone(),
+                    multisig_cost: free.clone(),
+                    multisig_cost_per_key: free.clone(),
                 },
                 delete_key_cost: free.clone(),
-                delete_account_cost: free,
+                delete_account_cost: free.clone(),
+                delegate_cost: free.clone(),
             },
             storage_usage_config: StorageUsageConfig {
                 num_bytes_account: 0,
                 num_extra_bytes_record: 0,
             },
+            multisig_signature_cost: free,
             burnt_gas_reward: Rational::from_integer(0),
             pessimistic_gas_price_inflation_ratio: Rational::from_integer(0),
         }
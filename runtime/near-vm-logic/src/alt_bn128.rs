@@ -0,0 +1,125 @@
+//! Arithmetic on the alt_bn128 (BN254) curve backing the `alt_bn128_*` host functions.
+//!
+//! Points and scalars use the big-endian encoding of Ethereum's EIP-196 and EIP-197 precompiles:
+//! a field element is 32 bytes, a G1 point is `x || y`, a G2 point is `x_im || x_re || y_im || y_re`
+//! and the point at infinity is encoded as all zeros.
+use bn::{pairing_batch, AffineG1, AffineG2, Fq, Fq2, Fr, Group, Gt, G1, G2};
+use near_vm_errors::{HostError, VMLogicError};
+
+type Result<T> = ::std::result::Result<T, VMLogicError>;
+
+const FIELD_SIZE: usize = 32;
+const G1_SIZE: usize = 2 * FIELD_SIZE;
+const G2_SIZE: usize = 4 * FIELD_SIZE;
+
+/// A G1 point followed by a scalar.
+const G1_MULTIEXP_ELEMENT_SIZE: usize = G1_SIZE + FIELD_SIZE;
+/// A sign byte (0 to add the point, 1 to subtract it) followed by a G1 point.
+const G1_SUM_ELEMENT_SIZE: usize = 1 + G1_SIZE;
+/// A G1 point followed by a G2 point.
+const PAIRING_ELEMENT_SIZE: usize = G1_SIZE + G2_SIZE;
+
+fn invalid_input(msg: &str) -> VMLogicError {
+    HostError::AltBn128InvalidInput { msg: msg.to_string() }.into()
+}
+
+fn element_count(data: &[u8], element_size: usize) -> Result<u64> {
+    if data.len() % element_size != 0 {
+        return Err(invalid_input(&format!(
+            "input length {} is not a multiple of {}",
+            data.len(),
+            element_size
+        )));
+    }
+    Ok((data.len() / element_size) as u64)
+}
+
+/// Number of (point, scalar) elements in the input of `g1_multiexp`.
+pub(crate) fn g1_multiexp_element_count(data: &[u8]) -> Result<u64> {
+    element_count(data, G1_MULTIEXP_ELEMENT_SIZE)
+}
+
+/// Number of signed points in the input of `g1_sum`.
+pub(crate) fn g1_sum_element_count(data: &[u8]) -> Result<u64> {
+    element_count(data, G1_SUM_ELEMENT_SIZE)
+}
+
+/// Number of (G1, G2) pairs in the input of `pairing_check`.
+pub(crate) fn pairing_element_count(data: &[u8]) -> Result<u64> {
+    element_count(data, PAIRING_ELEMENT_SIZE)
+}
+
+fn read_fq(data: &[u8]) -> Result<Fq> {
+    Fq::from_slice(data).map_err(|_| invalid_input("invalid field element"))
+}
+
+fn read_fr(data: &[u8]) -> Result<Fr> {
+    Fr::from_slice(data).map_err(|_| invalid_input("invalid scalar"))
+}
+
+fn read_g1(data: &[u8]) -> Result<G1> {
+    let x = read_fq(&data[..FIELD_SIZE])?;
+    let y = read_fq(&data[FIELD_SIZE..G1_SIZE])?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1::zero());
+    }
+    AffineG1::new(x, y).map(Into::into).map_err(|_| invalid_input("G1 point is not on the curve"))
+}
+
+fn read_g2(data: &[u8]) -> Result<G2> {
+    let x_im = read_fq(&data[..FIELD_SIZE])?;
+    let x_re = read_fq(&data[FIELD_SIZE..2 * FIELD_SIZE])?;
+    let y_im = read_fq(&data[2 * FIELD_SIZE..3 * FIELD_SIZE])?;
+    let y_re = read_fq(&data[3 * FIELD_SIZE..G2_SIZE])?;
+    let x = Fq2::new(x_re, x_im);
+    let y = Fq2::new(y_re, y_im);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2::zero());
+    }
+    AffineG2::new(x, y).map(Into::into).map_err(|_| invalid_input("G2 point is not on the curve"))
+}
+
+fn write_g1(point: G1) -> Vec<u8> {
+    let mut res = vec![0u8; G1_SIZE];
+    if let Some(affine) = AffineG1::from_jacobian(point) {
+        affine.x().to_big_endian(&mut res[..FIELD_SIZE]).expect("slice has the field size");
+        affine.y().to_big_endian(&mut res[FIELD_SIZE..]).expect("slice has the field size");
+    }
+    res
+}
+
+/// Computes `sum(point_i * scalar_i)` over the concatenated `(G1 point, scalar)` elements and
+/// returns the encoded G1 result.
+pub(crate) fn g1_multiexp(data: &[u8]) -> Result<Vec<u8>> {
+    let mut acc = G1::zero();
+    for element in data.chunks(G1_MULTIEXP_ELEMENT_SIZE) {
+        let point = read_g1(&element[..G1_SIZE])?;
+        let scalar = read_fr(&element[G1_SIZE..])?;
+        acc = acc + point * scalar;
+    }
+    Ok(write_g1(acc))
+}
+
+/// Adds up the concatenated signed G1 points and returns the encoded G1 result.
+pub(crate) fn g1_sum(data: &[u8]) -> Result<Vec<u8>> {
+    let mut acc = G1::zero();
+    for element in data.chunks(G1_SUM_ELEMENT_SIZE) {
+        let point = read_g1(&element[1..])?;
+        acc = match element[0] {
+            0 => acc + point,
+            1 => acc - point,
+            _ => return Err(invalid_input("sign byte must be 0 or 1")),
+        };
+    }
+    Ok(write_g1(acc))
+}
+
+/// Checks whether the product of pairings of the concatenated `(G1 point, G2 point)` elements
+/// equals one.
+pub(crate) fn pairing_check(data: &[u8]) -> Result<bool> {
+    let pairs = data
+        .chunks(PAIRING_ELEMENT_SIZE)
+        .map(|element| Ok((read_g1(&element[..G1_SIZE])?, read_g2(&element[G1_SIZE..])?)))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(pairing_batch(&pairs) == Gt::one())
+}
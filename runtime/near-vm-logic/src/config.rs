@@ -99,6 +99,14 @@ pub struct VMLimitConfig {
     pub max_promises_per_function_call_action: u64,
     /// Max number of input data dependencies
     pub max_number_input_data_dependencies: u64,
+    /// Max number of member keys of a multi-signature access key.
+    #[serde(default = "default_max_multisig_keys")]
+    pub max_multisig_keys: u64,
+}
+
+fn default_max_multisig_keys() -> u64 {
+    // Every member signature is verified for each transaction signed with the key.
+    32
 }
 
 impl Default for VMConfig {
@@ -179,6 +187,7 @@ impl Default for VMLimitConfig {
             max_promises_per_function_call_action: 1024,
             // Unlikely to hit it for normal development.
             max_number_input_data_dependencies: 128,
+            max_multisig_keys: default_max_multisig_keys(),
         }
     }
 }
@@ -238,6 +247,56 @@ pub struct ExtCostsConfig {
     /// Cost of getting sha256 per byte
     pub keccak512_byte: Gas,
 
+    /// Cost of getting ripemd160 base
+    #[serde(default = "default_ripemd160_base")]
+    pub ripemd160_base: Gas,
+    /// Cost of getting ripemd160 per byte
+    #[serde(default = "default_ripemd160_byte")]
+    pub ripemd160_byte: Gas,
+
+    /// Cost of getting blake2b base
+    #[serde(default = "default_blake2b_base")]
+    pub blake2b_base: Gas,
+    /// Cost of getting blake2b per byte
+    #[serde(default = "default_blake2b_byte")]
+    pub blake2b_byte: Gas,
+
+    /// Cost of verifying an ed25519 signature base
+    #[serde(default = "default_ed25519_verify_base")]
+    pub ed25519_verify_base: Gas,
+    /// Cost of verifying an ed25519 signature per byte of the message
+    #[serde(default = "default_ed25519_verify_byte")]
+    pub ed25519_verify_byte: Gas,
+
+    /// Cost of verifying a secp256k1 signature of a 32 byte message hash
+    #[serde(default = "default_secp256k1_verify_base")]
+    pub secp256k1_verify_base: Gas,
+
+    /// Cost of recovering a secp256k1 public key from a signature of a 32 byte message hash
+    #[serde(default = "default_ecrecover_base")]
+    pub ecrecover_base: Gas,
+
+    /// Cost of multiexp on alt_bn128 G1 base
+    #[serde(default = "default_alt_bn128_g1_multiexp_base")]
+    pub alt_bn128_g1_multiexp_base: Gas,
+    /// Cost of multiexp on alt_bn128 G1 per (point, scalar) element
+    #[serde(default = "default_alt_bn128_g1_multiexp_element")]
+    pub alt_bn128_g1_multiexp_element: Gas,
+
+    /// Cost of summing alt_bn128 G1 points base
+    #[serde(default = "default_alt_bn128_g1_sum_base")]
+    pub alt_bn128_g1_sum_base: Gas,
+    /// Cost of summing alt_bn128 G1 points per point
+    #[serde(default = "default_alt_bn128_g1_sum_element")]
+    pub alt_bn128_g1_sum_element: Gas,
+
+    /// Cost of alt_bn128 pairing check base
+    #[serde(default = "default_alt_bn128_pairing_check_base")]
+    pub alt_bn128_pairing_check_base: Gas,
+    /// Cost of alt_bn128 pairing check per (G1, G2) pair
+    #[serde(default = "default_alt_bn128_pairing_check_element")]
+    pub alt_bn128_pairing_check_element: Gas,
+
     /// Cost for calling logging.
     pub log_base: Gas,
     /// Cost for logging per byte
@@ -319,6 +378,62 @@ pub struct ExtCostsConfig {
 // have certain reserve for further gas price variation.
 const SAFETY_MULTIPLIER: u64 = 3;
 
+fn default_ripemd160_base() -> Gas {
+    SAFETY_MULTIPLIER * 1500000000
+}
+
+fn default_ripemd160_byte() -> Gas {
+    SAFETY_MULTIPLIER * 11000000
+}
+
+fn default_blake2b_base() -> Gas {
+    SAFETY_MULTIPLIER * 1500000000
+}
+
+fn default_blake2b_byte() -> Gas {
+    SAFETY_MULTIPLIER * 5000000
+}
+
+fn default_ed25519_verify_base() -> Gas {
+    SAFETY_MULTIPLIER * 70000000000
+}
+
+fn default_ed25519_verify_byte() -> Gas {
+    SAFETY_MULTIPLIER * 3000000
+}
+
+fn default_secp256k1_verify_base() -> Gas {
+    SAFETY_MULTIPLIER * 80000000000
+}
+
+fn default_ecrecover_base() -> Gas {
+    SAFETY_MULTIPLIER * 95000000000
+}
+
+fn default_alt_bn128_g1_multiexp_base() -> Gas {
+    SAFETY_MULTIPLIER * 500000000
+}
+
+fn default_alt_bn128_g1_multiexp_element() -> Gas {
+    SAFETY_MULTIPLIER * 150000000000
+}
+
+fn default_alt_bn128_g1_sum_base() -> Gas {
+    SAFETY_MULTIPLIER * 500000000
+}
+
+fn default_alt_bn128_g1_sum_element() -> Gas {
+    SAFETY_MULTIPLIER * 2000000000
+}
+
+fn default_alt_bn128_pairing_check_base() -> Gas {
+    SAFETY_MULTIPLIER * 1000000000000
+}
+
+fn default_alt_bn128_pairing_check_element() -> Gas {
+    SAFETY_MULTIPLIER * 1500000000000
+}
+
 impl Default for ExtCostsConfig {
     fn default() -> ExtCostsConfig {
         ExtCostsConfig {
@@ -343,6 +458,20 @@ impl Default for ExtCostsConfig {
             keccak256_byte: SAFETY_MULTIPLIER * 7157035,
             keccak512_base: SAFETY_MULTIPLIER * 1937129412,
             keccak512_byte: SAFETY_MULTIPLIER * 12216567,
+            ripemd160_base: default_ripemd160_base(),
+            ripemd160_byte: default_ripemd160_byte(),
+            blake2b_base: default_blake2b_base(),
+            blake2b_byte: default_blake2b_byte(),
+            ed25519_verify_base: default_ed25519_verify_base(),
+            ed25519_verify_byte: default_ed25519_verify_byte(),
+            secp256k1_verify_base: default_secp256k1_verify_base(),
+            ecrecover_base: default_ecrecover_base(),
+            alt_bn128_g1_multiexp_base: default_alt_bn128_g1_multiexp_base(),
+            alt_bn128_g1_multiexp_element: default_alt_bn128_g1_multiexp_element(),
+            alt_bn128_g1_sum_base: default_alt_bn128_g1_sum_base(),
+            alt_bn128_g1_sum_element: default_alt_bn128_g1_sum_element(),
+            alt_bn128_pairing_check_base: default_alt_bn128_pairing_check_base(),
+            alt_bn128_pairing_check_element: default_alt_bn128_pairing_check_element(),
             log_base: SAFETY_MULTIPLIER * 1181104350,
             log_byte: SAFETY_MULTIPLIER * 4399597,
             storage_write_base: SAFETY_MULTIPLIER * 21398912000,
@@ -399,6 +528,20 @@ impl ExtCostsConfig {
             keccak256_byte: 0,
             keccak512_base: 0,
             keccak512_byte: 0,
+            ripemd160_base: 0,
+            ripemd160_byte: 0,
+            blake2b_base: 0,
+            blake2b_byte: 0,
+            ed25519_verify_base: 0,
+            ed25519_verify_byte: 0,
+            secp256k1_verify_base: 0,
+            ecrecover_base: 0,
+            alt_bn128_g1_multiexp_base: 0,
+            alt_bn128_g1_multiexp_element: 0,
+            alt_bn128_g1_sum_base: 0,
+            alt_bn128_g1_sum_element: 0,
+            alt_bn128_pairing_check_base: 0,
+            alt_bn128_pairing_check_element: 0,
             log_base: 0,
             log_byte: 0,
             storage_write_base: 0,
@@ -432,7 +575,7 @@ impl ExtCostsConfig {
 }
 
 /// Strongly-typed representation of the fees for counting.
-#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug, PartialOrd, Ord)]
+#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug, PartialOrd, Ord, Serialize, Deserialize)]
 #[allow(non_camel_case_types)]
 pub enum ExtCosts {
     base,
@@ -456,6 +599,20 @@ pub enum ExtCosts {
     keccak256_byte,
     keccak512_base,
     keccak512_byte,
+    ripemd160_base,
+    ripemd160_byte,
+    blake2b_base,
+    blake2b_byte,
+    ed25519_verify_base,
+    ed25519_verify_byte,
+    secp256k1_verify_base,
+    ecrecover_base,
+    alt_bn128_g1_multiexp_base,
+    alt_bn128_g1_multiexp_element,
+    alt_bn128_g1_sum_base,
+    alt_bn128_g1_sum_element,
+    alt_bn128_pairing_check_base,
+    alt_bn128_pairing_check_element,
     log_base,
     log_byte,
     storage_write_base,
@@ -511,6 +668,20 @@ impl ExtCosts {
             keccak256_byte => config.keccak256_byte,
             keccak512_base => config.keccak512_base,
             keccak512_byte => config.keccak512_byte,
+            ripemd160_base => config.ripemd160_base,
+            ripemd160_byte => config.ripemd160_byte,
+            blake2b_base => config.blake2b_base,
+            blake2b_byte => config.blake2b_byte,
+            ed25519_verify_base => config.ed25519_verify_base,
+            ed25519_verify_byte => config.ed25519_verify_byte,
+            secp256k1_verify_base => config.secp256k1_verify_base,
+            ecrecover_base => config.ecrecover_base,
+            alt_bn128_g1_multiexp_base => config.alt_bn128_g1_multiexp_base,
+            alt_bn128_g1_multiexp_element => config.alt_bn128_g1_multiexp_element,
+            alt_bn128_g1_sum_base => config.alt_bn128_g1_sum_base,
+            alt_bn128_g1_sum_element => config.alt_bn128_g1_sum_element,
+            alt_bn128_pairing_check_base => config.alt_bn128_pairing_check_base,
+            alt_bn128_pairing_check_element => config.alt_bn128_pairing_check_element,
             log_base => config.log_base,
             log_byte => config.log_byte,
             storage_write_base => config.storage_write_base,
@@ -50,6 +50,9 @@ pub struct VMContext {
     pub random_seed: Vec<u8>,
     /// Whether the execution should not charge any costs.
     pub is_view: bool,
+    /// Whether to collect the breakdown of the burnt gas into `VMOutcome::profile`.
+    #[serde(default)]
+    pub profile_gas: bool,
     /// How many `DataReceipt`'s should receive this execution result. This should be empty if
     /// this function call is a part of a batch and it is not the last action.
     pub output_data_receivers: Vec<AccountId>,
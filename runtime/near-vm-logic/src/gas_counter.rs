@@ -1,4 +1,5 @@
 use crate::config::{ExtCosts, ExtCostsConfig};
+use crate::profile::ProfileData;
 use crate::types::Gas;
 use crate::{HostError, VMLogicError};
 use near_runtime_fees::Fee;
@@ -22,6 +23,16 @@ pub struct GasCounter {
     prepaid_gas: Gas,
     is_view: bool,
     ext_costs_config: ExtCostsConfig,
+    /// Breakdown of `burnt_gas`, only collected if profiling is enabled.
+    profile: Option<ProfileData>,
+}
+
+/// What the burnt gas is attributed to in the profile.
+#[derive(Clone, Copy)]
+enum GasCategory {
+    Wasm,
+    Action,
+    ExtCost(ExtCosts),
 }
 
 impl GasCounter {
@@ -30,8 +41,17 @@ impl GasCounter {
         max_gas_burnt: Gas,
         prepaid_gas: Gas,
         is_view: bool,
+        profile: bool,
     ) -> Self {
-        Self { ext_costs_config, burnt_gas: 0, used_gas: 0, max_gas_burnt, prepaid_gas, is_view }
+        Self {
+            ext_costs_config,
+            burnt_gas: 0,
+            used_gas: 0,
+            max_gas_burnt,
+            prepaid_gas,
+            is_view,
+            profile: if profile { Some(ProfileData::default()) } else { None },
+        }
     }
 
     pub fn deduct_gas(&mut self, burn_gas: Gas, use_gas: Gas) -> Result<()> {
@@ -74,20 +94,40 @@ impl GasCounter {
     #[inline]
     fn inc_ext_costs_counter(&self, _cost: ExtCosts, _value: u64) {}
 
+    /// Deducts gas like `deduct_gas` and attributes the burnt part to `category` in the profile.
+    fn deduct_gas_for(&mut self, category: GasCategory, burn_gas: Gas, use_gas: Gas) -> Result<()> {
+        let burnt_gas_before = self.burnt_gas;
+        let res = self.deduct_gas(burn_gas, use_gas);
+        if let Some(profile) = self.profile.as_mut() {
+            let burnt = self.burnt_gas.saturating_sub(burnt_gas_before);
+            match category {
+                GasCategory::Wasm => profile.add_wasm_gas(burnt),
+                GasCategory::Action => profile.add_action_gas(burnt),
+                GasCategory::ExtCost(cost) => profile.add_ext_cost(cost, burnt),
+            }
+        }
+        res
+    }
+
+    /// A helper function to pay gas for the executed Wasm instructions.
+    pub fn pay_wasm_gas(&mut self, value: Gas) -> Result<()> {
+        self.deduct_gas_for(GasCategory::Wasm, value, value)
+    }
+
     /// A helper function to pay per byte gas
     pub fn pay_per_byte(&mut self, cost: ExtCosts, num_bytes: u64) -> Result<()> {
         self.inc_ext_costs_counter(cost, num_bytes);
         let use_gas = num_bytes
             .checked_mul(cost.value(&self.ext_costs_config))
             .ok_or(HostError::IntegerOverflow)?;
-        self.deduct_gas(use_gas, use_gas)
+        self.deduct_gas_for(GasCategory::ExtCost(cost), use_gas, use_gas)
     }
 
     /// A helper function to pay base cost gas
     pub fn pay_base(&mut self, cost: ExtCosts) -> Result<()> {
         self.inc_ext_costs_counter(cost, 1);
         let base_fee = cost.value(&self.ext_costs_config);
-        self.deduct_gas(base_fee, base_fee)
+        self.deduct_gas_for(GasCategory::ExtCost(cost), base_fee, base_fee)
     }
 
     /// A helper function to pay per byte gas fee for batching an action.
@@ -109,7 +149,7 @@ impl GasCounter {
             )
             .ok_or(HostError::IntegerOverflow)?;
 
-        self.deduct_gas(burn_gas, use_gas)
+        self.deduct_gas_for(GasCategory::Action, burn_gas, use_gas)
     }
 
     /// A helper function to pay base cost gas fee for batching an action.
@@ -120,7 +160,12 @@ impl GasCounter {
         let burn_gas = base_fee.send_fee(sir);
         let use_gas =
             burn_gas.checked_add(base_fee.exec_fee()).ok_or(HostError::IntegerOverflow)?;
-        self.deduct_gas(burn_gas, use_gas)
+        self.deduct_gas_for(GasCategory::Action, burn_gas, use_gas)
+    }
+
+    /// A helper function to pay the already computed gas fees of the created receipts.
+    pub fn pay_action_accumulated(&mut self, burn_gas: Gas, use_gas: Gas) -> Result<()> {
+        self.deduct_gas_for(GasCategory::Action, burn_gas, use_gas)
     }
 
     pub fn burnt_gas(&self) -> Gas {
@@ -129,6 +174,9 @@ impl GasCounter {
     pub fn used_gas(&self) -> Gas {
         self.used_gas
     }
+    pub fn profile_data(&self) -> Option<ProfileData> {
+        self.profile.clone()
+    }
 }
 
 #[cfg(test)]
@@ -136,16 +184,33 @@ mod tests {
     use super::*;
     #[test]
     fn test_deduct_gas() {
-        let mut counter = GasCounter::new(ExtCostsConfig::default(), 10, 10, false);
+        let mut counter = GasCounter::new(ExtCostsConfig::default(), 10, 10, false, false);
         counter.deduct_gas(5, 10).expect("deduct_gas should work");
         assert_eq!(counter.burnt_gas(), 5);
         assert_eq!(counter.used_gas(), 10);
     }
 
+    #[test]
+    fn test_profile() {
+        let mut counter = GasCounter::new(ExtCostsConfig::default(), 1_000, 1_000, false, true);
+        counter.pay_wasm_gas(100).expect("pay_wasm_gas should work");
+        counter.pay_action_accumulated(20, 30).expect("pay_action_accumulated should work");
+        counter.deduct_gas(0, 10).expect("deduct_gas should work");
+        let profile = counter.profile_data().unwrap();
+        assert_eq!(profile.wasm_gas, 100);
+        assert_eq!(profile.action_gas, 20);
+        assert_eq!(profile.ext_costs_gas(), 0);
+        assert_eq!(counter.burnt_gas(), 120);
+
+        // Only the gas that was actually burnt before hitting the limit is attributed.
+        assert!(counter.pay_wasm_gas(1_000).is_err());
+        assert_eq!(counter.profile_data().unwrap().wasm_gas, 100 + 1_000 - 120);
+    }
+
     #[test]
     #[should_panic]
     fn test_prepaid_gas_min() {
-        let mut counter = GasCounter::new(ExtCostsConfig::default(), 100, 10, false);
+        let mut counter = GasCounter::new(ExtCostsConfig::default(), 100, 10, false, false);
         counter.deduct_gas(10, 5).unwrap();
     }
 }
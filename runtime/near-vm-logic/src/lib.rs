@@ -1,10 +1,14 @@
+mod alt_bn128;
 mod config;
 mod context;
 mod dependencies;
 mod gas_counter;
 mod logic;
 pub mod mocks;
+mod profile;
 pub mod serde_with;
+#[cfg(feature = "host_calls_tracing")]
+pub mod trace;
 pub mod types;
 mod utils;
 
@@ -13,6 +17,7 @@ pub use context::VMContext;
 pub use dependencies::{External, MemoryLike, ValuePtr};
 pub use logic::{VMLogic, VMOutcome};
 pub use near_vm_errors::{HostError, VMLogicError};
+pub use profile::ProfileData;
 pub use types::ReturnData;
 
 #[cfg(feature = "costs_counting")]
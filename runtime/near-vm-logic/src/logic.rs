@@ -1,8 +1,12 @@
+use crate::alt_bn128;
 use crate::config::ExtCosts::*;
 use crate::config::VMConfig;
 use crate::context::VMContext;
 use crate::dependencies::{External, MemoryLike};
 use crate::gas_counter::GasCounter;
+use crate::profile::ProfileData;
+#[cfg(feature = "host_calls_tracing")]
+use crate::trace;
 use crate::types::{
     AccountId, Balance, EpochHeight, Gas, PromiseIndex, PromiseResult, ReceiptIndex, ReturnData,
     StorageUsage,
@@ -10,6 +14,7 @@ use crate::types::{
 use crate::utils::split_method_names;
 use crate::{ExtCosts, HostError, VMLogicError, ValuePtr};
 use byteorder::ByteOrder;
+use near_crypto::{KeyType, PublicKey, Secp256K1Signature, Signature};
 use near_runtime_fees::RuntimeFeesConfig;
 use near_vm_errors::InconsistentStateError;
 use serde::{Deserialize, Serialize};
@@ -90,6 +95,18 @@ macro_rules! memory_set {
     };
 }
 
+/// Verifies a signature given as raw bytes. Malformed signatures and public keys are invalid.
+fn verify_signature(key_type: KeyType, signature: &[u8], data: &[u8], public_key: &[u8]) -> bool {
+    let public_key = match PublicKey::from_parts(key_type, public_key) {
+        Ok(public_key) => public_key,
+        Err(_) => return false,
+    };
+    match Signature::from_parts(public_key.key_type(), signature) {
+        Ok(signature) => signature.verify(data, &public_key),
+        Err(_) => false,
+    }
+}
+
 impl<'a> VMLogic<'a> {
     pub fn new(
         ext: &'a mut dyn External,
@@ -114,6 +131,7 @@ impl<'a> VMLogic<'a> {
             max_gas_burnt,
             context.prepaid_gas,
             context.is_view,
+            context.profile_gas,
         );
         Self {
             ext,
@@ -220,6 +238,8 @@ impl<'a> VMLogic<'a> {
         {
             return Err(HostError::MemoryAccessViolation.into());
         }
+        #[cfg(feature = "host_calls_tracing")]
+        trace::record_register_write(register_id, &data);
         self.registers.insert(register_id, data);
 
         // Calculate the new memory usage.
@@ -821,6 +841,238 @@ impl<'a> VMLogic<'a> {
         self.internal_write_register(register_id, value_hash.as_ref().to_vec())
     }
 
+    /// Hashes the given value using ripemd160 and returns it into `register_id`.
+    ///
+    /// # Errors
+    ///
+    /// If `value_len + value_ptr` points outside the memory or the registers use more memory than
+    /// the limit with `MemoryAccessViolation`.
+    ///
+    /// # Cost
+    ///
+    /// `base + write_register_base + write_register_byte * num_bytes + ripemd160_base + ripemd160_byte * num_bytes`
+    pub fn ripemd160(&mut self, value_len: u64, value_ptr: u64, register_id: u64) -> Result<()> {
+        self.gas_counter.pay_base(ripemd160_base)?;
+        let value = self.get_vec_from_memory_or_register(value_ptr, value_len)?;
+        self.gas_counter.pay_per_byte(ripemd160_byte, value.len() as u64)?;
+
+        use ripemd160::Digest;
+
+        let value_hash = ripemd160::Ripemd160::digest(&value);
+        self.internal_write_register(register_id, value_hash.as_ref().to_vec())
+    }
+
+    /// Hashes the given value using blake2b with a 64 byte digest and returns it into
+    /// `register_id`.
+    ///
+    /// # Errors
+    ///
+    /// If `value_len + value_ptr` points outside the memory or the registers use more memory than
+    /// the limit with `MemoryAccessViolation`.
+    ///
+    /// # Cost
+    ///
+    /// `base + write_register_base + write_register_byte * num_bytes + blake2b_base + blake2b_byte * num_bytes`
+    pub fn blake2b(&mut self, value_len: u64, value_ptr: u64, register_id: u64) -> Result<()> {
+        self.gas_counter.pay_base(blake2b_base)?;
+        let value = self.get_vec_from_memory_or_register(value_ptr, value_len)?;
+        self.gas_counter.pay_per_byte(blake2b_byte, value.len() as u64)?;
+
+        use blake2::Digest;
+
+        let value_hash = blake2::Blake2b::digest(&value);
+        self.internal_write_register(register_id, value_hash.as_ref().to_vec())
+    }
+
+    /// Verifies that `signature` is an ed25519 signature of `message` made with `public_key`.
+    /// Returns 1 if the signature is valid and 0 otherwise, including when the signature is not
+    /// 64 bytes long or the public key is not 32 bytes long.
+    ///
+    /// # Errors
+    ///
+    /// If `signature_len + signature_ptr`, `message_len + message_ptr` or
+    /// `public_key_len + public_key_ptr` point outside the memory or the registers use more memory
+    /// than the limit with `MemoryAccessViolation`.
+    ///
+    /// # Cost
+    ///
+    /// `base + ed25519_verify_base + ed25519_verify_byte * message_len`
+    pub fn ed25519_verify(
+        &mut self,
+        signature_len: u64,
+        signature_ptr: u64,
+        message_len: u64,
+        message_ptr: u64,
+        public_key_len: u64,
+        public_key_ptr: u64,
+    ) -> Result<u64> {
+        self.gas_counter.pay_base(ed25519_verify_base)?;
+        let message = self.get_vec_from_memory_or_register(message_ptr, message_len)?;
+        self.gas_counter.pay_per_byte(ed25519_verify_byte, message.len() as u64)?;
+        let signature = self.get_vec_from_memory_or_register(signature_ptr, signature_len)?;
+        let public_key = self.get_vec_from_memory_or_register(public_key_ptr, public_key_len)?;
+
+        Ok(verify_signature(KeyType::ED25519, &signature, &message, &public_key) as u64)
+    }
+
+    /// Verifies that `signature` is a secp256k1 signature of the 32 byte `hash` made with
+    /// `public_key`. The signature is 65 bytes long with the recovery id in the last byte and the
+    /// public key is 64 bytes long without the prefix byte. Returns 1 if the signature is valid
+    /// and 0 otherwise, including when any of the arguments have the wrong length.
+    ///
+    /// # Errors
+    ///
+    /// If `signature_len + signature_ptr`, `hash_len + hash_ptr` or
+    /// `public_key_len + public_key_ptr` point outside the memory or the registers use more memory
+    /// than the limit with `MemoryAccessViolation`.
+    ///
+    /// # Cost
+    ///
+    /// `base + secp256k1_verify_base`
+    pub fn secp256k1_verify(
+        &mut self,
+        signature_len: u64,
+        signature_ptr: u64,
+        hash_len: u64,
+        hash_ptr: u64,
+        public_key_len: u64,
+        public_key_ptr: u64,
+    ) -> Result<u64> {
+        self.gas_counter.pay_base(secp256k1_verify_base)?;
+        let hash = self.get_vec_from_memory_or_register(hash_ptr, hash_len)?;
+        let signature = self.get_vec_from_memory_or_register(signature_ptr, signature_len)?;
+        let public_key = self.get_vec_from_memory_or_register(public_key_ptr, public_key_len)?;
+        if hash.len() != 32 {
+            return Ok(0);
+        }
+
+        Ok(verify_signature(KeyType::SECP256K1, &signature, &hash, &public_key) as u64)
+    }
+
+    /// Recovers the secp256k1 public key that signed the 32 byte `hash` and writes it into
+    /// `register_id` as 64 bytes without the prefix byte. The signature is 65 bytes long with the
+    /// recovery id (0 or 1) in the last byte. Returns 1 if the key was recovered and 0 otherwise,
+    /// in which case the register is not modified.
+    ///
+    /// # Errors
+    ///
+    /// If `hash_len + hash_ptr` or `signature_len + signature_ptr` point outside the memory or the
+    /// registers use more memory than the limit with `MemoryAccessViolation`.
+    ///
+    /// # Cost
+    ///
+    /// `base + ecrecover_base + write_register_base + write_register_byte * 64`
+    pub fn ecrecover(
+        &mut self,
+        hash_len: u64,
+        hash_ptr: u64,
+        signature_len: u64,
+        signature_ptr: u64,
+        register_id: u64,
+    ) -> Result<u64> {
+        self.gas_counter.pay_base(ecrecover_base)?;
+        let hash = self.get_vec_from_memory_or_register(hash_ptr, hash_len)?;
+        let signature = self.get_vec_from_memory_or_register(signature_ptr, signature_len)?;
+        if hash.len() != 32 || signature.len() != 65 {
+            return Ok(0);
+        }
+
+        let mut msg = [0; 32];
+        msg.copy_from_slice(&hash);
+        let mut signature_bytes = [0; 65];
+        signature_bytes.copy_from_slice(&signature);
+        match Secp256K1Signature::from(signature_bytes).recover(msg) {
+            Some(public_key) => {
+                self.internal_write_register(register_id, public_key.as_ref().to_vec())?;
+                Ok(1)
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Computes the multiexp `sum(point_i * scalar_i)` on the alt_bn128 G1 group and writes the
+    /// resulting point into `register_id`. The value is a concatenation of elements, each a
+    /// 64 byte G1 point followed by a 32 byte scalar, all encoded in big-endian as in EIP-196.
+    ///
+    /// # Errors
+    ///
+    /// * If `value_len + value_ptr` points outside the memory or the registers use more memory
+    ///   than the limit with `MemoryAccessViolation`;
+    /// * If the value length is not a multiple of the element size or a point is not on the curve
+    ///   with `AltBn128InvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `base + write_register_base + write_register_byte * 64 + alt_bn128_g1_multiexp_base +
+    /// alt_bn128_g1_multiexp_element * num_elements`
+    pub fn alt_bn128_g1_multiexp(
+        &mut self,
+        value_len: u64,
+        value_ptr: u64,
+        register_id: u64,
+    ) -> Result<()> {
+        self.gas_counter.pay_base(alt_bn128_g1_multiexp_base)?;
+        let data = self.get_vec_from_memory_or_register(value_ptr, value_len)?;
+        let num_elements = alt_bn128::g1_multiexp_element_count(&data)?;
+        self.gas_counter.pay_per_byte(alt_bn128_g1_multiexp_element, num_elements)?;
+
+        let res = alt_bn128::g1_multiexp(&data)?;
+        self.internal_write_register(register_id, res)
+    }
+
+    /// Computes the sum of signed points on the alt_bn128 G1 group and writes the resulting point
+    /// into `register_id`. The value is a concatenation of elements, each a sign byte (0 to add
+    /// the point, 1 to subtract it) followed by a 64 byte G1 point encoded as in EIP-196.
+    ///
+    /// # Errors
+    ///
+    /// * If `value_len + value_ptr` points outside the memory or the registers use more memory
+    ///   than the limit with `MemoryAccessViolation`;
+    /// * If the value length is not a multiple of the element size, a sign byte is not 0 or 1 or
+    ///   a point is not on the curve with `AltBn128InvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `base + write_register_base + write_register_byte * 64 + alt_bn128_g1_sum_base +
+    /// alt_bn128_g1_sum_element * num_elements`
+    pub fn alt_bn128_g1_sum(
+        &mut self,
+        value_len: u64,
+        value_ptr: u64,
+        register_id: u64,
+    ) -> Result<()> {
+        self.gas_counter.pay_base(alt_bn128_g1_sum_base)?;
+        let data = self.get_vec_from_memory_or_register(value_ptr, value_len)?;
+        let num_elements = alt_bn128::g1_sum_element_count(&data)?;
+        self.gas_counter.pay_per_byte(alt_bn128_g1_sum_element, num_elements)?;
+
+        let res = alt_bn128::g1_sum(&data)?;
+        self.internal_write_register(register_id, res)
+    }
+
+    /// Checks that the product of alt_bn128 pairings `e(g1_i, g2_i)` equals one, as done by the
+    /// EIP-197 precompile. The value is a concatenation of elements, each a 64 byte G1 point
+    /// followed by a 128 byte G2 point. Returns 1 if the check passes and 0 otherwise.
+    ///
+    /// # Errors
+    ///
+    /// * If `value_len + value_ptr` points outside the memory or the registers use more memory
+    ///   than the limit with `MemoryAccessViolation`;
+    /// * If the value length is not a multiple of the element size or a point is not on the curve
+    ///   with `AltBn128InvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `base + alt_bn128_pairing_check_base + alt_bn128_pairing_check_element * num_elements`
+    pub fn alt_bn128_pairing_check(&mut self, value_len: u64, value_ptr: u64) -> Result<u64> {
+        self.gas_counter.pay_base(alt_bn128_pairing_check_base)?;
+        let data = self.get_vec_from_memory_or_register(value_ptr, value_len)?;
+        let num_elements = alt_bn128::pairing_element_count(&data)?;
+        self.gas_counter.pay_per_byte(alt_bn128_pairing_check_element, num_elements)?;
+
+        Ok(alt_bn128::pairing_check(&data)? as u64)
+    }
+
     /// Called by gas metering injected into Wasm. Counts both towards `burnt_gas` and `used_gas`.
     ///
     /// # Errors
@@ -830,7 +1082,7 @@ impl<'a> VMLogic<'a> {
     /// * If we exceed the `prepaid_gas` then returns `GasExceeded`.
     pub fn gas(&mut self, gas_amount: u32) -> Result<()> {
         let value = Gas::from(gas_amount) * Gas::from(self.config.regular_op_cost);
-        self.gas_counter.deduct_gas(value, value)
+        self.gas_counter.pay_wasm_gas(value)
     }
 
     // ################
@@ -864,7 +1116,7 @@ impl<'a> VMLogic<'a> {
                 .ok_or(HostError::IntegerOverflow)?;
         }
         use_gas = use_gas.checked_add(burn_gas).ok_or(HostError::IntegerOverflow)?;
-        self.gas_counter.deduct_gas(burn_gas, use_gas)
+        self.gas_counter.pay_action_accumulated(burn_gas, use_gas)
     }
 
     /// A helper function to subtract balance on transfer or attached deposit for promises.
@@ -1742,7 +1994,7 @@ impl<'a> VMLogic<'a> {
                 )
                 .ok_or(HostError::IntegerOverflow)?;
         }
-        self.gas_counter.deduct_gas(burn_gas, burn_gas)?;
+        self.gas_counter.pay_action_accumulated(burn_gas, burn_gas)?;
         self.return_data = ReturnData::Value(return_val);
         Ok(())
     }
@@ -1943,6 +2195,8 @@ impl<'a> VMLogic<'a> {
         self.gas_counter
             .pay_per_byte(touching_trie_node, self.ext.get_touched_nodes_count() - nodes_before)?;
         self.ext.storage_set(&key, &value)?;
+        #[cfg(feature = "host_calls_tracing")]
+        trace::record_storage_access(trace::StorageOperation::Write, &key, Some(&value));
         let storage_config = &self.fees_config.storage_usage_config;
         match evicted {
             Some(old_value) => {
@@ -2022,6 +2276,8 @@ impl<'a> VMLogic<'a> {
         self.gas_counter
             .pay_per_byte(touching_trie_node, self.ext.get_touched_nodes_count() - nodes_before)?;
         let read = Self::deref_value(&mut self.gas_counter, storage_read_value_byte, read?)?;
+        #[cfg(feature = "host_calls_tracing")]
+        trace::record_storage_access(trace::StorageOperation::Read, &key, read.as_deref());
         match read {
             Some(value) => {
                 self.internal_write_register(register_id, value)?;
@@ -2073,6 +2329,8 @@ impl<'a> VMLogic<'a> {
             Self::deref_value(&mut self.gas_counter, storage_remove_ret_value_byte, removed_ptr)?;
 
         self.ext.storage_remove(&key)?;
+        #[cfg(feature = "host_calls_tracing")]
+        trace::record_storage_access(trace::StorageOperation::Remove, &key, removed.as_deref());
         self.gas_counter
             .pay_per_byte(touching_trie_node, self.ext.get_touched_nodes_count() - nodes_before)?;
         let storage_config = &self.fees_config.storage_usage_config;
@@ -2120,6 +2378,8 @@ impl<'a> VMLogic<'a> {
         self.gas_counter.pay_per_byte(storage_has_key_byte, key.len() as u64)?;
         let nodes_before = self.ext.get_touched_nodes_count();
         let res = self.ext.storage_has_key(&key);
+        #[cfg(feature = "host_calls_tracing")]
+        trace::record_storage_access(trace::StorageOperation::HasKey, &key, None);
         self.gas_counter
             .pay_per_byte(touching_trie_node, self.ext.get_touched_nodes_count() - nodes_before)?;
         Ok(res? as u64)
@@ -2216,6 +2476,28 @@ impl<'a> VMLogic<'a> {
         }))
     }
 
+    /// Makes the host function call `call` named `name` with the given arguments and records it
+    /// in the host calls trace of the current thread, if it was started with `trace::start`.
+    #[cfg(feature = "host_calls_tracing")]
+    pub fn traced<T: trace::TracedValue>(
+        &mut self,
+        name: &'static str,
+        args: &[(&'static str, u64)],
+        call: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let burnt_gas = self.gas_counter.burnt_gas();
+        let used_gas = self.gas_counter.used_gas();
+        trace::begin_call(name, args);
+        let res = call(self);
+        trace::end_call(
+            res.as_ref().ok().and_then(trace::TracedValue::traced_value),
+            res.as_ref().err(),
+            self.gas_counter.burnt_gas().saturating_sub(burnt_gas),
+            self.gas_counter.used_gas().saturating_sub(used_gas),
+        );
+        res
+    }
+
     /// Computes the outcome of execution.
     pub fn outcome(self) -> VMOutcome {
         VMOutcome {
@@ -2224,6 +2506,7 @@ impl<'a> VMLogic<'a> {
             return_data: self.return_data,
             burnt_gas: self.gas_counter.burnt_gas(),
             used_gas: self.gas_counter.used_gas(),
+            profile: self.gas_counter.profile_data(),
             logs: self.logs,
         }
     }
@@ -2238,6 +2521,7 @@ impl<'a> VMLogic<'a> {
             return_data,
             burnt_gas: self.gas_counter.burnt_gas(),
             used_gas: self.gas_counter.used_gas(),
+            profile: self.gas_counter.profile_data(),
             logs,
         }
     }
@@ -2256,5 +2540,8 @@ pub struct VMOutcome {
     pub return_data: ReturnData,
     pub burnt_gas: Gas,
     pub used_gas: Gas,
+    /// Breakdown of `burnt_gas`, only collected if `VMContext::profile_gas` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ProfileData>,
     pub logs: Vec<String>,
 }
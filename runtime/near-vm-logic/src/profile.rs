@@ -0,0 +1,47 @@
+use crate::config::ExtCosts;
+use crate::types::Gas;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Breakdown of the gas burnt by a contract call.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileData {
+    /// Gas burnt by executing Wasm instructions.
+    pub wasm_gas: Gas,
+    /// Gas burnt by the fees of the receipts and actions created by the contract.
+    pub action_gas: Gas,
+    /// Gas burnt by the host functions per cost category. Categories without burnt gas are
+    /// omitted.
+    pub ext_costs: BTreeMap<ExtCosts, Gas>,
+}
+
+impl ProfileData {
+    pub(crate) fn add_wasm_gas(&mut self, gas: Gas) {
+        self.wasm_gas = self.wasm_gas.saturating_add(gas);
+    }
+
+    pub(crate) fn add_action_gas(&mut self, gas: Gas) {
+        self.action_gas = self.action_gas.saturating_add(gas);
+    }
+
+    pub(crate) fn add_ext_cost(&mut self, cost: ExtCosts, gas: Gas) {
+        if gas > 0 {
+            let value = self.ext_costs.entry(cost).or_default();
+            *value = value.saturating_add(gas);
+        }
+    }
+
+    /// Adds the gas of another profile to this one.
+    pub fn merge(&mut self, other: &ProfileData) {
+        self.add_wasm_gas(other.wasm_gas);
+        self.add_action_gas(other.action_gas);
+        for (cost, gas) in other.ext_costs.iter() {
+            self.add_ext_cost(*cost, *gas);
+        }
+    }
+
+    /// Total gas burnt by the host functions.
+    pub fn ext_costs_gas(&self) -> Gas {
+        self.ext_costs.values().fold(0, |acc, gas| acc.saturating_add(*gas))
+    }
+}
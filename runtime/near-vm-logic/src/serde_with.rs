@@ -18,6 +18,29 @@ pub mod bytes_as_base64 {
     }
 }
 
+/// Serialize `Option<Vec<u8>>` as optional base64 encoding.
+pub mod option_bytes_as_base64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(data: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match data {
+            Some(arr) => serializer.serialize_some(&base64::encode(arr)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = Option::<String>::deserialize(deserializer)?;
+        Ok(s.map(|s| base64::decode(&s).expect("Failed to deserialize base64 string")))
+    }
+}
+
 /// Serialize `Vec<u8>` as `String`.
 pub mod bytes_as_str {
     use serde::{Deserialize, Deserializer, Serializer};
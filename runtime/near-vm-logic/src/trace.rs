@@ -0,0 +1,148 @@
+//! Recording of the host function calls made by a contract, used to debug contracts that fail
+//! deep inside their execution.
+//!
+//! Recording is per thread and is off until `start` is called. Every host function call made
+//! after that is appended to the trace, together with the registers it wrote, the storage it
+//! accessed and the gas it spent, until the trace is collected with `take`.
+use crate::types::Gas;
+use crate::VMLogicError;
+use serde::Serialize;
+use std::cell::RefCell;
+
+thread_local! {
+    static HOST_CALLS_TRACE: RefCell<Option<Vec<HostCallTrace>>> = RefCell::new(None);
+}
+
+/// A single call of a host function.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HostCallTrace {
+    /// Name of the host function.
+    pub name: &'static str,
+    /// Arguments in the order they are declared by the host function.
+    pub args: Vec<HostCallArg>,
+    /// Returned value, `None` for functions that return nothing or failed.
+    pub result: Option<u64>,
+    /// Error returned by the host function, if any.
+    pub error: Option<VMLogicError>,
+    /// Gas burnt by the call.
+    pub burnt_gas: Gas,
+    /// Gas used by the call, including the gas attached to the created promises.
+    pub used_gas: Gas,
+    /// Registers written by the call in the order of writing.
+    pub registers: Vec<RegisterWrite>,
+    /// Storage accessed by the call in the order of access.
+    pub storage: Vec<StorageAccess>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HostCallArg {
+    pub name: &'static str,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RegisterWrite {
+    pub register_id: u64,
+    #[serde(with = "crate::serde_with::bytes_as_base64")]
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum StorageOperation {
+    Read,
+    Write,
+    Remove,
+    HasKey,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StorageAccess {
+    pub operation: StorageOperation,
+    #[serde(with = "crate::serde_with::bytes_as_base64")]
+    pub key: Vec<u8>,
+    /// The value read, written or removed. `None` if the key is absent or for `HasKey`.
+    #[serde(with = "crate::serde_with::option_bytes_as_base64")]
+    pub value: Option<Vec<u8>>,
+}
+
+/// Host function return values that can be recorded in the trace.
+pub trait TracedValue {
+    fn traced_value(&self) -> Option<u64>;
+}
+
+impl TracedValue for () {
+    fn traced_value(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl TracedValue for u64 {
+    fn traced_value(&self) -> Option<u64> {
+        Some(*self)
+    }
+}
+
+/// Starts recording host function calls on the current thread, dropping the previous trace.
+pub fn start() {
+    HOST_CALLS_TRACE.with(|trace| *trace.borrow_mut() = Some(vec![]));
+}
+
+/// Stops recording and returns the calls recorded since `start`.
+pub fn take() -> Vec<HostCallTrace> {
+    HOST_CALLS_TRACE.with(|trace| trace.borrow_mut().take().unwrap_or_default())
+}
+
+fn with_current_call(f: impl FnOnce(&mut HostCallTrace)) {
+    HOST_CALLS_TRACE.with(|trace| {
+        if let Some(call) = trace.borrow_mut().as_mut().and_then(|calls| calls.last_mut()) {
+            f(call);
+        }
+    });
+}
+
+pub(crate) fn begin_call(name: &'static str, args: &[(&'static str, u64)]) {
+    HOST_CALLS_TRACE.with(|trace| {
+        if let Some(calls) = trace.borrow_mut().as_mut() {
+            calls.push(HostCallTrace {
+                name,
+                args: args.iter().map(|&(name, value)| HostCallArg { name, value }).collect(),
+                result: None,
+                error: None,
+                burnt_gas: 0,
+                used_gas: 0,
+                registers: vec![],
+                storage: vec![],
+            });
+        }
+    });
+}
+
+pub(crate) fn end_call(
+    result: Option<u64>,
+    error: Option<&VMLogicError>,
+    burnt_gas: Gas,
+    used_gas: Gas,
+) {
+    with_current_call(|call| {
+        call.result = result;
+        call.error = error.cloned();
+        call.burnt_gas = burnt_gas;
+        call.used_gas = used_gas;
+    });
+}
+
+pub(crate) fn record_register_write(register_id: u64, data: &[u8]) {
+    with_current_call(|call| {
+        call.registers.push(RegisterWrite { register_id, data: data.to_vec() })
+    });
+}
+
+pub(crate) fn record_storage_access(operation: StorageOperation, key: &[u8], value: Option<&[u8]>) {
+    with_current_call(|call| {
+        call.storage.push(StorageAccess {
+            operation,
+            key: key.to_vec(),
+            value: value.map(|v| v.to_vec()),
+        })
+    });
+}
@@ -18,6 +18,7 @@ pub fn get_context(input: Vec<u8>, is_view: bool) -> VMContext {
         prepaid_gas: 10_u64.pow(14),
         random_seed: vec![],
         is_view,
+        profile_gas: false,
         output_data_receivers: vec![],
     }
 }
@@ -21,6 +21,7 @@ pub fn create_context() -> VMContext {
         prepaid_gas: 10_u64.pow(14),
         random_seed: vec![0, 1, 2],
         is_view: false,
+        profile_gas: false,
         output_data_receivers: vec![],
     }
 }
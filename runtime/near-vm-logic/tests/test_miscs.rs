@@ -1,7 +1,9 @@
+use borsh::BorshSerialize;
 use fixtures::get_context;
 use helpers::*;
+use near_crypto::{KeyType, SecretKey};
 use near_vm_errors::HostError;
-use near_vm_logic::ExtCosts;
+use near_vm_logic::{ExtCosts, VMLogic};
 use vm_logic_builder::VMLogicBuilder;
 
 mod fixtures;
@@ -530,6 +532,342 @@ fn test_keccak512() {
     });
 }
 
+#[test]
+fn test_ripemd160() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let data = b"abc";
+
+    logic.ripemd160(data.len() as _, data.as_ptr() as _, 0).unwrap();
+    let res = &vec![0u8; 20];
+    logic.read_register(0, res.as_ptr() as _).expect("OK");
+    assert_eq!(
+        res,
+        &[
+            142, 178, 8, 247, 224, 93, 152, 122, 155, 4, 74, 142, 152, 198, 176, 135, 241, 90, 11,
+            252
+        ]
+        .to_vec()
+    );
+    let len = data.len() as u64;
+    assert_costs(map! {
+        ExtCosts::base: 1,
+        ExtCosts::read_memory_base: 1,
+        ExtCosts::read_memory_byte: len,
+        ExtCosts::write_memory_base: 1,
+        ExtCosts::write_memory_byte: 20,
+        ExtCosts::read_register_base: 1,
+        ExtCosts::read_register_byte: 20,
+        ExtCosts::write_register_base: 1,
+        ExtCosts::write_register_byte: 20,
+        ExtCosts::ripemd160_base: 1,
+        ExtCosts::ripemd160_byte: len,
+    });
+}
+
+#[test]
+fn test_blake2b() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let data = b"abc";
+
+    logic.blake2b(data.len() as _, data.as_ptr() as _, 0).unwrap();
+    let res = &vec![0u8; 64];
+    logic.read_register(0, res.as_ptr() as _).expect("OK");
+    assert_eq!(
+        res,
+        &[
+            186, 128, 165, 63, 152, 28, 77, 13, 106, 39, 151, 182, 159, 18, 246, 233, 76, 33, 47,
+            20, 104, 90, 196, 183, 75, 18, 187, 111, 219, 255, 162, 209, 125, 135, 197, 57, 42,
+            171, 121, 45, 194, 82, 213, 222, 69, 51, 204, 149, 24, 211, 138, 168, 219, 241, 146,
+            90, 185, 35, 134, 237, 212, 0, 153, 35
+        ]
+        .to_vec()
+    );
+    let len = data.len() as u64;
+    assert_costs(map! {
+        ExtCosts::base: 1,
+        ExtCosts::read_memory_base: 1,
+        ExtCosts::read_memory_byte: len,
+        ExtCosts::write_memory_base: 1,
+        ExtCosts::write_memory_byte: 64,
+        ExtCosts::read_register_base: 1,
+        ExtCosts::read_register_byte: 64,
+        ExtCosts::write_register_base: 1,
+        ExtCosts::write_register_byte: 64,
+        ExtCosts::blake2b_base: 1,
+        ExtCosts::blake2b_byte: len,
+    });
+}
+
+/// Raw bytes of a key or signature without the key type prefix.
+fn raw_bytes<T: BorshSerialize>(value: &T) -> Vec<u8> {
+    value.try_to_vec().unwrap()[1..].to_vec()
+}
+
+#[test]
+fn test_ed25519_verify() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let sk = SecretKey::from_seed(KeyType::ED25519, "test");
+    let message = b"message";
+    let signature = raw_bytes(&sk.sign(message));
+    let public_key = raw_bytes(&sk.public_key());
+
+    let verify = |logic: &mut VMLogic, message: &[u8], signature: &[u8]| {
+        logic
+            .ed25519_verify(
+                signature.len() as _,
+                signature.as_ptr() as _,
+                message.len() as _,
+                message.as_ptr() as _,
+                public_key.len() as _,
+                public_key.as_ptr() as _,
+            )
+            .unwrap()
+    };
+    assert_eq!(verify(&mut logic, message, &signature), 1);
+    let len = message.len() as u64;
+    assert_costs(map! {
+        ExtCosts::read_memory_base: 3,
+        ExtCosts::read_memory_byte: len + 64 + 32,
+        ExtCosts::ed25519_verify_base: 1,
+        ExtCosts::ed25519_verify_byte: len,
+    });
+
+    assert_eq!(verify(&mut logic, b"other message", &signature), 0);
+    assert_eq!(verify(&mut logic, message, &signature[1..]), 0);
+}
+
+#[test]
+fn test_secp256k1_verify() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let sk = SecretKey::from_seed(KeyType::SECP256K1, "test");
+    let hash = [7u8; 32];
+    let signature = raw_bytes(&sk.sign(&hash));
+    let public_key = raw_bytes(&sk.public_key());
+
+    let verify = |logic: &mut VMLogic, hash: &[u8], signature: &[u8]| {
+        logic
+            .secp256k1_verify(
+                signature.len() as _,
+                signature.as_ptr() as _,
+                hash.len() as _,
+                hash.as_ptr() as _,
+                public_key.len() as _,
+                public_key.as_ptr() as _,
+            )
+            .unwrap()
+    };
+    assert_eq!(verify(&mut logic, &hash, &signature), 1);
+    assert_costs(map! {
+        ExtCosts::read_memory_base: 3,
+        ExtCosts::read_memory_byte: 32 + 65 + 64,
+        ExtCosts::secp256k1_verify_base: 1,
+    });
+
+    assert_eq!(verify(&mut logic, &[8u8; 32], &signature), 0);
+    assert_eq!(verify(&mut logic, &hash[1..], &signature), 0);
+    let mut malformed = signature.clone();
+    malformed[64] = 4;
+    assert_eq!(verify(&mut logic, &hash, &malformed), 0);
+}
+
+#[test]
+fn test_ecrecover() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let sk = SecretKey::from_seed(KeyType::SECP256K1, "test");
+    let hash = [7u8; 32];
+    let signature = raw_bytes(&sk.sign(&hash));
+
+    assert_eq!(
+        logic
+            .ecrecover(
+                hash.len() as _,
+                hash.as_ptr() as _,
+                signature.len() as _,
+                signature.as_ptr() as _,
+                0
+            )
+            .unwrap(),
+        1
+    );
+    let res = &vec![0u8; 64];
+    logic.read_register(0, res.as_ptr() as _).expect("OK");
+    assert_eq!(res, &raw_bytes(&sk.public_key()));
+    assert_costs(map! {
+        ExtCosts::base: 1,
+        ExtCosts::read_memory_base: 2,
+        ExtCosts::read_memory_byte: 32 + 65,
+        ExtCosts::write_memory_base: 1,
+        ExtCosts::write_memory_byte: 64,
+        ExtCosts::read_register_base: 1,
+        ExtCosts::read_register_byte: 64,
+        ExtCosts::write_register_base: 1,
+        ExtCosts::write_register_byte: 64,
+        ExtCosts::ecrecover_base: 1,
+    });
+
+    let mut malformed = signature.clone();
+    malformed[64] = 4;
+    assert_eq!(
+        logic
+            .ecrecover(
+                hash.len() as _,
+                hash.as_ptr() as _,
+                malformed.len() as _,
+                malformed.as_ptr() as _,
+                1
+            )
+            .unwrap(),
+        0
+    );
+    assert_eq!(logic.register_len(1).unwrap(), std::u64::MAX);
+}
+
+/// The generator of the alt_bn128 G1 group, `(1, 2)`.
+fn alt_bn128_g1_generator() -> Vec<u8> {
+    let mut res = vec![0u8; 64];
+    res[31] = 1;
+    res[63] = 2;
+    res
+}
+
+/// The negated generator of the alt_bn128 G1 group, `(1, p - 2)`.
+fn alt_bn128_g1_generator_neg() -> Vec<u8> {
+    let mut res = vec![0u8; 32];
+    res[31] = 1;
+    res.extend(
+        hex::decode("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45").unwrap(),
+    );
+    res
+}
+
+/// The generator of the alt_bn128 G2 group encoded as `x_im || x_re || y_im || y_re`.
+fn alt_bn128_g2_generator() -> Vec<u8> {
+    hex::decode(concat!(
+        "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+        "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+        "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+        "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+    ))
+    .unwrap()
+}
+
+/// Twice the generator of the alt_bn128 G1 group.
+fn alt_bn128_g1_generator_double() -> Vec<u8> {
+    hex::decode(concat!(
+        "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3",
+        "15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4",
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_alt_bn128_g1_multiexp() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let mut scalar = vec![0u8; 32];
+    scalar[31] = 2;
+    let data = [alt_bn128_g1_generator(), scalar].concat();
+
+    logic.alt_bn128_g1_multiexp(data.len() as _, data.as_ptr() as _, 0).unwrap();
+    let res = &vec![0u8; 64];
+    logic.read_register(0, res.as_ptr() as _).unwrap();
+    assert_eq!(res, &alt_bn128_g1_generator_double());
+    assert_costs(map! {
+        ExtCosts::base: 1,
+        ExtCosts::read_memory_base: 1,
+        ExtCosts::read_memory_byte: data.len() as u64,
+        ExtCosts::write_memory_base: 1,
+        ExtCosts::write_memory_byte: 64,
+        ExtCosts::read_register_base: 1,
+        ExtCosts::read_register_byte: 64,
+        ExtCosts::write_register_base: 1,
+        ExtCosts::write_register_byte: 64,
+        ExtCosts::alt_bn128_g1_multiexp_base: 1,
+        ExtCosts::alt_bn128_g1_multiexp_element: 1,
+    });
+}
+
+#[test]
+fn test_alt_bn128_g1_sum() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let g = alt_bn128_g1_generator();
+
+    let data = [vec![0], g.clone(), vec![0], g.clone()].concat();
+    logic.alt_bn128_g1_sum(data.len() as _, data.as_ptr() as _, 0).unwrap();
+    let res = &vec![0u8; 64];
+    logic.read_register(0, res.as_ptr() as _).unwrap();
+    assert_eq!(res, &alt_bn128_g1_generator_double());
+    assert_costs(map! {
+        ExtCosts::base: 1,
+        ExtCosts::read_memory_base: 1,
+        ExtCosts::read_memory_byte: data.len() as u64,
+        ExtCosts::write_memory_base: 1,
+        ExtCosts::write_memory_byte: 64,
+        ExtCosts::read_register_base: 1,
+        ExtCosts::read_register_byte: 64,
+        ExtCosts::write_register_base: 1,
+        ExtCosts::write_register_byte: 64,
+        ExtCosts::alt_bn128_g1_sum_base: 1,
+        ExtCosts::alt_bn128_g1_sum_element: 2,
+    });
+
+    let data = [vec![0], g.clone(), vec![1], g].concat();
+    logic.alt_bn128_g1_sum(data.len() as _, data.as_ptr() as _, 0).unwrap();
+    logic.read_register(0, res.as_ptr() as _).unwrap();
+    assert_eq!(res, &vec![0u8; 64]);
+}
+
+#[test]
+fn test_alt_bn128_pairing_check() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let g2 = alt_bn128_g2_generator();
+
+    let data =
+        [alt_bn128_g1_generator(), g2.clone(), alt_bn128_g1_generator_neg(), g2.clone()].concat();
+    assert_eq!(logic.alt_bn128_pairing_check(data.len() as _, data.as_ptr() as _).unwrap(), 1);
+    assert_costs(map! {
+        ExtCosts::base: 1,
+        ExtCosts::read_memory_base: 1,
+        ExtCosts::read_memory_byte: data.len() as u64,
+        ExtCosts::alt_bn128_pairing_check_base: 1,
+        ExtCosts::alt_bn128_pairing_check_element: 2,
+    });
+
+    let data = [alt_bn128_g1_generator(), g2].concat();
+    assert_eq!(logic.alt_bn128_pairing_check(data.len() as _, data.as_ptr() as _).unwrap(), 0);
+}
+
+#[test]
+fn test_alt_bn128_invalid_input() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+
+    let mut not_on_curve = alt_bn128_g1_generator();
+    not_on_curve[63] = 3;
+    let data = [vec![0], not_on_curve].concat();
+    assert_eq!(
+        logic.alt_bn128_g1_sum(data.len() as _, data.as_ptr() as _, 0),
+        Err(HostError::AltBn128InvalidInput { msg: "G1 point is not on the curve".to_string() }
+            .into())
+    );
+
+    let data = alt_bn128_g1_generator();
+    assert_eq!(
+        logic.alt_bn128_g1_multiexp(data.len() as _, data.as_ptr() as _, 0),
+        Err(HostError::AltBn128InvalidInput {
+            msg: "input length 64 is not a multiple of 96".to_string()
+        }
+        .into())
+    );
+}
+
 #[test]
 fn test_hash256_register() {
     let mut logic_builder = VMLogicBuilder::default();
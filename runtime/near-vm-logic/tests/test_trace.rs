@@ -0,0 +1,86 @@
+mod fixtures;
+mod vm_logic_builder;
+
+use fixtures::get_context;
+use near_vm_errors::HostError;
+use near_vm_logic::trace::{self, HostCallArg, RegisterWrite, StorageAccess, StorageOperation};
+use near_vm_logic::External;
+use vm_logic_builder::VMLogicBuilder;
+
+#[test]
+fn test_trace_storage_and_registers() {
+    let mut logic_builder = VMLogicBuilder::default();
+    logic_builder.ext.storage_set(b"foo", b"bar").unwrap();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let key = b"foo";
+    let value = b"baz";
+
+    trace::start();
+    let res = logic.traced(
+        "storage_write",
+        &[
+            ("key_len", key.len() as u64),
+            ("key_ptr", key.as_ptr() as u64),
+            ("value_len", value.len() as u64),
+            ("value_ptr", value.as_ptr() as u64),
+            ("register_id", 0),
+        ],
+        |logic| {
+            logic.storage_write(
+                key.len() as _,
+                key.as_ptr() as _,
+                value.len() as _,
+                value.as_ptr() as _,
+                0,
+            )
+        },
+    );
+    assert_eq!(res, Ok(1));
+    let calls = trace::take();
+
+    assert_eq!(calls.len(), 1);
+    let call = &calls[0];
+    assert_eq!(call.name, "storage_write");
+    assert_eq!(call.args[4], HostCallArg { name: "register_id", value: 0 });
+    assert_eq!(call.result, Some(1));
+    assert_eq!(call.error, None);
+    assert!(call.burnt_gas > 0);
+    assert_eq!(call.burnt_gas, call.used_gas);
+    assert_eq!(call.registers, vec![RegisterWrite { register_id: 0, data: b"bar".to_vec() }]);
+    assert_eq!(
+        call.storage,
+        vec![StorageAccess {
+            operation: StorageOperation::Write,
+            key: key.to_vec(),
+            value: Some(value.to_vec()),
+        }]
+    );
+}
+
+#[test]
+fn test_trace_error() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+
+    trace::start();
+    let res = logic.traced("read_register", &[("register_id", 7), ("ptr", 0)], |logic| {
+        logic.read_register(7, 0)
+    });
+    assert_eq!(res, Err(HostError::InvalidRegisterId { register_id: 7 }.into()));
+    let calls = trace::take();
+
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].result, None);
+    assert_eq!(calls[0].error, Some(HostError::InvalidRegisterId { register_id: 7 }.into()));
+}
+
+#[test]
+fn test_trace_not_started() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+
+    logic
+        .traced("storage_has_key", &[], |logic| logic.storage_has_key(3, b"foo".as_ptr() as _))
+        .unwrap();
+    assert!(trace::take().is_empty());
+}
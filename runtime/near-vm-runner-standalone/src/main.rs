@@ -5,13 +5,16 @@
 //! -- --method-name=hello --wasm-file=/tmp/main.wasm
 //! ```
 //! Optional `--context-file=/tmp/context.json --config-file=/tmp/config.json` could be added
-//! to provide custom context and VM config.
+//! to provide custom context and VM config. With `--trace` every host function call made by the
+//! contract is included in the output, `--vm-kind=wasmtime` runs the contract with Wasmtime.
 use clap::{App, Arg};
+use near_primitives::version::PROTOCOL_VERSION;
 use near_runtime_fees::RuntimeFeesConfig;
 use near_vm_logic::mocks::mock_external::{MockedExternal, Receipt};
+use near_vm_logic::trace::{self, HostCallTrace};
 use near_vm_logic::types::PromiseResult;
-use near_vm_logic::{VMConfig, VMContext, VMOutcome};
-use near_vm_runner::{run, VMError};
+use near_vm_logic::{VMConfig, VMContext, VMKind, VMOutcome};
+use near_vm_runner::{run_vm, VMError};
 use serde::de::{MapAccess, Visitor};
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -66,6 +69,8 @@ struct StandaloneOutput {
     pub err: Option<VMError>,
     pub receipts: Vec<Receipt>,
     pub state: State,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Vec<HostCallTrace>>,
 }
 
 fn default_vm_context() -> VMContext {
@@ -84,6 +89,7 @@ fn default_vm_context() -> VMContext {
         prepaid_gas: 10u64.pow(18),
         random_seed: vec![0, 1, 2],
         is_view: false,
+        profile_gas: false,
         output_data_receivers: vec![],
         epoch_height: 1,
     };
@@ -160,6 +166,21 @@ fn main() {
                 .help("File path that contains the Wasm code to run.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("vm-kind")
+                .long("vm-kind")
+                .value_name("VM_KIND")
+                .help("Wasm backend to run the contract with.")
+                .possible_values(&["wasmer", "wasmtime"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("trace")
+                .long("trace")
+                .help("Records every host function call with its arguments, written registers, \
+                accessed storage and gas, and adds them to the output.")
+                .takes_value(false),
+        )
         .get_matches();
 
     let mut context: VMContext = match matches.value_of("context") {
@@ -210,8 +231,19 @@ fn main() {
     let code =
         fs::read(matches.value_of("wasm-file").expect("Wasm file needs to be specified")).unwrap();
 
+    let vm_kind = match matches.value_of("vm-kind") {
+        Some("wasmtime") => VMKind::Wasmtime,
+        Some(_) => VMKind::Wasmer,
+        None => VMKind::default(),
+    };
+
+    let tracing = matches.is_present("trace");
+    if tracing {
+        trace::start();
+    }
+
     let fees = RuntimeFeesConfig::default();
-    let (outcome, err) = run(
+    let (outcome, err) = run_vm(
         vec![],
         &code,
         &method_name,
@@ -220,7 +252,11 @@ fn main() {
         &config,
         &fees,
         &promise_results,
+        vm_kind,
+        PROTOCOL_VERSION,
+        None,
     );
+    let trace = if tracing { Some(trace::take()) } else { None };
 
     println!(
         "{}",
@@ -229,6 +265,7 @@ fn main() {
             err,
             receipts: fake_external.get_receipt_create_calls().clone(),
             state: State(fake_external.fake_trie),
+            trace,
         })
         .unwrap()
     )
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 
 use bencher::{benchmark_group, benchmark_main, Bencher};
 
+use near_primitives::version::PROTOCOL_VERSION;
 use near_runtime_fees::RuntimeFeesConfig;
 use near_vm_logic::mocks::mock_external::MockedExternal;
 use near_vm_logic::types::PromiseResult;
@@ -34,6 +35,7 @@ fn setup(
         prepaid_gas: 10u64.pow(15),
         random_seed: vec![0, 1, 2],
         is_view: false,
+        profile_gas: false,
         output_data_receivers: vec![],
     };
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -73,6 +75,8 @@ fn pass_through(bench: &mut Bencher) {
             &config,
             &fees_config,
             &promise_results,
+            PROTOCOL_VERSION,
+            None,
         );
         assert_run_result(result, 42);
     });
@@ -90,6 +94,8 @@ fn benchmark_fake_storage_8b_1000(bench: &mut Bencher) {
             &config,
             &fees_config,
             &promise_results,
+            PROTOCOL_VERSION,
+            None,
         );
         assert_run_result(result, 999 * 1000 / 2);
     });
@@ -107,6 +113,8 @@ fn benchmark_fake_storage_10kib_1000(bench: &mut Bencher) {
             &config,
             &fees_config,
             &promise_results,
+            PROTOCOL_VERSION,
+            None,
         );
         assert_run_result(result, 999 * 1000 / 2);
     });
@@ -124,6 +132,8 @@ fn sum_n_1000000(bench: &mut Bencher) {
             &config,
             &fees_config,
             &promise_results,
+            PROTOCOL_VERSION,
+            None,
         );
         assert_run_result(result, (1000000 - 1) * 1000000 / 2);
     });
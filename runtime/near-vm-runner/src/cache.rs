@@ -3,35 +3,146 @@ use cached::{cached_key, SizedCache};
 
 use crate::errors::IntoVMError;
 use crate::prepare;
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_primitives::hash::hash;
+use near_primitives::types::CompiledContractCache;
 use near_vm_errors::VMError;
 use near_vm_logic::VMConfig;
+use wasmer_runtime::cache::Artifact;
 
 /// Cache size in number of cached modules to hold.
 #[cfg(not(feature = "no_cache"))]
 const CACHE_SIZE: usize = 128;
-// TODO: store a larger on-disk cache
+
+/// Version of the on-disk cache layout. Bump it whenever the key or the record format changes,
+/// so that records written by an older binary are never looked up again.
+const CACHE_VERSION: u32 = 1;
+
+/// Key of the on-disk cache. Artifacts are only valid for the exact same code, VM configuration
+/// and Wasmer version, so all of them are part of the key.
+#[derive(BorshSerialize)]
+struct ContractCacheKey<'a> {
+    cache_version: u32,
+    wasmer_version: &'a str,
+    code_hash: &'a [u8],
+    vm_config_non_crypto_hash: u64,
+}
+
+/// Record stored in the on-disk cache. Compilation is deterministic, so compilation errors are
+/// cached as well.
+#[derive(BorshSerialize, BorshDeserialize)]
+enum CacheRecord {
+    Error(VMError),
+    Code(Vec<u8>),
+}
+
+fn get_key(code_hash: &[u8], config: &VMConfig) -> Vec<u8> {
+    let key = ContractCacheKey {
+        cache_version: CACHE_VERSION,
+        wasmer_version: wasmer_runtime_core::VERSION,
+        code_hash,
+        vm_config_non_crypto_hash: config.non_crypto_hash(),
+    };
+    hash(&key.try_to_vec().expect("Borsh serializer is not expected to ever fail"))
+        .as_ref()
+        .to_vec()
+}
+
+fn compile_module(code: &[u8], config: &VMConfig) -> Result<wasmer_runtime::Module, VMError> {
+    let prepared_code = prepare::prepare_contract(code, config)?;
+    wasmer_runtime::compile(&prepared_code).map_err(|err| err.into_vm_error())
+}
+
+/// Serializes the compilation result and stores it in the on-disk cache.
+/// Failing to store the record is not fatal, the module will be compiled again next time.
+fn store_to_cache(
+    key: &[u8],
+    result: &Result<wasmer_runtime::Module, VMError>,
+    cache: &dyn CompiledContractCache,
+) {
+    let record = match result {
+        Ok(module) => match module.cache().and_then(|artifact: Artifact| artifact.serialize()) {
+            Ok(code) => CacheRecord::Code(code),
+            Err(_) => return,
+        },
+        Err(err) => CacheRecord::Error(err.clone()),
+    };
+    let serialized = record.try_to_vec().expect("Borsh serializer is not expected to ever fail");
+    let _ = cache.put(key, &serialized);
+}
+
+/// Restores the compilation result from a serialized record.
+/// Returns `None` if the record cannot be used, e.g. it was produced by another Wasmer version.
+fn load_from_cache(serialized: &[u8]) -> Option<Result<wasmer_runtime::Module, VMError>> {
+    let code = match CacheRecord::try_from_slice(serialized).ok()? {
+        CacheRecord::Error(err) => return Some(Err(err)),
+        CacheRecord::Code(code) => code,
+    };
+    // `Artifact::deserialize` checks the Wasmer version hash embedded into the artifact.
+    let artifact = Artifact::deserialize(&code).ok()?;
+    let compiler = wasmer_runtime::compiler_for_backend(wasmer_runtime::Backend::Singlepass)?;
+    // The artifact was produced by `Module::cache` of the same Wasmer version and backend.
+    unsafe { wasmer_runtime_core::load_cache_with(artifact, compiler.as_ref()).ok().map(Ok) }
+}
+
+/// Looks the module up in the on-disk cache and compiles it on a miss.
+fn compile_module_with_cache(
+    code_hash: &[u8],
+    code: &[u8],
+    config: &VMConfig,
+    cache: Option<&dyn CompiledContractCache>,
+) -> Result<wasmer_runtime::Module, VMError> {
+    let cache = match cache {
+        Some(cache) => cache,
+        None => return compile_module(code, config),
+    };
+    let key = get_key(code_hash, config);
+    if let Ok(Some(serialized)) = cache.get(&key) {
+        if let Some(result) = load_from_cache(&serialized) {
+            return result;
+        }
+    }
+    let result = compile_module(code, config);
+    store_to_cache(&key, &result, cache);
+    result
+}
+
+/// Compiles the contract and puts it into the on-disk cache, unless it is already there.
+pub(crate) fn precompile(
+    code_hash: &[u8],
+    code: &[u8],
+    config: &VMConfig,
+    cache: &dyn CompiledContractCache,
+) -> Option<VMError> {
+    let key = get_key(code_hash, config);
+    if let Ok(Some(_)) = cache.get(&key) {
+        return None;
+    }
+    let result = compile_module(code, config);
+    store_to_cache(&key, &result, cache);
+    result.err()
+}
 
 #[cfg(not(feature = "no_cache"))]
 cached_key! {
     MODULES: SizedCache<(Vec<u8>, u64), Result<wasmer_runtime::Module, VMError>>
         = SizedCache::with_size(CACHE_SIZE);
     Key = {
-        (code_hash, config.non_crypto_hash())
+        (code_hash.clone(), config.non_crypto_hash())
     };
 
-    fn compile_module(code_hash: Vec<u8>, code: &[u8], config: &VMConfig
-        ) -> Result<wasmer_runtime::Module, VMError> = {
-        let prepared_code = prepare::prepare_contract(code, config)?;
-        wasmer_runtime::compile(&prepared_code).map_err(|err| err.into_vm_error())
+    fn compile_module_cached(code_hash: Vec<u8>, code: &[u8], config: &VMConfig,
+        cache: Option<&dyn CompiledContractCache>) -> Result<wasmer_runtime::Module, VMError> = {
+        compile_module_with_cache(&code_hash, code, config, cache)
     }
 }
 
 #[cfg(feature = "no_cache")]
-pub(crate) fn compile_module(
-    _code_hash: Vec<u8>,
+pub(crate) fn compile_module_cached(
+    code_hash: Vec<u8>,
     code: &[u8],
     config: &VMConfig,
+    cache: Option<&dyn CompiledContractCache>,
 ) -> Result<wasmer_runtime::Module, VMError> {
-    let prepared_code = prepare::prepare_contract(code, config)?;
-    wasmer_runtime::compile(&prepared_code).map_err(|err| err.into_vm_error())
+    compile_module_with_cache(&code_hash, code, config, cache)
 }
@@ -1,3 +1,6 @@
+use near_primitives::version::{
+    ProtocolVersion, ALT_BN128_PROTOCOL_VERSION, CRYPTO_HOST_FUNCTIONS_PROTOCOL_VERSION,
+};
 use near_vm_logic::VMLogic;
 
 use std::ffi::c_void;
@@ -23,8 +26,28 @@ macro_rules! rust2wasm {
     };
 }
 
+/// Calls the host function on `VMLogic`, recording the call in the host calls trace when it is
+/// enabled.
+#[cfg(feature = "host_calls_tracing")]
+macro_rules! call_host_function {
+    ( $logic:ident, $func:ident $( , $arg_name:ident = $arg:expr )* ) => {
+        $logic.traced(
+            stringify!($func),
+            &[ $( (stringify!($arg_name), $arg as u64) ),* ],
+            |logic| logic.$func( $( $arg, )* ),
+        )
+    };
+}
+
+#[cfg(not(feature = "host_calls_tracing"))]
+macro_rules! call_host_function {
+    ( $logic:ident, $func:ident $( , $arg_name:ident = $arg:expr )* ) => {
+        $logic.$func( $( $arg, )* )
+    };
+}
+
 macro_rules! wrapped_imports {
-        ( $( $func:ident < [ $( $arg_name:ident : $arg_type:ident ),* ] -> [ $( $returns:ident ),* ] >, )* ) => {
+        ( $( $func:ident < [ $( $arg_name:ident : $arg_type:ident ),* ] -> [ $( $returns:ident ),* ] > $( @ $min_protocol_version:ident )?, )* ) => {
             pub mod wasmer_ext {
             use near_vm_logic::VMLogic;
             use wasmer_runtime::Ctx;
@@ -33,7 +56,7 @@ macro_rules! wrapped_imports {
                 #[allow(unused_parens)]
                 pub fn $func( ctx: &mut Ctx, $( $arg_name: $arg_type ),* ) -> VMResult<($( $returns ),*)> {
                     let logic: &mut VMLogic<'_> = unsafe { &mut *(ctx.data as *mut VMLogic<'_>) };
-                    logic.$func( $( $arg_name, )* )
+                    call_host_function!(logic, $func $( , $arg_name = $arg_name )* )
                 }
             )*
             }
@@ -61,7 +84,7 @@ macro_rules! wrapped_imports {
                         }
                     });
                     let logic: &mut VMLogic<'_> = unsafe { &mut *(data as *mut VMLogic<'_>) };
-                    match logic.$func( $( $arg_name as $arg_type, )* ) {
+                    match call_host_function!(logic, $func $( , $arg_name = $arg_name as $arg_type )* ) {
                         Ok(result) => Ok(result as ($( rust2wasm!($returns) ),* ) ),
                         Err(err) => {
                             // Wasmtime doesn't have proper mechanism for wrapping custom errors
@@ -76,22 +99,28 @@ macro_rules! wrapped_imports {
             )*
             }
 
-            pub(crate) fn build_wasmer(memory: wasmer_runtime::memory::Memory, logic: &mut VMLogic<'_>) ->
-                wasmer_runtime::ImportObject {
+            /// Functions annotated with `@ MIN_PROTOCOL_VERSION` are only imported starting from
+            /// that protocol version. Contracts importing them in earlier versions fail to link.
+            pub(crate) fn build_wasmer(
+                memory: wasmer_runtime::memory::Memory,
+                logic: &mut VMLogic<'_>,
+                protocol_version: ProtocolVersion,
+            ) -> wasmer_runtime::ImportObject {
                 let raw_ptr = logic as *mut _ as *mut c_void;
                 let import_reference = ImportReference(raw_ptr);
-                wasmer_runtime::imports! {
-                    move || {
-                        let dtor = (|_: *mut c_void| {}) as fn(*mut c_void);
-                        (import_reference.0, dtor)
-                    },
-                    "env" => {
-                        "memory" => memory,
-                        $(
-                            stringify!($func) => wasmer_runtime::func!(wasmer_ext::$func),
-                        )*
-                    },
-                }
+                let mut import_object = wasmer_runtime::ImportObject::new_with_data(move || {
+                    let dtor = (|_: *mut c_void| {}) as fn(*mut c_void);
+                    (import_reference.0, dtor)
+                });
+                let mut namespace = wasmer_runtime_core::import::Namespace::new();
+                namespace.insert("memory", memory);
+                $(
+                    if true $( && protocol_version >= $min_protocol_version )? {
+                        namespace.insert(stringify!($func), wasmer_runtime::func!(wasmer_ext::$func));
+                    }
+                )*
+                import_object.register("env", namespace);
+                import_object
             }
 
             #[cfg(feature = "wasmtime_vm")]
@@ -99,6 +128,7 @@ macro_rules! wrapped_imports {
                     linker: &mut wasmtime::Linker,
                     memory: wasmtime::Memory,
                     raw_logic: *mut c_void,
+                    protocol_version: ProtocolVersion,
              ) {
                 wasmtime_ext::CALLER_CONTEXT.with(|caller_context| {
                     unsafe {
@@ -108,8 +138,10 @@ macro_rules! wrapped_imports {
                 linker.define("env", "memory", memory).
                     expect("cannot define memory");
                 $(
-                   linker.func("env", stringify!($func), wasmtime_ext::$func).
-                    expect("cannot link external");
+                    if true $( && protocol_version >= $min_protocol_version )? {
+                        linker.func("env", stringify!($func), wasmtime_ext::$func).
+                            expect("cannot link external");
+                    }
                   )*
             }
 
@@ -157,6 +189,39 @@ wrapped_imports! {
     sha256<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
     keccak256<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
     keccak512<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
+    ripemd160<[value_len: u64, value_ptr: u64, register_id: u64] -> []>
+        @ CRYPTO_HOST_FUNCTIONS_PROTOCOL_VERSION,
+    blake2b<[value_len: u64, value_ptr: u64, register_id: u64] -> []>
+        @ CRYPTO_HOST_FUNCTIONS_PROTOCOL_VERSION,
+    ed25519_verify<[
+        signature_len: u64,
+        signature_ptr: u64,
+        message_len: u64,
+        message_ptr: u64,
+        public_key_len: u64,
+        public_key_ptr: u64
+    ] -> [u64]> @ CRYPTO_HOST_FUNCTIONS_PROTOCOL_VERSION,
+    secp256k1_verify<[
+        signature_len: u64,
+        signature_ptr: u64,
+        hash_len: u64,
+        hash_ptr: u64,
+        public_key_len: u64,
+        public_key_ptr: u64
+    ] -> [u64]> @ CRYPTO_HOST_FUNCTIONS_PROTOCOL_VERSION,
+    ecrecover<[
+        hash_len: u64,
+        hash_ptr: u64,
+        signature_len: u64,
+        signature_ptr: u64,
+        register_id: u64
+    ] -> [u64]> @ CRYPTO_HOST_FUNCTIONS_PROTOCOL_VERSION,
+    alt_bn128_g1_multiexp<[value_len: u64, value_ptr: u64, register_id: u64] -> []>
+        @ ALT_BN128_PROTOCOL_VERSION,
+    alt_bn128_g1_sum<[value_len: u64, value_ptr: u64, register_id: u64] -> []>
+        @ ALT_BN128_PROTOCOL_VERSION,
+    alt_bn128_pairing_check<[value_len: u64, value_ptr: u64] -> [u64]>
+        @ ALT_BN128_PROTOCOL_VERSION,
     // #####################
     // # Miscellaneous API #
     // #####################
@@ -9,6 +9,7 @@ mod wasmer_runner;
 mod wasmtime_runner;
 pub use near_vm_errors::VMError;
 pub use runner::compile_module;
+pub use runner::precompile;
 pub use runner::run;
 pub use runner::run_vm;
 pub use runner::with_vm_variants;
@@ -1,3 +1,5 @@
+use near_primitives::types::CompiledContractCache;
+use near_primitives::version::ProtocolVersion;
 use near_runtime_fees::RuntimeFeesConfig;
 use near_vm_errors::VMError;
 use near_vm_logic::types::PromiseResult;
@@ -7,7 +9,8 @@ use near_vm_logic::{External, VMConfig, VMContext, VMKind, VMOutcome};
 /// - deserializes and validate the `code` binary (see `prepare::prepare_contract`)
 /// - injects gas counting into
 /// - adds fee to VMLogic's GasCounter for size of contract
-/// - instantiates (links) `VMLogic` externs with the imports of the binary
+/// - instantiates (links) `VMLogic` externs with the imports of the binary, only host functions
+///   available in `current_protocol_version` can be imported
 /// - calls the `method_name` with `context.input`
 ///   - updates `ext` with new receipts, created during the execution
 ///   - counts burnt and used gas
@@ -15,6 +18,9 @@ use near_vm_logic::{External, VMConfig, VMContext, VMKind, VMOutcome};
 ///   - collects logs
 ///   - sets the return data
 ///  returns result as `VMOutcome`
+///
+/// If `cache` is given, compiled modules are looked up in it before compiling and stored in it
+/// after compiling.
 pub fn run<'a>(
     code_hash: Vec<u8>,
     code: &[u8],
@@ -24,6 +30,8 @@ pub fn run<'a>(
     wasm_config: &'a VMConfig,
     fees_config: &'a RuntimeFeesConfig,
     promise_results: &'a [PromiseResult],
+    current_protocol_version: ProtocolVersion,
+    cache: Option<&'a dyn CompiledContractCache>,
 ) -> (Option<VMOutcome>, Option<VMError>) {
     run_vm(
         code_hash,
@@ -35,6 +43,8 @@ pub fn run<'a>(
         fees_config,
         promise_results,
         VMKind::default(),
+        current_protocol_version,
+        cache,
     )
 }
 pub fn run_vm<'a>(
@@ -47,6 +57,8 @@ pub fn run_vm<'a>(
     fees_config: &'a RuntimeFeesConfig,
     promise_results: &'a [PromiseResult],
     vm_kind: VMKind,
+    current_protocol_version: ProtocolVersion,
+    cache: Option<&'a dyn CompiledContractCache>,
 ) -> (Option<VMOutcome>, Option<VMError>) {
     use crate::wasmer_runner::run_wasmer;
     #[cfg(feature = "wasmtime_vm")]
@@ -61,6 +73,8 @@ pub fn run_vm<'a>(
             wasm_config,
             fees_config,
             promise_results,
+            current_protocol_version,
+            cache,
         ),
         #[cfg(feature = "wasmtime_vm")]
         VMKind::Wasmtime => run_wasmtime(
@@ -72,6 +86,8 @@ pub fn run_vm<'a>(
             wasm_config,
             fees_config,
             promise_results,
+            current_protocol_version,
+            cache,
         ),
         #[cfg(not(feature = "wasmtime_vm"))]
         VMKind::Wasmtime => {
@@ -80,6 +96,22 @@ pub fn run_vm<'a>(
     }
 }
 
+/// Compiles the contract ahead of its first call and stores the result in `cache`.
+/// Only Wasmer modules can be cached, for other VMs this is a no-op.
+/// Returns the compilation error, if any.
+pub fn precompile(
+    code_hash: &[u8],
+    code: &[u8],
+    wasm_config: &VMConfig,
+    cache: &dyn CompiledContractCache,
+    vm_kind: VMKind,
+) -> Option<VMError> {
+    match vm_kind {
+        VMKind::Wasmer => crate::cache::precompile(code_hash, code, wasm_config, cache),
+        VMKind::Wasmtime => None,
+    }
+}
+
 pub fn with_vm_variants(runner: fn(VMKind) -> ()) {
     runner(VMKind::Wasmer);
     #[cfg(feature = "wasmtime_vm")]
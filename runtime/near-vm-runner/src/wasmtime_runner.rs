@@ -5,6 +5,8 @@ use wasmtime::{Engine, Module};
 pub mod wasmtime_runner {
     use crate::errors::IntoVMError;
     use crate::{imports, prepare};
+    use near_primitives::types::CompiledContractCache;
+    use near_primitives::version::ProtocolVersion;
     use near_runtime_fees::RuntimeFeesConfig;
     use near_vm_errors::FunctionCallError::{LinkError, WasmUnknownError};
     use near_vm_errors::{FunctionCallError, MethodResolveError, VMError, VMLogicError};
@@ -114,6 +116,8 @@ pub mod wasmtime_runner {
         wasm_config: &'a VMConfig,
         fees_config: &'a RuntimeFeesConfig,
         promise_results: &'a [PromiseResult],
+        current_protocol_version: ProtocolVersion,
+        _cache: Option<&'a dyn CompiledContractCache>,
     ) -> (Option<VMOutcome>, Option<VMError>) {
         let engine = Engine::default();
         let store = Store::new(&engine);
@@ -144,7 +148,7 @@ pub mod wasmtime_runner {
         // Unfortunately, due to the Wasmtime implementation we have to do tricks with the
         // lifetimes of the logic instance and pass raw pointers here.
         let raw_logic = &mut logic as *mut _ as *mut c_void;
-        imports::link_wasmtime(&mut linker, memory_copy, raw_logic);
+        imports::link_wasmtime(&mut linker, memory_copy, raw_logic, current_protocol_version);
         let func_name = match str::from_utf8(method_name) {
             Ok(name) => name,
             Err(_) => {
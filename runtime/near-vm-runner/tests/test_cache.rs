@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::Mutex;
+
+use near_primitives::types::CompiledContractCache;
+use near_primitives::version::PROTOCOL_VERSION;
+use near_runtime_fees::RuntimeFeesConfig;
+use near_vm_errors::{CompilationError, FunctionCallError, PrepareError};
+use near_vm_logic::mocks::mock_external::MockedExternal;
+use near_vm_logic::{VMConfig, VMKind};
+use near_vm_runner::{precompile, run_vm, VMError};
+
+pub mod test_utils;
+
+use self::test_utils::create_context;
+
+#[derive(Default)]
+struct MockCompiledContractCache {
+    store: Mutex<HashMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl MockCompiledContractCache {
+    fn len(&self) -> usize {
+        self.store.lock().unwrap().len()
+    }
+}
+
+impl CompiledContractCache for MockCompiledContractCache {
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<(), io::Error> {
+        self.store.lock().unwrap().insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, io::Error> {
+        Ok(self.store.lock().unwrap().get(key).cloned())
+    }
+}
+
+fn simple_contract() -> Vec<u8> {
+    wabt::wat2wasm(
+        r#"
+            (module
+              (type (;0;) (func))
+              (func (;0;) (type 0))
+              (export "hello" (func 0))
+            )"#,
+    )
+    .unwrap()
+}
+
+fn call_with_cache(
+    code_hash: &[u8],
+    code: &[u8],
+    config: &VMConfig,
+    cache: &dyn CompiledContractCache,
+) -> Option<VMError> {
+    let mut fake_external = MockedExternal::new();
+    let fees = RuntimeFeesConfig::default();
+    run_vm(
+        code_hash.to_vec(),
+        code,
+        b"hello",
+        &mut fake_external,
+        create_context(vec![]),
+        config,
+        &fees,
+        &[],
+        VMKind::Wasmer,
+        PROTOCOL_VERSION,
+        Some(cache),
+    )
+    .1
+}
+
+#[test]
+fn test_cache_is_filled_on_call() {
+    let cache = MockCompiledContractCache::default();
+    let config = VMConfig::default();
+    let code = simple_contract();
+    assert_eq!(call_with_cache(b"cache_fill", &code, &config, &cache), None);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(call_with_cache(b"cache_fill", &code, &config, &cache), None);
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_precompile_fills_cache() {
+    let cache = MockCompiledContractCache::default();
+    let config = VMConfig::default();
+    let code = simple_contract();
+    assert_eq!(precompile(b"precompile", &code, &config, &cache, VMKind::Wasmer), None);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(precompile(b"precompile", &code, &config, &cache, VMKind::Wasmer), None);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(call_with_cache(b"precompile", &code, &config, &cache), None);
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_cache_key_depends_on_config() {
+    let cache = MockCompiledContractCache::default();
+    let code = simple_contract();
+    let config = VMConfig::default();
+    let mut other_config = VMConfig::default();
+    other_config.regular_op_cost += 1;
+    assert_eq!(call_with_cache(b"config", &code, &config, &cache), None);
+    assert_eq!(call_with_cache(b"config", &code, &other_config, &cache), None);
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn test_compilation_error_is_cached() {
+    let cache = MockCompiledContractCache::default();
+    let config = VMConfig::default();
+    let expected = Some(VMError::FunctionCallError(FunctionCallError::CompilationError(
+        CompilationError::PrepareError(PrepareError::Deserialization),
+    )));
+    assert_eq!(precompile(b"invalid", &[1, 2, 3], &config, &cache, VMKind::Wasmer), expected);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(call_with_cache(b"invalid", &[1, 2, 3], &config, &cache), expected);
+}
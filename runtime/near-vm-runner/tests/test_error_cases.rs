@@ -1,10 +1,17 @@
+use assert_matches::assert_matches;
+use near_primitives::version::{
+    ALT_BN128_PROTOCOL_VERSION, CRYPTO_HOST_FUNCTIONS_PROTOCOL_VERSION,
+};
 use near_vm_errors::{CompilationError, FunctionCallError, MethodResolveError, PrepareError};
 use near_vm_logic::{HostError, ReturnData, VMKind, VMOutcome};
 use near_vm_runner::{with_vm_variants, VMError};
 
 pub mod test_utils;
 
-use self::test_utils::{make_simple_contract_call_vm, make_simple_contract_call_with_gas_vm};
+use self::test_utils::{
+    make_simple_contract_call_vm, make_simple_contract_call_with_gas_vm,
+    make_simple_contract_call_with_protocol_version_vm,
+};
 
 fn vm_outcome_with_gas(gas: u64) -> VMOutcome {
     VMOutcome {
@@ -13,6 +20,7 @@ fn vm_outcome_with_gas(gas: u64) -> VMOutcome {
         return_data: ReturnData::None,
         burnt_gas: gas,
         used_gas: gas,
+        profile: None,
         logs: vec![],
     }
 }
@@ -525,3 +533,67 @@ fn test_external_call_error() {
         );
     });
 }
+
+fn crypto_import_contract() -> Vec<u8> {
+    wabt::wat2wasm(
+        r#"
+            (module
+              (import "env" "ripemd160" (func (;0;) (param i64 i64 i64)))
+              (export "hello" (func 1))
+              (func (;1;))
+            )"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_crypto_import_before_protocol_version() {
+    with_vm_variants(|vm_kind: VMKind| {
+        let result = make_simple_contract_call_with_protocol_version_vm(
+            &crypto_import_contract(),
+            b"hello",
+            CRYPTO_HOST_FUNCTIONS_PROTOCOL_VERSION - 1,
+            vm_kind,
+        );
+        assert_matches!(result.1, Some(VMError::FunctionCallError(FunctionCallError::LinkError { .. })));
+        let result = make_simple_contract_call_with_protocol_version_vm(
+            &crypto_import_contract(),
+            b"hello",
+            CRYPTO_HOST_FUNCTIONS_PROTOCOL_VERSION,
+            vm_kind,
+        );
+        assert_eq!(result.1, None);
+    });
+}
+
+fn alt_bn128_import_contract() -> Vec<u8> {
+    wabt::wat2wasm(
+        r#"
+            (module
+              (import "env" "alt_bn128_pairing_check" (func (;0;) (param i64 i64) (result i64)))
+              (export "hello" (func 1))
+              (func (;1;))
+            )"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_alt_bn128_import_before_protocol_version() {
+    with_vm_variants(|vm_kind: VMKind| {
+        let result = make_simple_contract_call_with_protocol_version_vm(
+            &alt_bn128_import_contract(),
+            b"hello",
+            ALT_BN128_PROTOCOL_VERSION - 1,
+            vm_kind,
+        );
+        assert_matches!(result.1, Some(VMError::FunctionCallError(FunctionCallError::LinkError { .. })));
+        let result = make_simple_contract_call_with_protocol_version_vm(
+            &alt_bn128_import_contract(),
+            b"hello",
+            ALT_BN128_PROTOCOL_VERSION,
+            vm_kind,
+        );
+        assert_eq!(result.1, None);
+    });
+}
@@ -1,3 +1,4 @@
+use near_primitives::version::PROTOCOL_VERSION;
 use near_runtime_fees::RuntimeFeesConfig;
 use near_vm_errors::FunctionCallError;
 use near_vm_logic::mocks::mock_external::MockedExternal;
@@ -66,6 +67,8 @@ pub fn test_read_write() {
             &fees,
             &promise_results,
             vm_kind.clone(),
+            PROTOCOL_VERSION,
+            None,
         );
         assert_run_result(result, 0);
 
@@ -80,6 +83,8 @@ pub fn test_read_write() {
             &fees,
             &promise_results,
             vm_kind,
+            PROTOCOL_VERSION,
+            None,
         );
         assert_run_result(result, 20);
     });
@@ -136,6 +141,8 @@ fn run_test_ext(
         &fees,
         &[],
         vm_kind,
+        PROTOCOL_VERSION,
+        None,
     );
 
     if let Some(_) = err {
@@ -235,6 +242,8 @@ pub fn test_out_of_memory() {
         &fees,
         &promise_results,
         VMKind::Wasmer,
+        PROTOCOL_VERSION,
+        None,
     );
     assert_eq!(result.1, Some(VMError::FunctionCallError(FunctionCallError::WasmUnknownError)));
 }
@@ -1,3 +1,4 @@
+use near_primitives::version::PROTOCOL_VERSION;
 use near_runtime_fees::RuntimeFeesConfig;
 use near_vm_errors::FunctionCallError;
 use near_vm_logic::mocks::mock_external::MockedExternal;
@@ -36,6 +37,8 @@ pub fn test_ts_contract() {
             &fees,
             &promise_results,
             vm_kind.clone(),
+            PROTOCOL_VERSION,
+            None,
         );
         assert_eq!(
             result.1,
@@ -56,6 +59,8 @@ pub fn test_ts_contract() {
             &fees,
             &promise_results,
             vm_kind.clone(),
+            PROTOCOL_VERSION,
+            None,
         )
         .0
         .unwrap();
@@ -80,6 +85,8 @@ pub fn test_ts_contract() {
             &fees,
             &promise_results,
             vm_kind,
+            PROTOCOL_VERSION,
+            None,
         );
 
         if let ReturnData::Value(value) = result.0.unwrap().return_data {
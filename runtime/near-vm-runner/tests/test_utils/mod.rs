@@ -3,6 +3,7 @@ use std::hash::{Hash, Hasher};
 
 use wabt::Wat2Wasm;
 
+use near_primitives::version::{ProtocolVersion, PROTOCOL_VERSION};
 use near_runtime_fees::RuntimeFeesConfig;
 use near_vm_logic::mocks::mock_external::MockedExternal;
 use near_vm_logic::{VMConfig, VMContext, VMKind, VMOutcome};
@@ -30,6 +31,7 @@ pub fn create_context(input: Vec<u8>) -> VMContext {
         prepaid_gas: 10_u64.pow(14),
         random_seed: vec![0, 1, 2],
         is_view: false,
+        profile_gas: false,
         output_data_receivers: vec![],
     }
 }
@@ -39,6 +41,25 @@ pub fn make_simple_contract_call_with_gas_vm(
     method_name: &[u8],
     prepaid_gas: u64,
     vm_kind: VMKind,
+) -> (Option<VMOutcome>, Option<VMError>) {
+    make_contract_call_vm(code, method_name, prepaid_gas, PROTOCOL_VERSION, vm_kind)
+}
+
+pub fn make_simple_contract_call_with_protocol_version_vm(
+    code: &[u8],
+    method_name: &[u8],
+    protocol_version: ProtocolVersion,
+    vm_kind: VMKind,
+) -> (Option<VMOutcome>, Option<VMError>) {
+    make_contract_call_vm(code, method_name, 10u64.pow(14), protocol_version, vm_kind)
+}
+
+fn make_contract_call_vm(
+    code: &[u8],
+    method_name: &[u8],
+    prepaid_gas: u64,
+    protocol_version: ProtocolVersion,
+    vm_kind: VMKind,
 ) -> (Option<VMOutcome>, Option<VMError>) {
     let mut fake_external = MockedExternal::new();
     let mut context = create_context(vec![]);
@@ -61,6 +82,8 @@ pub fn make_simple_contract_call_with_gas_vm(
         &fees,
         &promise_results,
         vm_kind,
+        protocol_version,
+        None,
     )
 }
 
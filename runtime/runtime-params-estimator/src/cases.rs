@@ -121,6 +121,20 @@ pub enum Metric {
     keccak256_10kib_10k,
     keccak512_10b_10k,
     keccak512_10kib_10k,
+    ripemd160_10b_10k,
+    ripemd160_10kib_10k,
+    blake2b_10b_10k,
+    blake2b_10kib_10k,
+    ed25519_verify_32b_1k,
+    ed25519_verify_10kib_1k,
+    secp256k1_verify_1k,
+    ecrecover_1k,
+    alt_bn128_g1_multiexp_1_1k,
+    alt_bn128_g1_multiexp_10_1k,
+    alt_bn128_g1_sum_1_1k,
+    alt_bn128_g1_sum_10_1k,
+    alt_bn128_pairing_check_1_10,
+    alt_bn128_pairing_check_10_10,
     storage_write_10b_key_10b_value_1k,
     storage_write_10kib_key_10b_value_1k,
     storage_write_10b_key_10kib_value_1k,
@@ -432,6 +446,20 @@ pub fn run(mut config: Config) -> RuntimeConfig {
     keccak256_10kib_10k => keccak256_10kib_10k,
     keccak512_10b_10k => keccak512_10b_10k,
     keccak512_10kib_10k => keccak512_10kib_10k,
+    ripemd160_10b_10k => ripemd160_10b_10k,
+    ripemd160_10kib_10k => ripemd160_10kib_10k,
+    blake2b_10b_10k => blake2b_10b_10k,
+    blake2b_10kib_10k => blake2b_10kib_10k,
+    ed25519_verify_32b_1k => ed25519_verify_32b_1k,
+    ed25519_verify_10kib_1k => ed25519_verify_10kib_1k,
+    secp256k1_verify_1k => secp256k1_verify_1k,
+    ecrecover_1k => ecrecover_1k,
+    alt_bn128_g1_multiexp_1_1k => alt_bn128_g1_multiexp_1_1k,
+    alt_bn128_g1_multiexp_10_1k => alt_bn128_g1_multiexp_10_1k,
+    alt_bn128_g1_sum_1_1k => alt_bn128_g1_sum_1_1k,
+    alt_bn128_g1_sum_10_1k => alt_bn128_g1_sum_10_1k,
+    alt_bn128_pairing_check_1_10 => alt_bn128_pairing_check_1_10,
+    alt_bn128_pairing_check_10_10 => alt_bn128_pairing_check_10_10,
     storage_write_10b_key_10b_value_1k => storage_write_10b_key_10b_value_1k,
     storage_read_10b_key_10b_value_1k => storage_read_10b_key_10b_value_1k,
     storage_has_key_10b_key_10b_value_1k => storage_has_key_10b_key_10b_value_1k,
@@ -547,9 +575,11 @@ fn get_runtime_fees_config(measurement: &Measurements) -> RuntimeFeesConfig {
                     metric,
                     measured[&ActionAddFunctionAccessKeyPerByte],
                 ),
+                ..RuntimeFeesConfig::default().action_creation_config.add_key_cost
             },
             delete_key_cost: measured_to_fee(metric, measured[&ActionDeleteKey]),
             delete_account_cost: measured_to_fee(metric, measured[&ActionDeleteAccount]),
+            ..RuntimeFeesConfig::default().action_creation_config
         },
         ..Default::default()
     }
@@ -584,6 +614,32 @@ fn get_ext_costs_config(measurement: &Measurements) -> ExtCostsConfig {
         keccak256_byte: measured_to_gas(metric, &measured, keccak256_byte),
         keccak512_base: measured_to_gas(metric, &measured, keccak512_base),
         keccak512_byte: measured_to_gas(metric, &measured, keccak512_byte),
+        ripemd160_base: measured_to_gas(metric, &measured, ripemd160_base),
+        ripemd160_byte: measured_to_gas(metric, &measured, ripemd160_byte),
+        blake2b_base: measured_to_gas(metric, &measured, blake2b_base),
+        blake2b_byte: measured_to_gas(metric, &measured, blake2b_byte),
+        ed25519_verify_base: measured_to_gas(metric, &measured, ed25519_verify_base),
+        ed25519_verify_byte: measured_to_gas(metric, &measured, ed25519_verify_byte),
+        secp256k1_verify_base: measured_to_gas(metric, &measured, secp256k1_verify_base),
+        ecrecover_base: measured_to_gas(metric, &measured, ecrecover_base),
+        alt_bn128_g1_multiexp_base: measured_to_gas(metric, &measured, alt_bn128_g1_multiexp_base),
+        alt_bn128_g1_multiexp_element: measured_to_gas(
+            metric,
+            &measured,
+            alt_bn128_g1_multiexp_element,
+        ),
+        alt_bn128_g1_sum_base: measured_to_gas(metric, &measured, alt_bn128_g1_sum_base),
+        alt_bn128_g1_sum_element: measured_to_gas(metric, &measured, alt_bn128_g1_sum_element),
+        alt_bn128_pairing_check_base: measured_to_gas(
+            metric,
+            &measured,
+            alt_bn128_pairing_check_base,
+        ),
+        alt_bn128_pairing_check_element: measured_to_gas(
+            metric,
+            &measured,
+            alt_bn128_pairing_check_element,
+        ),
         log_base: measured_to_gas(metric, &measured, log_base),
         log_byte: measured_to_gas(metric, &measured, log_byte),
         storage_write_base: measured_to_gas(metric, &measured, storage_write_base),
@@ -64,6 +64,28 @@ impl ExtCostsGenerator {
         self.extract(keccak512_10b_10k, keccak512_base);
         self.extract(keccak512_10kib_10k, keccak512_byte);
 
+        self.extract(ripemd160_10b_10k, ripemd160_base);
+        self.extract(ripemd160_10kib_10k, ripemd160_byte);
+
+        self.extract(blake2b_10b_10k, blake2b_base);
+        self.extract(blake2b_10kib_10k, blake2b_byte);
+
+        self.extract(ed25519_verify_32b_1k, ed25519_verify_base);
+        self.extract(ed25519_verify_10kib_1k, ed25519_verify_byte);
+
+        self.extract(secp256k1_verify_1k, secp256k1_verify_base);
+
+        self.extract(ecrecover_1k, ecrecover_base);
+
+        self.extract(alt_bn128_g1_multiexp_1_1k, alt_bn128_g1_multiexp_base);
+        self.extract(alt_bn128_g1_multiexp_10_1k, alt_bn128_g1_multiexp_element);
+
+        self.extract(alt_bn128_g1_sum_1_1k, alt_bn128_g1_sum_base);
+        self.extract(alt_bn128_g1_sum_10_1k, alt_bn128_g1_sum_element);
+
+        self.extract(alt_bn128_pairing_check_1_10, alt_bn128_pairing_check_base);
+        self.extract(alt_bn128_pairing_check_10_10, alt_bn128_pairing_check_element);
+
         // TODO: Redo storage costs once we have counting of nodes and we have size peek.
         self.extract(storage_write_10b_key_10b_value_1k, storage_write_base);
         self.extract(storage_write_10kib_key_10b_value_1k, storage_write_key_byte);
@@ -86,6 +86,8 @@ impl RuntimeTestbed {
             gas_limit: None,
             random_seed: Default::default(),
             current_protocol_version: PROTOCOL_VERSION,
+            cache: None,
+            record_gas_profiles: false,
         };
         Self {
             workdir,
@@ -2,6 +2,7 @@ use crate::stats::fit;
 use crate::testbed_runners::end_count;
 use crate::testbed_runners::start_count;
 use crate::testbed_runners::GasMetric;
+use near_primitives::version::PROTOCOL_VERSION;
 use near_runtime_fees::RuntimeFeesConfig;
 use near_vm_logic::mocks::mock_external::MockedExternal;
 use near_vm_logic::{VMConfig, VMContext, VMKind, VMOutcome};
@@ -32,6 +33,7 @@ fn create_context(input: Vec<u8>) -> VMContext {
         prepaid_gas: 10_u64.pow(18),
         random_seed: vec![0, 1, 2],
         is_view: false,
+        profile_gas: false,
         output_data_receivers: vec![],
     }
 }
@@ -57,6 +59,8 @@ fn call() -> (Option<VMOutcome>, Option<VMError>) {
         &config,
         &fees,
         &promise_results,
+        PROTOCOL_VERSION,
+        None,
     )
 }
 
@@ -44,6 +44,34 @@ extern "C" {
     fn sha256(value_len: u64, value_ptr: u64, register_id: u64);
     fn keccak256(value_len: u64, value_ptr: u64, register_id: u64);
     fn keccak512(value_len: u64, value_ptr: u64, register_id: u64);
+    fn ripemd160(value_len: u64, value_ptr: u64, register_id: u64);
+    fn blake2b(value_len: u64, value_ptr: u64, register_id: u64);
+    fn ed25519_verify(
+        signature_len: u64,
+        signature_ptr: u64,
+        message_len: u64,
+        message_ptr: u64,
+        public_key_len: u64,
+        public_key_ptr: u64,
+    ) -> u64;
+    fn secp256k1_verify(
+        signature_len: u64,
+        signature_ptr: u64,
+        hash_len: u64,
+        hash_ptr: u64,
+        public_key_len: u64,
+        public_key_ptr: u64,
+    ) -> u64;
+    fn ecrecover(
+        hash_len: u64,
+        hash_ptr: u64,
+        signature_len: u64,
+        signature_ptr: u64,
+        register_id: u64,
+    ) -> u64;
+    fn alt_bn128_g1_multiexp(value_len: u64, value_ptr: u64, register_id: u64);
+    fn alt_bn128_g1_sum(value_len: u64, value_ptr: u64, register_id: u64);
+    fn alt_bn128_pairing_check(value_len: u64, value_ptr: u64) -> u64;
     // #####################
     // # Miscellaneous API #
     // #####################
@@ -439,6 +467,252 @@ pub unsafe fn keccak512_10kib_10k() {
     }
 }
 
+// Function to measure `ripemd160_base` and `ripemd160_byte`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `ripemd160` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute ripemd160 on 10b 10k times.
+#[no_mangle]
+pub unsafe fn ripemd160_10b_10k() {
+    let buffer = [65u8; 10];
+    for _ in 0..10_000 {
+        ripemd160(buffer.len() as u64, buffer.as_ptr() as *const u64 as u64, 0);
+    }
+}
+// Function to measure `ripemd160_base` and `ripemd160_byte`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `ripemd160` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute ripemd160 on 10kib 10k times.
+#[no_mangle]
+pub unsafe fn ripemd160_10kib_10k() {
+    let buffer = [65u8; 10240];
+    for _ in 0..10_000 {
+        ripemd160(buffer.len() as u64, buffer.as_ptr() as *const u64 as u64, 0);
+    }
+}
+
+// Function to measure `blake2b_base` and `blake2b_byte`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `blake2b` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute blake2b on 10b 10k times.
+#[no_mangle]
+pub unsafe fn blake2b_10b_10k() {
+    let buffer = [65u8; 10];
+    for _ in 0..10_000 {
+        blake2b(buffer.len() as u64, buffer.as_ptr() as *const u64 as u64, 0);
+    }
+}
+// Function to measure `blake2b_base` and `blake2b_byte`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `blake2b` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute blake2b on 10kib 10k times.
+#[no_mangle]
+pub unsafe fn blake2b_10kib_10k() {
+    let buffer = [65u8; 10240];
+    for _ in 0..10_000 {
+        blake2b(buffer.len() as u64, buffer.as_ptr() as *const u64 as u64, 0);
+    }
+}
+
+// The signatures below don't match the public keys. The verification still does all the work as
+// the keys and signatures are well formed, so it costs the same as for a valid signature.
+// Encoded ed25519 base point.
+const ED25519_PUBLIC_KEY: [u8; 32] = [
+    88, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102,
+    102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102,
+];
+const ED25519_SIGNATURE: [u8; 64] = [
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 0,
+];
+// secp256k1 generator point.
+const SECP256K1_PUBLIC_KEY: [u8; 64] = [
+    121, 190, 102, 126, 249, 220, 187, 172, 85, 160, 98, 149, 206, 135, 11, 7, 2, 155, 252, 219,
+    45, 206, 40, 217, 89, 242, 129, 91, 22, 248, 23, 152, 72, 58, 218, 119, 38, 163, 196, 101, 93,
+    164, 251, 252, 14, 17, 8, 168, 253, 23, 180, 72, 166, 133, 84, 25, 156, 71, 208, 143, 251, 16,
+    212, 184,
+];
+// Signature and message hash of the Ethereum ecrecover test vector.
+const SECP256K1_HASH: [u8; 32] = [
+    69, 110, 154, 234, 94, 25, 122, 31, 26, 247, 163, 232, 90, 50, 18, 250, 64, 73, 163, 186, 52,
+    194, 40, 155, 76, 134, 15, 192, 176, 198, 78, 243,
+];
+const SECP256K1_SIGNATURE: [u8; 65] = [
+    146, 66, 104, 91, 241, 97, 121, 60, 194, 86, 3, 194, 49, 188, 47, 86, 142, 182, 48, 234, 22,
+    170, 19, 125, 38, 100, 172, 128, 56, 130, 86, 8, 79, 138, 227, 189, 117, 53, 36, 141, 11, 212,
+    72, 41, 140, 194, 226, 7, 30, 86, 153, 45, 7, 116, 220, 52, 12, 54, 138, 233, 80, 133, 42, 218,
+    1,
+];
+
+// Function to measure `ed25519_verify_base` and `ed25519_verify_byte`.
+// Verify ed25519 signature of 32b 1k times.
+#[no_mangle]
+pub unsafe fn ed25519_verify_32b_1k() {
+    let buffer = [65u8; 32];
+    for _ in 0..1_000 {
+        ed25519_verify(
+            ED25519_SIGNATURE.len() as u64,
+            ED25519_SIGNATURE.as_ptr() as *const u64 as u64,
+            buffer.len() as u64,
+            buffer.as_ptr() as *const u64 as u64,
+            ED25519_PUBLIC_KEY.len() as u64,
+            ED25519_PUBLIC_KEY.as_ptr() as *const u64 as u64,
+        );
+    }
+}
+// Function to measure `ed25519_verify_base` and `ed25519_verify_byte`.
+// Verify ed25519 signature of 10kib 1k times.
+#[no_mangle]
+pub unsafe fn ed25519_verify_10kib_1k() {
+    let buffer = [65u8; 10240];
+    for _ in 0..1_000 {
+        ed25519_verify(
+            ED25519_SIGNATURE.len() as u64,
+            ED25519_SIGNATURE.as_ptr() as *const u64 as u64,
+            buffer.len() as u64,
+            buffer.as_ptr() as *const u64 as u64,
+            ED25519_PUBLIC_KEY.len() as u64,
+            ED25519_PUBLIC_KEY.as_ptr() as *const u64 as u64,
+        );
+    }
+}
+
+// Function to measure `secp256k1_verify_base`.
+// Verify secp256k1 signature 1k times.
+#[no_mangle]
+pub unsafe fn secp256k1_verify_1k() {
+    for _ in 0..1_000 {
+        secp256k1_verify(
+            SECP256K1_SIGNATURE.len() as u64,
+            SECP256K1_SIGNATURE.as_ptr() as *const u64 as u64,
+            SECP256K1_HASH.len() as u64,
+            SECP256K1_HASH.as_ptr() as *const u64 as u64,
+            SECP256K1_PUBLIC_KEY.len() as u64,
+            SECP256K1_PUBLIC_KEY.as_ptr() as *const u64 as u64,
+        );
+    }
+}
+
+// Function to measure `ecrecover_base`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `ecrecover` computation is more expensive than register
+// writing so we are okay overcharging it.
+// Recover secp256k1 public key 1k times.
+#[no_mangle]
+pub unsafe fn ecrecover_1k() {
+    for _ in 0..1_000 {
+        ecrecover(
+            SECP256K1_HASH.len() as u64,
+            SECP256K1_HASH.as_ptr() as *const u64 as u64,
+            SECP256K1_SIGNATURE.len() as u64,
+            SECP256K1_SIGNATURE.as_ptr() as *const u64 as u64,
+            0,
+        );
+    }
+}
+
+// Each element is a G1 generator point `(1, 2)` followed by a scalar.
+const ALT_BN128_G1_MULTIEXP_ELEMENT: [u8; 96] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2,
+    7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+];
+// Each element is a sign byte followed by the G1 generator point `(1, 2)`.
+const ALT_BN128_G1_SUM_ELEMENT: [u8; 65] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    2,
+];
+// Each element is the G1 generator point `(1, 2)` followed by the G2 generator point encoded as
+// `x_im || x_re || y_im || y_re`.
+const ALT_BN128_PAIRING_ELEMENT: [u8; 192] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2,
+    25, 142, 147, 147, 146, 13, 72, 58, 114, 96, 191, 183, 49, 251, 93, 37, 241, 170, 73, 51, 53,
+    169, 231, 18, 151, 228, 133, 183, 174, 243, 18, 194, 24, 0, 222, 239, 18, 31, 30, 118, 66, 106,
+    0, 102, 94, 92, 68, 121, 103, 67, 34, 212, 247, 94, 218, 221, 70, 222, 189, 92, 217, 146, 246,
+    237, 9, 6, 137, 208, 88, 95, 240, 117, 236, 158, 153, 173, 105, 12, 51, 149, 188, 75, 49, 51,
+    112, 179, 142, 243, 85, 172, 218, 220, 209, 34, 151, 91, 18, 200, 94, 165, 219, 140, 109, 235,
+    74, 171, 113, 128, 141, 203, 64, 143, 227, 209, 231, 105, 12, 67, 211, 123, 76, 230, 204, 1,
+    102, 250, 125, 170,
+];
+
+// Fills the buffer with copies of the element.
+fn fill_repeated(buffer: &mut [u8], element: &[u8]) {
+    for chunk in buffer.chunks_mut(element.len()) {
+        chunk.copy_from_slice(element);
+    }
+}
+
+// Function to measure `alt_bn128_g1_multiexp_base` and `alt_bn128_g1_multiexp_element`. Also
+// measures `base`, `write_register_base`, and `write_register_byte`. However the multiexp
+// computation is more expensive than register writing so we are okay overcharging it.
+// Compute multiexp of 1 element 1k times.
+#[no_mangle]
+pub unsafe fn alt_bn128_g1_multiexp_1_1k() {
+    let mut buffer = [0u8; 96];
+    fill_repeated(&mut buffer, &ALT_BN128_G1_MULTIEXP_ELEMENT);
+    for _ in 0..1_000 {
+        alt_bn128_g1_multiexp(buffer.len() as u64, buffer.as_ptr() as *const u64 as u64, 0);
+    }
+}
+// Function to measure `alt_bn128_g1_multiexp_base` and `alt_bn128_g1_multiexp_element`. Also
+// measures `base`, `write_register_base`, and `write_register_byte`. However the multiexp
+// computation is more expensive than register writing so we are okay overcharging it.
+// Compute multiexp of 10 elements 1k times.
+#[no_mangle]
+pub unsafe fn alt_bn128_g1_multiexp_10_1k() {
+    let mut buffer = [0u8; 96 * 10];
+    fill_repeated(&mut buffer, &ALT_BN128_G1_MULTIEXP_ELEMENT);
+    for _ in 0..1_000 {
+        alt_bn128_g1_multiexp(buffer.len() as u64, buffer.as_ptr() as *const u64 as u64, 0);
+    }
+}
+
+// Function to measure `alt_bn128_g1_sum_base` and `alt_bn128_g1_sum_element`. Also measures
+// `base`, `write_register_base`, and `write_register_byte`. However the point addition is more
+// expensive than register writing so we are okay overcharging it.
+// Compute sum of 1 point 1k times.
+#[no_mangle]
+pub unsafe fn alt_bn128_g1_sum_1_1k() {
+    let mut buffer = [0u8; 65];
+    fill_repeated(&mut buffer, &ALT_BN128_G1_SUM_ELEMENT);
+    for _ in 0..1_000 {
+        alt_bn128_g1_sum(buffer.len() as u64, buffer.as_ptr() as *const u64 as u64, 0);
+    }
+}
+// Function to measure `alt_bn128_g1_sum_base` and `alt_bn128_g1_sum_element`. Also measures
+// `base`, `write_register_base`, and `write_register_byte`. However the point addition is more
+// expensive than register writing so we are okay overcharging it.
+// Compute sum of 10 points 1k times.
+#[no_mangle]
+pub unsafe fn alt_bn128_g1_sum_10_1k() {
+    let mut buffer = [0u8; 65 * 10];
+    fill_repeated(&mut buffer, &ALT_BN128_G1_SUM_ELEMENT);
+    for _ in 0..1_000 {
+        alt_bn128_g1_sum(buffer.len() as u64, buffer.as_ptr() as *const u64 as u64, 0);
+    }
+}
+
+// Function to measure `alt_bn128_pairing_check_base` and `alt_bn128_pairing_check_element`.
+// Check pairing of 1 pair 10 times.
+#[no_mangle]
+pub unsafe fn alt_bn128_pairing_check_1_10() {
+    let mut buffer = [0u8; 192];
+    fill_repeated(&mut buffer, &ALT_BN128_PAIRING_ELEMENT);
+    for _ in 0..10 {
+        alt_bn128_pairing_check(buffer.len() as u64, buffer.as_ptr() as *const u64 as u64);
+    }
+}
+// Function to measure `alt_bn128_pairing_check_base` and `alt_bn128_pairing_check_element`.
+// Check pairing of 10 pairs 10 times.
+#[no_mangle]
+pub unsafe fn alt_bn128_pairing_check_10_10() {
+    let mut buffer = [0u8; 192 * 10];
+    fill_repeated(&mut buffer, &ALT_BN128_PAIRING_ELEMENT);
+    for _ in 0..10 {
+        alt_bn128_pairing_check(buffer.len() as u64, buffer.as_ptr() as *const u64 as u64);
+    }
+}
+
 // ###############
 // # Storage API #
 // ###############
@@ -2,7 +2,10 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use near_crypto::{InMemorySigner, KeyType, PublicKey, Signer};
-use near_pool::{types::PoolIterator, TransactionPool};
+use near_pool::{
+    types::{PoolIterator, TransactionPoolConfig},
+    TransactionPool,
+};
 use near_primitives::account::{AccessKey, Account};
 use near_primitives::errors::RuntimeError;
 use near_primitives::hash::CryptoHash;
@@ -144,7 +147,7 @@ impl RuntimeStandalone {
             transactions: HashMap::new(),
             outcomes: HashMap::new(),
             cur_block: genesis_block,
-            tx_pool: TransactionPool::new(),
+            tx_pool: TransactionPool::new(TransactionPoolConfig::default()),
             pending_receipts: vec![],
             epoch_info_provider: Box::new(MockEpochInfoProvider::new(
                 validators.into_iter().map(|info| (info.account_id, info.amount)),
@@ -216,6 +219,8 @@ impl RuntimeStandalone {
             last_block_hash: CryptoHash::default(),
             epoch_id: EpochId::default(),
             current_protocol_version: PROTOCOL_VERSION,
+            cache: None,
+            record_gas_profiles: false,
         };
 
         let apply_result = self.runtime.apply(
@@ -299,6 +304,7 @@ impl RuntimeStandalone {
             &CryptoHash::default(),
             self.cur_block.epoch_height,
             &EpochId::default(),
+            PROTOCOL_VERSION,
             account_id,
             method_name,
             args,
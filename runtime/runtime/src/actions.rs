@@ -6,10 +6,10 @@ use log::debug;
 use near_primitives::account::{AccessKeyPermission, Account};
 use near_primitives::contract::ContractCode;
 use near_primitives::hash::CryptoHash;
-use near_primitives::receipt::{ActionReceipt, Receipt};
+use near_primitives::receipt::{ActionReceipt, Receipt, ReceiptEnum};
 use near_primitives::transaction::{
     Action, AddKeyAction, DeleteAccountAction, DeleteKeyAction, DeployContractAction,
-    FunctionCallAction, StakeAction, TransferAction,
+    FunctionCallAction, SignedDelegateAction, StakeAction, TransferAction,
 };
 use near_primitives::types::{AccountId, Balance, EpochInfoProvider, ValidatorStake};
 use near_primitives::utils::{
@@ -21,13 +21,16 @@ use near_store::{
     StorageError, TrieUpdate,
 };
 use near_vm_logic::types::PromiseResult;
-use near_vm_logic::VMContext;
+use near_vm_logic::{VMContext, VMKind};
 
-use crate::config::{safe_add_gas, RuntimeConfig};
+use crate::config::{safe_add_gas, total_exec_fees, total_prepaid_gas, RuntimeConfig};
 use crate::ext::RuntimeExt;
+use crate::verifier::check_function_call_permission;
 use crate::{ActionResult, ApplyState};
 use near_crypto::PublicKey;
-use near_primitives::errors::{ActionError, ActionErrorKind, ExternalError, RuntimeError};
+use near_primitives::errors::{
+    ActionError, ActionErrorKind, ExternalError, InvalidAccessKeyError, RuntimeError,
+};
 use near_primitives::version::CORRECT_RANDOM_VALUE_PROTOCOL_VERSION;
 use near_runtime_configs::AccountCreationConfig;
 use near_vm_errors::{CompilationError, FunctionCallError};
@@ -152,6 +155,7 @@ pub(crate) fn action_function_call(
             apply_state.random_seed.as_ref().to_vec()
         },
         is_view: false,
+        profile_gas: apply_state.record_gas_profiles,
         output_data_receivers,
     };
 
@@ -164,6 +168,8 @@ pub(crate) fn action_function_call(
         &config.wasm_config,
         &config.transaction_costs,
         promise_results,
+        apply_state.current_protocol_version,
+        apply_state.cache.as_deref(),
     );
     let execution_succeeded = match err {
         Some(VMError::FunctionCallError(err)) => {
@@ -193,6 +199,9 @@ pub(crate) fn action_function_call(
         // `FunctionCall`s error.
         result.gas_used = safe_add_gas(result.gas_used, outcome.used_gas)?;
         result.logs.extend(outcome.logs.into_iter());
+        if let Some(profile) = outcome.profile {
+            result.profile.get_or_insert_with(Default::default).merge(&profile);
+        }
         if execution_succeeded {
             account.amount = outcome.balance;
             account.storage_usage = outcome.storage_usage;
@@ -345,6 +354,8 @@ pub(crate) fn action_create_account(
 
 pub(crate) fn action_deploy_contract(
     state_update: &mut TrieUpdate,
+    apply_state: &ApplyState,
+    config: &RuntimeConfig,
     account: &mut Account,
     account_id: &AccountId,
     deploy_contract: &DeployContractAction,
@@ -367,6 +378,16 @@ pub(crate) fn action_deploy_contract(
             ))
         })?;
     account.code_hash = code.get_hash();
+    // Compile the contract ahead of the first call. Compilation errors are reported on the call.
+    if let Some(cache) = apply_state.cache.as_deref() {
+        near_vm_runner::precompile(
+            code.hash.as_ref(),
+            &code.code,
+            &config.wasm_config,
+            cache,
+            VMKind::default(),
+        );
+    }
     set_code(state_update, account_id.clone(), &code);
     Ok(())
 }
@@ -468,6 +489,118 @@ pub(crate) fn action_add_key(
     Ok(())
 }
 
+pub(crate) fn action_delegate(
+    fee_config: &RuntimeFeesConfig,
+    state_update: &mut TrieUpdate,
+    apply_state: &ApplyState,
+    action_receipt: &ActionReceipt,
+    result: &mut ActionResult,
+    account_id: &AccountId,
+    signed_delegate_action: &SignedDelegateAction,
+) -> Result<(), RuntimeError> {
+    let delegate_action = &signed_delegate_action.delegate_action;
+    if &delegate_action.sender_id != account_id {
+        result.result = Err(ActionErrorKind::DelegateActionSenderDoesNotMatchReceiver {
+            sender_id: delegate_action.sender_id.clone(),
+            receiver_id: account_id.clone(),
+        }
+        .into());
+        return Ok(());
+    }
+    if !signed_delegate_action.verify() {
+        result.result = Err(ActionErrorKind::DelegateActionInvalidSignature.into());
+        return Ok(());
+    }
+    if apply_state.block_index > delegate_action.max_block_height {
+        result.result = Err(ActionErrorKind::DelegateActionExpired {
+            max_block_height: delegate_action.max_block_height,
+            block_height: apply_state.block_index,
+        }
+        .into());
+        return Ok(());
+    }
+    let mut access_key =
+        match get_access_key(state_update, account_id, &delegate_action.public_key)? {
+            Some(access_key) => access_key,
+            None => {
+                result.result = Err(ActionErrorKind::DelegateActionAccessKeyError(
+                    InvalidAccessKeyError::AccessKeyNotFound {
+                        account_id: account_id.clone(),
+                        public_key: delegate_action.public_key.clone(),
+                    },
+                )
+                .into());
+                return Ok(());
+            }
+        };
+    if delegate_action.nonce <= access_key.nonce {
+        result.result = Err(ActionErrorKind::DelegateActionInvalidNonce {
+            delegate_nonce: delegate_action.nonce,
+            ak_nonce: access_key.nonce,
+        }
+        .into());
+        return Ok(());
+    }
+    let permission_check = match &access_key.permission {
+        AccessKeyPermission::FullAccess => Ok(()),
+        AccessKeyPermission::FunctionCall(function_call_permission) => {
+            check_function_call_permission(
+                function_call_permission,
+                &delegate_action.receiver_id,
+                &delegate_action.actions,
+            )
+        }
+        // A delegate action carries a single signature, so it can't satisfy a multi-signature key.
+        AccessKeyPermission::MultiSig(multi_sig_permission) => {
+            Err(InvalidAccessKeyError::NotEnoughSignatures {
+                account_id: account_id.clone(),
+                public_key: delegate_action.public_key.clone(),
+                num_signatures: 0,
+                threshold: u64::from(multi_sig_permission.threshold),
+            })
+        }
+    };
+    if let Err(e) = permission_check {
+        result.result = Err(ActionErrorKind::DelegateActionAccessKeyError(e).into());
+        return Ok(());
+    }
+
+    access_key.nonce = delegate_action.nonce;
+    set_access_key(
+        state_update,
+        account_id.clone(),
+        delegate_action.public_key.clone(),
+        &access_key,
+    );
+
+    // The delegated actions are executed on behalf of the sender, while the relayer who signed
+    // the transaction keeps paying for gas and receives the gas refunds.
+    result.new_receipts.push(Receipt {
+        predecessor_id: account_id.clone(),
+        receiver_id: delegate_action.receiver_id.clone(),
+        // Actual receipt ID is set in the Runtime.apply_action_receipt(...) in the
+        // "Generating receipt IDs" section
+        receipt_id: CryptoHash::default(),
+        receipt: ReceiptEnum::Action(ActionReceipt {
+            signer_id: action_receipt.signer_id.clone(),
+            signer_public_key: action_receipt.signer_public_key.clone(),
+            gas_price: action_receipt.gas_price,
+            output_data_receivers: vec![],
+            input_data_ids: vec![],
+            actions: delegate_action.actions.clone(),
+        }),
+    });
+
+    // The gas for the new receipt was prepaid by the relayer, so it's used by this action.
+    let new_receipt_gas = fee_config.action_receipt_creation_config.exec_fee();
+    let new_receipt_gas =
+        safe_add_gas(new_receipt_gas, total_exec_fees(fee_config, &delegate_action.actions)?)?;
+    let new_receipt_gas =
+        safe_add_gas(new_receipt_gas, total_prepaid_gas(&delegate_action.actions)?)?;
+    result.gas_used = safe_add_gas(result.gas_used, new_receipt_gas)?;
+    Ok(())
+}
+
 pub(crate) fn check_actor_permissions(
     action: &Action,
     account: &Option<Account>,
@@ -500,7 +633,10 @@ pub(crate) fn check_actor_permissions(
                 .into());
             }
         }
-        Action::CreateAccount(_) | Action::FunctionCall(_) | Action::Transfer(_) => (),
+        Action::CreateAccount(_)
+        | Action::FunctionCall(_)
+        | Action::Transfer(_)
+        | Action::Delegate(_) => (),
     };
     Ok(())
 }
@@ -525,7 +661,8 @@ pub(crate) fn check_account_existence(
         | Action::Stake(_)
         | Action::AddKey(_)
         | Action::DeleteKey(_)
-        | Action::DeleteAccount(_) => {
+        | Action::DeleteAccount(_)
+        | Action::Delegate(_) => {
             if account.is_none() {
                 return Err(ActionErrorKind::AccountDoesNotExist {
                     account_id: account_id.clone(),
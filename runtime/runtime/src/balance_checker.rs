@@ -378,6 +378,7 @@ mod tests {
                 gas_deficit_amount: 0,
                 other_burnt_amount: 0,
                 slashed_burnt_amount: 0,
+                gas_profiles: vec![],
             },
         )
         .unwrap();
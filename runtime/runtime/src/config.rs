@@ -108,9 +108,26 @@ pub fn total_send_fees(
                 AccessKeyPermission::FullAccess => {
                     cfg.add_key_cost.full_access_cost.send_fee(sender_is_receiver)
                 }
+                AccessKeyPermission::MultiSig(multi_sig_perm) => {
+                    let num_keys = multi_sig_perm.public_keys.len() as u64;
+                    cfg.add_key_cost.multisig_cost.send_fee(sender_is_receiver)
+                        + num_keys
+                            * cfg.add_key_cost.multisig_cost_per_key.send_fee(sender_is_receiver)
+                }
             },
             DeleteKey(_) => cfg.delete_key_cost.send_fee(sender_is_receiver),
             DeleteAccount(_) => cfg.delete_account_cost.send_fee(sender_is_receiver),
+            Delegate(signed_delegate_action) => {
+                // The delegated actions are sent in a new receipt, so the relayer also pays
+                // for creating that receipt and for sending the delegated actions.
+                let delegate_action = &signed_delegate_action.delegate_action;
+                let inner_sir = delegate_action.sender_id == delegate_action.receiver_id;
+                safe_add_gas(
+                    cfg.delegate_cost.send_fee(sender_is_receiver)
+                        + config.action_receipt_creation_config.send_fee(inner_sir),
+                    total_send_fees(config, inner_sir, &delegate_action.actions)?,
+                )?
+            }
         };
         result = safe_add_gas(result, delta)?;
     }
@@ -146,15 +163,24 @@ pub fn exec_fee(config: &RuntimeFeesConfig, action: &Action) -> Gas {
                     + num_bytes * cfg.add_key_cost.function_call_cost_per_byte.exec_fee()
             }
             AccessKeyPermission::FullAccess => cfg.add_key_cost.full_access_cost.exec_fee(),
+            AccessKeyPermission::MultiSig(multi_sig_perm) => {
+                let num_keys = multi_sig_perm.public_keys.len() as u64;
+                cfg.add_key_cost.multisig_cost.exec_fee()
+                    + num_keys * cfg.add_key_cost.multisig_cost_per_key.exec_fee()
+            }
         },
         DeleteKey(_) => cfg.delete_key_cost.exec_fee(),
         DeleteAccount(_) => cfg.delete_account_cost.exec_fee(),
+        Delegate(_) => cfg.delegate_cost.exec_fee(),
     }
 }
 /// Returns transaction costs for a given transaction.
+/// `num_multisig_signatures` is the number of signatures of a transaction signed with
+/// a multi-signature access key, or 0 otherwise.
 pub fn tx_cost(
     config: &RuntimeFeesConfig,
     transaction: &Transaction,
+    num_multisig_signatures: u64,
     gas_price: Balance,
     sender_is_receiver: bool,
 ) -> Result<TransactionCost, IntegerOverflowError> {
@@ -163,6 +189,14 @@ pub fn tx_cost(
         gas_burnt,
         total_send_fees(&config, sender_is_receiver, &transaction.actions)?,
     )?;
+    gas_burnt = safe_add_gas(
+        gas_burnt,
+        config
+            .multisig_signature_cost
+            .send_fee(sender_is_receiver)
+            .checked_mul(num_multisig_signatures)
+            .ok_or_else(|| IntegerOverflowError {})?,
+    )?;
     let prepaid_gas = total_prepaid_gas(&transaction.actions)?;
     // If signer is equals to receiver the receipt will be processed at the same block as this
     // transaction. Otherwise it will processed in the next block and the gas might be inflated.
@@ -194,6 +228,8 @@ pub fn tx_cost(
 }
 
 /// Total sum of gas that would need to be burnt before we start executing the given actions.
+/// For delegate actions this includes the execution fees of the receipt with the delegated
+/// actions, since it is paid for upfront by the relayer.
 pub fn total_exec_fees(
     config: &RuntimeFeesConfig,
     actions: &[Action],
@@ -202,6 +238,13 @@ pub fn total_exec_fees(
     for action in actions {
         let delta = exec_fee(&config, action);
         result = safe_add_gas(result, delta)?;
+        if let Action::Delegate(signed_delegate_action) = action {
+            result = safe_add_gas(result, config.action_receipt_creation_config.exec_fee())?;
+            result = safe_add_gas(
+                result,
+                total_exec_fees(config, &signed_delegate_action.delegate_action.actions)?,
+            )?;
+        }
     }
     Ok(result)
 }
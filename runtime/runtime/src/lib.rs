@@ -1,5 +1,5 @@
 use std::cmp::max;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use log::debug;
@@ -12,12 +12,14 @@ use near_primitives::hash::CryptoHash;
 use near_primitives::receipt::{ActionReceipt, DataReceipt, Receipt, ReceiptEnum, ReceivedData};
 use near_primitives::state_record::StateRecord;
 use near_primitives::transaction::{
-    Action, ExecutionOutcome, ExecutionOutcomeWithId, ExecutionStatus, LogEntry, SignedTransaction,
+    Action, ExecutionOutcome, ExecutionOutcomeWithId, ExecutionStatus, GasProfile, LogEntry,
+    SignedTransaction,
 };
 use near_primitives::trie_key::TrieKey;
 use near_primitives::types::{
-    AccountId, Balance, BlockHeight, EpochHeight, EpochId, EpochInfoProvider, Gas, MerkleHash,
-    Nonce, RawStateChangesWithTrieKey, ShardId, StateChangeCause, StateRoot, ValidatorStake,
+    AccountId, Balance, BlockHeight, CompiledContractCache, EpochHeight, EpochId,
+    EpochInfoProvider, Gas, MerkleHash, Nonce, RawStateChangesWithTrieKey, ShardId,
+    StateChangeCause, StateRoot, ValidatorStake,
 };
 use near_primitives::utils::{create_nonce_with_nonce, system_account};
 use near_store::{
@@ -26,7 +28,7 @@ use near_store::{
     PartialStorage, ShardTries, StorageError, StoreUpdate, Trie, TrieChanges, TrieUpdate,
 };
 use near_vm_logic::types::PromiseResult;
-use near_vm_logic::ReturnData;
+use near_vm_logic::{ProfileData, ReturnData};
 #[cfg(feature = "costs_counting")]
 pub use near_vm_runner::EXT_COSTS_COUNTER;
 
@@ -37,9 +39,12 @@ use crate::config::{
     total_prepaid_gas, RuntimeConfig,
 };
 use crate::verifier::validate_receipt;
-pub use crate::verifier::{validate_transaction, verify_and_charge_transaction};
+pub use crate::verifier::{
+    charge_unsigned_transaction, validate_transaction, verify_and_charge_transaction,
+};
 use near_primitives::version::ProtocolVersion;
 use std::rc::Rc;
+use std::sync::Arc;
 
 mod actions;
 pub mod adapter;
@@ -75,6 +80,10 @@ pub struct ApplyState {
     pub random_seed: CryptoHash,
     /// Current Protocol version when we apply the state transition
     pub current_protocol_version: ProtocolVersion,
+    /// Cache for compiled contracts.
+    pub cache: Option<Arc<dyn CompiledContractCache>>,
+    /// Whether to record the breakdown of the burnt gas of the execution outcomes.
+    pub record_gas_profiles: bool,
 }
 
 /// Contains information to update validators accounts at the first block of a new epoch.
@@ -112,6 +121,9 @@ pub struct ApplyStats {
     /// This is a negative amount. This amount was not charged from the account that issued
     /// the transaction. It's likely due to the delayed queue of the receipts.
     pub gas_deficit_amount: Balance,
+    /// Breakdown of the burnt gas per outcome id, recorded if `ApplyState::record_gas_profiles`
+    /// is set.
+    pub gas_profiles: Vec<(CryptoHash, GasProfile)>,
 }
 
 pub struct ApplyResult {
@@ -125,6 +137,20 @@ pub struct ApplyResult {
     pub proof: Option<PartialStorage>,
 }
 
+/// Result of `Runtime::simulate_transaction`.
+#[derive(Debug)]
+pub struct SimulationResult {
+    /// Outcome of the transaction followed by the outcomes of the executed receipts in the order
+    /// of execution.
+    pub outcomes: Vec<ExecutionOutcomeWithId>,
+    /// Receipts to receivers outside of the simulated state, which were not executed.
+    pub outgoing_receipts: Vec<Receipt>,
+    pub state_changes: Vec<RawStateChangesWithTrieKey>,
+    /// Breakdown of the burnt gas per outcome id, recorded if `ApplyState::record_gas_profiles`
+    /// is set.
+    pub gas_profiles: Vec<(CryptoHash, GasProfile)>,
+}
+
 /// Stores indices for a persistent queue for delayed receipts that didn't fit into a block.
 #[derive(Default, BorshSerialize, BorshDeserialize, Clone, PartialEq)]
 pub struct DelayedReceiptIndices {
@@ -143,6 +169,8 @@ pub struct ActionResult {
     pub logs: Vec<LogEntry>,
     pub new_receipts: Vec<Receipt>,
     pub validator_proposals: Vec<ValidatorStake>,
+    /// Gas profile of the contract calls, recorded if `ApplyState::record_gas_profiles` is set.
+    pub profile: Option<ProfileData>,
 }
 
 impl ActionResult {
@@ -162,6 +190,9 @@ impl ActionResult {
         self.gas_used = safe_add_gas(self.gas_used, next_result.gas_used)?;
         self.result = next_result.result;
         self.logs.append(&mut next_result.logs);
+        if let Some(profile) = next_result.profile {
+            self.profile.get_or_insert_with(Default::default).merge(&profile);
+        }
         if let Ok(ReturnData::ReceiptIndex(ref mut receipt_index)) = self.result {
             // Shifting local receipt index to be global receipt index.
             *receipt_index += self.new_receipts.len() as u64;
@@ -187,6 +218,7 @@ impl Default for ActionResult {
             logs: vec![],
             new_receipts: vec![],
             validator_proposals: vec![],
+            profile: None,
         }
     }
 }
@@ -195,6 +227,23 @@ pub struct Runtime {
     pub config: RuntimeConfig,
 }
 
+/// Splits the burnt gas into the gas burnt by the contract calls and the rest, which is
+/// attributed to the fees of the actions.
+fn gas_profile(gas_burnt: Gas, vm_profile: Option<&ProfileData>) -> GasProfile {
+    let mut profile = GasProfile::default();
+    if let Some(vm_profile) = vm_profile {
+        profile.wasm_gas = vm_profile.wasm_gas;
+        profile.ext_costs =
+            vm_profile.ext_costs.iter().map(|(cost, gas)| (format!("{:?}", cost), *gas)).collect();
+        profile.action_gas = gas_burnt
+            .saturating_sub(vm_profile.wasm_gas)
+            .saturating_sub(vm_profile.ext_costs_gas());
+    } else {
+        profile.action_gas = gas_burnt;
+    }
+    profile
+}
+
 impl Runtime {
     pub fn new(config: RuntimeConfig) -> Self {
         Runtime { config }
@@ -223,20 +272,35 @@ impl Runtime {
     /// `ExecutionOutcomeWithId` for the transaction.
     /// In case of an error, returns either `InvalidTxError` if the transaction verification failed
     /// or a `StorageError` wrapped into `RuntimeError`.
+    /// Signature checks are skipped if `verify_signature` is false, which is only done to simulate
+    /// unsigned transactions.
     fn process_transaction(
         &self,
         state_update: &mut TrieUpdate,
         apply_state: &ApplyState,
         signed_transaction: &SignedTransaction,
+        verify_signature: bool,
         stats: &mut ApplyStats,
     ) -> Result<(Receipt, ExecutionOutcomeWithId), RuntimeError> {
         near_metrics::inc_counter(&metrics::TRANSACTION_PROCESSED_TOTAL);
-        match verify_and_charge_transaction(
-            &self.config,
-            state_update,
-            apply_state.gas_price,
-            signed_transaction,
-        ) {
+        let verification_result = if verify_signature {
+            verify_and_charge_transaction(
+                &self.config,
+                state_update,
+                apply_state.gas_price,
+                signed_transaction,
+                apply_state.current_protocol_version,
+            )
+        } else {
+            charge_unsigned_transaction(
+                &self.config,
+                state_update,
+                apply_state.gas_price,
+                signed_transaction,
+                apply_state.current_protocol_version,
+            )
+        };
+        match verification_result {
             Ok(verification_result) => {
                 near_metrics::inc_counter(&metrics::TRANSACTION_PROCESSED_SUCCESSFULLY_TOTAL);
                 state_update.commit(StateChangeCause::TransactionProcessing {
@@ -269,6 +333,11 @@ impl Runtime {
                         executor_id: transaction.signer_id.clone(),
                     },
                 };
+                if apply_state.record_gas_profiles {
+                    stats
+                        .gas_profiles
+                        .push((outcome.id, gas_profile(verification_result.gas_burnt, None)));
+                }
                 Ok((receipt, outcome))
             }
             Err(e) => {
@@ -325,6 +394,8 @@ impl Runtime {
                 near_metrics::inc_counter(&metrics::ACTION_DEPLOY_CONTRACT_TOTAL);
                 action_deploy_contract(
                     state_update,
+                    apply_state,
+                    &self.config,
                     account.as_mut().expect(EXPECT_ACCOUNT_EXISTS),
                     &account_id,
                     deploy_contract,
@@ -408,6 +479,18 @@ impl Runtime {
                     delete_account,
                 )?;
             }
+            Action::Delegate(signed_delegate_action) => {
+                near_metrics::inc_counter(&metrics::ACTION_DELEGATE_TOTAL);
+                action_delegate(
+                    &self.config.transaction_costs,
+                    state_update,
+                    apply_state,
+                    action_receipt,
+                    &mut result,
+                    account_id,
+                    signed_delegate_action,
+                )?;
+            }
         };
         Ok(result)
     }
@@ -659,6 +742,12 @@ impl Runtime {
 
         Self::print_log(&result.logs);
 
+        if apply_state.record_gas_profiles {
+            stats
+                .gas_profiles
+                .push((receipt.receipt_id, gas_profile(result.gas_burnt, result.profile.as_ref())));
+        }
+
         Ok(ExecutionOutcomeWithId {
             id: receipt.receipt_id,
             outcome: ExecutionOutcome {
@@ -1051,6 +1140,7 @@ impl Runtime {
                 &mut state_update,
                 apply_state,
                 signed_transaction,
+                true,
                 &mut stats,
             )?;
             if receipt.receiver_id == signed_transaction.transaction.signer_id {
@@ -1190,6 +1280,76 @@ impl Runtime {
         })
     }
 
+    /// Simulates the transaction on top of the given state without persisting anything. The
+    /// transaction is converted to a receipt, then the receipts it produces are executed in order
+    /// as long as their receiver passes `is_local`. Receipts to other receivers are returned
+    /// without being executed.
+    /// Unlike `apply`, ignores the gas limit and the delayed receipts and doesn't check the
+    /// balance. Instead, stops with `RuntimeError::SimulationGasLimitExceeded` once the burnt gas
+    /// exceeds `max_gas_burnt_view`. Signature checks are skipped if `verify_signature` is false.
+    pub fn simulate_transaction(
+        &self,
+        trie: Trie,
+        root: CryptoHash,
+        apply_state: &ApplyState,
+        signed_transaction: &SignedTransaction,
+        verify_signature: bool,
+        is_local: &dyn Fn(&AccountId) -> bool,
+        epoch_info_provider: &dyn EpochInfoProvider,
+    ) -> Result<SimulationResult, RuntimeError> {
+        let mut state_update = TrieUpdate::new(Rc::new(trie), root);
+        let mut stats = ApplyStats::default();
+        let mut validator_proposals = vec![];
+        let mut outgoing_receipts = vec![];
+
+        let (receipt, outcome_with_id) = self.process_transaction(
+            &mut state_update,
+            apply_state,
+            signed_transaction,
+            verify_signature,
+            &mut stats,
+        )?;
+        let max_gas_burnt = self.config.wasm_config.limit_config.max_gas_burnt_view;
+        let mut total_gas_burnt = outcome_with_id.outcome.gas_burnt;
+        if total_gas_burnt > max_gas_burnt {
+            return Err(RuntimeError::SimulationGasLimitExceeded);
+        }
+        let mut outcomes = vec![outcome_with_id];
+        let mut pending_receipts = VecDeque::new();
+        pending_receipts.push_back(receipt);
+
+        while let Some(receipt) = pending_receipts.pop_front() {
+            if !is_local(&receipt.receiver_id) {
+                outgoing_receipts.push(receipt);
+                continue;
+            }
+            let mut new_receipts = vec![];
+            if let Some(outcome_with_id) = self.process_receipt(
+                &mut state_update,
+                apply_state,
+                &receipt,
+                &mut new_receipts,
+                &mut validator_proposals,
+                &mut stats,
+                epoch_info_provider,
+            )? {
+                total_gas_burnt = safe_add_gas(total_gas_burnt, outcome_with_id.outcome.gas_burnt)?;
+                if total_gas_burnt > max_gas_burnt {
+                    return Err(RuntimeError::SimulationGasLimitExceeded);
+                }
+                outcomes.push(outcome_with_id);
+            }
+            pending_receipts.extend(new_receipts);
+        }
+
+        Ok(SimulationResult {
+            outcomes,
+            outgoing_receipts,
+            state_changes: state_update.into_state_changes(),
+            gas_profiles: stats.gas_profiles,
+        })
+    }
+
     // Adds the given receipt into the end of the delayed receipt queue in the state.
     fn delay_receipt(
         state_update: &mut TrieUpdate,
@@ -1369,15 +1529,19 @@ impl Runtime {
 mod tests {
     use super::*;
 
-    use near_crypto::{InMemorySigner, KeyType, Signer};
-    use near_primitives::errors::ReceiptValidationError;
+    use near_crypto::{InMemorySigner, KeyType, Signature, Signer};
+    use near_primitives::errors::{InvalidTxError, ReceiptValidationError};
     use near_primitives::hash::hash;
     use near_primitives::test_utils::{account_new, MockEpochInfoProvider};
-    use near_primitives::transaction::{FunctionCallAction, TransferAction};
+    use near_primitives::transaction::{
+        DelegateAction, DeployContractAction, FunctionCallAction, SignedDelegateAction,
+        TransferAction,
+    };
     use near_primitives::types::MerkleHash;
+    use near_store::get_access_key;
     use near_store::test_utils::create_tries;
     use std::sync::Arc;
-    use testlib::runtime_utils::{alice_account, bob_account};
+    use testlib::runtime_utils::{alice_account, bob_account, eve_dot_alice_account};
 
     const GAS_PRICE: Balance = 5000;
 
@@ -1456,6 +1620,8 @@ mod tests {
             gas_limit: Some(gas_limit),
             random_seed: Default::default(),
             current_protocol_version: 0,
+            cache: None,
+            record_gas_profiles: false,
         };
 
         (runtime, tries, root, apply_state, signer, MockEpochInfoProvider::default())
@@ -1961,6 +2127,226 @@ mod tests {
         assert_eq!(result.stats.gas_deficit_amount, result.stats.tx_burnt_amount * 9)
     }
 
+    #[test]
+    fn test_apply_records_gas_profiles() {
+        let initial_balance = to_yocto(1_000_000);
+        let initial_locked = to_yocto(500_000);
+        let gas_limit = 10u64.pow(15);
+        let (runtime, tries, root, mut apply_state, _, epoch_info_provider) =
+            setup_runtime(initial_balance, initial_locked, gas_limit);
+        let receipts = generate_receipts(to_yocto(10_000), 1);
+
+        let result = runtime
+            .apply(
+                tries.get_trie_for_shard(0),
+                root,
+                &None,
+                &apply_state,
+                &receipts,
+                &[],
+                &epoch_info_provider,
+            )
+            .unwrap();
+        assert!(result.stats.gas_profiles.is_empty());
+
+        apply_state.record_gas_profiles = true;
+        let result = runtime
+            .apply(
+                tries.get_trie_for_shard(0),
+                root,
+                &None,
+                &apply_state,
+                &receipts,
+                &[],
+                &epoch_info_provider,
+            )
+            .unwrap();
+        let outcome = &result.outcomes[0];
+        assert_eq!(
+            result.stats.gas_profiles,
+            vec![(
+                outcome.id,
+                GasProfile { action_gas: outcome.outcome.gas_burnt, ..Default::default() }
+            )]
+        );
+
+        let receipts = vec![Receipt {
+            predecessor_id: alice_account(),
+            receiver_id: alice_account(),
+            receipt_id: create_nonce_with_nonce(&CryptoHash::default(), 0),
+            receipt: ReceiptEnum::Action(ActionReceipt {
+                signer_id: alice_account(),
+                signer_public_key: PublicKey::empty(KeyType::ED25519),
+                gas_price: GAS_PRICE,
+                output_data_receivers: vec![],
+                input_data_ids: vec![],
+                actions: vec![
+                    Action::DeployContract(DeployContractAction {
+                        code: include_bytes!(
+                            "../../near-vm-runner/tests/res/test_contract_rs.wasm"
+                        )
+                        .to_vec(),
+                    }),
+                    Action::FunctionCall(FunctionCallAction {
+                        method_name: "log_something".to_string(),
+                        args: vec![],
+                        gas: 2 * 10u64.pow(14),
+                        deposit: 0,
+                    }),
+                ],
+            }),
+        }];
+        let result = runtime
+            .apply(
+                tries.get_trie_for_shard(0),
+                root,
+                &None,
+                &apply_state,
+                &receipts,
+                &[],
+                &epoch_info_provider,
+            )
+            .unwrap();
+        let outcome = &result.outcomes[0];
+        assert_eq!(outcome.outcome.status, ExecutionStatus::SuccessValue(vec![]));
+        let (id, profile) = &result.stats.gas_profiles[0];
+        assert_eq!(*id, outcome.id);
+        assert!(profile.wasm_gas > 0);
+        assert!(profile.ext_costs["log_base"] > 0);
+        assert!(profile.ext_costs["log_byte"] > 0);
+        assert_eq!(
+            profile.action_gas + profile.wasm_gas + profile.ext_costs.values().sum::<Gas>(),
+            outcome.outcome.gas_burnt
+        );
+    }
+
+    #[test]
+    fn test_simulate_transaction() {
+        let initial_balance = to_yocto(1_000_000);
+        let (runtime, tries, root, apply_state, signer, epoch_info_provider) =
+            setup_runtime(initial_balance, 0, 10u64.pow(15));
+        let transaction = SignedTransaction::send_money(
+            1,
+            alice_account(),
+            alice_account(),
+            &*signer,
+            to_yocto(10_000),
+            CryptoHash::default(),
+        );
+
+        let result = runtime
+            .simulate_transaction(
+                tries.get_trie_for_shard(0),
+                root,
+                &apply_state,
+                &transaction,
+                true,
+                &|_| true,
+                &epoch_info_provider,
+            )
+            .unwrap();
+        assert_eq!(result.outcomes.len(), 2);
+        assert_eq!(result.outcomes[0].id, transaction.get_hash());
+        assert_eq!(result.outcomes[1].outcome.status, ExecutionStatus::SuccessValue(vec![]));
+        assert!(result.outgoing_receipts.is_empty());
+        assert!(!result.state_changes.is_empty());
+        // Nothing is persisted.
+        let state_update = tries.new_trie_update(0, root);
+        let account = get_account(&state_update, &alice_account()).unwrap().unwrap();
+        assert_eq!(account.amount, initial_balance);
+
+        let result = runtime
+            .simulate_transaction(
+                tries.get_trie_for_shard(0),
+                root,
+                &apply_state,
+                &transaction,
+                true,
+                &|_| false,
+                &epoch_info_provider,
+            )
+            .unwrap();
+        assert_eq!(result.outcomes.len(), 1);
+        assert_eq!(result.outgoing_receipts.len(), 1);
+    }
+
+    #[test]
+    fn test_simulate_transaction_gas_limit() {
+        let (mut runtime, tries, root, apply_state, signer, epoch_info_provider) =
+            setup_runtime(to_yocto(1_000_000), 0, 10u64.pow(15));
+        let transaction = SignedTransaction::send_money(
+            1,
+            alice_account(),
+            alice_account(),
+            &*signer,
+            to_yocto(10_000),
+            CryptoHash::default(),
+        );
+        let simulate = |runtime: &Runtime| {
+            runtime.simulate_transaction(
+                tries.get_trie_for_shard(0),
+                root,
+                &apply_state,
+                &transaction,
+                true,
+                &|_| true,
+                &epoch_info_provider,
+            )
+        };
+
+        let outcomes = simulate(&runtime).unwrap().outcomes;
+        assert_eq!(outcomes.len(), 2);
+
+        // The transaction fits into the limit, but the receipt doesn't.
+        runtime.config.wasm_config.limit_config.max_gas_burnt_view = outcomes[0].outcome.gas_burnt;
+        assert_eq!(simulate(&runtime).unwrap_err(), RuntimeError::SimulationGasLimitExceeded);
+
+        runtime.config.wasm_config.limit_config.max_gas_burnt_view =
+            outcomes[0].outcome.gas_burnt + outcomes[1].outcome.gas_burnt;
+        assert_eq!(simulate(&runtime).unwrap().outcomes, outcomes);
+    }
+
+    #[test]
+    fn test_simulate_unsigned_transaction() {
+        let (runtime, tries, root, apply_state, signer, epoch_info_provider) =
+            setup_runtime(to_yocto(1_000_000), 0, 10u64.pow(15));
+        let mut transaction = SignedTransaction::send_money(
+            1,
+            alice_account(),
+            alice_account(),
+            &*signer,
+            to_yocto(10_000),
+            CryptoHash::default(),
+        );
+        transaction.signature = Signature::empty(KeyType::ED25519).into();
+
+        let err = runtime
+            .simulate_transaction(
+                tries.get_trie_for_shard(0),
+                root,
+                &apply_state,
+                &transaction,
+                true,
+                &|_| true,
+                &epoch_info_provider,
+            )
+            .unwrap_err();
+        assert_eq!(err, RuntimeError::InvalidTxError(InvalidTxError::InvalidSignature));
+
+        let result = runtime
+            .simulate_transaction(
+                tries.get_trie_for_shard(0),
+                root,
+                &apply_state,
+                &transaction,
+                false,
+                &|_| true,
+                &epoch_info_provider,
+            )
+            .unwrap();
+        assert_eq!(result.outcomes.len(), 2);
+    }
+
     #[test]
     fn test_apply_deficit_gas_for_function_call_covered() {
         let initial_balance = to_yocto(1_000_000);
@@ -2079,4 +2465,292 @@ mod tests {
         // Burnt all the fees + all prepaid gas.
         assert_eq!(result.stats.tx_burnt_amount, total_receipt_cost);
     }
+
+    /// Sets up the runtime with the relayer `bob_account()` and the receiver of delegated
+    /// transfers `eve_dot_alice_account()`, in addition to `alice_account()` who signs the delegate
+    /// actions.
+    fn setup_delegate(
+    ) -> (Runtime, ShardTries, CryptoHash, ApplyState, Arc<InMemorySigner>, impl EpochInfoProvider)
+    {
+        let (runtime, tries, root, apply_state, signer, epoch_info_provider) =
+            setup_runtime(to_yocto(1_000_000), 0, 10u64.pow(15));
+        let mut state_update = tries.new_trie_update(0, root);
+        for account_id in vec![bob_account(), eve_dot_alice_account()] {
+            set_account(&mut state_update, account_id, &account_new(to_yocto(1_000), hash(&[])));
+        }
+        state_update.commit(StateChangeCause::InitialState);
+        let trie_changes = state_update.finalize().unwrap().0;
+        let (store_update, root) = tries.apply_all(&trie_changes, 0).unwrap();
+        store_update.commit().unwrap();
+        (runtime, tries, root, apply_state, signer, epoch_info_provider)
+    }
+
+    /// Delegate action of `alice_account()` to transfer `deposit` to `eve_dot_alice_account()`.
+    fn delegate_transfer(
+        signer: &InMemorySigner,
+        deposit: Balance,
+        nonce: Nonce,
+        max_block_height: BlockHeight,
+    ) -> SignedDelegateAction {
+        DelegateAction {
+            sender_id: alice_account(),
+            receiver_id: eve_dot_alice_account(),
+            actions: vec![Action::Transfer(TransferAction { deposit })],
+            nonce,
+            max_block_height,
+            public_key: signer.public_key(),
+        }
+        .sign(signer)
+    }
+
+    /// Receipt of a transaction of the relayer `bob_account()` that relays the delegate action to
+    /// `receiver_id`.
+    fn delegate_receipt(
+        receiver_id: AccountId,
+        signed_delegate_action: SignedDelegateAction,
+        gas_price: Balance,
+    ) -> Receipt {
+        Receipt {
+            predecessor_id: bob_account(),
+            receiver_id,
+            receipt_id: hash(&signed_delegate_action.signature.try_to_vec().unwrap()),
+            receipt: ReceiptEnum::Action(ActionReceipt {
+                signer_id: bob_account(),
+                signer_public_key: PublicKey::empty(KeyType::ED25519),
+                gas_price,
+                output_data_receivers: vec![],
+                input_data_ids: vec![],
+                actions: vec![Action::Delegate(signed_delegate_action)],
+            }),
+        }
+    }
+
+    /// Applies the receipts and commits the resulting state.
+    fn apply_and_commit(
+        runtime: &Runtime,
+        tries: &ShardTries,
+        root: &mut CryptoHash,
+        apply_state: &ApplyState,
+        receipts: &[Receipt],
+        epoch_info_provider: &dyn EpochInfoProvider,
+    ) -> ApplyResult {
+        let result = runtime
+            .apply(
+                tries.get_trie_for_shard(0),
+                *root,
+                &None,
+                apply_state,
+                receipts,
+                &[],
+                epoch_info_provider,
+            )
+            .unwrap();
+        let (store_update, new_root) = tries.apply_all(&result.trie_changes, 0).unwrap();
+        store_update.commit().unwrap();
+        *root = new_root;
+        result
+    }
+
+    fn get_balance(tries: &ShardTries, root: CryptoHash, account_id: &AccountId) -> Balance {
+        let state_update = tries.new_trie_update(0, root);
+        get_account(&state_update, account_id).unwrap().unwrap().amount
+    }
+
+    fn delegate_action_error(result: &ApplyResult) -> ActionErrorKind {
+        match &result.outcomes[0].outcome.status {
+            ExecutionStatus::Failure(TxExecutionError::ActionError(ActionError {
+                kind, ..
+            })) => kind.clone(),
+            status => panic!("Unexpected status {:?}", status),
+        }
+    }
+
+    /// The delegated actions are executed on behalf of the sender, who doesn't pay for them, and
+    /// the access key nonce of the sender is bumped so that the delegate action can't be replayed.
+    #[test]
+    fn test_apply_delegate_action() {
+        let (runtime, tries, mut root, apply_state, signer, epoch_info_provider) = setup_delegate();
+        let alice_balance = get_balance(&tries, root, &alice_account());
+        let eve_balance = get_balance(&tries, root, &eve_dot_alice_account());
+        let deposit = to_yocto(10);
+        let receipt = delegate_receipt(
+            alice_account(),
+            delegate_transfer(&signer, deposit, 5, 100),
+            GAS_PRICE,
+        );
+
+        let result = apply_and_commit(
+            &runtime,
+            &tries,
+            &mut root,
+            &apply_state,
+            &[receipt.clone()],
+            &epoch_info_provider,
+        );
+        assert_eq!(result.outcomes[0].outcome.status, ExecutionStatus::SuccessValue(vec![]));
+        let delegated_receipts: Vec<_> = result
+            .outgoing_receipts
+            .iter()
+            .filter(|receipt| receipt.predecessor_id != system_account())
+            .collect();
+        assert_eq!(delegated_receipts.len(), 1);
+        assert_eq!(delegated_receipts[0].predecessor_id, alice_account());
+        assert_eq!(delegated_receipts[0].receiver_id, eve_dot_alice_account());
+        let state_update = tries.new_trie_update(0, root);
+        let access_key =
+            get_access_key(&state_update, &alice_account(), &signer.public_key()).unwrap().unwrap();
+        assert_eq!(access_key.nonce, 5);
+
+        let result = apply_and_commit(
+            &runtime,
+            &tries,
+            &mut root,
+            &apply_state,
+            &result.outgoing_receipts,
+            &epoch_info_provider,
+        );
+        assert!(result
+            .outcomes
+            .iter()
+            .all(|outcome| outcome.outcome.status == ExecutionStatus::SuccessValue(vec![])));
+        assert_eq!(get_balance(&tries, root, &eve_dot_alice_account()), eve_balance + deposit);
+        assert_eq!(get_balance(&tries, root, &alice_account()), alice_balance);
+
+        // Replaying the same delegate action fails on the nonce.
+        let result = apply_and_commit(
+            &runtime,
+            &tries,
+            &mut root,
+            &apply_state,
+            &[receipt],
+            &epoch_info_provider,
+        );
+        assert_eq!(
+            delegate_action_error(&result),
+            ActionErrorKind::DelegateActionInvalidNonce { delegate_nonce: 5, ak_nonce: 5 }
+        );
+    }
+
+    #[test]
+    fn test_apply_delegate_action_expired() {
+        let (runtime, tries, mut root, mut apply_state, signer, epoch_info_provider) =
+            setup_delegate();
+        apply_state.block_index = 101;
+        let receipt =
+            delegate_receipt(alice_account(), delegate_transfer(&signer, 100, 1, 100), GAS_PRICE);
+
+        let result = apply_and_commit(
+            &runtime,
+            &tries,
+            &mut root,
+            &apply_state,
+            &[receipt],
+            &epoch_info_provider,
+        );
+        assert_eq!(
+            delegate_action_error(&result),
+            ActionErrorKind::DelegateActionExpired { max_block_height: 100, block_height: 101 }
+        );
+        let state_update = tries.new_trie_update(0, root);
+        let access_key =
+            get_access_key(&state_update, &alice_account(), &signer.public_key()).unwrap().unwrap();
+        assert_eq!(access_key.nonce, 0);
+    }
+
+    #[test]
+    fn test_apply_delegate_action_sender_does_not_match_receiver() {
+        let (runtime, tries, mut root, apply_state, signer, epoch_info_provider) = setup_delegate();
+        let receipt =
+            delegate_receipt(bob_account(), delegate_transfer(&signer, 100, 1, 100), GAS_PRICE);
+
+        let result = apply_and_commit(
+            &runtime,
+            &tries,
+            &mut root,
+            &apply_state,
+            &[receipt],
+            &epoch_info_provider,
+        );
+        assert_eq!(
+            delegate_action_error(&result),
+            ActionErrorKind::DelegateActionSenderDoesNotMatchReceiver {
+                sender_id: alice_account(),
+                receiver_id: bob_account(),
+            }
+        );
+    }
+
+    /// Gas refunds of the delegate action and of the delegated actions go to the relayer, who
+    /// bought the gas, and so does the deposit of a failed delegate action.
+    #[test]
+    fn test_apply_delegate_action_refunds_relayer() {
+        let (runtime, tries, mut root, mut apply_state, signer, epoch_info_provider) =
+            setup_delegate();
+        let deposit = to_yocto(10);
+        let is_refund = |receipt: &Receipt| receipt.predecessor_id == system_account();
+
+        // The gas was bought at a higher price, so the difference is refunded.
+        let receipt = delegate_receipt(
+            alice_account(),
+            delegate_transfer(&signer, deposit, 1, 100),
+            GAS_PRICE * 2,
+        );
+        let result = apply_and_commit(
+            &runtime,
+            &tries,
+            &mut root,
+            &apply_state,
+            &[receipt],
+            &epoch_info_provider,
+        );
+        let refunds: Vec<_> = result.outgoing_receipts.iter().filter(|r| is_refund(r)).collect();
+        assert_eq!(refunds.len(), 1);
+        assert_eq!(refunds[0].receiver_id, bob_account());
+        let result = apply_and_commit(
+            &runtime,
+            &tries,
+            &mut root,
+            &apply_state,
+            &result.outgoing_receipts,
+            &epoch_info_provider,
+        );
+        let refunds: Vec<_> = result.outgoing_receipts.iter().filter(|r| is_refund(r)).collect();
+        assert_eq!(refunds.len(), 1);
+        assert_eq!(refunds[0].receiver_id, bob_account());
+
+        // A failed delegate action refunds the deposit and the unused gas to the relayer.
+        apply_state.block_index = 101;
+        let receipt = delegate_receipt(
+            alice_account(),
+            delegate_transfer(&signer, deposit, 2, 100),
+            GAS_PRICE,
+        );
+        let result = apply_and_commit(
+            &runtime,
+            &tries,
+            &mut root,
+            &apply_state,
+            &[receipt],
+            &epoch_info_provider,
+        );
+        assert!(matches!(
+            delegate_action_error(&result),
+            ActionErrorKind::DelegateActionExpired { .. }
+        ));
+        let refunds: Vec<_> = result.outgoing_receipts.iter().filter(|r| is_refund(r)).collect();
+        assert_eq!(refunds.len(), 2);
+        assert!(refunds.iter().all(|refund| refund.receiver_id == bob_account()));
+        let bob_balance = get_balance(&tries, root, &bob_account());
+        let alice_balance = get_balance(&tries, root, &alice_account());
+        apply_and_commit(
+            &runtime,
+            &tries,
+            &mut root,
+            &apply_state,
+            &result.outgoing_receipts,
+            &epoch_info_provider,
+        );
+        assert!(get_balance(&tries, root, &bob_account()) > bob_balance + deposit);
+        assert_eq!(get_balance(&tries, root, &alice_account()), alice_balance);
+    }
 }
@@ -12,6 +12,7 @@ use near_primitives::trie_key::trie_key_parsers;
 use near_primitives::types::EpochHeight;
 use near_primitives::types::{AccountId, BlockHeight, EpochId, EpochInfoProvider};
 use near_primitives::utils::is_valid_account_id;
+use near_primitives::version::ProtocolVersion;
 use near_primitives::views::{StateItem, ViewStateResult};
 use near_runtime_fees::RuntimeFeesConfig;
 use near_store::{get_access_key, get_account, TrieUpdate};
@@ -91,6 +92,7 @@ impl TrieViewer {
         last_block_hash: &CryptoHash,
         epoch_height: EpochHeight,
         epoch_id: &EpochId,
+        current_protocol_version: ProtocolVersion,
         contract_id: &AccountId,
         method_name: &str,
         args: &[u8],
@@ -140,6 +142,7 @@ impl TrieViewer {
                 prepaid_gas: 0,
                 random_seed: root.as_ref().into(),
                 is_view: true,
+                profile_gas: false,
                 output_data_receivers: vec![],
             };
 
@@ -152,6 +155,8 @@ impl TrieViewer {
                 &VMConfig::default(),
                 &RuntimeFeesConfig::default(),
                 &[],
+                current_protocol_version,
+                None,
             )
         };
         let elapsed = now.elapsed();
@@ -190,6 +195,7 @@ mod tests {
 
     use super::*;
     use near_primitives::test_utils::MockEpochInfoProvider;
+    use near_primitives::version::PROTOCOL_VERSION;
 
     #[test]
     fn test_view_call() {
@@ -203,6 +209,7 @@ mod tests {
             &CryptoHash::default(),
             0,
             &EpochId::default(),
+            PROTOCOL_VERSION,
             &AccountId::from("test.contract"),
             "run_test",
             &[],
@@ -225,6 +232,7 @@ mod tests {
             &CryptoHash::default(),
             0,
             &EpochId::default(),
+            PROTOCOL_VERSION,
             &"bad!contract".to_string(),
             "run_test",
             &[],
@@ -251,6 +259,7 @@ mod tests {
             &CryptoHash::default(),
             0,
             &EpochId::default(),
+            PROTOCOL_VERSION,
             &AccountId::from("test.contract"),
             "run_test_with_storage_change",
             &[],
@@ -276,6 +285,7 @@ mod tests {
             &CryptoHash::default(),
             0,
             &EpochId::default(),
+            PROTOCOL_VERSION,
             &AccountId::from("test.contract"),
             "sum_with_input",
             &args,
@@ -355,6 +365,7 @@ mod tests {
                 &CryptoHash::default(),
                 0,
                 &EpochId::default(),
+                PROTOCOL_VERSION,
                 &AccountId::from("test.contract"),
                 "panic_after_logging",
                 &[],
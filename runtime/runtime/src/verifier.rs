@@ -1,8 +1,10 @@
+use std::collections::HashSet;
+
 use crate::actions::get_insufficient_storage_stake;
 use crate::config::{total_prepaid_gas, tx_cost, RuntimeConfig, TransactionCost};
 use crate::VerificationResult;
 use near_crypto::key_conversion::is_valid_staking_key;
-use near_primitives::account::AccessKeyPermission;
+use near_primitives::account::{AccessKeyPermission, FunctionCallPermission};
 use near_primitives::errors::{
     ActionsValidationError, InvalidAccessKeyError, InvalidTxError, ReceiptValidationError,
     RuntimeError,
@@ -10,9 +12,13 @@ use near_primitives::errors::{
 use near_primitives::receipt::{ActionReceipt, DataReceipt, Receipt, ReceiptEnum};
 use near_primitives::transaction::{
     Action, AddKeyAction, DeleteAccountAction, DeployContractAction, FunctionCallAction,
-    SignedTransaction, StakeAction,
+    SignedDelegateAction, SignedTransaction, StakeAction, TransactionSignature,
 };
+use near_primitives::types::AccountId;
 use near_primitives::utils::is_valid_account_id;
+use near_primitives::version::{
+    ProtocolVersion, DELEGATE_ACTION_PROTOCOL_VERSION, MULTISIG_ACCESS_KEY_PROTOCOL_VERSION,
+};
 use near_store::{get_access_key, get_account, set_access_key, set_account, TrieUpdate};
 use near_vm_logic::types::Balance;
 use near_vm_logic::VMLimitConfig;
@@ -23,6 +29,17 @@ pub fn validate_transaction(
     config: &RuntimeConfig,
     gas_price: Balance,
     signed_transaction: &SignedTransaction,
+    current_protocol_version: ProtocolVersion,
+) -> Result<TransactionCost, RuntimeError> {
+    validate_transaction_impl(config, gas_price, signed_transaction, true, current_protocol_version)
+}
+
+fn validate_transaction_impl(
+    config: &RuntimeConfig,
+    gas_price: Balance,
+    signed_transaction: &SignedTransaction,
+    verify_signature: bool,
+    current_protocol_version: ProtocolVersion,
 ) -> Result<TransactionCost, RuntimeError> {
     let transaction = &signed_transaction.transaction;
     let signer_id = &transaction.signer_id;
@@ -36,20 +53,108 @@ pub fn validate_transaction(
         .into());
     }
 
-    if !signed_transaction
-        .signature
-        .verify(signed_transaction.get_hash().as_ref(), &transaction.public_key)
+    if (current_protocol_version < MULTISIG_ACCESS_KEY_PROTOCOL_VERSION
+        && uses_multisig_access_keys(signed_transaction))
+        || (current_protocol_version < DELEGATE_ACTION_PROTOCOL_VERSION
+            && uses_delegate_actions(signed_transaction))
     {
-        return Err(InvalidTxError::InvalidSignature.into());
+        return Err(InvalidTxError::UnsupportedProtocolFeature {
+            protocol_version: current_protocol_version,
+        }
+        .into());
     }
 
+    let hash = signed_transaction.get_hash();
+    let num_multisig_signatures = match &signed_transaction.signature {
+        TransactionSignature::Single(signature) => {
+            if verify_signature && !signature.verify(hash.as_ref(), &transaction.public_key) {
+                return Err(InvalidTxError::InvalidSignature.into());
+            }
+            0
+        }
+        // The member keys are checked against the access key permission with the state.
+        TransactionSignature::Multi(multi_signature) => {
+            if verify_signature && !multi_signature.verify(hash.as_ref()) {
+                return Err(InvalidTxError::InvalidSignature.into());
+            }
+            multi_signature.signatures.len() as u64
+        }
+    };
+
     validate_actions(&config.wasm_config.limit_config, &transaction.actions)
         .map_err(|e| InvalidTxError::ActionsValidation(e))?;
 
     let sender_is_receiver = &transaction.receiver_id == signer_id;
 
-    tx_cost(&config.transaction_costs, &transaction, gas_price, sender_is_receiver)
-        .map_err(|_| InvalidTxError::CostOverflow.into())
+    tx_cost(
+        &config.transaction_costs,
+        &transaction,
+        num_multisig_signatures,
+        gas_price,
+        sender_is_receiver,
+    )
+    .map_err(|_| InvalidTxError::CostOverflow.into())
+}
+
+/// Returns whether the transaction is multi-signed or adds a multi-signature access key.
+fn uses_multisig_access_keys(signed_transaction: &SignedTransaction) -> bool {
+    if let TransactionSignature::Multi(_) = signed_transaction.signature {
+        return true;
+    }
+    signed_transaction.transaction.actions.iter().any(|action| match action {
+        Action::AddKey(add_key) => match add_key.access_key.permission {
+            AccessKeyPermission::MultiSig(_) => true,
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+/// Returns whether the transaction contains delegate actions.
+fn uses_delegate_actions(signed_transaction: &SignedTransaction) -> bool {
+    signed_transaction.transaction.actions.iter().any(|action| match action {
+        Action::Delegate(_) => true,
+        _ => false,
+    })
+}
+
+/// Checks the signatures of the transaction against the permission of the access key. A transaction
+/// for a multi-signature access key has to be signed by at least `threshold` distinct member keys.
+/// Other access keys only accept a single signature by the access key itself.
+fn verify_access_key_signatures(
+    signed_transaction: &SignedTransaction,
+    permission: &AccessKeyPermission,
+) -> Result<(), InvalidTxError> {
+    let transaction = &signed_transaction.transaction;
+    match (permission, &signed_transaction.signature) {
+        (AccessKeyPermission::MultiSig(multi_sig_permission), signature) => {
+            let num_signatures = match signature {
+                // The public key of a multi-signature access key can't sign on its own.
+                TransactionSignature::Single(_) => 0,
+                TransactionSignature::Multi(multi_signature) => {
+                    if multi_signature.signatures.iter().any(|(public_key, _)| {
+                        !multi_sig_permission.public_keys.contains(public_key)
+                    }) {
+                        return Err(InvalidTxError::InvalidSignature);
+                    }
+                    multi_signature.signatures.len() as u64
+                }
+            };
+            if num_signatures < u64::from(multi_sig_permission.threshold) {
+                return Err(InvalidTxError::InvalidAccessKeyError(
+                    InvalidAccessKeyError::NotEnoughSignatures {
+                        account_id: transaction.signer_id.clone(),
+                        public_key: transaction.public_key.clone(),
+                        num_signatures,
+                        threshold: u64::from(multi_sig_permission.threshold),
+                    },
+                ));
+            }
+            Ok(())
+        }
+        (_, TransactionSignature::Multi(_)) => Err(InvalidTxError::InvalidSignature),
+        (_, TransactionSignature::Single(_)) => Ok(()),
+    }
 }
 
 /// Verifies the signed transaction on top of given state, charges transaction fees
@@ -59,9 +164,53 @@ pub fn verify_and_charge_transaction(
     state_update: &mut TrieUpdate,
     gas_price: Balance,
     signed_transaction: &SignedTransaction,
+    current_protocol_version: ProtocolVersion,
+) -> Result<VerificationResult, RuntimeError> {
+    verify_and_charge_transaction_impl(
+        config,
+        state_update,
+        gas_price,
+        signed_transaction,
+        true,
+        current_protocol_version,
+    )
+}
+
+/// Same as `verify_and_charge_transaction`, but skips all signature checks. Only used to simulate
+/// unsigned transactions, never to apply chunks.
+pub fn charge_unsigned_transaction(
+    config: &RuntimeConfig,
+    state_update: &mut TrieUpdate,
+    gas_price: Balance,
+    signed_transaction: &SignedTransaction,
+    current_protocol_version: ProtocolVersion,
+) -> Result<VerificationResult, RuntimeError> {
+    verify_and_charge_transaction_impl(
+        config,
+        state_update,
+        gas_price,
+        signed_transaction,
+        false,
+        current_protocol_version,
+    )
+}
+
+fn verify_and_charge_transaction_impl(
+    config: &RuntimeConfig,
+    state_update: &mut TrieUpdate,
+    gas_price: Balance,
+    signed_transaction: &SignedTransaction,
+    verify_signature: bool,
+    current_protocol_version: ProtocolVersion,
 ) -> Result<VerificationResult, RuntimeError> {
     let TransactionCost { gas_burnt, gas_remaining, receipt_gas_price, total_cost, burnt_amount } =
-        validate_transaction(config, gas_price, signed_transaction)?;
+        validate_transaction_impl(
+            config,
+            gas_price,
+            signed_transaction,
+            verify_signature,
+            current_protocol_version,
+        )?;
     let transaction = &signed_transaction.transaction;
     let signer_id = &transaction.signer_id;
 
@@ -84,6 +233,10 @@ pub fn verify_and_charge_transaction(
         }
     };
 
+    if verify_signature {
+        verify_access_key_signatures(signed_transaction, &access_key.permission)?;
+    }
+
     if transaction.nonce <= access_key.nonce {
         return Err(InvalidTxError::InvalidNonce {
             tx_nonce: transaction.nonce,
@@ -129,47 +282,12 @@ pub fn verify_and_charge_transaction(
     };
 
     if let AccessKeyPermission::FunctionCall(ref function_call_permission) = access_key.permission {
-        if transaction.actions.len() != 1 {
-            return Err(InvalidTxError::InvalidAccessKeyError(
-                InvalidAccessKeyError::RequiresFullAccess,
-            )
-            .into());
-        }
-        if let Some(Action::FunctionCall(ref function_call)) = transaction.actions.get(0) {
-            if function_call.deposit > 0 {
-                return Err(InvalidTxError::InvalidAccessKeyError(
-                    InvalidAccessKeyError::DepositWithFunctionCall,
-                )
-                .into());
-            }
-            if transaction.receiver_id != function_call_permission.receiver_id {
-                return Err(InvalidTxError::InvalidAccessKeyError(
-                    InvalidAccessKeyError::ReceiverMismatch {
-                        tx_receiver: transaction.receiver_id.clone(),
-                        ak_receiver: function_call_permission.receiver_id.clone(),
-                    },
-                )
-                .into());
-            }
-            if !function_call_permission.method_names.is_empty()
-                && function_call_permission
-                    .method_names
-                    .iter()
-                    .all(|method_name| &function_call.method_name != method_name)
-            {
-                return Err(InvalidTxError::InvalidAccessKeyError(
-                    InvalidAccessKeyError::MethodNameMismatch {
-                        method_name: function_call.method_name.clone(),
-                    },
-                )
-                .into());
-            }
-        } else {
-            return Err(InvalidTxError::InvalidAccessKeyError(
-                InvalidAccessKeyError::RequiresFullAccess,
-            )
-            .into());
-        }
+        check_function_call_permission(
+            function_call_permission,
+            &transaction.receiver_id,
+            &transaction.actions,
+        )
+        .map_err(InvalidTxError::InvalidAccessKeyError)?;
     };
 
     set_access_key(state_update, signer_id.clone(), transaction.public_key.clone(), &access_key);
@@ -178,6 +296,42 @@ pub fn verify_and_charge_transaction(
     Ok(VerificationResult { gas_burnt, gas_remaining, receipt_gas_price, burnt_amount })
 }
 
+/// Checks that the given actions sent to `receiver_id` are allowed by a function call access key:
+/// a single function call without a deposit to the allowed receiver and one of the allowed methods.
+pub(crate) fn check_function_call_permission(
+    function_call_permission: &FunctionCallPermission,
+    receiver_id: &AccountId,
+    actions: &[Action],
+) -> Result<(), InvalidAccessKeyError> {
+    if actions.len() != 1 {
+        return Err(InvalidAccessKeyError::RequiresFullAccess);
+    }
+    if let Some(Action::FunctionCall(ref function_call)) = actions.get(0) {
+        if function_call.deposit > 0 {
+            return Err(InvalidAccessKeyError::DepositWithFunctionCall);
+        }
+        if receiver_id != &function_call_permission.receiver_id {
+            return Err(InvalidAccessKeyError::ReceiverMismatch {
+                tx_receiver: receiver_id.clone(),
+                ak_receiver: function_call_permission.receiver_id.clone(),
+            });
+        }
+        if !function_call_permission.method_names.is_empty()
+            && function_call_permission
+                .method_names
+                .iter()
+                .all(|method_name| &function_call.method_name != method_name)
+        {
+            return Err(InvalidAccessKeyError::MethodNameMismatch {
+                method_name: function_call.method_name.clone(),
+            });
+        }
+        Ok(())
+    } else {
+        Err(InvalidAccessKeyError::RequiresFullAccess)
+    }
+}
+
 /// Validates a given receipt. Checks validity of the predecessor and receiver account IDs and
 /// the validity of the Action or Data receipt.
 pub(crate) fn validate_receipt(
@@ -296,9 +450,26 @@ pub fn validate_action(
         Action::AddKey(a) => validate_add_key_action(limit_config, a),
         Action::DeleteKey(_) => Ok(()),
         Action::DeleteAccount(a) => validate_delete_account_action(a),
+        Action::Delegate(a) => validate_delegate_action(limit_config, a),
     }
 }
 
+/// Validates `SignedDelegateAction`. Validates the delegated actions and checks that they don't
+/// contain another delegate action.
+fn validate_delegate_action(
+    limit_config: &VMLimitConfig,
+    action: &SignedDelegateAction,
+) -> Result<(), ActionsValidationError> {
+    let actions = &action.delegate_action.actions;
+    if actions.iter().any(|action| match action {
+        Action::Delegate(_) => true,
+        _ => false,
+    }) {
+        return Err(ActionsValidationError::NestedDelegateAction);
+    }
+    validate_actions(limit_config, actions)
+}
+
 /// Validates `DeployContractAction`. Checks that the given contract size doesn't exceed the limit.
 fn validate_deploy_contract_action(
     limit_config: &VMLimitConfig,
@@ -355,6 +526,8 @@ fn validate_stake_action(action: &StakeAction) -> Result<(), ActionsValidationEr
 /// Validates `AddKeyAction`. If the access key permission is `FunctionCall` checks that the
 /// `receiver_id` is a valid account ID, checks the total number of bytes of the method names
 /// doesn't exceed the limit and every method name length doesn't exceed the limit.
+/// If the access key permission is `MultiSig` checks that the number of member keys doesn't
+/// exceed the limit, the threshold is reachable and the member keys are distinct.
 fn validate_add_key_action(
     limit_config: &VMLimitConfig,
     action: &AddKeyAction,
@@ -385,6 +558,30 @@ fn validate_add_key_action(
             });
         }
     }
+    if let AccessKeyPermission::MultiSig(multi_sig) = &action.access_key.permission {
+        let threshold = u64::from(multi_sig.threshold);
+        let number_of_keys = multi_sig.public_keys.len() as u64;
+        if number_of_keys > limit_config.max_multisig_keys {
+            return Err(ActionsValidationError::AddKeyMultiSigNumberOfKeysExceeded {
+                number_of_keys,
+                limit: limit_config.max_multisig_keys,
+            });
+        }
+        if threshold == 0 || threshold > number_of_keys {
+            return Err(ActionsValidationError::AddKeyMultiSigInvalidThreshold {
+                threshold,
+                number_of_keys,
+            });
+        }
+        let mut public_keys = HashSet::new();
+        for public_key in &multi_sig.public_keys {
+            if !public_keys.insert(public_key) {
+                return Err(ActionsValidationError::AddKeyMultiSigDuplicateKey {
+                    public_key: public_key.clone(),
+                });
+            }
+        }
+    }
 
     Ok(())
 }
@@ -406,14 +603,18 @@ fn validate_delete_account_action(
 mod tests {
     use super::*;
     use near_crypto::{InMemorySigner, KeyType, PublicKey, Signer};
-    use near_primitives::account::{AccessKey, Account, FunctionCallPermission};
+    use near_primitives::account::{
+        AccessKey, Account, FunctionCallPermission, MultiSigPermission,
+    };
     use near_primitives::hash::{hash, CryptoHash};
     use near_primitives::receipt::DataReceiver;
     use near_primitives::test_utils::account_new;
     use near_primitives::transaction::{
-        CreateAccountAction, DeleteKeyAction, StakeAction, TransferAction,
+        CreateAccountAction, DelegateAction, DeleteKeyAction, StakeAction, Transaction,
+        TransferAction,
     };
     use near_primitives::types::{AccountId, Balance, MerkleHash, StateChangeCause};
+    use near_primitives::version::PROTOCOL_VERSION;
     use near_store::test_utils::create_tries;
     use std::convert::TryInto;
     use std::sync::Arc;
@@ -473,13 +674,19 @@ mod tests {
         expected_err: RuntimeError,
     ) {
         assert_eq!(
-            validate_transaction(&config, gas_price, &signed_transaction)
+            validate_transaction(&config, gas_price, &signed_transaction, PROTOCOL_VERSION)
                 .expect_err("expected an error"),
             expected_err,
         );
         assert_eq!(
-            verify_and_charge_transaction(&config, state_update, gas_price, &signed_transaction)
-                .expect_err("expected an error"),
+            verify_and_charge_transaction(
+                &config,
+                state_update,
+                gas_price,
+                &signed_transaction,
+                PROTOCOL_VERSION
+            )
+            .expect_err("expected an error"),
             expected_err,
         );
     }
@@ -501,10 +708,16 @@ mod tests {
             deposit,
             CryptoHash::default(),
         );
-        validate_transaction(&config, gas_price, &transaction).expect("valid transaction");
-        let verification_result =
-            verify_and_charge_transaction(&config, &mut state_update, gas_price, &transaction)
-                .expect("valid transaction");
+        validate_transaction(&config, gas_price, &transaction, PROTOCOL_VERSION)
+            .expect("valid transaction");
+        let verification_result = verify_and_charge_transaction(
+            &config,
+            &mut state_update,
+            gas_price,
+            &transaction,
+            PROTOCOL_VERSION,
+        )
+        .expect("valid transaction");
         // Should not be free. Burning for sending
         assert!(verification_result.gas_burnt > 0);
         // All burned gas goes to the validators at current gas price
@@ -593,7 +806,7 @@ mod tests {
             100,
             CryptoHash::default(),
         );
-        tx.signature = signer.sign(CryptoHash::default().as_ref());
+        tx.signature = signer.sign(CryptoHash::default().as_ref()).into();
 
         assert_err_both_validations(
             &config,
@@ -622,6 +835,7 @@ mod tests {
                     100,
                     CryptoHash::default(),
                 ),
+                PROTOCOL_VERSION,
             )
             .expect_err("expected an error"),
             RuntimeError::InvalidTxError(InvalidTxError::InvalidAccessKeyError(
@@ -686,6 +900,7 @@ mod tests {
                     100,
                     CryptoHash::default(),
                 ),
+                PROTOCOL_VERSION,
             )
             .expect_err("expected an error"),
             RuntimeError::InvalidTxError(InvalidTxError::SignerDoesNotExist {
@@ -716,6 +931,7 @@ mod tests {
                     100,
                     CryptoHash::default(),
                 ),
+                PROTOCOL_VERSION,
             )
             .expect_err("expected an error"),
             RuntimeError::InvalidTxError(InvalidTxError::InvalidNonce { tx_nonce: 1, ak_nonce: 2 }),
@@ -762,6 +978,7 @@ mod tests {
                 TESTING_INIT_BALANCE,
                 CryptoHash::default(),
             ),
+            PROTOCOL_VERSION,
         )
         .expect_err("expected an error");
         if let RuntimeError::InvalidTxError(InvalidTxError::NotEnoughBalance {
@@ -811,6 +1028,7 @@ mod tests {
                 })],
                 CryptoHash::default(),
             ),
+            PROTOCOL_VERSION,
         )
         .expect_err("expected an error");
         if let RuntimeError::InvalidTxError(InvalidTxError::InvalidAccessKeyError(
@@ -850,6 +1068,7 @@ mod tests {
                     transfer_amount,
                     CryptoHash::default(),
                 ),
+                PROTOCOL_VERSION,
             )
             .expect_err("expected an error"),
             RuntimeError::InvalidTxError(InvalidTxError::LackBalanceForState {
@@ -898,6 +1117,7 @@ mod tests {
                     ],
                     CryptoHash::default(),
                 ),
+                PROTOCOL_VERSION,
             )
             .expect_err("expected an error"),
             RuntimeError::InvalidTxError(InvalidTxError::InvalidAccessKeyError(
@@ -918,6 +1138,7 @@ mod tests {
                     vec![],
                     CryptoHash::default(),
                 ),
+                PROTOCOL_VERSION,
             )
             .expect_err("expected an error"),
             RuntimeError::InvalidTxError(InvalidTxError::InvalidAccessKeyError(
@@ -938,6 +1159,7 @@ mod tests {
                     vec![Action::CreateAccount(CreateAccountAction {})],
                     CryptoHash::default(),
                 ),
+                PROTOCOL_VERSION,
             )
             .expect_err("expected an error"),
             RuntimeError::InvalidTxError(InvalidTxError::InvalidAccessKeyError(
@@ -980,6 +1202,7 @@ mod tests {
                     }),],
                     CryptoHash::default(),
                 ),
+                PROTOCOL_VERSION,
             )
             .expect_err("expected an error"),
             RuntimeError::InvalidTxError(InvalidTxError::InvalidAccessKeyError(
@@ -1025,6 +1248,7 @@ mod tests {
                     }),],
                     CryptoHash::default(),
                 ),
+                PROTOCOL_VERSION,
             )
             .expect_err("expected an error"),
             RuntimeError::InvalidTxError(InvalidTxError::InvalidAccessKeyError(
@@ -1067,6 +1291,7 @@ mod tests {
                     }),],
                     CryptoHash::default(),
                 ),
+                PROTOCOL_VERSION,
             )
             .expect_err("expected an error"),
             RuntimeError::InvalidTxError(InvalidTxError::InvalidAccessKeyError(
@@ -1075,6 +1300,247 @@ mod tests {
         );
     }
 
+    fn multisig_members() -> Vec<InMemorySigner> {
+        (0..3)
+            .map(|i| {
+                InMemorySigner::from_seed(
+                    &alice_account(),
+                    KeyType::ED25519,
+                    &format!("member{}", i),
+                )
+            })
+            .collect()
+    }
+
+    /// Sets up alice with a 2 out of 3 multi-signature access key identified by the public key of
+    /// the returned signer.
+    fn setup_multisig() -> (Arc<InMemorySigner>, Vec<InMemorySigner>, TrieUpdate, Balance) {
+        let members = multisig_members();
+        let (signer, state_update, gas_price) = setup_common(
+            TESTING_INIT_BALANCE,
+            0,
+            Some(AccessKey {
+                nonce: 0,
+                permission: AccessKeyPermission::MultiSig(MultiSigPermission {
+                    threshold: 2,
+                    public_keys: members.iter().map(|member| member.public_key()).collect(),
+                }),
+            }),
+        );
+        (signer, members, state_update, gas_price)
+    }
+
+    fn multisig_transfer(signer: &InMemorySigner) -> Transaction {
+        Transaction::new(
+            alice_account(),
+            signer.public_key(),
+            bob_account(),
+            1,
+            CryptoHash::default(),
+        )
+        .transfer(100)
+    }
+
+    #[test]
+    fn test_validate_transaction_multisig_valid() {
+        let config = RuntimeConfig::default();
+        let (signer, members, mut state_update, gas_price) = setup_multisig();
+
+        let single_signed = multisig_transfer(&signer).sign(&*signer);
+        let transaction = multisig_transfer(&signer).multi_sign(&[&members[0], &members[2]]);
+        let single_cost =
+            validate_transaction(&config, gas_price, &single_signed, PROTOCOL_VERSION)
+                .expect("valid transaction");
+        let verification_result = verify_and_charge_transaction(
+            &config,
+            &mut state_update,
+            gas_price,
+            &transaction,
+            PROTOCOL_VERSION,
+        )
+        .expect("valid transaction");
+        // Every signature of a multi-signed transaction is paid for.
+        assert_eq!(
+            verification_result.gas_burnt,
+            single_cost.gas_burnt
+                + 2 * config.transaction_costs.multisig_signature_cost.send_fee(false)
+        );
+
+        let access_key =
+            get_access_key(&state_update, &alice_account(), &signer.public_key()).unwrap().unwrap();
+        assert_eq!(access_key.nonce, 1);
+    }
+
+    #[test]
+    fn test_validate_transaction_multisig_not_enough_signatures() {
+        let config = RuntimeConfig::default();
+        let (signer, members, mut state_update, gas_price) = setup_multisig();
+
+        for (transaction, num_signatures) in vec![
+            (multisig_transfer(&signer).multi_sign(&[&members[1]]), 1),
+            (multisig_transfer(&signer).sign(&*signer), 0),
+        ] {
+            assert_eq!(
+                verify_and_charge_transaction(
+                    &config,
+                    &mut state_update,
+                    gas_price,
+                    &transaction,
+                    PROTOCOL_VERSION
+                )
+                .expect_err("expected an error"),
+                RuntimeError::InvalidTxError(InvalidTxError::InvalidAccessKeyError(
+                    InvalidAccessKeyError::NotEnoughSignatures {
+                        account_id: alice_account(),
+                        public_key: signer.public_key(),
+                        num_signatures,
+                        threshold: 2,
+                    },
+                )),
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_transaction_multisig_invalid_signatures() {
+        let config = RuntimeConfig::default();
+        let (signer, members, mut state_update, gas_price) = setup_multisig();
+        let stranger = InMemorySigner::from_seed(&alice_account(), KeyType::ED25519, "stranger");
+
+        // Not a member key.
+        let transaction = multisig_transfer(&signer).multi_sign(&[&members[0], &stranger]);
+        assert_eq!(
+            verify_and_charge_transaction(
+                &config,
+                &mut state_update,
+                gas_price,
+                &transaction,
+                PROTOCOL_VERSION
+            )
+            .expect_err("expected an error"),
+            RuntimeError::InvalidTxError(InvalidTxError::InvalidSignature),
+        );
+
+        // The same member key signed twice.
+        let transaction = multisig_transfer(&signer).multi_sign(&[&members[0], &members[0]]);
+        assert_err_both_validations(
+            &config,
+            &mut state_update,
+            gas_price,
+            &transaction,
+            RuntimeError::InvalidTxError(InvalidTxError::InvalidSignature),
+        );
+    }
+
+    #[test]
+    fn test_validate_transaction_multisig_for_full_access_key() {
+        let config = RuntimeConfig::default();
+        let (signer, mut state_update, gas_price) =
+            setup_common(TESTING_INIT_BALANCE, 0, Some(AccessKey::full_access()));
+        let members = multisig_members();
+
+        let transaction = multisig_transfer(&signer).multi_sign(&[&members[0], &members[1]]);
+        assert_eq!(
+            verify_and_charge_transaction(
+                &config,
+                &mut state_update,
+                gas_price,
+                &transaction,
+                PROTOCOL_VERSION
+            )
+            .expect_err("expected an error"),
+            RuntimeError::InvalidTxError(InvalidTxError::InvalidSignature),
+        );
+    }
+
+    #[test]
+    fn test_validate_transaction_multisig_unsupported_protocol_version() {
+        let config = RuntimeConfig::default();
+        let (signer, members, _state_update, gas_price) = setup_multisig();
+        let protocol_version = MULTISIG_ACCESS_KEY_PROTOCOL_VERSION - 1;
+
+        let multi_signed = multisig_transfer(&signer).multi_sign(&[&members[0], &members[1]]);
+        let add_multisig_key = multisig_transfer(&signer)
+            .add_key(
+                PublicKey::empty(KeyType::ED25519),
+                AccessKey {
+                    nonce: 0,
+                    permission: AccessKeyPermission::MultiSig(MultiSigPermission {
+                        threshold: 1,
+                        public_keys: vec![members[0].public_key()],
+                    }),
+                },
+            )
+            .sign(&*signer);
+        for transaction in vec![multi_signed, add_multisig_key] {
+            assert_eq!(
+                validate_transaction(&config, gas_price, &transaction, protocol_version)
+                    .expect_err("expected an error"),
+                RuntimeError::InvalidTxError(InvalidTxError::UnsupportedProtocolFeature {
+                    protocol_version
+                }),
+            );
+        }
+    }
+
+    fn delegate_transfer(signer: &InMemorySigner) -> Action {
+        Action::Delegate(
+            DelegateAction {
+                sender_id: alice_account(),
+                receiver_id: bob_account(),
+                actions: vec![Action::Transfer(TransferAction { deposit: 100 })],
+                nonce: 1,
+                max_block_height: 100,
+                public_key: signer.public_key(),
+            }
+            .sign(signer),
+        )
+    }
+
+    #[test]
+    fn test_validate_transaction_delegate_valid() {
+        let config = RuntimeConfig::default();
+        let (signer, mut state_update, gas_price) =
+            setup_common(TESTING_INIT_BALANCE, 0, Some(AccessKey::full_access()));
+
+        let mut transaction =
+            Transaction::new(alice_account(), signer.public_key(), alice_account(), 1, hash(&[]));
+        transaction.actions.push(delegate_transfer(&signer));
+        let transaction = transaction.sign(&*signer);
+        let cost = validate_transaction(&config, gas_price, &transaction, PROTOCOL_VERSION)
+            .expect("valid transaction");
+        // The relayer pays for the deposit of the delegated transfer.
+        assert!(cost.total_cost > 100);
+        verify_and_charge_transaction(
+            &config,
+            &mut state_update,
+            gas_price,
+            &transaction,
+            PROTOCOL_VERSION,
+        )
+        .expect("valid transaction");
+    }
+
+    #[test]
+    fn test_validate_transaction_delegate_unsupported_protocol_version() {
+        let config = RuntimeConfig::default();
+        let (signer, _state_update, gas_price) =
+            setup_common(TESTING_INIT_BALANCE, 0, Some(AccessKey::full_access()));
+        let protocol_version = DELEGATE_ACTION_PROTOCOL_VERSION - 1;
+
+        let mut transaction =
+            Transaction::new(alice_account(), signer.public_key(), alice_account(), 1, hash(&[]));
+        transaction.actions.push(delegate_transfer(&signer));
+        let transaction = transaction.sign(&*signer);
+        assert_eq!(
+            validate_transaction(&config, gas_price, &transaction, protocol_version)
+                .expect_err("expected an error"),
+            RuntimeError::InvalidTxError(InvalidTxError::UnsupportedProtocolFeature {
+                protocol_version
+            }),
+        );
+    }
+
     // Receipts
 
     #[test]
@@ -1454,6 +1920,85 @@ mod tests {
         .expect("valid action");
     }
 
+    #[test]
+    fn test_validate_action_valid_add_key_multisig() {
+        validate_action(
+            &VMLimitConfig::default(),
+            &Action::AddKey(AddKeyAction {
+                public_key: PublicKey::empty(KeyType::ED25519),
+                access_key: AccessKey {
+                    nonce: 0,
+                    permission: AccessKeyPermission::MultiSig(MultiSigPermission {
+                        threshold: 2,
+                        public_keys: multisig_members()
+                            .iter()
+                            .map(|member| member.public_key())
+                            .collect(),
+                    }),
+                },
+            }),
+        )
+        .expect("valid action");
+    }
+
+    #[test]
+    fn test_validate_action_invalid_add_key_multisig() {
+        let public_keys: Vec<_> =
+            multisig_members().iter().map(|member| member.public_key()).collect();
+        for (permission, expected_err) in vec![
+            (
+                MultiSigPermission { threshold: 0, public_keys: public_keys.clone() },
+                ActionsValidationError::AddKeyMultiSigInvalidThreshold {
+                    threshold: 0,
+                    number_of_keys: 3,
+                },
+            ),
+            (
+                MultiSigPermission { threshold: 4, public_keys: public_keys.clone() },
+                ActionsValidationError::AddKeyMultiSigInvalidThreshold {
+                    threshold: 4,
+                    number_of_keys: 3,
+                },
+            ),
+            (
+                MultiSigPermission {
+                    threshold: 2,
+                    public_keys: vec![public_keys[0].clone(), public_keys[0].clone()],
+                },
+                ActionsValidationError::AddKeyMultiSigDuplicateKey {
+                    public_key: public_keys[0].clone(),
+                },
+            ),
+            (
+                MultiSigPermission {
+                    threshold: 1,
+                    public_keys: (0..33)
+                        .map(|i| PublicKey::from_seed(KeyType::ED25519, &format!("member{}", i)))
+                        .collect(),
+                },
+                ActionsValidationError::AddKeyMultiSigNumberOfKeysExceeded {
+                    number_of_keys: 33,
+                    limit: 32,
+                },
+            ),
+        ] {
+            assert_eq!(
+                validate_action(
+                    &VMLimitConfig::default(),
+                    &Action::AddKey(AddKeyAction {
+                        public_key: PublicKey::empty(KeyType::ED25519),
+                        access_key: AccessKey {
+                            nonce: 0,
+                            permission: AccessKeyPermission::MultiSig(permission),
+                        },
+                    }),
+                )
+                .expect_err("expected an error"),
+                expected_err,
+            );
+        }
+    }
+
     #[test]
     fn test_validate_action_valid_delete_key() {
         validate_action(
@@ -1471,4 +2016,31 @@ mod tests {
         )
         .expect("valid action");
     }
+
+    #[test]
+    fn test_validate_action_valid_delegate() {
+        let signer = InMemorySigner::from_seed(&alice_account(), KeyType::ED25519, "alice");
+        validate_action(&VMLimitConfig::default(), &delegate_transfer(&signer))
+            .expect("valid action");
+    }
+
+    #[test]
+    fn test_validate_action_invalid_nested_delegate() {
+        let signer = InMemorySigner::from_seed(&alice_account(), KeyType::ED25519, "alice");
+        let action = Action::Delegate(
+            DelegateAction {
+                sender_id: alice_account(),
+                receiver_id: alice_account(),
+                actions: vec![delegate_transfer(&signer)],
+                nonce: 2,
+                max_block_height: 100,
+                public_key: signer.public_key(),
+            }
+            .sign(&signer),
+        );
+        assert_eq!(
+            validate_action(&VMLimitConfig::default(), &action).expect_err("expected an error"),
+            ActionsValidationError::NestedDelegateAction,
+        );
+    }
 }
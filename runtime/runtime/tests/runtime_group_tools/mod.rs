@@ -66,6 +66,8 @@ impl StandaloneRuntime {
             gas_limit: None,
             random_seed: Default::default(),
             current_protocol_version: PROTOCOL_VERSION,
+            cache: None,
+            record_gas_profiles: false,
         };
 
         Self {
@@ -33,10 +33,14 @@ pub fn random_config() -> RuntimeConfig {
                     full_access_cost: random_fee(),
                     function_call_cost: random_fee(),
                     function_call_cost_per_byte: random_fee(),
+                    multisig_cost: random_fee(),
+                    multisig_cost_per_key: random_fee(),
                 },
                 delete_key_cost: random_fee(),
                 delete_account_cost: random_fee(),
+                delegate_cost: random_fee(),
             },
+            multisig_signature_cost: random_fee(),
             storage_usage_config: StorageUsageConfig {
                 num_bytes_account: rng.next_u64() % 10000,
                 num_extra_bytes_record: rng.next_u64() % 10000,
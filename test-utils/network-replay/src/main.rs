@@ -0,0 +1,97 @@
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use actix::{Actor, System};
+use clap::{App, Arg};
+use log::info;
+
+use near_chain::ChainGenesis;
+use near_client::start_client;
+use near_crypto::PublicKey;
+use near_logger_utils::init_integration_logger;
+use near_network::capture::read_capture;
+use near_network::test_utils::MockNetworkAdapter;
+use near_primitives::network::PeerId;
+use near_telemetry::TelemetryActor;
+use neard::{
+    get_default_home, get_store_path, init_and_migrate_store, load_config, NightshadeRuntime,
+};
+
+use crate::replay::{copy_dir, replay};
+
+mod replay;
+
+fn main() {
+    init_integration_logger();
+
+    let default_home = get_default_home();
+    let matches = App::new("network-replay")
+        .about(
+            "Replays the messages received from peers in a network capture into a client without \
+             network. The client runs on a temporary copy of the node data, which is left intact.",
+        )
+        .arg(
+            Arg::with_name("home")
+                .long("home")
+                .default_value(&default_home)
+                .help("Directory for config and data (default \"~/.near\")")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("peer")
+                .long("peer")
+                .help("Only replay messages exchanged with this peer")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("capture")
+                .help("Directory with the capture files")
+                .required(true)
+                .takes_value(true),
+        )
+        .get_matches();
+
+    let home_dir = matches.value_of("home").map(PathBuf::from).unwrap();
+    let capture_dir = matches.value_of("capture").map(|dir| Path::new(dir)).unwrap();
+    let peer = matches
+        .value_of("peer")
+        .map(|peer| PeerId::from(PublicKey::try_from(peer).expect("Failed to parse the peer id")));
+    let messages = read_capture(capture_dir).expect("Failed to read the capture");
+    let near_config = load_config(&home_dir);
+    // The client writes to the store and migrations rewrite it, so work on a copy of the data.
+    let replay_home = tempfile::Builder::new()
+        .prefix("network-replay")
+        .tempdir()
+        .expect("Failed to create a temporary directory");
+    let store_path = PathBuf::from(get_store_path(&home_dir));
+    info!(target: "replay", "Copying {:?} to {:?}", store_path, replay_home.path());
+    copy_dir(&store_path, &PathBuf::from(get_store_path(replay_home.path())))
+        .expect("Failed to copy the node data");
+    let store = init_and_migrate_store(replay_home.path());
+
+    System::run(move || {
+        let runtime = Arc::new(NightshadeRuntime::new(
+            &home_dir,
+            store,
+            Arc::clone(&near_config.genesis),
+            near_config.client_config.tracked_accounts.clone(),
+            near_config.client_config.tracked_shards.clone(),
+        ));
+        let network_adapter = Arc::new(MockNetworkAdapter::default());
+        let (client, _) = start_client(
+            near_config.client_config,
+            ChainGenesis::from(&near_config.genesis),
+            runtime,
+            near_config.network_config.public_key.clone().into(),
+            network_adapter.clone(),
+            near_config.validator_signer,
+            TelemetryActor::default().start(),
+        );
+        actix::spawn(async move {
+            replay(messages, peer, client.recipient(), network_adapter).await;
+            System::current().stop();
+        });
+    })
+    .unwrap();
+}
@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use actix::Recipient;
+use log::debug;
+
+use near_network::capture::{CapturedMessage, Direction};
+use near_network::test_utils::MockNetworkAdapter;
+use near_network::types::PeerMessage;
+use near_network::NetworkClientMessages;
+use near_primitives::network::PeerId;
+use near_primitives::utils::from_timestamp;
+
+/// Send the messages received from peers to the client in the captured order, waiting for each
+/// one to be processed. Prints the response of the client and the requests it sent to the network.
+pub async fn replay(
+    messages: Vec<CapturedMessage>,
+    peer: Option<PeerId>,
+    client: Recipient<NetworkClientMessages>,
+    network_adapter: Arc<MockNetworkAdapter>,
+) {
+    // Blocks requested from each peer, to tell the client whether a received block was requested.
+    let mut requested_blocks = HashSet::new();
+    for (index, captured) in messages.into_iter().enumerate() {
+        let peer_id = match captured.peer_id {
+            Some(peer_id) if peer.as_ref().map_or(true, |peer| peer == &peer_id) => peer_id,
+            _ => continue,
+        };
+        let variant = captured.message.msg_variant().to_string();
+        match (captured.direction, captured.message) {
+            (Direction::Sent, PeerMessage::BlockRequest(hash)) => {
+                requested_blocks.insert((peer_id, hash));
+            }
+            (Direction::Sent, _) => {}
+            (Direction::Received, message) => {
+                let was_requested = match &message {
+                    PeerMessage::Block(block) => {
+                        requested_blocks.contains(&(peer_id.clone(), *block.hash()))
+                    }
+                    _ => false,
+                };
+                let client_message = match message
+                    .into_client_message(peer_id.clone(), was_requested)
+                {
+                    Some(client_message) => client_message,
+                    None => {
+                        debug!(target: "replay", "Skipping #{} {} from {}", index, variant, peer_id);
+                        continue;
+                    }
+                };
+                let response = client.send(client_message).await;
+                println!(
+                    "#{} {} {} from {}: {:?}",
+                    index,
+                    from_timestamp(captured.timestamp),
+                    variant,
+                    peer_id,
+                    response
+                );
+                while let Some(request) = network_adapter.pop() {
+                    println!("    {}", request.as_ref());
+                }
+            }
+        }
+    }
+}
+
+/// Recursively copy the directory `from` into the new directory `to`.
+pub fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use actix::actors::mocker::Mocker;
+    use actix::{Actor, System};
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    use near_client::ClientActor;
+    use near_network::capture::{read_capture, CaptureConfig, MessageCapture};
+    use near_network::test_utils::peer_id_from_seed;
+    use near_network::NetworkClientResponses;
+    use near_primitives::block::Block;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::transaction::SignedTransaction;
+    use near_primitives::version::PROTOCOL_VERSION;
+
+    use super::*;
+
+    fn block(height: u64) -> Block {
+        Block::genesis(
+            PROTOCOL_VERSION,
+            vec![],
+            Utc::now(),
+            height,
+            100,
+            1000,
+            CryptoHash::default(),
+        )
+    }
+
+    fn captured(
+        timestamp: u64,
+        direction: Direction,
+        peer_id: &PeerId,
+        message: PeerMessage,
+    ) -> CapturedMessage {
+        CapturedMessage { timestamp, direction, peer_id: Some(peer_id.clone()), message }
+    }
+
+    /// Replays the capture written to disk into a client that records the messages it receives.
+    fn replay_capture(messages: Vec<CapturedMessage>, peer: Option<PeerId>) -> Vec<String> {
+        let dir = tempdir().unwrap();
+        let config =
+            CaptureConfig { dir: dir.path().to_path_buf(), max_file_size: 1 << 20, max_files: 10 };
+        let mut capture = MessageCapture::new(config).unwrap();
+        for message in messages.iter() {
+            capture.write(message).unwrap();
+        }
+        let messages = read_capture(dir.path()).unwrap();
+
+        let received = Arc::new(Mutex::new(vec![]));
+        let received1 = received.clone();
+        System::run(move || {
+            let client = Mocker::<ClientActor>::mock(Box::new(move |msg, _ctx| {
+                let msg = msg.downcast_ref::<NetworkClientMessages>().unwrap();
+                let description = match msg {
+                    NetworkClientMessages::Block(block, peer_id, was_requested) => format!(
+                        "block {} from {} requested {}",
+                        block.header().height(),
+                        peer_id,
+                        was_requested
+                    ),
+                    NetworkClientMessages::Transaction { is_forwarded, .. } => {
+                        format!("transaction forwarded {}", is_forwarded)
+                    }
+                    msg => panic!("Unexpected message {:?}", msg),
+                };
+                received1.lock().unwrap().push(description);
+                Box::new(Some(NetworkClientResponses::NoResponse))
+            }))
+            .start();
+            let network_adapter = Arc::new(MockNetworkAdapter::default());
+            actix::spawn(async move {
+                replay(messages, peer, client.recipient(), network_adapter).await;
+                System::current().stop();
+            });
+        })
+        .unwrap();
+        let received = received.lock().unwrap();
+        received.clone()
+    }
+
+    #[test]
+    fn test_capture_replay_roundtrip() {
+        let (peer0, peer1) = (peer_id_from_seed("test0"), peer_id_from_seed("test1"));
+        let (block1, block2) = (block(1), block(2));
+        let messages = vec![
+            captured(0, Direction::Sent, &peer0, PeerMessage::BlockRequest(*block1.hash())),
+            captured(1, Direction::Received, &peer0, PeerMessage::Block(block1)),
+            captured(2, Direction::Received, &peer1, PeerMessage::Block(block2)),
+            captured(3, Direction::Received, &peer1, PeerMessage::PeersRequest),
+            captured(
+                4,
+                Direction::Received,
+                &peer1,
+                PeerMessage::Transaction(SignedTransaction::empty(CryptoHash::default())),
+            ),
+            captured(5, Direction::Sent, &peer1, PeerMessage::PeersRequest),
+        ];
+
+        assert_eq!(
+            replay_capture(messages.clone(), None),
+            vec![
+                format!("block 1 from {} requested true", peer0),
+                format!("block 2 from {} requested false", peer1),
+                "transaction forwarded false".to_string(),
+            ]
+        );
+        assert_eq!(
+            replay_capture(messages, Some(peer0.clone())),
+            vec![format!("block 1 from {} requested true", peer0)]
+        );
+    }
+
+    #[test]
+    fn test_copy_dir() {
+        let from = tempdir().unwrap();
+        fs::create_dir(from.path().join("data")).unwrap();
+        fs::write(from.path().join("data").join("CURRENT"), b"MANIFEST-000001").unwrap();
+        fs::write(from.path().join("config.json"), b"{}").unwrap();
+        let to = tempdir().unwrap();
+        copy_dir(from.path(), &to.path().join("copy")).unwrap();
+        assert_eq!(
+            fs::read(to.path().join("copy").join("data").join("CURRENT")).unwrap(),
+            b"MANIFEST-000001"
+        );
+        assert_eq!(fs::read(to.path().join("copy").join("config.json")).unwrap(), b"{}");
+    }
+}
@@ -99,6 +99,9 @@ impl RuntimeUser {
                     }
                     RuntimeError::ReceiptValidationError(e) => panic!("{}", e),
                     RuntimeError::ValidatorError(e) => panic!("{}", e),
+                    RuntimeError::SimulationGasLimitExceeded => {
+                        unreachable!("Only returned by simulate_transaction")
+                    }
                 })?;
             for outcome_with_id in apply_result.outcomes {
                 self.transaction_results
@@ -129,6 +132,8 @@ impl RuntimeUser {
             random_seed: Default::default(),
             epoch_id: Default::default(),
             current_protocol_version: PROTOCOL_VERSION,
+            cache: None,
+            record_gas_profiles: false,
         }
     }
 
@@ -0,0 +1,105 @@
+//! Builds and signs a transaction without access to the network, e.g. on a cold storage machine.
+//! The nonce and the block hash are given explicitly, the signed transaction is printed as base64
+//! encoded Borsh, ready to be sent with `broadcast_tx_async` or `broadcast_tx_commit`.
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+
+use borsh::BorshSerialize;
+use clap::{App, Arg, ArgGroup};
+
+use near_crypto::{InMemorySigner, KeyFile, Signer};
+use near_primitives::hash::CryptoHash;
+use near_primitives::serialize::to_base64;
+use near_primitives::transaction::{Action, SignedTransaction, Transaction};
+use near_primitives::types::Nonce;
+
+fn main() {
+    let matches = App::new("Offline transaction signer")
+        .about("Builds a transaction from JSON actions, signs it and prints it as base64 Borsh")
+        .arg(
+            Arg::with_name("key-file")
+                .long("key-file")
+                .required(true)
+                .help("Key file of the signer, in the same format as validator_key.json")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("signer-id")
+                .long("signer-id")
+                .help("Account that signs the transaction (default account_id of the key file)")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("receiver-id")
+                .long("receiver-id")
+                .required(true)
+                .help("Account that receives the transaction")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("nonce")
+                .long("nonce")
+                .required(true)
+                .help("Nonce of the transaction, must be greater than the nonce of the access key")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("block-hash")
+                .long("block-hash")
+                .required(true)
+                .help("Base58 hash of a recent block, the transaction expires some time after it")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("actions")
+                .long("actions")
+                .help("JSON list of actions, e.g. '[{\"Transfer\": {\"deposit\": \"1\"}}]'")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("actions-file")
+                .long("actions-file")
+                .help("Path to a file with the JSON list of actions")
+                .takes_value(true),
+        )
+        .group(
+            ArgGroup::with_name("actions-source").args(&["actions", "actions-file"]).required(true),
+        )
+        .get_matches();
+
+    let key_file = KeyFile::from_file(Path::new(matches.value_of("key-file").unwrap()));
+    let signer_id = matches
+        .value_of("signer-id")
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| key_file.account_id.clone());
+    let signer: InMemorySigner = key_file.into();
+    let receiver_id = matches.value_of("receiver-id").unwrap().to_string();
+    let nonce: Nonce =
+        matches.value_of("nonce").unwrap().parse().expect("Failed to parse the nonce");
+    let block_hash = CryptoHash::try_from(matches.value_of("block-hash").unwrap())
+        .expect("Failed to parse the block hash");
+    let actions_json = match matches.value_of("actions") {
+        Some(actions) => actions.to_string(),
+        None => fs::read_to_string(matches.value_of("actions-file").unwrap())
+            .expect("Failed to read the actions file"),
+    };
+    let actions: Vec<Action> =
+        serde_json::from_str(&actions_json).expect("Failed to parse the actions");
+
+    let transaction = Transaction {
+        signer_id,
+        public_key: signer.public_key(),
+        nonce,
+        receiver_id,
+        block_hash,
+        actions,
+    };
+    let signature = signer.sign(transaction.get_hash().as_ref());
+    let signed_transaction = SignedTransaction::new(signature, transaction);
+    eprintln!("Transaction hash: {}", signed_transaction.get_hash());
+    println!(
+        "{}",
+        to_base64(&signed_transaction.try_to_vec().expect("Failed to serialize the transaction"))
+    );
+}